@@ -0,0 +1,59 @@
+#!/usr/bin/env node
+
+/**
+ * extract-event-schema.mjs - Bundle the opencode event schema for Rust-side validation
+ *
+ * Usage:
+ *   node scripts/extract-event-schema.mjs [openapi.json]
+ *
+ * Reads the OpenAPI document (default: openapi_doc.json), keeps only the component
+ * schemas reachable from `Event` and `GlobalEvent`, and writes them to
+ * src-tauri/schemas/opencode-events.json, which the bridge embeds at compile time.
+ * Re-run after updating openapi_doc.json for a new server version.
+ */
+
+import { readFileSync, writeFileSync, mkdirSync } from 'fs'
+import { resolve, dirname } from 'path'
+import { fileURLToPath } from 'url'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+const root = resolve(__dirname, '..')
+
+const source = resolve(root, process.argv[2] || 'openapi_doc.json')
+const doc = JSON.parse(readFileSync(source, 'utf-8'))
+const schemas = doc.components?.schemas ?? {}
+
+const prefix = '#/components/schemas/'
+const reachable = {}
+
+function visit(value) {
+  if (Array.isArray(value)) {
+    value.forEach(visit)
+    return
+  }
+  if (!value || typeof value !== 'object') return
+
+  const ref = value.$ref
+  if (typeof ref === 'string' && ref.startsWith(prefix)) {
+    const name = ref.slice(prefix.length)
+    if (!(name in reachable) && name in schemas) {
+      reachable[name] = schemas[name]
+      visit(schemas[name])
+    }
+  }
+  Object.values(value).forEach(visit)
+}
+
+for (const name of ['Event', 'GlobalEvent']) {
+  visit({ $ref: `${prefix}${name}` })
+}
+
+const outDir = resolve(root, 'src-tauri', 'schemas')
+const out = resolve(outDir, 'opencode-events.json')
+mkdirSync(outDir, { recursive: true })
+writeFileSync(
+  out,
+  JSON.stringify({ serverVersion: doc.info?.version ?? 'unknown', schemas: reachable }) + '\n',
+)
+
+console.log(`Wrote ${Object.keys(reachable).length} schemas to ${out}`)
@@ -0,0 +1,62 @@
+#!/usr/bin/env node
+
+/**
+ * prepare-sidecar.mjs - Copy an opencode binary into src-tauri/binaries for sidecar builds
+ *
+ * Usage:
+ *   node scripts/prepare-sidecar.mjs [path/to/opencode]
+ *
+ * Without an argument the opencode found on PATH is used. The binary is copied to
+ * src-tauri/binaries/opencode-<target-triple>[.exe], which is the name Tauri expects
+ * for `bundle.externalBin`. Then build with:
+ *
+ *   npm run tauri build -- --config src-tauri/tauri.sidecar.conf.json
+ *
+ * build.rs embeds the binary's SHA-256 so the app can verify the bundled copy at runtime.
+ */
+
+import { copyFileSync, chmodSync, existsSync, mkdirSync, realpathSync } from 'fs'
+import { execSync } from 'child_process'
+import { resolve, dirname } from 'path'
+import { fileURLToPath } from 'url'
+
+const __dirname = dirname(fileURLToPath(import.meta.url))
+const root = resolve(__dirname, '..')
+
+const target =
+  process.env.TAURI_ENV_TARGET_TRIPLE ||
+  execSync('rustc -vV', { encoding: 'utf-8' })
+    .split(/\r?\n/)
+    .find(line => line.startsWith('host:'))
+    ?.slice('host:'.length)
+    .trim()
+
+if (!target) {
+  console.error('Unable to determine the Rust target triple')
+  process.exit(1)
+}
+
+function findOnPath() {
+  const command = process.platform === 'win32' ? 'where opencode' : 'command -v opencode'
+  try {
+    return execSync(command, { encoding: 'utf-8' }).split(/\r?\n/)[0].trim()
+  } catch {
+    return ''
+  }
+}
+
+const source = process.argv[2] || findOnPath()
+if (!source || !existsSync(source)) {
+  console.error('opencode binary not found; pass its path as the first argument')
+  process.exit(1)
+}
+
+const ext = target.includes('windows') ? '.exe' : ''
+const outDir = resolve(root, 'src-tauri', 'binaries')
+const dest = resolve(outDir, `opencode-${target}${ext}`)
+
+mkdirSync(outDir, { recursive: true })
+copyFileSync(realpathSync(source), dest)
+if (!ext) chmodSync(dest, 0o755)
+
+console.log(`Copied ${source} -> ${dest}`)
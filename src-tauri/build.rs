@@ -1,3 +1,28 @@
+use sha2::{Digest, Sha256};
+use std::{env, fs::File, io, path::PathBuf};
+
+/// 如果 binaries/ 下存在当前目标的 opencode sidecar，写入其 SHA-256 供运行时校验
+fn embed_sidecar_checksum() {
+  println!("cargo:rerun-if-changed=binaries");
+
+  let target = env::var("TARGET").unwrap_or_default();
+  let ext = if target.contains("windows") { ".exe" } else { "" };
+  let sidecar = PathBuf::from("binaries").join(format!("opencode-{}{}", target, ext));
+  let Ok(mut file) = File::open(&sidecar) else {
+    return;
+  };
+
+  let mut hasher = Sha256::new();
+  io::copy(&mut file, &mut hasher).expect("failed to hash opencode sidecar");
+  let digest: String = hasher
+    .finalize()
+    .iter()
+    .map(|byte| format!("{:02x}", byte))
+    .collect();
+  println!("cargo:rustc-env=OPENCODE_SIDECAR_SHA256={}", digest);
+}
+
 fn main() {
+  embed_sidecar_checksum();
   tauri_build::build()
 }
@@ -0,0 +1,169 @@
+// ============================================
+// OpenCode Server API Client
+// Rust 侧直接调用 opencode HTTP API 的最小封装
+// ============================================
+
+use crate::app::{credentials, http_tuning::HttpTuning};
+use serde_json::Value;
+use std::time::Duration;
+
+/// 目标服务器：地址 + 可选的工作目录与认证头
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerTarget {
+    pub url: String,
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default)]
+    pub auth_header: Option<String>,
+    /// 服务器条目 id；设置后从钥匙串取凭据，忽略 `auth_header`
+    #[serde(default)]
+    pub server_id: Option<String>,
+    #[serde(default)]
+    pub http: HttpTuning,
+}
+
+impl ServerTarget {
+    /// 保存到磁盘的目标不能带明文凭据：设置了 `server_id` 时丢弃 `auth_header`
+    /// （运行时从钥匙串取凭据），只有 `auth_header` 时报错
+    pub fn strip_auth_header(&mut self) -> Result<(), String> {
+        if self.auth_header.take().is_some() && self.server_id.is_none() {
+            return Err(
+                "an auth header cannot be stored; save the credential for the server and set serverId instead"
+                    .to_string(),
+            );
+        }
+        Ok(())
+    }
+}
+
+fn client(target: &ServerTarget) -> Result<reqwest::Client, String> {
+    let builder = crate::app::dns::client_builder().connect_timeout(Duration::from_secs(5));
+    let builder = crate::app::network::apply_server(builder, target.server_id.as_deref());
+    target
+        .http
+        .apply(builder)
+        .build()
+        .map_err(|e| format!("failed to create HTTP client: {}", e))
+}
+
+fn request(
+    client: &reqwest::Client,
+    method: reqwest::Method,
+    target: &ServerTarget,
+    path: &str,
+) -> reqwest::RequestBuilder {
+    let endpoint = format!("{}{}", target.url.trim_end_matches('/'), path);
+    let mut req = client
+        .request(method, endpoint)
+        .timeout(Duration::from_secs(30));
+    if let Some(directory) = target.directory.as_deref() {
+        req = req.query(&[("directory", directory)]);
+    }
+    match credentials::resolve(target.server_id.as_deref(), target.auth_header.as_deref()) {
+        Ok(Some(auth)) => req = req.header("Authorization", auth),
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to resolve credential for {}: {}", target.url, e),
+    }
+    req
+}
+
+async fn send(req: reqwest::RequestBuilder, path: &str) -> Result<Value, String> {
+    let response = req
+        .send()
+        .await
+        .map_err(|e| format!("request to {} failed: {}", path, e))?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", path, response.status()));
+    }
+
+    let body = response
+        .bytes()
+        .await
+        .map_err(|e| format!("failed to read {} response: {}", path, e))?;
+    if body.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(&body).map_err(|e| format!("invalid {} response: {}", path, e))
+}
+
+pub async fn get_json(target: &ServerTarget, path: &str) -> Result<Value, String> {
+    let client = client(target)?;
+    send(request(&client, reqwest::Method::GET, target, path), path).await
+}
+
+pub async fn post_json(target: &ServerTarget, path: &str, body: &Value) -> Result<Value, String> {
+    let client = client(target)?;
+    let req = request(&client, reqwest::Method::POST, target, path)
+        .header("Content-Type", "application/json")
+        .body(body.to_string());
+    send(req, path).await
+}
+
+/// 向会话异步发送一条文本 prompt；未指定会话时先新建会话。返回会话 id。
+pub async fn send_prompt(
+    target: &ServerTarget,
+    session_id: Option<&str>,
+    text: &str,
+    model: Option<&str>,
+    agent: Option<&str>,
+) -> Result<String, String> {
+    let session_id = match session_id {
+        Some(id) => id.to_string(),
+        None => {
+            let session = post_json(target, "/session", &serde_json::json!({})).await?;
+            session
+                .get("id")
+                .and_then(|id| id.as_str())
+                .map(str::to_string)
+                .ok_or("server did not return a session id")?
+        }
+    };
+
+    let mut body = serde_json::json!({
+        "parts": [{ "type": "text", "text": text }],
+    });
+    // model 形如 "provider/model"
+    if let Some((provider_id, model_id)) = model.and_then(|model| model.split_once('/')) {
+        body["model"] = serde_json::json!({ "providerID": provider_id, "modelID": model_id });
+    }
+    if let Some(agent) = agent {
+        body["agent"] = Value::String(agent.to_string());
+    }
+
+    post_json(
+        target,
+        &format!("/session/{}/prompt_async", session_id),
+        &body,
+    )
+    .await?;
+    Ok(session_id)
+}
+
+/// 回复权限请求（`once` / `always` / `reject`）；新接口失败时回退到旧版按会话的接口
+pub async fn reply_permission(
+    target: &ServerTarget,
+    session_id: &str,
+    request_id: &str,
+    reply: &str,
+    message: Option<&str>,
+) -> Result<(), String> {
+    let mut body = serde_json::json!({ "reply": reply });
+    if let Some(message) = message {
+        body["message"] = Value::String(message.to_string());
+    }
+    if post_json(target, &format!("/permission/{}/reply", request_id), &body)
+        .await
+        .is_ok()
+    {
+        return Ok(());
+    }
+
+    post_json(
+        target,
+        &format!("/session/{}/permissions/{}", session_id, request_id),
+        &serde_json::json!({ "response": reply }),
+    )
+    .await
+    .map(|_| ())
+}
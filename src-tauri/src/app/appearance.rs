@@ -0,0 +1,100 @@
+// ============================================
+// Window Appearance (desktop only)
+// 原生半透明效果：macOS vibrancy / Windows 11 Mica、Acrylic
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use tauri::{
+    utils::config::WindowEffectsConfig,
+    window::{Color, Effect, EffectState, EffectsBuilder},
+};
+
+/// 窗口背景效果
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WindowEffect {
+    #[default]
+    None,
+    /// macOS NSVisualEffectView
+    Vibrancy,
+    /// Windows 11 Mica
+    Mica,
+    /// Windows 10/11 Acrylic
+    Acrylic,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WindowAppearance {
+    pub effect: WindowEffect,
+    /// 透明的 webview 背景，配合 effect 让系统材质透出来
+    pub transparent: bool,
+    /// 使用系统原生标题栏，而非由前端绘制的一体化标题栏（新建窗口时生效）
+    pub native_titlebar: bool,
+}
+
+fn appearance_path(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
+    let dir = crate::app::profile::config_dir(app)?;
+    Some(dir.join("appearance.json"))
+}
+
+pub fn load_appearance(app: &tauri::AppHandle) -> WindowAppearance {
+    appearance_path(app)
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+pub fn save_appearance(
+    app: &tauri::AppHandle,
+    appearance: &WindowAppearance,
+) -> Result<(), String> {
+    let path = appearance_path(app).ok_or("app config dir unavailable")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let data = serde_json::to_string_pretty(appearance).map_err(|e| e.to_string())?;
+    std::fs::write(path, data).map_err(|e| e.to_string())
+}
+
+/// 当前平台是否支持该效果（不支持时静默回退为无效果）
+fn native_effect(effect: WindowEffect) -> Option<Effect> {
+    match effect {
+        WindowEffect::None => None,
+        WindowEffect::Vibrancy if cfg!(target_os = "macos") => Some(Effect::UnderWindowBackground),
+        WindowEffect::Mica if cfg!(windows) => Some(Effect::Mica),
+        WindowEffect::Acrylic if cfg!(windows) => Some(Effect::Acrylic),
+        _ => None,
+    }
+}
+
+/// 将外观设置应用到窗口（创建时和设置变更时调用）
+pub fn apply_appearance(window: &tauri::WebviewWindow, appearance: &WindowAppearance) {
+    match native_effect(appearance.effect) {
+        Some(effect) => {
+            let effects = EffectsBuilder::new()
+                .effect(effect)
+                .state(EffectState::FollowsWindowActiveState)
+                .build();
+            if let Err(e) = window.set_effects(effects) {
+                log::warn!(
+                    "Failed to apply window effect to '{}': {}",
+                    window.label(),
+                    e
+                );
+            }
+        }
+        None => {
+            let _ = window.set_effects(None::<WindowEffectsConfig>);
+        }
+    }
+
+    let background = appearance.transparent.then_some(Color(0, 0, 0, 0));
+    if let Err(e) = window.set_background_color(background) {
+        log::warn!(
+            "Failed to set background color for '{}': {}",
+            window.label(),
+            e
+        );
+    }
+}
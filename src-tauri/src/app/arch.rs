@@ -0,0 +1,239 @@
+// ============================================
+// Binary Architecture Detection (desktop only)
+// 读取 ELF / PE / Mach-O 头判断可执行文件架构，识别 ARM 主机上的模拟运行与不兼容
+// ============================================
+
+use serde::Serialize;
+use std::{fs::File, io::Read, path::Path};
+
+/// 只需文件头即可判断架构
+const HEADER_LEN: usize = 4096;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BinaryArch {
+    X86,
+    X86_64,
+    Arm,
+    Aarch64,
+    /// macOS universal (fat) binary
+    Universal,
+    /// 脚本或包管理器生成的 shim（如 npm 的 opencode.cmd）
+    Script,
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Compatibility {
+    Native,
+    /// 无法判断（脚本等），按可用处理
+    Unknown,
+    /// 可运行但依赖系统转译（Windows ARM64 x64 模拟、Rosetta 2）
+    Emulated,
+    Incompatible,
+}
+
+fn u16_at(bytes: &[u8], offset: usize, little_endian: bool) -> Option<u16> {
+    let raw: [u8; 2] = bytes.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if little_endian {
+        u16::from_le_bytes(raw)
+    } else {
+        u16::from_be_bytes(raw)
+    })
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    let raw: [u8; 4] = bytes.get(offset..offset + 4)?.try_into().ok()?;
+    Some(u32::from_le_bytes(raw))
+}
+
+fn elf_arch(header: &[u8]) -> BinaryArch {
+    let little_endian = header.get(5) != Some(&2);
+    match u16_at(header, 18, little_endian) {
+        Some(3) => BinaryArch::X86,
+        Some(62) => BinaryArch::X86_64,
+        Some(40) => BinaryArch::Arm,
+        Some(183) => BinaryArch::Aarch64,
+        _ => BinaryArch::Unknown,
+    }
+}
+
+fn pe_arch(header: &[u8]) -> BinaryArch {
+    let Some(pe_offset) = u32_at(header, 0x3c).map(|offset| offset as usize) else {
+        return BinaryArch::Unknown;
+    };
+    if header.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0".as_slice()) {
+        return BinaryArch::Unknown;
+    }
+    match u16_at(header, pe_offset + 4, true) {
+        Some(0x014c) => BinaryArch::X86,
+        Some(0x8664) => BinaryArch::X86_64,
+        Some(0x01c4) => BinaryArch::Arm,
+        // ARM64 / ARM64EC
+        Some(0xaa64) | Some(0xa641) => BinaryArch::Aarch64,
+        _ => BinaryArch::Unknown,
+    }
+}
+
+fn macho_arch(header: &[u8]) -> BinaryArch {
+    match u32_at(header, 4) {
+        Some(0x0100_0007) => BinaryArch::X86_64,
+        Some(0x0100_000c) => BinaryArch::Aarch64,
+        _ => BinaryArch::Unknown,
+    }
+}
+
+/// 根据文件头判断架构
+pub fn arch_from_header(header: &[u8]) -> BinaryArch {
+    match header {
+        [0x7f, b'E', b'L', b'F', ..] => elf_arch(header),
+        [b'M', b'Z', ..] => pe_arch(header),
+        [0xcf, 0xfa, 0xed, 0xfe, ..] => macho_arch(header),
+        [0xca, 0xfe, 0xba, 0xbe, ..] => BinaryArch::Universal,
+        [b'#', b'!', ..] => BinaryArch::Script,
+        _ => BinaryArch::Unknown,
+    }
+}
+
+/// 读取可执行文件的架构；.cmd / .bat 视为脚本
+pub fn binary_arch(path: &Path) -> BinaryArch {
+    let is_batch = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("cmd") || ext.eq_ignore_ascii_case("bat"));
+    if is_batch {
+        return BinaryArch::Script;
+    }
+
+    let Ok(file) = File::open(path) else {
+        return BinaryArch::Unknown;
+    };
+    let mut header = Vec::with_capacity(HEADER_LEN);
+    if file
+        .take(HEADER_LEN as u64)
+        .read_to_end(&mut header)
+        .is_err()
+    {
+        return BinaryArch::Unknown;
+    }
+    arch_from_header(&header)
+}
+
+/// 主机的真实架构。本应用自身可能以 x64 身份在 ARM64 上被模拟运行，
+/// 此时 `std::env::consts::ARCH` 不可信，需要额外向系统确认。
+pub fn host_arch() -> BinaryArch {
+    #[cfg(target_os = "windows")]
+    {
+        let is_arm64 = |key: &str| {
+            std::env::var(key)
+                .map(|value| value.eq_ignore_ascii_case("ARM64"))
+                .unwrap_or(false)
+        };
+        if is_arm64("PROCESSOR_ARCHITECTURE") || is_arm64("PROCESSOR_ARCHITEW6432") {
+            return BinaryArch::Aarch64;
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Rosetta 2 下运行时 sysctl.proc_translated 为 1
+        let translated = std::process::Command::new("sysctl")
+            .args(["-n", "sysctl.proc_translated"])
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim() == "1")
+            .unwrap_or(false);
+        if translated {
+            return BinaryArch::Aarch64;
+        }
+    }
+
+    match std::env::consts::ARCH {
+        "x86" => BinaryArch::X86,
+        "x86_64" => BinaryArch::X86_64,
+        "arm" => BinaryArch::Arm,
+        "aarch64" => BinaryArch::Aarch64,
+        _ => BinaryArch::Unknown,
+    }
+}
+
+/// 判断在 `host` 上运行 `binary` 的方式
+pub fn compatibility(host: BinaryArch, binary: BinaryArch) -> Compatibility {
+    use BinaryArch::*;
+
+    match (host, binary) {
+        (_, Script | Unknown) | (Unknown, _) => Compatibility::Unknown,
+        (_, Universal) => Compatibility::Native,
+        (host, binary) if host == binary => Compatibility::Native,
+        // 64 位系统运行 32 位程序（WOW64 / multilib）
+        (X86_64, X86) | (Aarch64, Arm) => Compatibility::Emulated,
+        // Windows 11 ARM64 与 macOS Rosetta 2 可转译 x64；Linux 默认不行
+        (Aarch64, X86_64 | X86) if cfg!(any(target_os = "windows", target_os = "macos")) => {
+            Compatibility::Emulated
+        }
+        _ => Compatibility::Incompatible,
+    }
+}
+
+/// 面向用户的提示，原生运行时为 None
+pub fn compatibility_warning(
+    host: BinaryArch,
+    binary: BinaryArch,
+    compatibility: Compatibility,
+) -> Option<String> {
+    match compatibility {
+        Compatibility::Native | Compatibility::Unknown => None,
+        Compatibility::Emulated => Some(format!(
+            "opencode is built for {:?} and will run under emulation on this {:?} machine; install the native {:?} build for better performance",
+            binary, host, host
+        )),
+        Compatibility::Incompatible => Some(format!(
+            "opencode is built for {:?} and cannot run on this {:?} machine; install the {:?} build",
+            binary, host, host
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pe_header(machine: u16) -> Vec<u8> {
+        let mut header = vec![0u8; 0x90];
+        header[..2].copy_from_slice(b"MZ");
+        header[0x3c..0x40].copy_from_slice(&0x80u32.to_le_bytes());
+        header[0x80..0x84].copy_from_slice(b"PE\0\0");
+        header[0x84..0x86].copy_from_slice(&machine.to_le_bytes());
+        header
+    }
+
+    #[test]
+    fn detects_arch_from_headers() {
+        assert_eq!(arch_from_header(&pe_header(0x8664)), BinaryArch::X86_64);
+        assert_eq!(arch_from_header(&pe_header(0xaa64)), BinaryArch::Aarch64);
+
+        let mut elf = vec![0u8; 20];
+        elf[..4].copy_from_slice(b"\x7fELF");
+        elf[5] = 1;
+        elf[18..20].copy_from_slice(&183u16.to_le_bytes());
+        assert_eq!(arch_from_header(&elf), BinaryArch::Aarch64);
+
+        let mut macho = vec![0xcf, 0xfa, 0xed, 0xfe];
+        macho.extend_from_slice(&0x0100_0007u32.to_le_bytes());
+        assert_eq!(arch_from_header(&macho), BinaryArch::X86_64);
+
+        assert_eq!(arch_from_header(b"#!/usr/bin/env node"), BinaryArch::Script);
+        assert_eq!(arch_from_header(b"MZ"), BinaryArch::Unknown);
+    }
+
+    #[test]
+    fn classifies_compatibility() {
+        use BinaryArch::*;
+
+        assert_eq!(compatibility(Aarch64, Aarch64), Compatibility::Native);
+        assert_eq!(compatibility(Aarch64, Universal), Compatibility::Native);
+        assert_eq!(compatibility(X86_64, Aarch64), Compatibility::Incompatible);
+        assert_eq!(compatibility(X86_64, X86), Compatibility::Emulated);
+        assert_eq!(compatibility(Aarch64, Script), Compatibility::Unknown);
+    }
+}
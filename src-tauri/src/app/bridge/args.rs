@@ -1,4 +1,23 @@
+use super::ReconnectPolicy;
+use crate::app::http_tuning::HttpTuning;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE},
+    Method,
+};
 use serde::Deserialize;
+use std::{collections::HashMap, time::Duration};
+use tauri::ipc::{Channel, InvokeResponseBody, JavaScriptChannelId};
+
+/// Lower bound for the polling fallback interval.
+const MIN_POLL_INTERVAL_MS: u64 = 500;
+/// Events per batch when `max_batch` is not given.
+const DEFAULT_MAX_BATCH: usize = 256;
+/// Silence after which a stream is considered dead, unless overridden.
+const DEFAULT_READ_TIMEOUT_SECS: u64 = 90;
+/// Lower bound for `read_timeout_secs`.
+const MIN_READ_TIMEOUT_SECS: u64 = 5;
+/// TCP keepalive interval for HTTP streams, unless overridden.
+const DEFAULT_TCP_KEEPALIVE_SECS: u64 = 30;
 
 /// Arguments for `bridge_connect`.
 ///
@@ -9,12 +28,94 @@ use serde::Deserialize;
 /// The Rust layer inspects the URL scheme to pick the transport:
 ///   - `ws://` / `wss://`  → WebSocket (bidirectional)
 ///   - `http://` / `https://` → HTTP streaming (read-only)
+///   - `unix:///path/to.sock:/event?...` → HTTP streaming over a Unix domain
+///     socket; the HTTP path follows the socket path after a `:`. Unix only:
+///     on Windows the connection is rejected.
+///
+/// Windows named pipes (`npipe://`) are not supported; connect to local
+/// servers there over TCP.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConnectArgs {
     bridge_id: String,
     url: String,
     auth_header: Option<String>,
+    /// Server entry id. On desktop the credential is looked up from the
+    /// keychain by this id and replaces `auth_header`; the client certificate
+    /// and proxy/TLS overrides registered for it apply too. A server profile
+    /// id can be passed here (or as `profileId`).
+    #[serde(alias = "profileId")]
+    server_id: Option<String>,
+    /// Extra request headers (e.g. `X-Api-Key`, `Cookie` for a reverse
+    /// proxy). `auth_header` takes precedence over an `Authorization` entry.
+    #[serde(default)]
+    headers: HashMap<String, String>,
+    /// HTTP stream only: request method, `GET` by default. Endpoints that
+    /// take a `POST` with a JSON body can stream their response too.
+    method: Option<String>,
+    /// HTTP stream only: request body, sent as JSON unless `headers` sets
+    /// another `Content-Type`.
+    body: Option<String>,
+    /// HTTP stream only: maximum size of a single SSE event in bytes.
+    max_event_bytes: Option<usize>,
+    /// HTTP stream only: maximum total bytes for the connection.
+    max_connection_bytes: Option<u64>,
+    /// HTTP stream only: when set, fall back to polling at this interval
+    /// (milliseconds) if the stream delivers no events.
+    poll_fallback_ms: Option<u64>,
+    /// HTTP stream only: reconnect with exponential backoff when the stream
+    /// fails, instead of ending the connection.
+    reconnect: Option<ReconnectPolicy>,
+    /// HTTP stream only: resume after this event ID (`Last-Event-ID`).
+    last_event_id: Option<String>,
+    /// HTTP stream only: forward only events whose JSON `type` is listed.
+    /// An entry ending in `.*` matches a prefix (`session.*`). Filtered
+    /// events still reach Rust-side observers; they just never cross IPC.
+    #[serde(default)]
+    include_types: Vec<String>,
+    /// HTTP stream only: drop events whose JSON `type` is listed (same
+    /// matching as `include_types`). Applied after `include_types`.
+    #[serde(default)]
+    exclude_types: Vec<String>,
+    /// HTTP stream only: when set, coalesce events into `MessageBatch`
+    /// messages flushed every `batch_ms` milliseconds.
+    batch_ms: Option<u64>,
+    /// HTTP stream only: flush a batch early once it holds this many events.
+    max_batch: Option<usize>,
+    /// HTTP stream only: parse known opencode events in Rust and send them
+    /// as `Typed` events; events that are not JSON are dropped. Ignored
+    /// while `batch_ms` is set.
+    #[serde(default)]
+    typed_events: bool,
+    /// HTTP stream only: seconds without any data before the stream is
+    /// treated as dead. Raise it for servers with long heartbeat intervals.
+    /// Defaults to 90; values below 5 (including 0) are raised to 5.
+    read_timeout_secs: Option<u64>,
+    /// HTTP stream only: TCP keepalive interval in seconds; 0 disables it.
+    /// Takes precedence over `http.tcpKeepaliveSecs`.
+    tcp_keepalive_secs: Option<u64>,
+    /// HTTP stream only: send a `Heartbeat` after every this many seconds
+    /// without data, so the UI can tell an idle stream from a dead one.
+    /// Off when absent or 0.
+    heartbeat_secs: Option<u64>,
+    /// HTTP stream only: send a `Throughput` event with the receive rate
+    /// every this many seconds. Off when absent or 0.
+    throughput_secs: Option<u64>,
+    /// HTTP stream only: validate events against the bundled server schema.
+    #[serde(default)]
+    validate_events: bool,
+    /// Per-server connection tuning (HTTP version, pool, keepalive, nodelay).
+    #[serde(default)]
+    http: HttpTuning,
+    /// HTTP stream only: a second channel that receives each SSE block as
+    /// raw UTF-8 bytes (an `ArrayBuffer` in JS) through Tauri's binary IPC
+    /// path instead of a `Data` event, so large payloads skip JSON string
+    /// escaping and copies. Other events stay on the main channel, and
+    /// there is no ordering between the two. Ignored with `typedEvents` or
+    /// `batchMs`.
+    binary_channel: Option<JavaScriptChannelId>,
+    #[serde(skip)]
+    binary: Option<Channel<InvokeResponseBody>>,
 }
 
 impl ConnectArgs {
@@ -33,12 +134,204 @@ impl ConnectArgs {
         self.auth_header.as_deref()
     }
 
+    #[inline(always)]
+    pub fn server_id(&self) -> Option<&str> {
+        self.server_id.as_deref()
+    }
+
+    /// Replaces the header sent by the frontend with a resolved credential.
+    pub fn set_auth_header(&mut self, auth_header: Option<String>) {
+        self.auth_header = auth_header;
+    }
+
+    /// Resolves `binary_channel` against the webview that connected.
+    pub fn bind_binary_channel(&mut self, webview: &tauri::Webview) {
+        self.binary = self
+            .binary_channel
+            .as_ref()
+            .map(|id| id.channel_on(webview.clone()));
+    }
+
+    #[inline(always)]
+    pub fn binary_channel(&self) -> Option<&Channel<InvokeResponseBody>> {
+        self.binary.as_ref()
+    }
+
+    /// All request headers: the custom `headers` plus `Authorization`.
+    pub fn header_map(&self) -> Result<HeaderMap, String> {
+        let mut map = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| format!("invalid header name '{}': {}", name, e))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| format!("invalid value for header '{}': {}", name, e))?;
+            map.insert(name, value);
+        }
+        if let Some(auth) = &self.auth_header {
+            let value = HeaderValue::from_str(auth)
+                .map_err(|e| format!("invalid Authorization header: {}", e))?;
+            map.insert(AUTHORIZATION, value);
+        }
+        if self.body.is_some() && !map.contains_key(CONTENT_TYPE) {
+            map.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        }
+        Ok(map)
+    }
+
+    /// HTTP stream request method.
+    pub fn method(&self) -> Result<Method, String> {
+        match self.method.as_deref() {
+            None => Ok(Method::GET),
+            Some(method) => Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .map_err(|_| format!("invalid HTTP method '{}'", method)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn body(&self) -> Option<&str> {
+        self.body.as_deref()
+    }
+
+    #[inline(always)]
+    pub fn max_event_bytes(&self) -> usize {
+        self.max_event_bytes
+            .unwrap_or(super::DEFAULT_MAX_EVENT_BYTES)
+    }
+
+    #[inline(always)]
+    pub fn max_connection_bytes(&self) -> Option<u64> {
+        self.max_connection_bytes
+    }
+
+    #[inline(always)]
+    pub fn poll_fallback_interval(&self) -> Option<Duration> {
+        self.poll_fallback_ms
+            .map(|ms| Duration::from_millis(ms.max(MIN_POLL_INTERVAL_MS)))
+    }
+
+    #[inline(always)]
+    pub fn reconnect(&self) -> Option<&ReconnectPolicy> {
+        self.reconnect.as_ref()
+    }
+
+    /// Batch interval and size when batching is enabled.
+    pub fn batch(&self) -> Option<(Duration, usize)> {
+        let interval = self.batch_ms.filter(|ms| *ms > 0)?;
+        Some((
+            Duration::from_millis(interval),
+            self.max_batch.unwrap_or(DEFAULT_MAX_BATCH).max(1),
+        ))
+    }
+
+    #[inline(always)]
+    pub fn read_timeout(&self) -> Duration {
+        Duration::from_secs(
+            self.read_timeout_secs
+                .unwrap_or(DEFAULT_READ_TIMEOUT_SECS)
+                .max(MIN_READ_TIMEOUT_SECS),
+        )
+    }
+
+    /// Interval of `Heartbeat` events while the stream is silent.
+    pub fn heartbeat(&self) -> Option<Duration> {
+        self.heartbeat_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// Interval of `Throughput` events.
+    pub fn throughput_interval(&self) -> Option<Duration> {
+        self.throughput_secs
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+    }
+
+    /// TCP keepalive for the stream connection, from `tcp_keepalive_secs`
+    /// or else the server's HTTP tuning; `None` disables it.
+    pub fn tcp_keepalive(&self) -> Option<Duration> {
+        match self.tcp_keepalive_secs.or(self.http.tcp_keepalive_secs) {
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+            None => Some(Duration::from_secs(DEFAULT_TCP_KEEPALIVE_SECS)),
+        }
+    }
+
+    #[inline(always)]
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref().filter(|id| !id.is_empty())
+    }
+
+    #[inline(always)]
+    pub fn typed_events(&self) -> bool {
+        self.typed_events
+    }
+
+    #[inline(always)]
+    pub fn validate_events(&self) -> bool {
+        self.validate_events
+    }
+
+    /// Whether an event block should be sent to the frontend according to
+    /// `include_types` / `exclude_types`. Events without a `type` are
+    /// always forwarded.
+    pub fn forwards_event(&self, block: &str) -> bool {
+        if self.include_types.is_empty() && self.exclude_types.is_empty() {
+            return true;
+        }
+        let Some(kind) = super::sse::event_type(block) else {
+            return true;
+        };
+        let listed = |types: &[String]| types.iter().any(|pattern| type_matches(pattern, &kind));
+        (self.include_types.is_empty() || listed(&self.include_types))
+            && !listed(&self.exclude_types)
+    }
+
+    #[inline(always)]
+    pub fn http(&self) -> &HttpTuning {
+        &self.http
+    }
+
+    /// Socket path when the URL uses the `unix://` scheme.
+    pub fn unix_socket(&self) -> Option<&str> {
+        split_unix_url(&self.url).map(|(socket, _)| socket)
+    }
+
+    /// URL to request: `unix://` URLs become `http://localhost/...` (the
+    /// client routes them through the socket), others are used as-is.
+    pub fn request_url(&self) -> String {
+        match split_unix_url(&self.url) {
+            Some((_, path)) => format!("http://localhost{}", path),
+            None => self.url.clone(),
+        }
+    }
+
     /// Returns `true` when the URL uses WebSocket scheme.
     pub fn is_websocket(&self) -> bool {
         self.url.starts_with("ws://") || self.url.starts_with("wss://")
     }
 }
 
+/// Splits `unix:///run/oc.sock:/event?x=1` into the socket path and the
+/// HTTP path (`/` when omitted).
+fn split_unix_url(url: &str) -> Option<(&str, &str)> {
+    let rest = url.strip_prefix("unix://")?;
+    let (socket, path) = match rest.split_once(':') {
+        Some((socket, path)) if path.starts_with('/') => (socket, path),
+        Some(_) => return None,
+        None => (rest, "/"),
+    };
+    (!socket.is_empty()).then_some((socket, path))
+}
+
+fn type_matches(pattern: &str, kind: &str) -> bool {
+    match pattern.strip_suffix(".*") {
+        Some(prefix) => kind
+            .strip_prefix(prefix)
+            .is_some_and(|rest| rest.starts_with('.')),
+        None => pattern == kind,
+    }
+}
+
 /// Arguments for `bridge_send` (WebSocket only).
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -72,3 +365,88 @@ impl DisconnectArgs {
         &self.bridge_id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        split_unix_url, type_matches, ConnectArgs, DEFAULT_READ_TIMEOUT_SECS,
+        DEFAULT_TCP_KEEPALIVE_SECS, MIN_READ_TIMEOUT_SECS,
+    };
+    use std::time::Duration;
+
+    fn connect_args(extra: serde_json::Value) -> ConnectArgs {
+        let mut args = serde_json::json!({ "bridgeId": "b", "url": "http://localhost:4096/event" });
+        args.as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        serde_json::from_value(args).unwrap()
+    }
+
+    #[test]
+    fn stream_timeouts_fall_back_to_tuning_and_defaults() {
+        let secs = Duration::from_secs;
+        let args = connect_args;
+
+        // Explicit value, then `http.tcpKeepaliveSecs`, then the default; 0 disables
+        assert_eq!(
+            args(serde_json::json!({ "tcpKeepaliveSecs": 10, "http": { "tcpKeepaliveSecs": 20 } }))
+                .tcp_keepalive(),
+            Some(secs(10))
+        );
+        assert_eq!(
+            args(serde_json::json!({ "http": { "tcpKeepaliveSecs": 20 } })).tcp_keepalive(),
+            Some(secs(20))
+        );
+        assert_eq!(
+            args(serde_json::json!({})).tcp_keepalive(),
+            Some(secs(DEFAULT_TCP_KEEPALIVE_SECS))
+        );
+        assert_eq!(
+            args(serde_json::json!({ "tcpKeepaliveSecs": 0, "http": { "tcpKeepaliveSecs": 20 } }))
+                .tcp_keepalive(),
+            None
+        );
+        assert_eq!(
+            args(serde_json::json!({ "http": { "tcpKeepaliveSecs": 0 } })).tcp_keepalive(),
+            None
+        );
+
+        // Explicit value, then the default; never below the lower bound
+        assert_eq!(
+            args(serde_json::json!({ "readTimeoutSecs": 300 })).read_timeout(),
+            secs(300)
+        );
+        assert_eq!(
+            args(serde_json::json!({})).read_timeout(),
+            secs(DEFAULT_READ_TIMEOUT_SECS)
+        );
+        assert_eq!(
+            args(serde_json::json!({ "readTimeoutSecs": 0 })).read_timeout(),
+            secs(MIN_READ_TIMEOUT_SECS)
+        );
+    }
+
+    #[test]
+    fn type_patterns_match_exactly_or_by_prefix() {
+        assert!(type_matches("storage.write", "storage.write"));
+        assert!(!type_matches("storage", "storage.write"));
+        assert!(type_matches("session.*", "session.status"));
+        assert!(type_matches("message.*", "message.part.updated"));
+        assert!(!type_matches("session.*", "session"));
+        assert!(!type_matches("session.*", "sessions.list"));
+    }
+
+    #[test]
+    fn splits_unix_socket_urls() {
+        assert_eq!(
+            split_unix_url("unix:///run/oc.sock:/event?directory=/p"),
+            Some(("/run/oc.sock", "/event?directory=/p"))
+        );
+        assert_eq!(
+            split_unix_url("unix:///tmp/oc.sock"),
+            Some(("/tmp/oc.sock", "/"))
+        );
+        assert_eq!(split_unix_url("unix://"), None);
+        assert_eq!(split_unix_url("http://localhost:4096/event"), None);
+    }
+}
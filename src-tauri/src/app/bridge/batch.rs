@@ -0,0 +1,77 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Coalesces framed SSE blocks so a fast stream crosses IPC as one
+/// `MessageBatch` every `interval` instead of one message per event.
+///
+/// A batch is flushed when it reaches `max_batch` events or `interval`
+/// after its first event, whichever comes first.
+pub struct Batcher {
+    interval: Duration,
+    max_batch: usize,
+    pending: Vec<String>,
+    /// When the current batch is due; `None` while empty.
+    deadline: Option<Instant>,
+}
+
+impl Batcher {
+    pub fn new(interval: Duration, max_batch: usize) -> Self {
+        Self {
+            interval,
+            max_batch: max_batch.max(1),
+            pending: Vec::new(),
+            deadline: None,
+        }
+    }
+
+    /// Adds an event; returns the batch once it is full.
+    pub fn push(&mut self, data: String) -> Option<Vec<String>> {
+        if self.pending.is_empty() {
+            self.deadline = Some(Instant::now() + self.interval);
+        }
+        self.pending.push(data);
+        (self.pending.len() >= self.max_batch).then(|| self.take())
+    }
+
+    /// Removes and returns everything batched so far.
+    pub fn take(&mut self) -> Vec<String> {
+        self.deadline = None;
+        std::mem::take(&mut self.pending)
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+/// Resolves when the batch is due; never resolves while it is empty.
+pub async fn due(batch: Option<&Batcher>) {
+    match batch.and_then(Batcher::deadline) {
+        Some(deadline) => tokio::time::sleep_until(deadline).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Batcher;
+    use std::time::Duration;
+
+    #[test]
+    fn flushes_when_full_and_resets_deadline() {
+        let mut batch = Batcher::new(Duration::from_millis(50), 3);
+        assert!(batch.deadline().is_none());
+        assert_eq!(batch.push("a".to_string()), None);
+        assert!(batch.deadline().is_some());
+        assert_eq!(batch.push("b".to_string()), None);
+        assert_eq!(
+            batch.push("c".to_string()),
+            Some(vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        );
+        assert!(batch.deadline().is_none());
+
+        batch.push("d".to_string());
+        assert_eq!(batch.take(), vec!["d".to_string()]);
+        assert!(batch.take().is_empty());
+    }
+}
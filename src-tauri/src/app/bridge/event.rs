@@ -1,15 +1,128 @@
 use serde::Serialize;
 
+use super::{sse, SchemaMismatch, ServerEvent};
+
 /// Unified bridge event pushed to the frontend via Tauri Channel.
 ///
 /// The Rust layer is a transparent proxy — `data` is forwarded as-is
 /// without parsing or field renaming. The frontend decides how to
-/// interpret it (SSE line parsing, terminal output, etc.).
+/// interpret it (SSE line parsing, terminal output, etc.), unless it asks
+/// for `Typed` events on an HTTP stream.
 #[derive(Clone, Serialize)]
 #[serde(rename_all = "camelCase", tag = "event", content = "data")]
 pub enum BridgeEvent {
     Connected,
-    Data { data: String },
-    Disconnected { code: Option<u16>, reason: String },
-    Error { message: String },
+    #[serde(rename_all = "camelCase")]
+    Data {
+        data: String,
+        /// SSE `event:` field of the block, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        event_name: Option<String>,
+        /// SSE `id:` field of the block, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+    },
+    /// A server event parsed in Rust (`typedEvents`). Event types without
+    /// a typed variant still arrive as `Data`.
+    Typed {
+        event: ServerEvent,
+        /// Project directory of a wrapped global event.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        directory: Option<String>,
+        /// SSE `id:` field of the block, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<String>,
+    },
+    /// Several framed SSE blocks coalesced into one message (`batchMs`).
+    /// Each entry is what a `Data` event would carry in `data`.
+    MessageBatch {
+        raw: Vec<String>,
+    },
+    Disconnected {
+        code: Option<u16>,
+        reason: String,
+    },
+    Error {
+        message: String,
+    },
+    /// The HTTP stream failed and will be retried after `delay_ms`. A
+    /// `Connected` event follows once the stream is back.
+    #[serde(rename_all = "camelCase")]
+    Reconnecting {
+        attempt: u32,
+        delay_ms: u64,
+        reason: String,
+    },
+    /// The server sent an SSE `retry:` field. With a reconnect policy the
+    /// bridge waits `ms` before reconnecting instead of its own backoff.
+    RetryHint {
+        ms: u64,
+    },
+    /// The HTTP stream is connected but has been silent for `idle_secs`
+    /// (`heartbeatSecs`). Synthesized by the bridge, not sent by the server.
+    #[serde(rename_all = "camelCase")]
+    Heartbeat {
+        idle_secs: u64,
+    },
+    /// Receive rate of the HTTP stream over the last `throughputSecs`, and
+    /// the bytes received since `bridge_connect` (across reconnects).
+    #[serde(rename_all = "camelCase")]
+    Throughput {
+        bytes_per_sec: u64,
+        total_bytes: u64,
+    },
+    /// The buffer of a paused stream (`sse_pause`) filled up; from now on
+    /// the oldest held events are dropped, so the frontend should refetch
+    /// after resuming. Sent once per pause.
+    PauseOverflow {
+        capacity: usize,
+    },
+    /// An event or the whole connection exceeded its configured size limit.
+    /// `scope` is `"event"` (the event was dropped) or `"connection"`
+    /// (the stream was closed).
+    PayloadTooLarge {
+        scope: &'static str,
+        limit: u64,
+        size: u64,
+    },
+    /// An event did not match the bundled server schema. The event itself
+    /// is still forwarded as `Data`; each distinct mismatch is reported once
+    /// per connection.
+    #[serde(rename_all = "camelCase")]
+    SchemaMismatch {
+        #[serde(flatten)]
+        mismatch: SchemaMismatch,
+        /// Server version the bundled schema was extracted from.
+        schema_version: &'static str,
+    },
+}
+
+impl BridgeEvent {
+    /// Raw payload (WebSocket frames, terminal output).
+    pub fn data(data: String) -> Self {
+        Self::Data {
+            data,
+            event_name: None,
+            id: None,
+        }
+    }
+
+    /// A framed SSE block, with its `event:` and `id:` fields lifted out so
+    /// the frontend can filter without parsing.
+    pub fn sse(data: String) -> Self {
+        Self::Data {
+            event_name: sse::event_name(&data),
+            id: sse::event_id(&data).filter(|id| !id.is_empty()),
+            data,
+        }
+    }
+
+    /// A parsed server event, keeping the block's `id:` field.
+    pub fn typed(block: &str, event: ServerEvent, directory: Option<String>) -> Self {
+        Self::Typed {
+            event,
+            directory,
+            id: sse::event_id(block).filter(|id| !id.is_empty()),
+        }
+    }
 }
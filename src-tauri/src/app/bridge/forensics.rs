@@ -0,0 +1,127 @@
+use serde::Serialize;
+use std::{collections::VecDeque, sync::Mutex};
+
+use super::BridgeKey;
+
+/// Maximum entries kept; the oldest are dropped first.
+const MAX_ENTRIES: usize = 500;
+
+/// Why an HTTP stream connection ended (or failed to start).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DisconnectKind {
+    ConnectFailed,
+    HttpStatus,
+    StreamError,
+    StreamEnded,
+    ReadTimeout,
+    PayloadTooLarge,
+    ClientClosed,
+    /// The stream was abandoned in favour of the polling fallback.
+    PollingFallback,
+    PollingFailed,
+}
+
+/// What happened on the next `bridge_connect` for the same key.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconnectOutcome {
+    pub succeeded: bool,
+    /// Time between the disconnect and the reconnect attempt.
+    pub after_ms: u64,
+    pub message: Option<String>,
+}
+
+/// One recorded disconnect.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForensicEntry {
+    /// Unix time in milliseconds.
+    pub at: u64,
+    pub webview: String,
+    pub bridge_id: String,
+    /// Stream URL without its query string.
+    pub url: String,
+    pub kind: DisconnectKind,
+    pub http_status: Option<u16>,
+    pub message: String,
+    pub bytes_since_connect: u64,
+    /// How long the stream had been connected, if it connected at all.
+    pub connected_ms: Option<u64>,
+    pub reconnect: Option<ReconnectOutcome>,
+}
+
+impl ForensicEntry {
+    pub fn new(
+        key: &BridgeKey,
+        url: &str,
+        kind: DisconnectKind,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            at: crate::app::now_millis(),
+            webview: key.window_label().to_string(),
+            bridge_id: key.bridge_id().to_string(),
+            url: url.split('?').next().unwrap_or_default().to_string(),
+            kind,
+            http_status: None,
+            message: message.into(),
+            bytes_since_connect: 0,
+            connected_ms: None,
+            reconnect: None,
+        }
+    }
+}
+
+/// Bounded log of stream disconnects, used to diagnose "random" drops.
+#[derive(Default)]
+pub struct ForensicLog {
+    entries: Mutex<VecDeque<ForensicEntry>>,
+}
+
+impl ForensicLog {
+    pub fn record(&self, entry: ForensicEntry) {
+        log::info!(
+            "Bridge '{}' disconnect: {:?} {}",
+            entry.bridge_id,
+            entry.kind,
+            entry.message
+        );
+        let mut entries = self.entries.lock().expect("forensic log poisoned");
+        if entries.len() >= MAX_ENTRIES {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+
+    /// Attach the outcome of a reconnect attempt to the latest unresolved
+    /// disconnect of the same bridge. Client-initiated closes are skipped.
+    pub fn record_reconnect(&self, key: &BridgeKey, succeeded: bool, message: Option<String>) {
+        let mut entries = self.entries.lock().expect("forensic log poisoned");
+        let pending = entries.iter_mut().rev().find(|entry| {
+            entry.webview == key.window_label()
+                && entry.bridge_id == key.bridge_id()
+                && entry.kind != DisconnectKind::ClientClosed
+        });
+        if let Some(entry) = pending.filter(|entry| entry.reconnect.is_none()) {
+            entry.reconnect = Some(ReconnectOutcome {
+                succeeded,
+                after_ms: crate::app::now_millis().saturating_sub(entry.at),
+                message,
+            });
+        }
+    }
+
+    pub fn snapshot(&self) -> Vec<ForensicEntry> {
+        self.entries
+            .lock()
+            .expect("forensic log poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().expect("forensic log poisoned").clear();
+    }
+}
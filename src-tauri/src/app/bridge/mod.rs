@@ -1,7 +1,27 @@
 mod args;
+mod batch;
 mod event;
+mod forensics;
+mod pause;
+mod poll;
+mod reconnect;
+mod recorder;
+mod schema;
+mod sse;
 mod state;
+mod stats;
+mod typed;
 
 pub use args::{ConnectArgs, DisconnectArgs, SendArgs};
+pub use batch::{due as batch_due, Batcher};
 pub use event::BridgeEvent;
-pub use state::{BridgeCommand, BridgeConnection, BridgeKey, BridgeState};
+pub use forensics::{DisconnectKind, ForensicEntry, ForensicLog};
+pub use pause::{PauseTable, DEFAULT_PAUSE_CAPACITY};
+pub use poll::{stream_endpoint, Poller};
+pub use reconnect::ReconnectPolicy;
+pub use recorder::{RecordingStatus, SseRecorder};
+pub use schema::{EventValidator, SchemaMismatch};
+pub use sse::{event_data, Frame, SseFramer, DEFAULT_MAX_EVENT_BYTES};
+pub use state::{BridgeCommand, BridgeConnection, BridgeKey, BridgeState, EventTap};
+pub use stats::{StatsTable, StreamStats};
+pub use typed::{parse as parse_typed, Parsed, ServerEvent};
@@ -0,0 +1,106 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::Mutex,
+};
+
+use tauri::ipc::Channel;
+
+use super::{BridgeEvent, BridgeKey};
+
+/// Events buffered per paused bridge when `sse_pause` gives no limit.
+pub const DEFAULT_PAUSE_CAPACITY: usize = 5_000;
+
+struct Paused {
+    events: VecDeque<String>,
+    capacity: usize,
+    /// Oldest events dropped because the buffer was full.
+    dropped: u64,
+    /// Channel of the connection, captured from the first held event so
+    /// the backlog can be delivered on resume.
+    channel: Option<Channel<BridgeEvent>>,
+}
+
+/// Streams whose delivery the frontend paused (e.g. while the user scrolls
+/// through history). Events are held in Rust and delivered on resume.
+///
+/// Holding and resuming share one lock, so the backlog always reaches the
+/// frontend before events that arrive after the resume.
+#[derive(Default)]
+pub struct PauseTable {
+    entries: Mutex<HashMap<BridgeKey, Paused>>,
+}
+
+impl PauseTable {
+    /// Start holding events for `key`. Pausing again only updates the
+    /// capacity; the buffer is kept.
+    pub fn pause(&self, key: &BridgeKey, capacity: usize) {
+        let mut entries = self.entries.lock().expect("pause table poisoned");
+        let paused = entries.entry(key.clone()).or_insert_with(|| Paused {
+            events: VecDeque::new(),
+            capacity,
+            dropped: 0,
+            channel: None,
+        });
+        paused.capacity = capacity.max(1);
+    }
+
+    /// Hold `data` if the bridge is paused. Returns the data back when it
+    /// should be delivered normally. The first time the buffer overflows a
+    /// `PauseOverflow` event is sent and the oldest events start being
+    /// dropped.
+    pub fn hold(
+        &self,
+        key: &BridgeKey,
+        data: String,
+        channel: &Channel<BridgeEvent>,
+    ) -> Option<String> {
+        let mut entries = self.entries.lock().expect("pause table poisoned");
+        let Some(paused) = entries.get_mut(key) else {
+            return Some(data);
+        };
+        if paused.channel.is_none() {
+            paused.channel = Some(channel.clone());
+        }
+        if paused.events.len() >= paused.capacity {
+            paused.events.pop_front();
+            if paused.dropped == 0 {
+                let _ = channel.send(BridgeEvent::PauseOverflow {
+                    capacity: paused.capacity,
+                });
+            }
+            paused.dropped += 1;
+        }
+        paused.events.push_back(data);
+        None
+    }
+
+    /// Stop holding events for `key` and deliver the backlog as one
+    /// `MessageBatch`. Returns `(delivered, dropped)`, or `None` when the
+    /// bridge was not paused.
+    pub fn resume(&self, key: &BridgeKey) -> Option<(usize, u64)> {
+        let mut entries = self.entries.lock().expect("pause table poisoned");
+        let paused = entries.remove(key)?;
+        let delivered = paused.events.len();
+        if let Some(channel) = paused.channel.filter(|_| delivered > 0) {
+            let _ = channel.send(BridgeEvent::MessageBatch {
+                raw: paused.events.into(),
+            });
+        }
+        Some((delivered, paused.dropped))
+    }
+
+    /// Drop the pause state of a closed or replaced connection.
+    pub fn clear(&self, key: &BridgeKey) {
+        self.entries
+            .lock()
+            .expect("pause table poisoned")
+            .remove(key);
+    }
+
+    pub fn clear_window(&self, window_label: &str) {
+        self.entries
+            .lock()
+            .expect("pause table poisoned")
+            .retain(|key, _| key.window_label() != window_label);
+    }
+}
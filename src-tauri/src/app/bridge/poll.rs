@@ -0,0 +1,271 @@
+use reqwest::header::HeaderMap;
+use serde_json::{json, Value};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// Messages fetched per session on each poll.
+const MESSAGE_LIMIT: &str = "20";
+/// Upper bound on sessions whose messages are refreshed per poll.
+const MAX_DIRTY_SESSIONS: usize = 10;
+
+/// Long-polling fallback for when an intermediary buffers or kills SSE.
+///
+/// Periodically reads the server's list endpoints and synthesizes the
+/// events the stream would have carried (`session.updated`,
+/// `session.status`, `message.updated`, `message.part.updated`, ...),
+/// formatted as SSE blocks so the frontend parses them unchanged.
+pub struct Poller {
+    client: reqwest::Client,
+    /// Server root, derived from the stream URL.
+    base: reqwest::Url,
+    /// `directory` query parameter of the stream URL, if any.
+    directory: Option<String>,
+    /// `/global/event` streams wrap each event as `{ directory, payload }`.
+    global: bool,
+    headers: HeaderMap,
+    primed: bool,
+    sessions: HashMap<String, (u64, String)>,
+    statuses: HashMap<String, u64>,
+    messages: HashMap<String, u64>,
+    parts: HashMap<String, u64>,
+}
+
+fn fingerprint(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Returns `true` and records the new fingerprint when `value` changed.
+fn changed(seen: &mut HashMap<String, u64>, id: &str, value: &Value) -> bool {
+    let hash = fingerprint(value);
+    seen.insert(id.to_string(), hash) != Some(hash)
+}
+
+/// Splits an opencode event stream URL into the server root (with a
+/// trailing slash), whether it is the `/global/event` stream, and its
+/// `directory` query parameter.
+pub fn stream_endpoint(url: &str) -> Option<(reqwest::Url, bool, Option<String>)> {
+    let mut base = reqwest::Url::parse(url).ok()?;
+    let path = base.path().trim_end_matches('/').to_string();
+    let (root, global) = if let Some(root) = path.strip_suffix("/global/event") {
+        (root.to_string(), true)
+    } else {
+        (path.strip_suffix("/event")?.to_string(), false)
+    };
+    let directory = base
+        .query_pairs()
+        .find(|(key, _)| key == "directory")
+        .map(|(_, value)| value.into_owned());
+
+    base.set_path(&format!("{}/", root));
+    base.set_query(None);
+    Some((base, global, directory))
+}
+
+impl Poller {
+    /// Builds a poller for an opencode event stream URL (`.../event` or
+    /// `.../global/event`). Returns `None` for any other URL.
+    pub fn from_stream_url(client: reqwest::Client, url: &str, headers: HeaderMap) -> Option<Self> {
+        let (base, global, directory) = stream_endpoint(url)?;
+        Some(Self {
+            client,
+            base,
+            directory,
+            global,
+            headers,
+            primed: false,
+            sessions: HashMap::new(),
+            statuses: HashMap::new(),
+            messages: HashMap::new(),
+            parts: HashMap::new(),
+        })
+    }
+
+    /// Server root URL, used for health probes.
+    pub fn base_url(&self) -> &str {
+        self.base.as_str()
+    }
+
+    async fn get(&self, path: &str, query: &[(&str, &str)]) -> Result<Value, String> {
+        let mut url = self.base.join(path).map_err(|e| e.to_string())?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            if let Some(directory) = &self.directory {
+                pairs.append_pair("directory", directory);
+            }
+            pairs.extend_pairs(query);
+        }
+
+        let response = self
+            .client
+            .get(url)
+            .headers(self.headers.clone())
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("{} returned {}", path, response.status()));
+        }
+        let bytes = response.bytes().await.map_err(|e| e.to_string())?;
+        serde_json::from_slice(&bytes).map_err(|e| e.to_string())
+    }
+
+    fn frame(&self, directory: &str, event_type: &str, properties: Value) -> String {
+        let event = json!({ "type": event_type, "properties": properties });
+        let payload = if self.global {
+            json!({ "directory": directory, "payload": event })
+        } else {
+            event
+        };
+        format!("data: {}\n\n", payload)
+    }
+
+    fn session_directory(&self, session_id: &str) -> String {
+        self.sessions
+            .get(session_id)
+            .map(|(_, directory)| directory.clone())
+            .or_else(|| self.directory.clone())
+            .unwrap_or_default()
+    }
+
+    /// Polls once and returns synthesized SSE blocks. The first call only
+    /// records a baseline snapshot.
+    pub async fn poll(&mut self) -> Result<Vec<String>, String> {
+        let primed = self.primed;
+        let mut events = Vec::new();
+        let mut dirty = HashSet::new();
+
+        let sessions = self.get("session", &[]).await?;
+        for session in sessions.as_array().into_iter().flatten() {
+            let Some(id) = session["id"].as_str() else {
+                continue;
+            };
+            let updated = session["time"]["updated"].as_f64().unwrap_or(0.0) as u64;
+            let directory = session["directory"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string();
+            let previous = self
+                .sessions
+                .insert(id.to_string(), (updated, directory.clone()));
+            if previous.as_ref().map(|(time, _)| *time) == Some(updated) {
+                continue;
+            }
+            if primed {
+                let event_type = if previous.is_some() {
+                    "session.updated"
+                } else {
+                    "session.created"
+                };
+                events.push(self.frame(&directory, event_type, json!({ "info": session })));
+                dirty.insert(id.to_string());
+            }
+        }
+
+        let statuses = self.get("session/status", &[]).await?;
+        let statuses = statuses.as_object().cloned().unwrap_or_default();
+        for (id, status) in &statuses {
+            if changed(&mut self.statuses, id, status) && primed {
+                let directory = self.session_directory(id);
+                events.push(self.frame(
+                    &directory,
+                    "session.status",
+                    json!({ "sessionID": id, "status": status }),
+                ));
+            }
+            // Busy sessions are refreshed every poll to stream new parts
+            dirty.insert(id.clone());
+        }
+        // Sessions missing from the status map have gone idle
+        let idle: Vec<String> = self
+            .statuses
+            .keys()
+            .filter(|id| !statuses.contains_key(*id))
+            .cloned()
+            .collect();
+        for id in idle {
+            self.statuses.remove(&id);
+            let directory = self.session_directory(&id);
+            events.push(self.frame(
+                &directory,
+                "session.status",
+                json!({ "sessionID": id, "status": { "type": "idle" } }),
+            ));
+            events.push(self.frame(&directory, "session.idle", json!({ "sessionID": id })));
+            dirty.insert(id);
+        }
+
+        for id in dirty.into_iter().take(MAX_DIRTY_SESSIONS) {
+            let messages = self
+                .get(
+                    &format!("session/{}/message", id),
+                    &[("limit", MESSAGE_LIMIT)],
+                )
+                .await?;
+            let directory = self.session_directory(&id);
+            for message in messages.as_array().into_iter().flatten() {
+                let info = &message["info"];
+                if let Some(message_id) = info["id"].as_str() {
+                    if changed(&mut self.messages, message_id, info) && primed {
+                        events.push(self.frame(
+                            &directory,
+                            "message.updated",
+                            json!({ "info": info }),
+                        ));
+                    }
+                }
+                for part in message["parts"].as_array().into_iter().flatten() {
+                    let Some(part_id) = part["id"].as_str() else {
+                        continue;
+                    };
+                    if changed(&mut self.parts, part_id, part) && primed {
+                        events.push(self.frame(
+                            &directory,
+                            "message.part.updated",
+                            json!({ "part": part }),
+                        ));
+                    }
+                }
+            }
+        }
+
+        self.primed = true;
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_endpoints_from_stream_url() {
+        let client = reqwest::Client::new();
+        let poller = Poller::from_stream_url(
+            client.clone(),
+            "http://127.0.0.1:4096/global/event",
+            HeaderMap::new(),
+        )
+        .unwrap();
+        assert!(poller.global);
+        assert_eq!(poller.base_url(), "http://127.0.0.1:4096/");
+
+        let poller = Poller::from_stream_url(
+            client.clone(),
+            "https://example.com/opencode/event?directory=%2Ftmp%2Fproject",
+            HeaderMap::new(),
+        )
+        .unwrap();
+        assert!(!poller.global);
+        assert_eq!(poller.base_url(), "https://example.com/opencode/");
+        assert_eq!(poller.directory.as_deref(), Some("/tmp/project"));
+
+        assert!(
+            Poller::from_stream_url(client, "http://127.0.0.1:4096/pty/1", HeaderMap::new())
+                .is_none()
+        );
+    }
+}
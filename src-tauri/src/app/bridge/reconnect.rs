@@ -0,0 +1,100 @@
+use serde::Deserialize;
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::Duration,
+};
+
+/// Automatic reconnect settings for HTTP streams.
+///
+/// The delay before attempt `n` (1-based) is
+/// `min(initial_delay * 2^(n-1), max_delay)`, then jittered down by up to
+/// half so that many windows reconnecting to the same server spread out.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReconnectPolicy {
+    /// Attempts after a failure before giving up. Reset once a reconnected
+    /// stream delivers events again.
+    pub max_retries: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 10,
+            initial_delay_ms: 500,
+            max_delay_ms: 30_000,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Delay before the given attempt, or `None` when retries are exhausted.
+    pub fn delay(&self, attempt: u32) -> Option<Duration> {
+        if attempt == 0 || attempt > self.max_retries {
+            return None;
+        }
+        let base = self.backoff(attempt);
+        Some(Duration::from_millis(base - jitter(base / 2)))
+    }
+
+    /// Like [`delay`](Self::delay), but a server-suggested delay (SSE
+    /// `retry:`) replaces the computed backoff. Retries are still limited by
+    /// `max_retries`.
+    pub fn delay_with_hint(&self, attempt: u32, server_ms: Option<u64>) -> Option<Duration> {
+        let delay = self.delay(attempt)?;
+        Some(server_ms.map_or(delay, Duration::from_millis))
+    }
+
+    fn backoff(&self, attempt: u32) -> u64 {
+        let factor = 1u64 << (attempt - 1).min(20);
+        self.initial_delay_ms
+            .max(1)
+            .saturating_mul(factor)
+            .min(self.max_delay_ms.max(1))
+    }
+}
+
+/// Random value in `0..=max`. `RandomState` is randomly seeded per
+/// instance, which is plenty for spreading reconnects.
+fn jitter(max: u64) -> u64 {
+    if max == 0 {
+        return 0;
+    }
+    let random = RandomState::new().build_hasher().finish();
+    random % (max + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_caps_and_stops() {
+        let policy = ReconnectPolicy {
+            max_retries: 8,
+            initial_delay_ms: 500,
+            max_delay_ms: 4_000,
+        };
+        assert_eq!(policy.backoff(1), 500);
+        assert_eq!(policy.backoff(2), 1_000);
+        assert_eq!(policy.backoff(4), 4_000);
+        assert_eq!(policy.backoff(8), 4_000);
+
+        for attempt in 1..=8 {
+            let delay = policy.delay(attempt).unwrap().as_millis() as u64;
+            let base = policy.backoff(attempt);
+            assert!(delay <= base && delay >= base / 2);
+        }
+        assert_eq!(policy.delay(0), None);
+        assert_eq!(policy.delay(9), None);
+
+        assert_eq!(
+            policy.delay_with_hint(3, Some(7_000)),
+            Some(Duration::from_millis(7_000))
+        );
+        assert_eq!(policy.delay_with_hint(9, Some(7_000)), None);
+    }
+}
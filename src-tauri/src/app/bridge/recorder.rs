@@ -0,0 +1,212 @@
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use super::BridgeKey;
+
+/// A part is rotated once it grows past this size.
+const MAX_PART_BYTES: u64 = 8 * 1024 * 1024;
+/// Parts kept per recording; the oldest are deleted first.
+const MAX_PARTS: u32 = 8;
+
+/// One recorded SSE frame (a line of the NDJSON file).
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedFrame<'a> {
+    /// Unix time in milliseconds.
+    at: u64,
+    webview: &'a str,
+    bridge_id: &'a str,
+    conn_id: u64,
+    /// `true` when the frame came from the polling fallback.
+    polled: bool,
+    data: &'a str,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatus {
+    pub name: String,
+    pub active: bool,
+    pub started_at: u64,
+    pub frames: u64,
+    pub bytes: u64,
+    /// Part files that still exist, oldest first.
+    pub parts: Vec<String>,
+}
+
+struct Recording {
+    dir: PathBuf,
+    name: String,
+    started_at: u64,
+    part: u32,
+    file: Option<File>,
+    part_bytes: u64,
+    frames: u64,
+    bytes: u64,
+}
+
+impl Recording {
+    fn part_path(&self, part: u32) -> PathBuf {
+        self.dir.join(format!("{}.{:03}.ndjson", self.name, part))
+    }
+
+    fn open_part(&mut self) -> Result<(), String> {
+        let path = self.part_path(self.part);
+        let file = File::options()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| format!("failed to open '{}': {}", path.display(), e))?;
+        self.file = Some(file);
+        self.part_bytes = 0;
+        if let Some(expired) = self.part.checked_sub(MAX_PARTS) {
+            let _ = std::fs::remove_file(self.part_path(expired));
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, line: &[u8]) -> Result<(), String> {
+        if self.part_bytes >= MAX_PART_BYTES {
+            self.part += 1;
+            self.open_part()?;
+        }
+        let file = self.file.as_mut().ok_or("recording file is closed")?;
+        file.write_all(line).map_err(|e| e.to_string())?;
+        self.part_bytes += line.len() as u64;
+        self.bytes += line.len() as u64;
+        self.frames += 1;
+        Ok(())
+    }
+
+    fn parts(&self) -> Vec<PathBuf> {
+        (0..=self.part)
+            .map(|part| self.part_path(part))
+            .filter(|path| path.exists())
+            .collect()
+    }
+
+    fn status(&self) -> RecordingStatus {
+        RecordingStatus {
+            name: self.name.clone(),
+            active: self.file.is_some(),
+            started_at: self.started_at,
+            frames: self.frames,
+            bytes: self.bytes,
+            parts: self
+                .parts()
+                .iter()
+                .map(|path| path.to_string_lossy().into_owned())
+                .collect(),
+        }
+    }
+}
+
+/// Opt-in debug recorder appending every received SSE frame to rotating
+/// NDJSON files, for diagnosing events the UI lost or misapplied.
+#[derive(Default)]
+pub struct SseRecorder {
+    /// Checked on every frame so an idle recorder costs no lock.
+    active: AtomicBool,
+    /// The running recording, or the last stopped one (kept for export).
+    recording: Mutex<Option<Recording>>,
+}
+
+impl SseRecorder {
+    /// Start a new recording in `dir`, ending any running one.
+    pub fn start(&self, dir: &Path) -> Result<RecordingStatus, String> {
+        std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let started_at = crate::app::now_millis();
+        let mut recording = Recording {
+            dir: dir.to_path_buf(),
+            name: format!("sse-{}", started_at),
+            started_at,
+            part: 0,
+            file: None,
+            part_bytes: 0,
+            frames: 0,
+            bytes: 0,
+        };
+        recording.open_part()?;
+        let status = recording.status();
+        *self.recording.lock().expect("sse recorder poisoned") = Some(recording);
+        self.active.store(true, Ordering::SeqCst);
+        log::info!("SSE recording started: {}", dir.display());
+        Ok(status)
+    }
+
+    /// Stop recording; the files stay available for export.
+    pub fn stop(&self) -> Option<RecordingStatus> {
+        self.active.store(false, Ordering::SeqCst);
+        let mut guard = self.recording.lock().expect("sse recorder poisoned");
+        let recording = guard.as_mut()?;
+        recording.file = None;
+        Some(recording.status())
+    }
+
+    pub fn status(&self) -> Option<RecordingStatus> {
+        self.recording
+            .lock()
+            .expect("sse recorder poisoned")
+            .as_ref()
+            .map(Recording::status)
+    }
+
+    /// Append a frame if a recording is running. Write failures stop the
+    /// recording rather than failing the stream.
+    pub fn record(&self, key: &BridgeKey, conn_id: u64, polled: bool, data: &str) {
+        if !self.active.load(Ordering::Relaxed) {
+            return;
+        }
+        let frame = RecordedFrame {
+            at: crate::app::now_millis(),
+            webview: key.window_label(),
+            bridge_id: key.bridge_id(),
+            conn_id,
+            polled,
+            data,
+        };
+        let Ok(mut line) = serde_json::to_vec(&frame) else {
+            return;
+        };
+        line.push(b'\n');
+
+        let mut guard = self.recording.lock().expect("sse recorder poisoned");
+        let Some(recording) = guard.as_mut().filter(|r| r.file.is_some()) else {
+            return;
+        };
+        if let Err(e) = recording.write(&line) {
+            log::warn!("SSE recording stopped: {}", e);
+            recording.file = None;
+            self.active.store(false, Ordering::SeqCst);
+        }
+    }
+
+    /// Concatenate the parts of the current (or last) recording into one
+    /// NDJSON file at `path`; returns the number of bytes written.
+    pub fn export(&self, path: &Path) -> Result<u64, String> {
+        let parts = self
+            .recording
+            .lock()
+            .expect("sse recorder poisoned")
+            .as_ref()
+            .map(Recording::parts)
+            .ok_or("no SSE recording to export")?;
+        let mut out = File::create(path)
+            .map_err(|e| format!("failed to write '{}': {}", path.display(), e))?;
+        let mut written = 0;
+        for part in parts {
+            // A part being appended to concurrently is read up to its current end
+            let mut input = File::open(&part).map_err(|e| e.to_string())?;
+            written += std::io::copy(&mut input, &mut out).map_err(|e| e.to_string())?;
+        }
+        Ok(written)
+    }
+}
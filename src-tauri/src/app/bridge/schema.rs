@@ -0,0 +1,306 @@
+use super::event_data;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::OnceLock,
+};
+
+/// Event schemas extracted from the supported server's OpenAPI document
+/// (see `scripts/extract-event-schema.mjs`).
+const BUNDLED_SCHEMA: &str = include_str!("../../../schemas/opencode-events.json");
+
+const REF_PREFIX: &str = "#/components/schemas/";
+/// Guards against pathological recursion in self-referencing schemas.
+const MAX_DEPTH: usize = 32;
+
+/// An event that does not match the bundled schema.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchemaMismatch {
+    /// Event `type`, or `"<unknown>"` when it could not be determined.
+    pub event_type: String,
+    /// JSON path of the offending value, e.g. `properties.info.time`.
+    pub path: String,
+    pub reason: String,
+}
+
+/// Lightweight structural validator for the subset of JSON Schema used by
+/// the opencode OpenAPI document (`$ref`, `anyOf`, `const`, `enum`,
+/// `type`, `required`, `properties`, `items`). Value constraints such as
+/// `minimum` or `pattern` are deliberately ignored: the goal is to detect
+/// shape drift between server and UI versions, not to reject data.
+pub struct EventSchema {
+    server_version: String,
+    schemas: Map<String, Value>,
+    /// Event `type` → schema name.
+    by_type: HashMap<String, String>,
+}
+
+impl EventSchema {
+    /// The schema bundled at compile time.
+    pub fn bundled() -> &'static EventSchema {
+        static SCHEMA: OnceLock<EventSchema> = OnceLock::new();
+        SCHEMA.get_or_init(|| {
+            let bundle: Value =
+                serde_json::from_str(BUNDLED_SCHEMA).expect("bundled event schema is valid JSON");
+            EventSchema::from_bundle(bundle)
+        })
+    }
+
+    fn from_bundle(mut bundle: Value) -> Self {
+        let server_version = bundle["serverVersion"]
+            .as_str()
+            .unwrap_or("unknown")
+            .to_string();
+        let schemas = match bundle["schemas"].take() {
+            Value::Object(schemas) => schemas,
+            _ => Map::new(),
+        };
+
+        let by_type = schemas
+            .get("Event")
+            .and_then(|event| event["anyOf"].as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|variant| {
+                let name = variant["$ref"].as_str()?.strip_prefix(REF_PREFIX)?;
+                let event_type = schemas.get(name)?["properties"]["type"]["const"].as_str()?;
+                Some((event_type.to_string(), name.to_string()))
+            })
+            .collect();
+
+        Self {
+            server_version,
+            schemas,
+            by_type,
+        }
+    }
+
+    /// Server version the bundled schema was extracted from.
+    pub fn server_version(&self) -> &str {
+        &self.server_version
+    }
+
+    /// Validates an event, unwrapping `/global/event` envelopes
+    /// (`{ directory, payload }`) first.
+    pub fn validate_event(&self, event: &Value) -> Result<(), SchemaMismatch> {
+        let (event, base) = match event.get("payload") {
+            Some(payload) if event.get("directory").is_some() => (payload, "payload"),
+            _ => (event, ""),
+        };
+        let Some(event_type) = event["type"].as_str() else {
+            return Err(SchemaMismatch {
+                event_type: "<unknown>".to_string(),
+                path: join(base, "type"),
+                reason: "missing event type".to_string(),
+            });
+        };
+        let Some(name) = self.by_type.get(event_type) else {
+            return Err(SchemaMismatch {
+                event_type: event_type.to_string(),
+                path: join(base, "type"),
+                reason: format!(
+                    "unknown event type for server schema {}",
+                    self.server_version
+                ),
+            });
+        };
+
+        self.validate(event, &self.schemas[name.as_str()], base.to_string(), 0)
+            .map_err(|(path, reason)| SchemaMismatch {
+                event_type: event_type.to_string(),
+                path,
+                reason,
+            })
+    }
+
+    fn validate(
+        &self,
+        value: &Value,
+        schema: &Value,
+        path: String,
+        depth: usize,
+    ) -> Result<(), (String, String)> {
+        if depth > MAX_DEPTH {
+            return Ok(());
+        }
+
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.strip_prefix(REF_PREFIX).unwrap_or(reference);
+            return match self.schemas.get(name) {
+                Some(target) => self.validate(value, target, path, depth + 1),
+                None => Ok(()),
+            };
+        }
+
+        if let Some(variants) = schema["anyOf"].as_array().or(schema["oneOf"].as_array()) {
+            let mut first_error = None;
+            for variant in variants {
+                match self.validate(value, variant, path.clone(), depth + 1) {
+                    Ok(()) => return Ok(()),
+                    Err(error) => {
+                        first_error.get_or_insert(error);
+                    }
+                }
+            }
+            return Err(first_error.unwrap_or((path, "no schema variants".to_string())));
+        }
+
+        if let Some(expected) = schema.get("const") {
+            if value != expected {
+                return Err((path, format!("expected {}", expected)));
+            }
+        }
+        if let Some(allowed) = schema["enum"].as_array() {
+            if !allowed.contains(value) {
+                return Err((path, format!("{} is not an allowed value", value)));
+            }
+        }
+
+        let types: Vec<&str> = match &schema["type"] {
+            Value::String(kind) => vec![kind.as_str()],
+            Value::Array(kinds) => kinds.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|kind| matches_type(value, kind)) {
+            return Err((
+                path,
+                format!("expected {}, got {}", types.join(" | "), type_name(value)),
+            ));
+        }
+
+        if let Value::Object(object) = value {
+            for key in schema["required"].as_array().into_iter().flatten() {
+                let Some(key) = key.as_str() else {
+                    continue;
+                };
+                if !object.contains_key(key) {
+                    return Err((join(&path, key), "missing required field".to_string()));
+                }
+            }
+            if let Some(properties) = schema["properties"].as_object() {
+                for (key, property) in properties {
+                    if let Some(child) = object.get(key) {
+                        self.validate(child, property, join(&path, key), depth + 1)?;
+                    }
+                }
+            }
+        }
+
+        if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+            for (index, item) in items.iter().enumerate() {
+                self.validate(item, item_schema, format!("{}[{}]", path, index), depth + 1)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn join(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+fn matches_type(value: &Value, kind: &str) -> bool {
+    match kind {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Per-connection validator that reports each distinct mismatch once.
+pub struct EventValidator {
+    schema: &'static EventSchema,
+    reported: HashSet<(String, String)>,
+}
+
+impl Default for EventValidator {
+    fn default() -> Self {
+        Self {
+            schema: EventSchema::bundled(),
+            reported: HashSet::new(),
+        }
+    }
+}
+
+impl EventValidator {
+    pub fn server_version(&self) -> &'static str {
+        self.schema.server_version()
+    }
+
+    /// Checks one framed SSE event block. Returns a mismatch the first time
+    /// a given (event type, path) combination fails.
+    pub fn check(&mut self, block: &str) -> Option<SchemaMismatch> {
+        let data = event_data(block)?;
+        let mismatch = match serde_json::from_str::<Value>(&data) {
+            Ok(event) => self.schema.validate_event(&event).err()?,
+            Err(e) => SchemaMismatch {
+                event_type: "<unknown>".to_string(),
+                path: String::new(),
+                reason: format!("invalid JSON: {}", e),
+            },
+        };
+
+        self.reported
+            .insert((mismatch.event_type.clone(), mismatch.path.clone()))
+            .then_some(mismatch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_events_against_bundled_schema() {
+        let schema = EventSchema::bundled();
+
+        let connected = json!({ "type": "server.connected", "properties": {} });
+        assert_eq!(schema.validate_event(&connected), Ok(()));
+
+        let wrapped = json!({ "directory": "/tmp", "payload": connected });
+        assert_eq!(schema.validate_event(&wrapped), Ok(()));
+
+        let missing = json!({ "type": "message.updated", "properties": {} });
+        let mismatch = schema.validate_event(&missing).unwrap_err();
+        assert_eq!(mismatch.event_type, "message.updated");
+        assert_eq!(mismatch.path, "properties.info");
+
+        let unknown = json!({ "type": "made.up", "properties": {} });
+        assert!(schema.validate_event(&unknown).is_err());
+    }
+
+    #[test]
+    fn reports_each_mismatch_once() {
+        let mut validator = EventValidator::default();
+        let block = "data: {\"type\":\"made.up\",\"properties\":{}}\n\n";
+        assert!(validator.check(block).is_some());
+        assert!(validator.check(block).is_none());
+        assert!(validator
+            .check("data: {\"type\":\"server.connected\",\"properties\":{}}\n\n")
+            .is_none());
+    }
+}
@@ -0,0 +1,281 @@
+use serde::Deserialize;
+
+/// Splits a decoded SSE text stream into complete events.
+///
+/// Events are forwarded only once their terminating blank line has
+/// arrived, so a single event can never grow without bound: once the
+/// buffered bytes exceed `max_event_bytes` the event is discarded up to
+/// its boundary and reported as [`Frame::Oversized`].
+pub struct SseFramer {
+    buf: String,
+    max_event_bytes: usize,
+    /// Bytes discarded from the current oversized event, if any.
+    skipped: Option<usize>,
+    /// Value of the most recent `id:` field, sent back as `Last-Event-ID`
+    /// when reconnecting.
+    last_event_id: Option<String>,
+    /// Most recent `retry:` field: the server-suggested reconnect delay.
+    retry_ms: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Frame {
+    /// A complete event block, including its terminating blank line.
+    Event(String),
+    /// An event exceeded the limit; `size` is the number of bytes seen so far.
+    Oversized { size: usize },
+}
+
+/// Default per-event limit (16 MiB).
+pub const DEFAULT_MAX_EVENT_BYTES: usize = 16 * 1024 * 1024;
+
+impl SseFramer {
+    pub fn new(max_event_bytes: usize) -> Self {
+        Self {
+            buf: String::new(),
+            max_event_bytes: max_event_bytes.max(1),
+            skipped: None,
+            last_event_id: None,
+            retry_ms: None,
+        }
+    }
+
+    /// Start from an ID seen on a previous connection.
+    pub fn with_last_event_id(mut self, id: Option<String>) -> Self {
+        self.last_event_id = id;
+        self
+    }
+
+    pub fn max_event_bytes(&self) -> usize {
+        self.max_event_bytes
+    }
+
+    pub fn last_event_id(&self) -> Option<&str> {
+        self.last_event_id.as_deref()
+    }
+
+    pub fn retry_ms(&self) -> Option<u64> {
+        self.retry_ms
+    }
+
+    pub fn push(&mut self, text: &str) -> Vec<Frame> {
+        let mut frames = Vec::new();
+        self.buf.push_str(text);
+
+        // While skipping, the buffer starts with the line terminators kept
+        // from the discarded content, i.e. it is *not* at a line start.
+        while let Some(end) = find_event_end(&self.buf, self.skipped.is_none()) {
+            let event: String = self.buf.drain(..end).collect();
+            if self.skipped.take().is_none() {
+                // An empty `id:` resets the last event ID
+                if let Some(id) = event_id(&event) {
+                    self.last_event_id = (!id.is_empty()).then_some(id);
+                }
+                if let Some(retry) = event_retry(&event) {
+                    self.retry_ms = Some(retry);
+                }
+                frames.push(Frame::Event(event));
+            }
+        }
+
+        if self.buf.len() > self.max_event_bytes || self.skipped.is_some() {
+            // Keep trailing line terminators so a boundary split across
+            // chunks is still detected.
+            let keep = self.buf.len() - self.buf.trim_end_matches(['\r', '\n']).len();
+            let discard = self.buf.len() - keep;
+            self.buf.drain(..discard);
+
+            match self.skipped.as_mut() {
+                Some(skipped) => *skipped += discard,
+                None => {
+                    self.skipped = Some(discard);
+                    frames.push(Frame::Oversized { size: discard });
+                }
+            }
+        }
+
+        frames
+    }
+}
+
+/// Extracts the `data:` payload of a framed SSE event block.
+pub fn event_data(block: &str) -> Option<String> {
+    let lines: Vec<&str> = block
+        .lines()
+        .filter_map(|line| line.strip_prefix("data:"))
+        .map(|data| data.strip_prefix(' ').unwrap_or(data))
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+/// Values of the `name` field in an SSE event block. A bare `name` line
+/// counts as an empty value, as in the SSE spec.
+fn field_values<'a>(block: &'a str, name: &'a str) -> impl Iterator<Item = &'a str> {
+    block.lines().filter_map(move |line| {
+        let rest = line.strip_prefix(name)?;
+        match rest.strip_prefix(':') {
+            Some(value) => Some(value.strip_prefix(' ').unwrap_or(value)),
+            None if rest.is_empty() => Some(""),
+            None => None,
+        }
+    })
+}
+
+/// Extracts the `id:` field of a framed SSE event block. Per the SSE spec
+/// the last `id:` line wins and IDs containing NUL are ignored.
+pub fn event_id(block: &str) -> Option<String> {
+    field_values(block, "id")
+        .filter(|id| !id.contains('\0'))
+        .last()
+        .map(str::to_string)
+}
+
+/// Only the `type` of an event; other fields are skipped without being
+/// materialized.
+#[derive(Deserialize)]
+struct TypePeek {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// Global events are wrapped as `{ directory, payload }`.
+    payload: Option<Box<TypePeek>>,
+}
+
+/// Extracts the JSON `type` of an event's data, looking inside the
+/// `payload` of wrapped global events.
+pub fn event_type(block: &str) -> Option<String> {
+    let data = event_data(block)?;
+    let peek: TypePeek = serde_json::from_str(&data).ok()?;
+    peek.kind
+        .or_else(|| peek.payload.and_then(|payload| payload.kind))
+}
+
+/// Extracts the `retry:` field (reconnect delay in milliseconds). Values
+/// that are not plain ASCII digits are ignored, as in the SSE spec.
+pub fn event_retry(block: &str) -> Option<u64> {
+    field_values(block, "retry")
+        .filter(|retry| !retry.is_empty() && retry.bytes().all(|b| b.is_ascii_digit()))
+        .filter_map(|retry| retry.parse().ok())
+        .last()
+}
+
+/// Extracts the `event:` field (event type) of a framed SSE event block.
+pub fn event_name(block: &str) -> Option<String> {
+    field_values(block, "event")
+        .last()
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// Returns the byte offset just past the first blank line (`\n\n`,
+/// `\r\n\r\n` or `\r\r`), i.e. the end of the first complete event.
+fn find_event_end(buf: &str, mut line_start: bool) -> Option<usize> {
+    let bytes = buf.as_bytes();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let terminator = match bytes[i] {
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => 2,
+            // A lone trailing `\r` may still be followed by `\n`.
+            b'\r' if i + 1 == bytes.len() => return None,
+            b'\r' | b'\n' => 1,
+            _ => {
+                line_start = false;
+                i += 1;
+                continue;
+            }
+        };
+
+        i += terminator;
+        if line_start {
+            return Some(i);
+        }
+        line_start = true;
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{event_name, event_type, Frame, SseFramer};
+
+    #[test]
+    fn framer_emits_only_complete_events() {
+        let mut framer = SseFramer::new(1024);
+
+        assert!(framer.push("data: {\"a\"").is_empty());
+        assert_eq!(
+            framer.push(":1}\n\ndata: b\r\n"),
+            vec![Frame::Event("data: {\"a\":1}\n\n".to_string())]
+        );
+        assert_eq!(
+            framer.push("\r\n"),
+            vec![Frame::Event("data: b\r\n\r\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn framer_discards_oversized_event_until_boundary() {
+        let mut framer = SseFramer::new(8);
+
+        assert_eq!(
+            framer.push("data: 0123456789"),
+            vec![Frame::Oversized { size: 16 }]
+        );
+        assert!(framer.push("more\n").is_empty());
+        assert_eq!(
+            framer.push("\ndata: ok\n\n"),
+            vec![Frame::Event("data: ok\n\n".to_string())]
+        );
+    }
+
+    #[test]
+    fn framer_tracks_last_event_id() {
+        let mut framer = SseFramer::new(1024);
+
+        framer.push("id: 41\ndata: a\n\ndata: b\n\n");
+        assert_eq!(framer.last_event_id(), Some("41"));
+        framer.push("id:42\nidentity: x\ndata: c\n\n");
+        assert_eq!(framer.last_event_id(), Some("42"));
+        framer.push("id\ndata: d\n\n");
+        assert_eq!(framer.last_event_id(), None);
+    }
+
+    #[test]
+    fn event_type_peeks_plain_and_wrapped_events() {
+        assert_eq!(
+            event_type("data: {\"type\":\"storage.write\",\"properties\":{}}\n\n").as_deref(),
+            Some("storage.write")
+        );
+        assert_eq!(
+            event_type("data: {\"directory\":\"/p\",\"payload\":{\"type\":\"session.idle\"}}\n\n")
+                .as_deref(),
+            Some("session.idle")
+        );
+        assert_eq!(event_type("data: not json\n\n"), None);
+        assert_eq!(event_type(": comment\n\n"), None);
+    }
+
+    #[test]
+    fn framer_tracks_retry_hint() {
+        let mut framer = SseFramer::new(1024);
+
+        // `retry:` may arrive in a block without data
+        framer.push("retry: 3000\n\n");
+        assert_eq!(framer.retry_ms(), Some(3000));
+        framer.push("retry: soon\ndata: a\n\nretry:-1\n\n");
+        assert_eq!(framer.retry_ms(), Some(3000));
+        framer.push("retry:250\ndata: b\n\n");
+        assert_eq!(framer.retry_ms(), Some(250));
+    }
+
+    #[test]
+    fn extracts_event_name() {
+        assert_eq!(
+            event_name("event: message.updated\ndata: {}\n\n"),
+            Some("message.updated".to_string())
+        );
+        assert_eq!(event_name("events: x\ndata: {}\n\n"), None);
+        assert_eq!(event_name("data: {}\n\n"), None);
+    }
+}
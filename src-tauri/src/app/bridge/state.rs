@@ -1,12 +1,14 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     sync::{
         atomic::{AtomicU64, Ordering},
-        Mutex,
+        Arc, Mutex,
     },
 };
 
-use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{mpsc::UnboundedSender, Notify};
+
+use super::{ConnectArgs, ForensicLog, PauseTable, SseRecorder, StatsTable};
 
 /// Command sent from the frontend to an active WebSocket bridge.
 #[derive(Debug)]
@@ -19,21 +21,47 @@ pub enum BridgeCommand {
 pub struct BridgeConnection {
     pub id: u64,
     /// `Some` for WebSocket connections (bidirectional),
-    /// `None` for HTTP stream connections (read-only, cancelled via id mismatch).
+    /// `None` for HTTP stream connections (read-only).
     pub tx: Option<UnboundedSender<BridgeCommand>>,
+    /// HTTP stream connections: woken on disconnect so the stream task
+    /// drops the request immediately instead of at the next chunk.
+    cancel: Option<Arc<Notify>>,
 }
 
 impl BridgeConnection {
     pub fn new_ws(id: u64, tx: UnboundedSender<BridgeCommand>) -> Self {
-        Self { id, tx: Some(tx) }
+        Self {
+            id,
+            tx: Some(tx),
+            cancel: None,
+        }
     }
 
-    pub fn new_stream(id: u64) -> Self {
-        Self { id, tx: None }
+    pub fn new_stream(id: u64, cancel: Arc<Notify>) -> Self {
+        Self {
+            id,
+            tx: None,
+            cancel: Some(cancel),
+        }
+    }
+
+    /// Tell the connection task to shut down. The connection must already
+    /// be removed from (or replaced in) the active map.
+    pub fn close(self) {
+        if let Some(tx) = self.tx {
+            let _ = tx.send(BridgeCommand::Close);
+        }
+        if let Some(cancel) = self.cancel {
+            // Stores a permit if the task is not waiting right now
+            cancel.notify_one();
+        }
     }
 }
 
-/// Composite key: (window label, bridge id).
+/// Composite key: (webview label, bridge id).
+///
+/// For regular windows the webview label equals the window label; split
+/// view panes are separate webviews inside one window.
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct BridgeKey {
     window_label: String,
@@ -51,16 +79,69 @@ impl BridgeKey {
     pub fn window_label(&self) -> &str {
         &self.window_label
     }
+
+    pub fn bridge_id(&self) -> &str {
+        &self.bridge_id
+    }
 }
 
+/// Observer invoked with every framed SSE event block (and the arguments of
+/// the connection it arrived on), in addition to the frontend delivery.
+/// Must return quickly: it runs on the stream task.
+pub type EventTap = Box<dyn Fn(&ConnectArgs, &str) + Send + Sync>;
+
+/// Maximum events buffered per bridge while its webview is recovering.
+const MAX_REPLAY_EVENTS: usize = 10_000;
+
 /// Global bridge state shared across all windows.
 #[derive(Default)]
 pub struct BridgeState {
     next_id: AtomicU64,
     active: Mutex<HashMap<BridgeKey, BridgeConnection>>,
+    /// Webviews whose renderer is being recovered: stream data is held
+    /// here instead of being sent to the dead channel, and replayed on
+    /// the next `bridge_connect` for the same key.
+    replay: Mutex<HashMap<String, HashMap<BridgeKey, VecDeque<String>>>>,
+    forensics: ForensicLog,
+    stats: StatsTable,
+    recorder: SseRecorder,
+    pauses: PauseTable,
+    taps: Mutex<Vec<EventTap>>,
 }
 
 impl BridgeState {
+    /// Log of stream disconnects.
+    pub fn forensics(&self) -> &ForensicLog {
+        &self.forensics
+    }
+
+    /// Metrics of active HTTP stream connections.
+    pub fn stats(&self) -> &StatsTable {
+        &self.stats
+    }
+
+    /// Opt-in raw event recorder.
+    pub fn recorder(&self) -> &SseRecorder {
+        &self.recorder
+    }
+
+    /// Streams whose delivery is paused by the frontend.
+    pub fn pauses(&self) -> &PauseTable {
+        &self.pauses
+    }
+
+    /// Register an observer for stream events (e.g. desktop-side analysis).
+    pub fn add_event_tap(&self, tap: EventTap) {
+        self.taps.lock().expect("bridge state poisoned").push(tap);
+    }
+
+    /// Pass an event block to every registered tap.
+    pub fn tap_event(&self, args: &ConnectArgs, data: &str) {
+        for tap in self.taps.lock().expect("bridge state poisoned").iter() {
+            tap(args, data);
+        }
+    }
+
     /// Allocate the next connection id.
     pub fn next_conn_id(&self) -> u64 {
         self.next_id.fetch_add(1, Ordering::SeqCst) + 1
@@ -69,6 +150,7 @@ impl BridgeState {
     /// Insert a new connection, returning the previous one (if any) so
     /// the caller can shut it down.
     pub fn replace(&self, key: BridgeKey, conn: BridgeConnection) -> Option<BridgeConnection> {
+        self.pauses.clear(&key);
         self.active
             .lock()
             .expect("bridge state poisoned")
@@ -84,13 +166,24 @@ impl BridgeState {
             .and_then(|conn| conn.tx.clone())
     }
 
+    /// Whether an HTTP stream (not a WebSocket) is active for `key`.
+    pub fn is_stream(&self, key: &BridgeKey) -> bool {
+        self.active
+            .lock()
+            .expect("bridge state poisoned")
+            .get(key)
+            .is_some_and(|conn| conn.tx.is_none())
+    }
+
     /// Remove the connection only if its id matches (prevents a new
     /// connection from being removed by an old task's cleanup).
     pub fn remove_if_current(&self, key: &BridgeKey, id: u64) {
         let mut guard = self.active.lock().expect("bridge state poisoned");
         if guard.get(key).is_some_and(|conn| conn.id == id) {
             guard.remove(key);
+            self.pauses.clear(key);
         }
+        self.stats.remove(key, Some(id));
     }
 
     /// Gracefully disconnect a specific bridge.
@@ -100,10 +193,10 @@ impl BridgeState {
             .lock()
             .expect("bridge state poisoned")
             .remove(key);
+        self.stats.remove(key, None);
+        self.pauses.clear(key);
         if let Some(conn) = removed {
-            if let Some(tx) = conn.tx {
-                let _ = tx.send(BridgeCommand::Close);
-            }
+            conn.close();
             return true;
         }
         false
@@ -111,6 +204,10 @@ impl BridgeState {
 
     /// Disconnect all bridges belonging to a window (called on window destroy).
     pub fn disconnect_window(&self, window_label: &str) {
+        self.clear_replay(window_label);
+        self.stats.remove_window(window_label);
+        self.pauses.clear_window(window_label);
+
         let removed = {
             let mut guard = self.active.lock().expect("bridge state poisoned");
             let keys: Vec<_> = guard
@@ -124,9 +221,7 @@ impl BridgeState {
         };
 
         for conn in removed {
-            if let Some(tx) = conn.tx {
-                let _ = tx.send(BridgeCommand::Close);
-            }
+            conn.close();
         }
     }
 
@@ -139,4 +234,50 @@ impl BridgeState {
             .get(key)
             .is_some_and(|conn| conn.id == id)
     }
+
+    /// Start holding stream data for a webview that is being recovered.
+    pub fn start_buffering(&self, webview_label: &str) {
+        self.replay
+            .lock()
+            .expect("bridge state poisoned")
+            .entry(webview_label.to_string())
+            .or_default();
+    }
+
+    /// Buffer `data` if the connection's webview is recovering.
+    /// Returns the data back when it should be delivered normally.
+    pub fn buffer_if_recovering(&self, key: &BridgeKey, data: String) -> Option<String> {
+        let mut replay = self.replay.lock().expect("bridge state poisoned");
+        let Some(buffers) = replay.get_mut(key.window_label()) else {
+            return Some(data);
+        };
+        let buffer = buffers.entry(key.clone()).or_default();
+        if buffer.len() >= MAX_REPLAY_EVENTS {
+            buffer.pop_front();
+        }
+        buffer.push_back(data);
+        None
+    }
+
+    /// Take the events buffered for `key` during recovery. Once the last
+    /// bridge of the webview has been replayed buffering stops.
+    pub fn take_replay(&self, key: &BridgeKey) -> Vec<String> {
+        let mut replay = self.replay.lock().expect("bridge state poisoned");
+        let Some(buffers) = replay.get_mut(key.window_label()) else {
+            return Vec::new();
+        };
+        let events = buffers.remove(key).map(Vec::from).unwrap_or_default();
+        if buffers.is_empty() {
+            replay.remove(key.window_label());
+        }
+        events
+    }
+
+    /// Drop any recovery buffers for a webview (e.g. window destroyed).
+    pub fn clear_replay(&self, webview_label: &str) {
+        self.replay
+            .lock()
+            .expect("bridge state poisoned")
+            .remove(webview_label);
+    }
 }
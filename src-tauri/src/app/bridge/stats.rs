@@ -0,0 +1,190 @@
+use serde::Serialize;
+use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+use super::BridgeKey;
+
+/// Live metrics of one HTTP stream connection, for connection health UI.
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StreamStats {
+    pub webview: String,
+    pub bridge_id: String,
+    /// Stream URL without its query string.
+    pub url: String,
+    /// Unix time in milliseconds the current stream connected; `None`
+    /// while connecting or waiting to reconnect.
+    pub connected_since: Option<u64>,
+    /// Totals across reconnects of the same connection.
+    pub bytes_received: u64,
+    /// Receive rate over the last `throughputSecs` interval; `None` until
+    /// the first sample or when throughput reporting is off.
+    pub bytes_per_sec: Option<u64>,
+    pub events_delivered: u64,
+    pub last_event_at: Option<u64>,
+    pub reconnect_count: u32,
+    /// Events arrive through the polling fallback instead of the stream.
+    pub polling: bool,
+}
+
+struct Entry {
+    conn_id: u64,
+    stats: StreamStats,
+    /// Start of the current throughput sampling window.
+    window_start: Instant,
+    window_bytes: u64,
+}
+
+/// Bytes received by a connection, reported as a `Throughput` event.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Throughput {
+    pub bytes_per_sec: u64,
+    pub total_bytes: u64,
+}
+
+/// Per-connection stats, keyed like the active connections. Updates carry
+/// the connection id so a replaced connection's task cannot touch the
+/// stats of its successor.
+#[derive(Default)]
+pub struct StatsTable {
+    entries: Mutex<HashMap<BridgeKey, Entry>>,
+}
+
+impl StatsTable {
+    /// Start tracking a new connection, replacing any previous stats.
+    pub fn start(&self, key: &BridgeKey, conn_id: u64, url: &str) {
+        let stats = StreamStats {
+            webview: key.window_label().to_string(),
+            bridge_id: key.bridge_id().to_string(),
+            url: url.split('?').next().unwrap_or_default().to_string(),
+            connected_since: None,
+            bytes_received: 0,
+            bytes_per_sec: None,
+            events_delivered: 0,
+            last_event_at: None,
+            reconnect_count: 0,
+            polling: false,
+        };
+        self.entries.lock().expect("stream stats poisoned").insert(
+            key.clone(),
+            Entry {
+                conn_id,
+                stats,
+                window_start: Instant::now(),
+                window_bytes: 0,
+            },
+        );
+    }
+
+    fn entry<R>(
+        &self,
+        key: &BridgeKey,
+        conn_id: u64,
+        f: impl FnOnce(&mut Entry) -> R,
+    ) -> Option<R> {
+        let mut entries = self.entries.lock().expect("stream stats poisoned");
+        entries
+            .get_mut(key)
+            .filter(|entry| entry.conn_id == conn_id)
+            .map(f)
+    }
+
+    pub fn update(&self, key: &BridgeKey, conn_id: u64, f: impl FnOnce(&mut StreamStats)) {
+        self.entry(key, conn_id, |entry| f(&mut entry.stats));
+    }
+
+    /// Count received bytes and framed events.
+    pub fn received(&self, key: &BridgeKey, conn_id: u64, bytes: u64, events: u64) {
+        self.entry(key, conn_id, |entry| {
+            entry.window_bytes += bytes;
+            let stats = &mut entry.stats;
+            stats.bytes_received += bytes;
+            if events > 0 {
+                stats.events_delivered += events;
+                stats.last_event_at = Some(crate::app::now_millis());
+            }
+        });
+    }
+
+    /// Close the current sampling window: the rate since the previous
+    /// sample (or since connecting) is stored in the stats and returned.
+    pub fn sample_throughput(&self, key: &BridgeKey, conn_id: u64) -> Option<Throughput> {
+        self.entry(key, conn_id, |entry| {
+            let secs = entry.window_start.elapsed().as_secs_f64().max(0.001);
+            let bytes_per_sec = (entry.window_bytes as f64 / secs).round() as u64;
+            entry.window_start = Instant::now();
+            entry.window_bytes = 0;
+            entry.stats.bytes_per_sec = Some(bytes_per_sec);
+            Throughput {
+                bytes_per_sec,
+                total_bytes: entry.stats.bytes_received,
+            }
+        })
+    }
+
+    /// Stop tracking; with `conn_id` only if it is still the current one.
+    pub fn remove(&self, key: &BridgeKey, conn_id: Option<u64>) {
+        let mut entries = self.entries.lock().expect("stream stats poisoned");
+        if entries
+            .get(key)
+            .is_some_and(|entry| conn_id.is_none_or(|conn_id| entry.conn_id == conn_id))
+        {
+            entries.remove(key);
+        }
+    }
+
+    pub fn remove_window(&self, window_label: &str) {
+        self.entries
+            .lock()
+            .expect("stream stats poisoned")
+            .retain(|key, _| key.window_label() != window_label);
+    }
+
+    /// Stats of active connections, optionally limited to one webview.
+    pub fn snapshot(&self, window_label: Option<&str>) -> Vec<StreamStats> {
+        let mut stats: Vec<StreamStats> = self
+            .entries
+            .lock()
+            .expect("stream stats poisoned")
+            .iter()
+            .filter(|(key, _)| window_label.is_none_or(|label| key.window_label() == label))
+            .map(|(_, entry)| entry.stats.clone())
+            .collect();
+        stats.sort_by(|a, b| (&a.webview, &a.bridge_id).cmp(&(&b.webview, &b.bridge_id)));
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_connection_does_not_update_successor() {
+        let table = StatsTable::default();
+        let key = BridgeKey::new("main", "events");
+        table.start(&key, 1, "http://h/event?directory=/p");
+        table.received(&key, 1, 100, 2);
+        table.start(&key, 2, "http://h/event");
+        table.received(&key, 1, 50, 1);
+        table.received(&key, 2, 10, 0);
+
+        let stats = table.snapshot(Some("main"));
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].url, "http://h/event");
+        assert_eq!(stats[0].bytes_received, 10);
+        assert_eq!(stats[0].events_delivered, 0);
+        assert_eq!(stats[0].last_event_at, None);
+
+        assert_eq!(table.sample_throughput(&key, 1), None);
+        assert_eq!(
+            table.sample_throughput(&key, 2).map(|t| t.total_bytes),
+            Some(10)
+        );
+        assert!(table.snapshot(None)[0].bytes_per_sec.is_some());
+
+        table.remove(&key, Some(1));
+        assert_eq!(table.snapshot(None).len(), 1);
+        table.remove(&key, Some(2));
+        assert!(table.snapshot(None).is_empty());
+    }
+}
@@ -0,0 +1,176 @@
+use super::event_data;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The opencode events the UI handles on hot paths, parsed in Rust so the
+/// webview receives structured data instead of a JSON string.
+///
+/// Large nested objects (`info`, `part`, permission requests) stay as
+/// [`Value`]: the win is moving the parse off the webview main thread, not
+/// duplicating the server's schema. Field names match the server's so the
+/// frontend can share types between raw and typed delivery.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "properties")]
+pub enum ServerEvent {
+    #[serde(rename = "session.created")]
+    SessionCreated { info: Value },
+    #[serde(rename = "session.updated")]
+    SessionUpdated { info: Value },
+    #[serde(rename = "session.deleted")]
+    SessionDeleted { info: Value },
+    #[serde(rename = "session.status")]
+    SessionStatus {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        status: Value,
+    },
+    #[serde(rename = "session.idle")]
+    SessionIdle {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+    },
+    #[serde(rename = "session.error")]
+    SessionError {
+        #[serde(rename = "sessionID", default)]
+        session_id: Option<String>,
+        #[serde(default)]
+        error: Option<Value>,
+    },
+    #[serde(rename = "session.diff")]
+    SessionDiff {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        diff: Value,
+    },
+    #[serde(rename = "message.updated")]
+    MessageUpdated { info: Value },
+    #[serde(rename = "message.removed")]
+    MessageRemoved {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        #[serde(rename = "messageID")]
+        message_id: String,
+    },
+    #[serde(rename = "message.part.updated")]
+    MessagePartUpdated {
+        part: Value,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        delta: Option<String>,
+    },
+    #[serde(rename = "message.part.delta")]
+    MessagePartDelta {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        #[serde(rename = "messageID")]
+        message_id: String,
+        #[serde(rename = "partID")]
+        part_id: String,
+        field: String,
+        delta: String,
+    },
+    #[serde(rename = "message.part.removed")]
+    MessagePartRemoved {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        #[serde(rename = "messageID")]
+        message_id: String,
+        #[serde(rename = "partID")]
+        part_id: String,
+    },
+    #[serde(rename = "permission.asked")]
+    PermissionAsked(Value),
+    #[serde(rename = "permission.replied")]
+    PermissionReplied {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        #[serde(rename = "requestID")]
+        request_id: String,
+        reply: String,
+    },
+    #[serde(rename = "question.asked")]
+    QuestionAsked(Value),
+    #[serde(rename = "todo.updated")]
+    TodoUpdated {
+        #[serde(rename = "sessionID")]
+        session_id: String,
+        todos: Value,
+    },
+}
+
+/// Result of parsing an SSE block for typed delivery.
+#[derive(Debug, PartialEq)]
+pub enum Parsed {
+    Typed {
+        /// Project directory of a wrapped global event.
+        directory: Option<String>,
+        event: ServerEvent,
+    },
+    /// No data, or an event type without a typed variant: forward as-is.
+    Untyped,
+    /// The data is not JSON: drop it.
+    Malformed,
+}
+
+pub fn parse(block: &str) -> Parsed {
+    let Some(data) = event_data(block) else {
+        return Parsed::Untyped;
+    };
+    let Ok(mut value) = serde_json::from_str::<Value>(&data) else {
+        return Parsed::Malformed;
+    };
+    // Global events are wrapped as `{ directory, payload }`
+    let directory = value["directory"].as_str().map(str::to_string);
+    let event = match value.get_mut("payload") {
+        Some(payload) => payload.take(),
+        None => value,
+    };
+    match ServerEvent::deserialize(event) {
+        Ok(event) => Parsed::Typed { directory, event },
+        Err(_) => Parsed::Untyped,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_plain_and_wrapped_events() {
+        assert_eq!(
+            parse("data: {\"type\":\"session.idle\",\"properties\":{\"sessionID\":\"ses_1\"}}\n\n"),
+            Parsed::Typed {
+                directory: None,
+                event: ServerEvent::SessionIdle {
+                    session_id: "ses_1".to_string()
+                },
+            }
+        );
+
+        let block = "data: {\"directory\":\"/p\",\"payload\":{\"type\":\"message.part.updated\",\
+                     \"properties\":{\"part\":{\"id\":\"prt_1\"},\"delta\":\"Hi\"}}}\n\n";
+        let Parsed::Typed { directory, event } = parse(block) else {
+            panic!("expected a typed event");
+        };
+        assert_eq!(directory.as_deref(), Some("/p"));
+        assert_eq!(
+            serde_json::to_value(&event).unwrap(),
+            json!({
+                "type": "message.part.updated",
+                "properties": { "part": { "id": "prt_1" }, "delta": "Hi" }
+            })
+        );
+
+        assert_eq!(
+            parse("data: {\"type\":\"server.heartbeat\",\"properties\":{}}\n\n"),
+            Parsed::Untyped
+        );
+        // A known type whose properties do not match is forwarded untouched
+        assert_eq!(
+            parse("data: {\"type\":\"session.idle\",\"properties\":{}}\n\n"),
+            Parsed::Untyped
+        );
+        assert_eq!(parse("data: {\"type\":\n\n"), Parsed::Malformed);
+        assert_eq!(parse(": keepalive\n\n"), Parsed::Untyped);
+    }
+}
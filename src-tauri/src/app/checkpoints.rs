@@ -0,0 +1,171 @@
+// ============================================
+// Workspace Checkpoints (desktop only)
+// 用临时 index 把整个工作区（含未跟踪文件）写成 git tree，不影响用户的暂存区；
+// tree 挂在 refs/opencodeui/checkpoints/<id> 下防止被 gc
+// ============================================
+
+use crate::app::git;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+const REF_PREFIX: &str = "refs/opencodeui/checkpoints/";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checkpoint {
+    pub id: String,
+    /// 项目根目录（git 工作区根）
+    pub directory: String,
+    #[serde(default)]
+    pub label: Option<String>,
+    /// 快照对应的 git tree 对象
+    pub tree: String,
+    pub created_at: u64,
+}
+
+/// 把工作区当前状态写成 tree（遵循 .gitignore），返回 tree id
+pub fn snapshot_tree(root: &Path) -> Result<String, String> {
+    let index = std::env::temp_dir().join(format!(
+        "opencodeui-index-{}-{}",
+        std::process::id(),
+        crate::app::now_millis()
+    ));
+    let result = git::output(
+        git::command()
+            .args(["add", "-A", "--", "."])
+            .env("GIT_INDEX_FILE", &index)
+            .current_dir(root),
+        &["add"],
+    )
+    .and_then(|_| {
+        git::output(
+            git::command()
+                .arg("write-tree")
+                .env("GIT_INDEX_FILE", &index)
+                .current_dir(root),
+            &["write-tree"],
+        )
+    });
+    let _ = std::fs::remove_file(&index);
+    result
+}
+
+/// 工作区根目录；不是 git 仓库时报错
+pub fn repo_root(directory: &Path) -> Result<PathBuf, String> {
+    git::run(&["rev-parse", "--show-toplevel"], directory)
+        .map(PathBuf::from)
+        .map_err(|_| {
+            format!(
+                "'{}' is not a git repository; checkpoints require git",
+                directory.display()
+            )
+        })
+}
+
+#[derive(Default)]
+pub struct CheckpointsState {
+    checkpoints: Mutex<Vec<Checkpoint>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl CheckpointsState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("checkpoints.json"));
+        let checkpoints = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        Self {
+            checkpoints: Mutex::new(checkpoints),
+            path: Mutex::new(path),
+        }
+    }
+
+    /// 列出检查点，可按项目目录过滤，最新的在前
+    pub fn list(&self, directory: Option<&str>) -> Vec<Checkpoint> {
+        let mut checkpoints: Vec<Checkpoint> = self
+            .checkpoints
+            .lock()
+            .expect("checkpoints state poisoned")
+            .iter()
+            .filter(|checkpoint| directory.is_none_or(|dir| checkpoint.directory == dir))
+            .cloned()
+            .collect();
+        checkpoints.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        checkpoints
+    }
+
+    pub fn get(&self, id: &str) -> Option<Checkpoint> {
+        self.checkpoints
+            .lock()
+            .expect("checkpoints state poisoned")
+            .iter()
+            .find(|checkpoint| checkpoint.id == id)
+            .cloned()
+    }
+
+    /// 为 `directory` 所在仓库创建检查点（阻塞，调用方应放到 blocking 线程）
+    pub fn create(&self, directory: &Path, label: Option<String>) -> Result<Checkpoint, String> {
+        let root = repo_root(directory)?;
+        let tree = snapshot_tree(&root)?;
+        let created_at = crate::app::now_millis();
+        let id = format!("{:x}-{}", created_at, &tree[..tree.len().min(8)]);
+        git::run(
+            &["update-ref", &format!("{}{}", REF_PREFIX, id), &tree],
+            &root,
+        )?;
+
+        let checkpoint = Checkpoint {
+            id,
+            directory: root.to_string_lossy().to_string(),
+            label,
+            tree,
+            created_at,
+        };
+        let mut checkpoints = self.checkpoints.lock().expect("checkpoints state poisoned");
+        checkpoints.push(checkpoint.clone());
+        self.persist(&checkpoints)?;
+        Ok(checkpoint)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool, String> {
+        let mut checkpoints = self.checkpoints.lock().expect("checkpoints state poisoned");
+        let Some(index) = checkpoints
+            .iter()
+            .position(|checkpoint| checkpoint.id == id)
+        else {
+            return Ok(false);
+        };
+        let checkpoint = checkpoints.remove(index);
+        // 仓库可能已被移动或删除，ref 清理失败不影响移除
+        let _ = git::run(
+            &[
+                "update-ref",
+                "-d",
+                &format!("{}{}", REF_PREFIX, checkpoint.id),
+            ],
+            Path::new(&checkpoint.directory),
+        );
+        self.persist(&checkpoints)?;
+        Ok(true)
+    }
+
+    fn persist(&self, checkpoints: &[Checkpoint]) -> Result<(), String> {
+        let path = self
+            .path
+            .lock()
+            .expect("checkpoints state poisoned")
+            .clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(checkpoints).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
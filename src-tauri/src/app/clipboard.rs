@@ -0,0 +1,246 @@
+// ============================================
+// Clipboard History (desktop only)
+// 记录从应用内复制的代码块，支持列出与重新复制
+// ============================================
+
+use serde::Serialize;
+use std::{
+    collections::VecDeque,
+    fmt,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+/// 历史最多保留的条目数，超出后淘汰最旧的
+const MAX_ENTRIES: usize = 50;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClipboardEntry {
+    pub id: u64,
+    pub text: String,
+    pub language: Option<String>,
+    pub session_id: Option<String>,
+    pub message_id: Option<String>,
+    pub copied_at: u64,
+}
+
+/// 复制内容可能包含密钥等敏感信息，Debug 输出中只保留长度
+impl fmt::Debug for ClipboardEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ClipboardEntry")
+            .field("id", &self.id)
+            .field("text", &format_args!("<{} bytes>", self.text.len()))
+            .field("language", &self.language)
+            .field("session_id", &self.session_id)
+            .field("message_id", &self.message_id)
+            .field("copied_at", &self.copied_at)
+            .finish()
+    }
+}
+
+#[derive(Default)]
+pub struct ClipboardHistory {
+    next_id: AtomicU64,
+    entries: Mutex<VecDeque<ClipboardEntry>>,
+}
+
+impl ClipboardHistory {
+    /// 记录一次复制，返回新条目的 id。
+    /// 与最近一条内容相同时只刷新时间，不重复记录。
+    pub fn push(
+        &self,
+        text: String,
+        language: Option<String>,
+        session_id: Option<String>,
+        message_id: Option<String>,
+    ) -> u64 {
+        let mut entries = self.entries.lock().expect("clipboard history poisoned");
+        let copied_at = crate::app::now_millis();
+
+        if let Some(front) = entries.front_mut() {
+            if front.text == text {
+                front.copied_at = copied_at;
+                return front.id;
+            }
+        }
+
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        entries.push_front(ClipboardEntry {
+            id,
+            text,
+            language,
+            session_id,
+            message_id,
+            copied_at,
+        });
+        entries.truncate(MAX_ENTRIES);
+        id
+    }
+
+    /// 按时间倒序返回所有条目
+    pub fn list(&self) -> Vec<ClipboardEntry> {
+        self.entries
+            .lock()
+            .expect("clipboard history poisoned")
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// 将某条记录移到最前并刷新时间（重新复制时调用）
+    pub fn promote(&self, id: u64) -> Option<ClipboardEntry> {
+        let mut entries = self.entries.lock().expect("clipboard history poisoned");
+        let index = entries.iter().position(|entry| entry.id == id)?;
+        let mut entry = entries.remove(index)?;
+        entry.copied_at = crate::app::now_millis();
+        entries.push_front(entry.clone());
+        Some(entry)
+    }
+
+    pub fn clear(&self) {
+        self.entries
+            .lock()
+            .expect("clipboard history poisoned")
+            .clear();
+    }
+}
+
+/// 会话中最近一次产出的可复制内容
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum Artifact {
+    /// 回复文本中的 fenced 代码块
+    Code {
+        text: String,
+        language: Option<String>,
+        message_id: Option<String>,
+    },
+    /// write/edit 工具写入的文件（复制其路径）
+    File {
+        path: String,
+        message_id: Option<String>,
+    },
+}
+
+/// 从 `/session/{id}/message` 的返回值中倒序查找最近的代码块或生成文件
+pub fn extract_latest_artifact(messages: &serde_json::Value) -> Option<Artifact> {
+    let messages = messages.as_array()?;
+
+    for message in messages.iter().rev() {
+        let is_assistant = message
+            .pointer("/info/role")
+            .and_then(|role| role.as_str())
+            .is_some_and(|role| role == "assistant");
+        if !is_assistant {
+            continue;
+        }
+
+        let Some(parts) = message.get("parts").and_then(|parts| parts.as_array()) else {
+            continue;
+        };
+
+        for part in parts.iter().rev() {
+            let message_id = part
+                .get("messageID")
+                .and_then(|id| id.as_str())
+                .map(str::to_string);
+
+            match part.get("type").and_then(|kind| kind.as_str()) {
+                Some("text") => {
+                    let text = part
+                        .get("text")
+                        .and_then(|text| text.as_str())
+                        .unwrap_or("");
+                    if let Some((code, language)) = last_fenced_block(text) {
+                        return Some(Artifact::Code {
+                            text: code,
+                            language,
+                            message_id,
+                        });
+                    }
+                }
+                Some("tool") => {
+                    let tool = part
+                        .get("tool")
+                        .and_then(|tool| tool.as_str())
+                        .unwrap_or("");
+                    let completed = part
+                        .pointer("/state/status")
+                        .and_then(|status| status.as_str())
+                        .is_some_and(|status| status == "completed");
+                    if !completed || !matches!(tool, "write" | "edit") {
+                        continue;
+                    }
+                    if let Some(path) = part
+                        .pointer("/state/input/filePath")
+                        .and_then(|path| path.as_str())
+                    {
+                        return Some(Artifact::File {
+                            path: path.to_string(),
+                            message_id,
+                        });
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    None
+}
+
+/// 返回文本中最后一个完整的 ``` 代码块及其语言标记
+fn last_fenced_block(text: &str) -> Option<(String, Option<String>)> {
+    let mut last = None;
+    let mut current: Option<(Option<String>, Vec<&str>)> = None;
+
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        match current.take() {
+            None => {
+                if let Some(info) = trimmed.strip_prefix("```") {
+                    let language = info.trim();
+                    let language = (!language.is_empty()).then(|| language.to_string());
+                    current = Some((language, Vec::new()));
+                }
+            }
+            Some((language, mut lines)) => {
+                if trimmed.starts_with("```") {
+                    last = Some((lines.join("\n"), language));
+                } else {
+                    lines.push(line);
+                    current = Some((language, lines));
+                }
+            }
+        }
+    }
+
+    last
+}
+
+#[cfg(test)]
+mod tests {
+    use super::last_fenced_block;
+
+    #[test]
+    fn last_fenced_block_returns_final_complete_block() {
+        let text = "a\n```rust\nfn a() {}\n```\nthen\n```\nsecond\nline\n```\n```py\nunterminated";
+
+        assert_eq!(
+            last_fenced_block(text),
+            Some(("second\nline".to_string(), None))
+        );
+    }
+
+    #[test]
+    fn last_fenced_block_keeps_language_tag() {
+        assert_eq!(
+            last_fenced_block("```ts\nconst a = 1\n```"),
+            Some(("const a = 1".to_string(), Some("ts".to_string())))
+        );
+        assert_eq!(last_fenced_block("no code here"), None);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::app::appearance::{
+    apply_appearance, load_appearance, save_appearance, WindowAppearance,
+};
+use serde::Serialize;
+use tauri::Manager;
+
+/// 读取窗口外观设置
+#[tauri::command]
+pub fn get_window_appearance(app: tauri::AppHandle) -> WindowAppearance {
+    load_appearance(&app)
+}
+
+/// 保存窗口外观设置，并立即应用到所有已打开的窗口
+#[tauri::command]
+pub fn set_window_appearance(
+    app: tauri::AppHandle,
+    appearance: WindowAppearance,
+) -> Result<(), String> {
+    save_appearance(&app, &appearance)?;
+    for window in app.webview_windows().values() {
+        apply_appearance(window, &appearance);
+    }
+    Ok(())
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TitlebarLayout {
+    /// 是否由前端绘制标题栏
+    custom: bool,
+    /// 系统窗口按钮所在一侧："left"（macOS 红绿灯）或 "right"（Windows caption buttons）
+    controls_side: &'static str,
+    /// macOS 红绿灯相对窗口左上角的偏移，前端据此留出空白
+    traffic_light_inset: Option<(f32, f32)>,
+}
+
+/// 返回自定义标题栏需要避让的原生窗口按钮布局
+#[tauri::command]
+pub fn get_titlebar_layout(app: tauri::AppHandle) -> TitlebarLayout {
+    let custom = !load_appearance(&app).native_titlebar;
+
+    #[cfg(target_os = "macos")]
+    let (controls_side, traffic_light_inset) = ("left", Some(crate::app::TRAFFIC_LIGHT_INSET));
+    #[cfg(not(target_os = "macos"))]
+    let (controls_side, traffic_light_inset) = ("right", None);
+
+    TitlebarLayout {
+        custom,
+        controls_side,
+        traffic_light_inset: traffic_light_inset.filter(|_| custom),
+    }
+}
+
+/// 在自定义标题栏上按下鼠标时开始拖动窗口
+#[tauri::command]
+pub fn start_window_dragging(window: tauri::Window) -> Result<(), String> {
+    window.start_dragging().map_err(|e| e.to_string())
+}
+
+/// 双击自定义标题栏：在最大化与还原之间切换
+#[tauri::command]
+pub fn titlebar_double_click(window: tauri::Window) -> Result<(), String> {
+    if window.is_maximized().map_err(|e| e.to_string())? {
+        window.unmaximize().map_err(|e| e.to_string())
+    } else {
+        window.maximize().map_err(|e| e.to_string())
+    }
+}
@@ -7,21 +7,69 @@
 // The frontend picks the transport by URL scheme:
 //   ws:// / wss://   → WebSocket (bidirectional)
 //   http:// / https:// → HTTP stream  (read-only)
+//   unix://          → HTTP stream over a Unix domain socket (Unix only;
+//                      Windows named pipes are not supported)
 // ============================================
 
 use crate::app::bridge::{
-    BridgeCommand, BridgeConnection, BridgeEvent, BridgeKey, BridgeState, ConnectArgs,
-    DisconnectArgs, SendArgs,
+    batch_due, parse_typed, Batcher, BridgeCommand, BridgeConnection, BridgeEvent, BridgeKey,
+    BridgeState, ConnectArgs, DisconnectArgs, DisconnectKind, EventValidator, ForensicEntry, Frame,
+    Parsed, Poller, RecordingStatus, SendArgs, SseFramer, StreamStats, DEFAULT_PAUSE_CAPACITY,
 };
 use futures_util::{SinkExt, StreamExt};
-use std::time::Duration;
-use tauri::{ipc::Channel, State};
-use tokio::sync::mpsc;
+use std::{sync::Arc, time::Duration};
+use tauri::{
+    ipc::{Channel, InvokeResponseBody},
+    State,
+};
+use tokio::sync::{mpsc, Notify};
 
 fn emit(channel: &Channel<BridgeEvent>, event: BridgeEvent) {
     let _ = channel.send(event);
 }
 
+/// Sends one SSE block, parsed into a `Typed` event when requested.
+/// Blocks whose data is not JSON are dropped in typed mode.
+fn emit_sse(channel: &Channel<BridgeEvent>, args: &ConnectArgs, data: String) {
+    if !args.typed_events() {
+        match args.binary_channel() {
+            Some(binary) => {
+                let _ = binary.send(InvokeResponseBody::Raw(data.into_bytes()));
+            }
+            None => emit(channel, BridgeEvent::sse(data)),
+        }
+        return;
+    }
+    match parse_typed(&data) {
+        Parsed::Typed { directory, event } => {
+            emit(channel, BridgeEvent::typed(&data, event, directory))
+        }
+        Parsed::Untyped => emit(channel, BridgeEvent::sse(data)),
+        Parsed::Malformed => log::debug!("Dropped malformed event: {:.200}", data),
+    }
+}
+
+/// Sends whatever the batcher holds as one `MessageBatch`.
+fn flush_batch(channel: &Channel<BridgeEvent>, batch: Option<&mut Batcher>) {
+    if let Some(raw) = batch.map(Batcher::take).filter(|raw| !raw.is_empty()) {
+        emit(channel, BridgeEvent::MessageBatch { raw });
+    }
+}
+
+/// Sends SSE blocks that arrive together (a poll response, a replay) as
+/// one `MessageBatch` when batching is enabled, otherwise one by one.
+fn emit_sse_blocks(channel: &Channel<BridgeEvent>, args: &ConnectArgs, blocks: Vec<String>) {
+    if args.batch().is_some() {
+        if !blocks.is_empty() {
+            emit(channel, BridgeEvent::MessageBatch { raw: blocks });
+        }
+        return;
+    }
+    for data in blocks {
+        emit_sse(channel, args, data);
+    }
+}
+
 fn split_valid_utf8_prefix(bytes: &[u8]) -> Option<(String, usize)> {
     if bytes.is_empty() {
         return None;
@@ -48,19 +96,87 @@ fn split_valid_utf8_prefix(bytes: &[u8]) -> Option<(String, usize)> {
     }
 }
 
-fn emit_stream_chunk(channel: &Channel<BridgeEvent>, pending_utf8: &mut Vec<u8>, chunk: &[u8]) {
+#[allow(clippy::too_many_arguments)]
+fn emit_stream_chunk(
+    channel: &Channel<BridgeEvent>,
+    state: &BridgeState,
+    key: &BridgeKey,
+    conn_id: u64,
+    args: &ConnectArgs,
+    pending_utf8: &mut Vec<u8>,
+    framer: &mut SseFramer,
+    mut validator: Option<&mut EventValidator>,
+    mut batch: Option<&mut Batcher>,
+    chunk: &[u8],
+) -> usize {
+    let mut events = 0;
     if chunk.is_empty() {
-        return;
+        return events;
     }
 
     pending_utf8.extend_from_slice(chunk);
 
     while let Some((text, consumed)) = split_valid_utf8_prefix(pending_utf8.as_slice()) {
         pending_utf8.drain(..consumed);
-        if !text.is_empty() {
-            emit(channel, BridgeEvent::Data { data: text });
+        for frame in framer.push(&text) {
+            match frame {
+                Frame::Event(data) => {
+                    events += 1;
+                    state.recorder().record(key, conn_id, false, &data);
+                    if let Some(validator) = validator.as_deref_mut() {
+                        if let Some(mismatch) = validator.check(&data) {
+                            log::warn!(
+                                "Event schema mismatch ({}): {} {}",
+                                mismatch.event_type,
+                                mismatch.path,
+                                mismatch.reason
+                            );
+                            flush_batch(channel, batch.as_deref_mut());
+                            emit(
+                                channel,
+                                BridgeEvent::SchemaMismatch {
+                                    mismatch,
+                                    schema_version: validator.server_version(),
+                                },
+                            );
+                        }
+                    }
+                    state.tap_event(args, &data);
+                    if !args.forwards_event(&data) {
+                        continue;
+                    }
+                    let Some(data) = state.buffer_if_recovering(key, data) else {
+                        continue;
+                    };
+                    let Some(data) = state.pauses().hold(key, data, channel) else {
+                        continue;
+                    };
+                    match batch.as_deref_mut() {
+                        Some(batch) => {
+                            if let Some(raw) = batch.push(data) {
+                                emit(channel, BridgeEvent::MessageBatch { raw });
+                            }
+                        }
+                        None => emit_sse(channel, args, data),
+                    }
+                }
+                Frame::Oversized { size } => {
+                    log::warn!("SSE event exceeded size limit ({} bytes), dropped", size);
+                    flush_batch(channel, batch.as_deref_mut());
+                    emit(
+                        channel,
+                        BridgeEvent::PayloadTooLarge {
+                            scope: "event",
+                            limit: framer.max_event_bytes() as u64,
+                            size: size as u64,
+                        },
+                    );
+                }
+            }
         }
     }
+
+    events
 }
 
 // ============================================
@@ -69,15 +185,25 @@ fn emit_stream_chunk(channel: &Channel<BridgeEvent>, pending_utf8: &mut Vec<u8>,
 
 #[tauri::command]
 pub async fn bridge_connect(
-    window: tauri::Window,
+    webview: tauri::Webview,
     state: State<'_, BridgeState>,
-    args: ConnectArgs,
+    mut args: ConnectArgs,
     on_event: Channel<BridgeEvent>,
 ) -> Result<(), String> {
+    #[cfg(not(target_os = "android"))]
+    if let Some(server_id) = args.server_id() {
+        let auth_header = crate::app::credentials::auth_header(server_id)?;
+        args.set_auth_header(auth_header);
+    }
+    // Reject malformed headers or methods before any connection is replaced
+    args.header_map()?;
+    args.method()?;
+    args.bind_binary_channel(&webview);
+
     if args.is_websocket() {
-        connect_ws(window, state, args, on_event).await
+        connect_ws(webview, state, args, on_event).await
     } else {
-        connect_stream(window, state, args, on_event).await
+        connect_stream(webview, state, args, on_event).await
     }
 }
 
@@ -87,11 +213,11 @@ pub async fn bridge_connect(
 
 #[tauri::command]
 pub async fn bridge_send(
-    window: tauri::Window,
+    webview: tauri::Webview,
     state: State<'_, BridgeState>,
     args: SendArgs,
 ) -> Result<(), String> {
-    let key = BridgeKey::new(window.label(), args.bridge_id());
+    let key = BridgeKey::new(webview.label(), args.bridge_id());
     let sender = state
         .sender(&key)
         .ok_or_else(|| format!("bridge '{}' is not active", args.bridge_id()))?;
@@ -107,132 +233,775 @@ pub async fn bridge_send(
 
 #[tauri::command]
 pub async fn bridge_disconnect(
-    window: tauri::Window,
+    webview: tauri::Webview,
     state: State<'_, BridgeState>,
     args: DisconnectArgs,
 ) -> Result<(), String> {
-    let key = BridgeKey::new(window.label(), args.bridge_id());
+    let key = BridgeKey::new(webview.label(), args.bridge_id());
     state.disconnect(&key);
     Ok(())
 }
 
+// ============================================
+// sse_pause / sse_resume — HTTP stream only
+// ============================================
+
+/// Hold events of an HTTP stream in Rust instead of delivering them, up
+/// to `max_events` (oldest dropped beyond that, with a `PauseOverflow`).
+#[tauri::command]
+pub fn sse_pause(
+    webview: tauri::Webview,
+    state: State<'_, BridgeState>,
+    bridge_id: String,
+    max_events: Option<usize>,
+) -> Result<(), String> {
+    let key = BridgeKey::new(webview.label(), &bridge_id);
+    if !state.is_stream(&key) {
+        return Err(format!("no HTTP stream bridge '{}'", bridge_id));
+    }
+    state
+        .pauses()
+        .pause(&key, max_events.unwrap_or(DEFAULT_PAUSE_CAPACITY));
+    Ok(())
+}
+
+/// Resume delivery; the held backlog arrives first as one `MessageBatch`.
+/// Returns the number of delivered events, or 0 if the stream was not
+/// paused.
+#[tauri::command]
+pub fn sse_resume(
+    webview: tauri::Webview,
+    state: State<'_, BridgeState>,
+    bridge_id: String,
+) -> usize {
+    let key = BridgeKey::new(webview.label(), &bridge_id);
+    match state.pauses().resume(&key) {
+        Some((delivered, dropped)) => {
+            if dropped > 0 {
+                log::info!(
+                    "Bridge '{}' resumed; {} events dropped while paused",
+                    bridge_id,
+                    dropped
+                );
+            }
+            delivered
+        }
+        None => 0,
+    }
+}
+
+// ============================================
+// Connection forensics
+// ============================================
+
+/// Recorded stream disconnects (bounded), newest last.
+#[tauri::command]
+pub fn get_connection_forensics(state: State<'_, BridgeState>) -> Vec<ForensicEntry> {
+    state.forensics().snapshot()
+}
+
+/// Write the forensic log to `path` as JSON; returns the number of entries.
+#[tauri::command]
+pub fn dump_connection_forensics(
+    state: State<'_, BridgeState>,
+    path: String,
+) -> Result<usize, String> {
+    let entries = state.forensics().snapshot();
+    let data = serde_json::to_string_pretty(&entries).map_err(|e| e.to_string())?;
+    std::fs::write(&path, data).map_err(|e| format!("failed to write '{}': {}", path, e))?;
+    Ok(entries.len())
+}
+
+#[tauri::command]
+pub fn clear_connection_forensics(state: State<'_, BridgeState>) {
+    state.forensics().clear();
+}
+
+/// Metrics of active HTTP stream connections (all webviews unless one is
+/// given), for connection health indicators.
+#[tauri::command]
+pub fn sse_stats(state: State<'_, BridgeState>, webview: Option<String>) -> Vec<StreamStats> {
+    state.stats().snapshot(webview.as_deref())
+}
+
+// ============================================
+// SSE debug recording
+// ============================================
+
+/// Start appending every received SSE frame to rotating NDJSON files under
+/// the app data dir. Replaces any running recording.
+#[tauri::command]
+pub fn sse_recording_start(
+    app: tauri::AppHandle,
+    state: State<'_, BridgeState>,
+) -> Result<RecordingStatus, String> {
+    #[cfg(not(target_os = "android"))]
+    let dir =
+        crate::app::storage::category_dir(&app, crate::app::storage::StorageCategory::Recordings);
+    #[cfg(target_os = "android")]
+    let dir = {
+        use tauri::Manager;
+        app.path()
+            .app_data_dir()
+            .ok()
+            .map(|dir| dir.join("recordings"))
+    };
+    state
+        .recorder()
+        .start(&dir.ok_or("app data dir unavailable")?)
+}
+
+/// Stop recording; the last recording stays available for export.
+#[tauri::command]
+pub fn sse_recording_stop(state: State<'_, BridgeState>) -> Option<RecordingStatus> {
+    state.recorder().stop()
+}
+
+#[tauri::command]
+pub fn sse_recording_status(state: State<'_, BridgeState>) -> Option<RecordingStatus> {
+    state.recorder().status()
+}
+
+/// Write the current (or last) recording to `path` as a single NDJSON
+/// file; returns the number of bytes written.
+#[tauri::command]
+pub fn sse_recording_export(state: State<'_, BridgeState>, path: String) -> Result<u64, String> {
+    state.recorder().export(std::path::Path::new(&path))
+}
+
 // ============================================
 // HTTP stream transport (for SSE)
 // ============================================
 
+/// How long a connected stream may stay silent before probing whether an
+/// intermediary is buffering it.
+const FALLBACK_DETECT_AFTER: Duration = Duration::from_secs(15);
+/// Consecutive polling failures before the fallback gives up.
+const MAX_POLL_FAILURES: u32 = 3;
+
+/// Switches to polling when the stream produced no events but the server
+/// itself answers a health probe. Returns `None` when the fallback is
+/// disabled or does not apply, leaving the caller's error handling intact.
+async fn poll_fallback(
+    client: &reqwest::Client,
+    state: &BridgeState,
+    key: &BridgeKey,
+    conn_id: u64,
+    cancel: &Notify,
+    on_event: &Channel<BridgeEvent>,
+    args: &ConnectArgs,
+) -> Option<Result<(), String>> {
+    let interval = args.poll_fallback_interval()?;
+    // Polling mirrors opencode's GET event streams only
+    if args.method().ok()? != reqwest::Method::GET {
+        return None;
+    }
+    let headers = args.header_map().unwrap_or_default();
+    let poller = Poller::from_stream_url(client.clone(), &args.request_url(), headers.clone())?;
+
+    let healthy = client
+        .get(format!("{}global/health", poller.base_url()))
+        .timeout(Duration::from_secs(5))
+        .headers(headers)
+        .send()
+        .await
+        .map(|r| r.status().is_success())
+        .unwrap_or(false);
+    if !healthy {
+        return None;
+    }
+
+    log::warn!(
+        "HTTP stream '{}' delivered no events; falling back to polling every {}ms",
+        args.url(),
+        interval.as_millis()
+    );
+    state.forensics().record(ForensicEntry::new(
+        key,
+        args.url(),
+        DisconnectKind::PollingFallback,
+        "Stream delivered no events while the server was healthy",
+    ));
+    Some(
+        run_polling(
+            poller, interval, state, key, conn_id, cancel, on_event, args,
+        )
+        .await,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn run_polling(
+    mut poller: Poller,
+    interval: Duration,
+    state: &BridgeState,
+    key: &BridgeKey,
+    conn_id: u64,
+    cancel: &Notify,
+    on_event: &Channel<BridgeEvent>,
+    args: &ConnectArgs,
+) -> Result<(), String> {
+    emit(
+        on_event,
+        BridgeEvent::sse(
+            "data: {\"type\":\"bridge.transport\",\"properties\":{\"mode\":\"polling\"}}\n\n"
+                .to_string(),
+        ),
+    );
+
+    state.stats().update(key, conn_id, |stats| {
+        stats.polling = true;
+        stats.connected_since = Some(crate::app::now_millis());
+    });
+
+    let mut failures = 0;
+    loop {
+        if !state.is_current(key, conn_id) {
+            emit(
+                on_event,
+                BridgeEvent::Disconnected {
+                    code: None,
+                    reason: "Disconnected by client".to_string(),
+                },
+            );
+            return Ok(());
+        }
+
+        let polled = tokio::select! {
+            polled = poller.poll() => polled,
+            // Disconnected: reported at the top of the loop
+            _ = cancel.notified() => continue,
+        };
+        match polled {
+            Ok(events) => {
+                failures = 0;
+                state.stats().received(key, conn_id, 0, events.len() as u64);
+                let mut forwarded = Vec::new();
+                for data in events {
+                    state.recorder().record(key, conn_id, true, &data);
+                    state.tap_event(args, &data);
+                    if !args.forwards_event(&data) {
+                        continue;
+                    }
+                    forwarded.extend(
+                        state
+                            .buffer_if_recovering(key, data)
+                            .and_then(|data| state.pauses().hold(key, data, on_event)),
+                    );
+                }
+                emit_sse_blocks(on_event, args, forwarded);
+            }
+            Err(e) => {
+                failures += 1;
+                log::warn!(
+                    "Polling fallback failed ({}/{}): {}",
+                    failures,
+                    MAX_POLL_FAILURES,
+                    e
+                );
+                if failures >= MAX_POLL_FAILURES {
+                    let msg = format!("Polling fallback failed: {}", e);
+                    state.forensics().record(ForensicEntry::new(
+                        key,
+                        args.url(),
+                        DisconnectKind::PollingFailed,
+                        &msg,
+                    ));
+                    emit(
+                        on_event,
+                        BridgeEvent::Error {
+                            message: msg.clone(),
+                        },
+                    );
+                    state.remove_if_current(key, conn_id);
+                    return Err(msg);
+                }
+            }
+        }
+
+        tokio::select! {
+            _ = tokio::time::sleep(interval) => {}
+            _ = cancel.notified() => {}
+        }
+    }
+}
+
+/// Resolves at `at`; never resolves when there is no deadline.
+async fn sleep_until_some(at: Option<tokio::time::Instant>) {
+    match at {
+        Some(at) => tokio::time::sleep_until(at).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// How a single HTTP stream attempt ended.
+enum StreamEnd {
+    /// The connection is over and already reported to the frontend
+    /// (client closed, polling fallback finished, size limit, ...).
+    Done(Result<(), String>),
+    /// The stream failed or ended and may be retried. `result` is what gets
+    /// reported when no retry happens.
+    Retry {
+        result: Result<(), String>,
+        events_seen: bool,
+    },
+}
+
+/// Whether a failed status is worth retrying (server-side or rate limit),
+/// as opposed to auth / routing errors that will not fix themselves.
+fn retryable_status(status: reqwest::StatusCode) -> bool {
+    status.is_server_error()
+        || status == reqwest::StatusCode::REQUEST_TIMEOUT
+        || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Sleeps for `delay`, returning `false` early if the connection was
+/// disconnected or replaced meanwhile.
+async fn wait_unless_replaced(
+    state: &BridgeState,
+    key: &BridgeKey,
+    conn_id: u64,
+    cancel: &Notify,
+    delay: Duration,
+) -> bool {
+    tokio::select! {
+        _ = tokio::time::sleep(delay) => {}
+        _ = cancel.notified() => {}
+    }
+    state.is_current(key, conn_id)
+}
+
 async fn connect_stream(
-    window: tauri::Window,
+    webview: tauri::Webview,
     state: State<'_, BridgeState>,
     args: ConnectArgs,
     on_event: Channel<BridgeEvent>,
 ) -> Result<(), String> {
     let conn_id = state.next_conn_id();
-    let key = BridgeKey::new(window.label(), args.bridge_id());
+    let key = BridgeKey::new(webview.label(), args.bridge_id());
 
     // Replace any previous connection with the same key
-    if let Some(prev) = state.replace(key.clone(), BridgeConnection::new_stream(conn_id)) {
-        if let Some(tx) = prev.tx {
-            let _ = tx.send(BridgeCommand::Close);
-        }
+    let cancel = Arc::new(Notify::new());
+    if let Some(prev) = state.replace(
+        key.clone(),
+        BridgeConnection::new_stream(conn_id, cancel.clone()),
+    ) {
+        prev.close();
     }
+    state.stats().start(&key, conn_id, args.url());
 
-    let client = reqwest::Client::builder()
+    // Sends `Accept-Encoding: gzip, deflate` and decompresses the body as it
+    // streams, so size limits and SSE framing see the decoded bytes
+    let builder = crate::app::dns::client_builder()
         .connect_timeout(Duration::from_secs(15))
-        .tcp_keepalive(Duration::from_secs(30))
+        .gzip(true)
+        .deflate(true);
+    let builder = crate::app::network::apply_server(builder, args.server_id());
+    let builder = match args.unix_socket() {
+        #[cfg(unix)]
+        Some(socket) => builder.unix_socket(socket),
+        #[cfg(not(unix))]
+        Some(_) => {
+            state.remove_if_current(&key, conn_id);
+            return Err(
+                "unix:// URLs are only supported on Unix systems; named pipes are not supported, connect over TCP instead"
+                    .to_string(),
+            );
+        }
+        None => builder,
+    };
+    let client = args
+        .http()
+        .apply(builder)
+        .tcp_keepalive(args.tcp_keepalive())
         .build()
         .map_err(|e| format!("failed to create HTTP client: {}", e))?;
 
-    let mut req = client.get(args.url());
-    if let Some(auth) = args.auth_header() {
-        req = req.header("Authorization", auth);
-    }
+    // Carried across reconnects: replay position and server retry hint
+    let mut resume = StreamResume {
+        last_event_id: args.last_event_id().map(str::to_string),
+        retry_ms: None,
+    };
+    let mut attempt = 0;
+    loop {
+        let (result, events_seen) = match stream_once(
+            &client,
+            &state,
+            &key,
+            conn_id,
+            &cancel,
+            &on_event,
+            &args,
+            &mut resume,
+        )
+        .await
+        {
+            StreamEnd::Done(result) => return result,
+            StreamEnd::Retry {
+                result,
+                events_seen,
+            } => (result, events_seen),
+        };
+        state
+            .stats()
+            .update(&key, conn_id, |stats| stats.connected_since = None);
 
-    let response = match req.send().await {
-        Ok(r) => r,
-        Err(e) => {
-            let msg = format!("HTTP stream connection failed: {}", e);
+        // A stream that delivered events counts as recovered
+        if events_seen {
+            attempt = 0;
+        }
+        attempt += 1;
+        let Some(delay) = args
+            .reconnect()
+            .and_then(|policy| policy.delay_with_hint(attempt, resume.retry_ms))
+        else {
+            match &result {
+                Err(message) => emit(
+                    &on_event,
+                    BridgeEvent::Error {
+                        message: message.clone(),
+                    },
+                ),
+                Ok(()) => emit(
+                    &on_event,
+                    BridgeEvent::Disconnected {
+                        code: None,
+                        reason: "Stream ended".to_string(),
+                    },
+                ),
+            }
+            state.remove_if_current(&key, conn_id);
+            return result;
+        };
+
+        let reason = result.err().unwrap_or_else(|| "Stream ended".to_string());
+        log::info!(
+            "HTTP stream '{}' dropped ({}), reconnecting in {}ms (attempt {})",
+            args.bridge_id(),
+            reason,
+            delay.as_millis(),
+            attempt
+        );
+        state
+            .stats()
+            .update(&key, conn_id, |stats| stats.reconnect_count += 1);
+        emit(
+            &on_event,
+            BridgeEvent::Reconnecting {
+                attempt,
+                delay_ms: delay.as_millis() as u64,
+                reason,
+            },
+        );
+        if !wait_unless_replaced(&state, &key, conn_id, &cancel, delay).await {
+            state.forensics().record(ForensicEntry::new(
+                &key,
+                args.url(),
+                DisconnectKind::ClientClosed,
+                "Disconnected by client while reconnecting",
+            ));
             emit(
                 &on_event,
-                BridgeEvent::Error {
-                    message: msg.clone(),
+                BridgeEvent::Disconnected {
+                    code: None,
+                    reason: "Disconnected by client".to_string(),
                 },
             );
-            state.remove_if_current(&key, conn_id);
-            return Err(msg);
+            return Ok(());
+        }
+    }
+}
+
+/// Stream state that survives reconnects.
+struct StreamResume {
+    /// Sent as `Last-Event-ID` so the server can replay missed events.
+    last_event_id: Option<String>,
+    /// Reconnect delay suggested by the server's `retry:` field.
+    retry_ms: Option<u64>,
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn stream_once(
+    client: &reqwest::Client,
+    state: &BridgeState,
+    key: &BridgeKey,
+    conn_id: u64,
+    cancel: &Notify,
+    on_event: &Channel<BridgeEvent>,
+    args: &ConnectArgs,
+    resume: &mut StreamResume,
+) -> StreamEnd {
+    let mut req = client
+        .request(args.method().unwrap_or_default(), args.request_url())
+        .headers(args.header_map().unwrap_or_default());
+    if let Some(body) = args.body() {
+        req = req.body(body.to_string());
+    }
+    if let Some(id) = resume.last_event_id.as_deref() {
+        req = req.header("Last-Event-ID", id);
+    }
+
+    let sent = tokio::select! {
+        sent = req.send() => sent,
+        _ = cancel.notified() => {
+            state.forensics().record(ForensicEntry::new(
+                key,
+                args.url(),
+                DisconnectKind::ClientClosed,
+                "Disconnected by client while connecting",
+            ));
+            emit(
+                on_event,
+                BridgeEvent::Disconnected {
+                    code: None,
+                    reason: "Disconnected by client".to_string(),
+                },
+            );
+            return StreamEnd::Done(Ok(()));
+        }
+    };
+    let response = match sent {
+        Ok(r) => r,
+        Err(e) => {
+            let msg = format!("HTTP stream connection failed: {}", e);
+            state
+                .forensics()
+                .record_reconnect(key, false, Some(msg.clone()));
+            state.forensics().record(ForensicEntry::new(
+                key,
+                args.url(),
+                DisconnectKind::ConnectFailed,
+                &msg,
+            ));
+            return StreamEnd::Retry {
+                result: Err(msg),
+                events_seen: false,
+            };
         }
     };
 
-    if !response.status().is_success() {
-        let msg = format!("HTTP stream server returned {}", response.status());
+    let status = response.status();
+    if !status.is_success() {
+        let msg = format!("HTTP stream server returned {}", status);
+        state
+            .forensics()
+            .record_reconnect(key, false, Some(msg.clone()));
+        let mut entry = ForensicEntry::new(key, args.url(), DisconnectKind::HttpStatus, &msg);
+        entry.http_status = Some(status.as_u16());
+        state.forensics().record(entry);
+        if retryable_status(status) {
+            return StreamEnd::Retry {
+                result: Err(msg),
+                events_seen: false,
+            };
+        }
         emit(
-            &on_event,
+            on_event,
             BridgeEvent::Error {
                 message: msg.clone(),
             },
         );
-        state.remove_if_current(&key, conn_id);
-        return Err(msg);
+        state.remove_if_current(key, conn_id);
+        return StreamEnd::Done(Err(msg));
     }
 
-    emit(&on_event, BridgeEvent::Connected);
+    emit(on_event, BridgeEvent::Connected);
+    state.forensics().record_reconnect(key, true, None);
+    state.stats().update(key, conn_id, |stats| {
+        stats.connected_since = Some(crate::app::now_millis())
+    });
 
-    // Read timeout — if no data arrives for 90s the connection is likely dead
-    const READ_TIMEOUT: Duration = Duration::from_secs(90);
+    // Replay events received while the webview was being recovered
+    emit_sse_blocks(on_event, args, state.take_replay(key));
+
+    // Read timeout — if no data arrives for this long the connection is likely dead
+    let read_timeout_limit = args.read_timeout();
     let mut stream = response.bytes_stream();
     let mut pending_utf8 = Vec::new();
+    let mut framer =
+        SseFramer::new(args.max_event_bytes()).with_last_event_id(resume.last_event_id.clone());
+    let mut validator = args.validate_events().then(EventValidator::default);
+    let mut batch = args
+        .batch()
+        .map(|(interval, max_batch)| Batcher::new(interval, max_batch));
+    let mut total_bytes: u64 = 0;
+    let mut events_seen = false;
+    let connected_at = tokio::time::Instant::now();
+    // Silence is measured from the last chunk, so heartbeats and batch
+    // flushes waking the loop don't push the read timeout back
+    let mut last_data = connected_at;
+    let mut read_since = connected_at;
+    let heartbeat = args.heartbeat();
+    let mut next_heartbeat = heartbeat.map(|every| connected_at + every);
+    let throughput = args.throughput_interval();
+    let mut next_throughput = throughput.map(|every| connected_at + every);
+    let record = |kind: DisconnectKind, message: &str, bytes: u64| {
+        let mut entry = ForensicEntry::new(key, args.url(), kind, message);
+        entry.bytes_since_connect = bytes;
+        entry.connected_ms = Some(connected_at.elapsed().as_millis() as u64);
+        state.forensics().record(entry);
+    };
 
     loop {
         // Check cancellation (disconnect or replaced by a new connect)
-        if !state.is_current(&key, conn_id) {
+        if !state.is_current(key, conn_id) {
+            record(
+                DisconnectKind::ClientClosed,
+                "Disconnected by client",
+                total_bytes,
+            );
+            flush_batch(on_event, batch.as_mut());
             emit(
-                &on_event,
+                on_event,
                 BridgeEvent::Disconnected {
                     code: None,
                     reason: "Disconnected by client".to_string(),
                 },
             );
-            return Ok(());
+            return StreamEnd::Done(Ok(()));
         }
 
-        match tokio::time::timeout(READ_TIMEOUT, stream.next()).await {
-            Ok(Some(Ok(chunk))) => {
-                emit_stream_chunk(&on_event, &mut pending_utf8, chunk.as_ref());
+        // Until the first event arrives, wake up early to check whether an
+        // intermediary is buffering the stream
+        let read_timeout = if events_seen || args.poll_fallback_interval().is_none() {
+            read_timeout_limit
+        } else {
+            FALLBACK_DETECT_AFTER.min(read_timeout_limit)
+        };
+
+        let next = tokio::select! {
+            next = tokio::time::timeout_at(read_since + read_timeout, stream.next()) => next,
+            // Drops the response and its socket right away; the
+            // cancellation is reported at the top of the loop
+            _ = cancel.notified() => continue,
+            _ = batch_due(batch.as_ref()) => {
+                flush_batch(on_event, batch.as_mut());
+                continue;
             }
-            Ok(Some(Err(e))) => {
-                let msg = format!("HTTP stream error: {}", e);
+            _ = sleep_until_some(next_heartbeat) => {
+                next_heartbeat = next_heartbeat.zip(heartbeat).map(|(at, every)| at + every);
                 emit(
-                    &on_event,
-                    BridgeEvent::Error {
-                        message: msg.clone(),
+                    on_event,
+                    BridgeEvent::Heartbeat {
+                        idle_secs: last_data.elapsed().as_secs(),
                     },
                 );
-                state.remove_if_current(&key, conn_id);
-                return Err(msg);
+                continue;
             }
-            Ok(None) => {
-                state.remove_if_current(&key, conn_id);
-                emit(
-                    &on_event,
-                    BridgeEvent::Disconnected {
-                        code: None,
-                        reason: "Stream ended".to_string(),
-                    },
+            _ = sleep_until_some(next_throughput) => {
+                next_throughput = next_throughput.zip(throughput).map(|(at, every)| at + every);
+                if let Some(sample) = state.stats().sample_throughput(key, conn_id) {
+                    emit(
+                        on_event,
+                        BridgeEvent::Throughput {
+                            bytes_per_sec: sample.bytes_per_sec,
+                            total_bytes: sample.total_bytes,
+                        },
+                    );
+                }
+                continue;
+            }
+        };
+        // Deliver batched events before reporting how the stream ended
+        if !matches!(next, Ok(Some(Ok(_)))) {
+            flush_batch(on_event, batch.as_mut());
+        }
+        match next {
+            Ok(Some(Ok(chunk))) => {
+                last_data = tokio::time::Instant::now();
+                read_since = last_data;
+                next_heartbeat = heartbeat.map(|every| last_data + every);
+                total_bytes += chunk.len() as u64;
+                if let Some(limit) = args.max_connection_bytes().filter(|l| total_bytes > *l) {
+                    let msg = format!("HTTP stream exceeded {} bytes, closing", limit);
+                    record(DisconnectKind::PayloadTooLarge, &msg, total_bytes);
+                    flush_batch(on_event, batch.as_mut());
+                    emit(
+                        on_event,
+                        BridgeEvent::PayloadTooLarge {
+                            scope: "connection",
+                            limit,
+                            size: total_bytes,
+                        },
+                    );
+                    state.remove_if_current(key, conn_id);
+                    return StreamEnd::Done(Err(msg));
+                }
+                let events = emit_stream_chunk(
+                    on_event,
+                    state,
+                    key,
+                    conn_id,
+                    args,
+                    &mut pending_utf8,
+                    &mut framer,
+                    validator.as_mut(),
+                    batch.as_mut(),
+                    chunk.as_ref(),
                 );
-                return Ok(());
+                events_seen |= events > 0;
+                state
+                    .stats()
+                    .received(key, conn_id, chunk.len() as u64, events as u64);
+                if events > 0 && framer.last_event_id() != resume.last_event_id.as_deref() {
+                    resume.last_event_id = framer.last_event_id().map(str::to_string);
+                }
+                if let Some(ms) = framer.retry_ms().filter(|ms| resume.retry_ms != Some(*ms)) {
+                    resume.retry_ms = Some(ms);
+                    emit(on_event, BridgeEvent::RetryHint { ms });
+                }
+            }
+            Ok(Some(Err(e))) => {
+                if !events_seen {
+                    if let Some(result) =
+                        poll_fallback(client, state, key, conn_id, cancel, on_event, args).await
+                    {
+                        return StreamEnd::Done(result);
+                    }
+                }
+                let msg = format!("HTTP stream error: {}", e);
+                record(DisconnectKind::StreamError, &msg, total_bytes);
+                return StreamEnd::Retry {
+                    result: Err(msg),
+                    events_seen,
+                };
+            }
+            Ok(None) => {
+                if !events_seen {
+                    if let Some(result) =
+                        poll_fallback(client, state, key, conn_id, cancel, on_event, args).await
+                    {
+                        return StreamEnd::Done(result);
+                    }
+                }
+                record(DisconnectKind::StreamEnded, "Stream ended", total_bytes);
+                return StreamEnd::Retry {
+                    result: Ok(()),
+                    events_seen,
+                };
             }
             Err(_) => {
+                if !events_seen {
+                    if let Some(result) =
+                        poll_fallback(client, state, key, conn_id, cancel, on_event, args).await
+                    {
+                        return StreamEnd::Done(result);
+                    }
+                    // Server unreachable as well: keep waiting for the stream
+                    if connected_at.elapsed() < read_timeout_limit {
+                        read_since = tokio::time::Instant::now();
+                        continue;
+                    }
+                }
                 let msg = format!(
                     "HTTP stream read timeout ({}s without data)",
-                    READ_TIMEOUT.as_secs()
-                );
-                emit(
-                    &on_event,
-                    BridgeEvent::Error {
-                        message: msg.clone(),
-                    },
+                    read_timeout_limit.as_secs()
                 );
-                state.remove_if_current(&key, conn_id);
-                return Err(msg);
+                record(DisconnectKind::ReadTimeout, &msg, total_bytes);
+                return StreamEnd::Retry {
+                    result: Err(msg),
+                    events_seen,
+                };
             }
         }
     }
@@ -268,6 +1037,23 @@ mod tests {
             Some(("中文".to_string(), "中文".len()))
         );
     }
+
+    #[test]
+    fn split_valid_utf8_prefix_reassembles_sequences_across_chunks() {
+        let text = "回复🙂完成";
+        let mut pending = Vec::new();
+        let mut decoded = String::new();
+        // One byte per chunk: every multi-byte sequence is split
+        for byte in text.as_bytes() {
+            pending.push(*byte);
+            while let Some((part, consumed)) = split_valid_utf8_prefix(&pending) {
+                pending.drain(..consumed);
+                decoded.push_str(&part);
+            }
+        }
+        assert!(pending.is_empty());
+        assert_eq!(decoded, text);
+    }
 }
 
 // ============================================
@@ -275,25 +1061,23 @@ mod tests {
 // ============================================
 
 async fn connect_ws(
-    window: tauri::Window,
+    webview: tauri::Webview,
     state: State<'_, BridgeState>,
     args: ConnectArgs,
     on_event: Channel<BridgeEvent>,
 ) -> Result<(), String> {
     use tokio_tungstenite::{
-        connect_async,
-        tungstenite::{client::IntoClientRequest, http::HeaderValue, Error as WsError, Message},
+        connect_async_with_config,
+        tungstenite::{client::IntoClientRequest, Error as WsError, Message},
     };
 
     let conn_id = state.next_conn_id();
-    let key = BridgeKey::new(window.label(), args.bridge_id());
+    let key = BridgeKey::new(webview.label(), args.bridge_id());
     let (tx, mut rx) = mpsc::unbounded_channel();
 
     // Replace any previous connection with the same key
     if let Some(prev) = state.replace(key.clone(), BridgeConnection::new_ws(conn_id, tx)) {
-        if let Some(prev_tx) = prev.tx {
-            let _ = prev_tx.send(BridgeCommand::Close);
-        }
+        prev.close();
     }
 
     let mut request = args
@@ -301,31 +1085,28 @@ async fn connect_ws(
         .into_client_request()
         .map_err(|e| format!("invalid WebSocket URL: {}", e))?;
 
-    if let Some(auth) = args.auth_header() {
-        let value = HeaderValue::from_str(auth)
-            .map_err(|e| format!("invalid Authorization header: {}", e))?;
-        request.headers_mut().insert("Authorization", value);
-    }
+    request.headers_mut().extend(args.header_map()?);
 
-    let (ws_stream, _) = match connect_async(request).await {
-        Ok(result) => result,
-        Err(error) => {
-            let message = match error {
-                WsError::Http(response) => {
-                    format!("WebSocket server returned {}", response.status())
-                }
-                other => format!("WebSocket connection failed: {}", other),
-            };
-            emit(
-                &on_event,
-                BridgeEvent::Error {
-                    message: message.clone(),
-                },
-            );
-            state.remove_if_current(&key, conn_id);
-            return Err(message);
-        }
-    };
+    let (ws_stream, _) =
+        match connect_async_with_config(request, None, args.http().ws_disable_nagle()).await {
+            Ok(result) => result,
+            Err(error) => {
+                let message = match error {
+                    WsError::Http(response) => {
+                        format!("WebSocket server returned {}", response.status())
+                    }
+                    other => format!("WebSocket connection failed: {}", other),
+                };
+                emit(
+                    &on_event,
+                    BridgeEvent::Error {
+                        message: message.clone(),
+                    },
+                );
+                state.remove_if_current(&key, conn_id);
+                return Err(message);
+            }
+        };
 
     emit(&on_event, BridgeEvent::Connected);
 
@@ -355,12 +1136,13 @@ async fn connect_ws(
             inbound = read.next() => match inbound {
                 Some(Ok(message)) => match message {
                     Message::Text(text) => {
-                        emit(&on_event, BridgeEvent::Data { data: text.to_string() });
+                        emit(&on_event, BridgeEvent::data(text.to_string()));
                     }
                     Message::Binary(bytes) => {
-                        emit(&on_event, BridgeEvent::Data {
-                            data: String::from_utf8_lossy(&bytes).into_owned(),
-                        });
+                        emit(
+                            &on_event,
+                            BridgeEvent::data(String::from_utf8_lossy(&bytes).into_owned()),
+                        );
                     }
                     Message::Ping(payload) => {
                         if let Err(error) = write.send(Message::Pong(payload)).await {
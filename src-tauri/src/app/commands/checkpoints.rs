@@ -0,0 +1,72 @@
+use crate::app::{
+    checkpoints::{Checkpoint, CheckpointsState},
+    workspace_diff::{self, DiffSummary, FileDiff},
+};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{Emitter, Manager, State};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkspaceDiffChunk {
+    checkpoint_id: String,
+    files: Vec<FileDiff>,
+}
+
+/// 列出检查点，可按项目目录过滤
+#[tauri::command]
+pub fn list_checkpoints(
+    state: State<'_, CheckpointsState>,
+    directory: Option<String>,
+) -> Vec<Checkpoint> {
+    state.list(directory.as_deref())
+}
+
+/// 为项目当前工作区创建检查点
+#[tauri::command]
+pub async fn create_checkpoint(
+    app: tauri::AppHandle,
+    directory: String,
+    label: Option<String>,
+) -> Result<Checkpoint, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        app.state::<CheckpointsState>()
+            .create(Path::new(&directory), label)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub fn delete_checkpoint(state: State<'_, CheckpointsState>, id: String) -> Result<bool, String> {
+    state.remove(&id)
+}
+
+/// 比较检查点与当前工作区。按文件分批发出 `workspace-diff-chunk` 事件，
+/// 全部完成后返回统计信息。
+#[tauri::command]
+pub async fn diff_workspace(
+    webview: tauri::Webview,
+    state: State<'_, CheckpointsState>,
+    checkpoint_id: String,
+) -> Result<DiffSummary, String> {
+    let checkpoint = state
+        .get(&checkpoint_id)
+        .ok_or_else(|| format!("checkpoint '{}' not found", checkpoint_id))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let root = PathBuf::from(&checkpoint.directory);
+        workspace_diff::diff_tree_to_workdir(&root, &checkpoint.tree, &mut |files| {
+            let _ = webview.emit_to(
+                webview.label(),
+                "workspace-diff-chunk",
+                WorkspaceDiffChunk {
+                    checkpoint_id: checkpoint.id.clone(),
+                    files,
+                },
+            );
+        })
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
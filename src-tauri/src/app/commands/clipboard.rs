@@ -0,0 +1,122 @@
+use crate::app::{
+    api::{self, ServerTarget},
+    clipboard::{extract_latest_artifact, Artifact, ClipboardEntry, ClipboardHistory},
+    http_tuning::HttpTuning,
+};
+use serde::Deserialize;
+use tauri::State;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyArgs {
+    text: String,
+    language: Option<String>,
+    session_id: Option<String>,
+    message_id: Option<String>,
+}
+
+/// 安全模式下不注册剪贴板插件
+fn ensure_clipboard() -> Result<(), String> {
+    if crate::app::safe_mode::active() {
+        return Err("clipboard is disabled in safe mode".to_string());
+    }
+    Ok(())
+}
+
+/// 写入系统剪贴板并记录到历史（内容不写日志）
+#[tauri::command]
+pub fn copy_to_clipboard(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistory>,
+    args: CopyArgs,
+) -> Result<u64, String> {
+    ensure_clipboard()?;
+    app.clipboard()
+        .write_text(args.text.clone())
+        .map_err(|e| e.to_string())?;
+
+    let id = state.push(args.text, args.language, args.session_id, args.message_id);
+    log::info!("Copied clipboard entry #{}", id);
+    Ok(id)
+}
+
+/// 列出剪贴板历史（最新在前）
+#[tauri::command]
+pub fn list_clipboard_history(state: State<'_, ClipboardHistory>) -> Vec<ClipboardEntry> {
+    state.list()
+}
+
+/// 重新复制某条历史记录
+#[tauri::command]
+pub fn recopy_clipboard_entry(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistory>,
+    id: u64,
+) -> Result<(), String> {
+    ensure_clipboard()?;
+    let entry = state
+        .promote(id)
+        .ok_or_else(|| format!("clipboard entry #{} not found", id))?;
+
+    app.clipboard()
+        .write_text(entry.text)
+        .map_err(|e| e.to_string())
+}
+
+/// 清空剪贴板历史（不影响系统剪贴板当前内容）
+#[tauri::command]
+pub fn clear_clipboard_history(state: State<'_, ClipboardHistory>) {
+    state.clear();
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CopyLatestArtifactArgs {
+    url: String,
+    session_id: String,
+    directory: Option<String>,
+    auth_header: Option<String>,
+    /// 服务器条目 id；设置后从钥匙串取凭据并应用该服务器的网络设置
+    server_id: Option<String>,
+    #[serde(default)]
+    http: HttpTuning,
+}
+
+/// 复制会话中最近一次产出的代码块或文件路径，供快捷键直接调用
+#[tauri::command]
+pub async fn copy_latest_artifact(
+    app: tauri::AppHandle,
+    state: State<'_, ClipboardHistory>,
+    args: CopyLatestArtifactArgs,
+) -> Result<Option<Artifact>, String> {
+    ensure_clipboard()?;
+    let target = ServerTarget {
+        url: args.url,
+        directory: args.directory,
+        auth_header: args.auth_header,
+        server_id: args.server_id,
+        http: args.http,
+    };
+    let messages = api::get_json(&target, &format!("/session/{}/message", args.session_id)).await?;
+
+    let Some(artifact) = extract_latest_artifact(&messages) else {
+        return Ok(None);
+    };
+
+    let (text, language, message_id) = match &artifact {
+        Artifact::Code {
+            text,
+            language,
+            message_id,
+        } => (text.clone(), language.clone(), message_id.clone()),
+        Artifact::File { path, message_id } => (path.clone(), None, message_id.clone()),
+    };
+
+    app.clipboard()
+        .write_text(text.clone())
+        .map_err(|e| e.to_string())?;
+    state.push(text, language, Some(args.session_id), message_id);
+
+    Ok(Some(artifact))
+}
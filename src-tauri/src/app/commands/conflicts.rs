@@ -0,0 +1,20 @@
+use crate::app::conflicts::{ConflictState, FileConflict};
+use std::path::Path;
+use tauri::State;
+
+/// 列出尚未确认的编辑冲突
+#[tauri::command]
+pub fn list_file_conflicts(state: State<'_, ConflictState>) -> Vec<FileConflict> {
+    state.list()
+}
+
+/// 查询某个文件是否存在冲突（批准 agent 编辑前调用）
+#[tauri::command]
+pub fn get_file_conflict(state: State<'_, ConflictState>, path: String) -> Option<FileConflict> {
+    state.get(Path::new(&path))
+}
+
+#[tauri::command]
+pub fn dismiss_file_conflict(state: State<'_, ConflictState>, path: String) -> bool {
+    state.dismiss(Path::new(&path))
+}
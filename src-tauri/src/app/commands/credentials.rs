@@ -0,0 +1,24 @@
+use crate::app::credentials::{self, ServerCredential};
+
+/// 保存服务器凭据到系统钥匙串（按服务器 id）
+#[tauri::command]
+pub fn set_server_credential(
+    server_id: String,
+    credential: ServerCredential,
+) -> Result<(), String> {
+    credentials::store(&server_id, &credential)?;
+    log::info!("Saved credential for server '{}'", server_id);
+    Ok(())
+}
+
+/// 删除服务器凭据，返回之前是否存在
+#[tauri::command]
+pub fn delete_server_credential(server_id: String) -> Result<bool, String> {
+    credentials::remove(&server_id)
+}
+
+/// 服务器是否已保存凭据（不返回凭据内容）
+#[tauri::command]
+pub fn has_server_credential(server_id: String) -> Result<bool, String> {
+    Ok(credentials::auth_header(&server_id)?.is_some())
+}
@@ -0,0 +1,11 @@
+use crate::app::diagnostics;
+
+/// 生成诊断包（日志、服务输出、版本与系统信息、去除敏感字段的设置、SSE 录制），
+/// 保存到下载目录并返回文件路径
+#[tauri::command]
+pub async fn export_diagnostics(app: tauri::AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || diagnostics::export(&app))
+        .await
+        .map_err(|e| e.to_string())?
+        .map(|path| path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,276 @@
+// ============================================
+// Environment Doctor (desktop only)
+// 检查 onboarding 依赖的环境，返回结构化报告与修复建议
+// ============================================
+
+use crate::app::{
+    arch::Compatibility,
+    commands::opencode::{
+        discover_opencode_binary, is_service_running, probe_version, BinaryDiscovery,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, net::TcpListener, time::Duration};
+
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorCheck {
+    id: &'static str,
+    status: CheckStatus,
+    message: String,
+    fix: Option<String>,
+}
+
+impl DoctorCheck {
+    fn new(id: &'static str, status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            id,
+            status,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DoctorReport {
+    /// 所有检查均无 Error
+    healthy: bool,
+    checks: Vec<DoctorCheck>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DoctorArgs {
+    binary_path: Option<String>,
+    url: Option<String>,
+    env_vars: HashMap<String, String>,
+}
+
+fn check_opencode_binary(args: &DoctorArgs) -> Vec<DoctorCheck> {
+    let discovery = args
+        .binary_path
+        .as_deref()
+        .filter(|path| !path.trim().is_empty())
+        .map(|path| BinaryDiscovery::inspect(std::path::Path::new(path)))
+        .or_else(|| discover_opencode_binary(&args.env_vars));
+
+    let Some(discovery) = discovery else {
+        return vec![DoctorCheck::new(
+            "opencode",
+            CheckStatus::Error,
+            "opencode executable not found",
+        )
+        .with_fix("Install opencode (npm i -g opencode-ai) or set its path in settings")];
+    };
+
+    let mut checks = vec![check_opencode_version(discovery.path())];
+    let arch_status = match discovery.compatibility() {
+        Compatibility::Native | Compatibility::Unknown => None,
+        Compatibility::Emulated => Some(CheckStatus::Warning),
+        Compatibility::Incompatible => Some(CheckStatus::Error),
+    };
+    if let (Some(status), Some(warning)) = (arch_status, discovery.warning()) {
+        checks.push(
+            DoctorCheck::new("arch", status, warning)
+                .with_fix("Reinstall opencode from a shell running natively on this machine"),
+        );
+    }
+    checks
+}
+
+fn check_opencode_version(binary: &str) -> DoctorCheck {
+    match probe_version(binary, VERSION_TIMEOUT) {
+        Some(version) => DoctorCheck::new(
+            "opencode",
+            CheckStatus::Ok,
+            format!("{} ({})", version, binary),
+        ),
+        None => DoctorCheck::new(
+            "opencode",
+            CheckStatus::Error,
+            format!("'{}' did not respond to --version", binary),
+        )
+        .with_fix("Check that the binary is executable and built for this platform"),
+    }
+}
+
+fn check_tool(id: &'static str, program: &str, required: bool, fix: &str) -> DoctorCheck {
+    match probe_version(program, VERSION_TIMEOUT) {
+        Some(version) => DoctorCheck::new(id, CheckStatus::Ok, version),
+        None => {
+            let status = if required {
+                CheckStatus::Error
+            } else {
+                CheckStatus::Warning
+            };
+            DoctorCheck::new(id, status, format!("{} not found on PATH", program)).with_fix(fix)
+        }
+    }
+}
+
+fn server_port(url: &str) -> Option<(String, u16)> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    let host = parsed.host_str()?.to_string();
+    Some((host, parsed.port_or_known_default()?))
+}
+
+fn is_local_host(host: &str) -> bool {
+    matches!(
+        host,
+        "127.0.0.1" | "localhost" | "0.0.0.0" | "::1" | "[::1]"
+    )
+}
+
+async fn check_server(url: Option<&str>) -> Vec<DoctorCheck> {
+    let Some(url) = url.filter(|url| !url.trim().is_empty()) else {
+        return vec![DoctorCheck::new(
+            "server",
+            CheckStatus::Skipped,
+            "No server URL configured",
+        )];
+    };
+
+    let Some((host, port)) = server_port(url) else {
+        return vec![DoctorCheck::new(
+            "server",
+            CheckStatus::Error,
+            format!("Invalid URL '{}'", url),
+        )
+        .with_fix("Use a full URL such as http://127.0.0.1:4096")];
+    };
+
+    if is_service_running(url).await {
+        return vec![DoctorCheck::new(
+            "server",
+            CheckStatus::Ok,
+            format!("Server reachable at {}", url),
+        )];
+    }
+
+    let mut checks = vec![DoctorCheck::new(
+        "server",
+        CheckStatus::Warning,
+        format!("Server not reachable at {}", url),
+    )
+    .with_fix("Start opencode serve or let the app start a managed service")];
+
+    // 本地地址无响应时，检查端口是否被其他程序占用
+    if is_local_host(&host) {
+        let port_check = match TcpListener::bind(("127.0.0.1", port)) {
+            Ok(_) => DoctorCheck::new("port", CheckStatus::Ok, format!("Port {} is free", port)),
+            Err(e) => DoctorCheck::new(
+                "port",
+                CheckStatus::Error,
+                format!("Port {} is in use by another program: {}", port, e),
+            )
+            .with_fix("Stop the program using this port or configure a different port"),
+        };
+        checks.push(port_check);
+    }
+
+    checks
+}
+
+fn check_config_dir(app: &tauri::AppHandle) -> DoctorCheck {
+    let Some(dir) = crate::app::profile::config_dir(app) else {
+        return DoctorCheck::new(
+            "filesystem",
+            CheckStatus::Error,
+            "App config directory unavailable",
+        );
+    };
+
+    let probe = dir.join(".doctor-probe");
+    let result = std::fs::create_dir_all(&dir)
+        .and_then(|_| std::fs::write(&probe, b"ok"))
+        .and_then(|_| std::fs::remove_file(&probe));
+
+    match result {
+        Ok(()) => DoctorCheck::new(
+            "filesystem",
+            CheckStatus::Ok,
+            format!("{} is writable", dir.display()),
+        ),
+        Err(e) => DoctorCheck::new(
+            "filesystem",
+            CheckStatus::Error,
+            format!("Cannot write to {}: {}", dir.display(), e),
+        )
+        .with_fix("Check the permissions of the app config directory"),
+    }
+}
+
+fn check_keychain() -> DoctorCheck {
+    match crate::app::credentials::probe() {
+        Ok(()) => DoctorCheck::new("keychain", CheckStatus::Ok, "System keychain is accessible"),
+        Err(e) => {
+            let fix = if cfg!(target_os = "macos") {
+                "Unlock the login keychain in Keychain Access"
+            } else if cfg!(windows) {
+                "Make sure Windows Credential Manager is available for your account"
+            } else {
+                "Install and start a Secret Service provider (GNOME Keyring or KWallet) and unlock its default collection"
+            };
+            DoctorCheck::new("keychain", CheckStatus::Warning, e).with_fix(fix)
+        }
+    }
+}
+
+/// 运行环境检查，返回结构化报告
+#[tauri::command]
+pub async fn run_doctor(
+    app: tauri::AppHandle,
+    args: Option<DoctorArgs>,
+) -> Result<DoctorReport, String> {
+    let args = args.unwrap_or_default();
+    let url = args.url.clone();
+
+    // 外部命令探测是阻塞的，放到阻塞线程池执行
+    let mut checks = tauri::async_runtime::spawn_blocking(move || {
+        let mut checks = check_opencode_binary(&args);
+        checks.extend([
+            check_tool(
+                "node",
+                "node",
+                false,
+                "Install Node.js if your providers or MCP servers need it",
+            ),
+            check_tool(
+                "git",
+                "git",
+                true,
+                "Install git; opencode uses it for snapshots and diffs",
+            ),
+            check_keychain(),
+        ]);
+        checks
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+
+    checks.extend(check_server(url.as_deref()).await);
+    checks.push(check_config_dir(&app));
+
+    let healthy = checks
+        .iter()
+        .all(|check| check.status != CheckStatus::Error);
+    Ok(DoctorReport { healthy, checks })
+}
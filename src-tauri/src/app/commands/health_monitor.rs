@@ -0,0 +1,40 @@
+use crate::app::{
+    health_monitor::{
+        HealthMonitorConfig, HealthMonitorState, MonitoredServer, ServerStatusChange,
+        HEALTH_MONITOR_SETTINGS_KEY,
+    },
+    settings::SettingsStore,
+};
+use tauri::State;
+
+/// 登记需要后台检查的服务器（替换之前的列表）；托管实例无需登记
+#[tauri::command]
+pub fn set_monitored_servers(state: State<'_, HealthMonitorState>, servers: Vec<MonitoredServer>) {
+    state.set_servers(servers);
+}
+
+/// 各服务器当前的状态；尚未完成首次检查的服务器不在其中
+#[tauri::command]
+pub fn get_server_statuses(state: State<'_, HealthMonitorState>) -> Vec<ServerStatusChange> {
+    state.statuses()
+}
+
+/// 读取后台健康检查设置
+#[tauri::command]
+pub fn get_health_monitor_config(
+    state: State<'_, HealthMonitorState>,
+) -> Result<HealthMonitorConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// 更新后台健康检查设置（持久化到设置存储，下一轮检查生效）
+#[tauri::command]
+pub fn set_health_monitor_config(
+    state: State<'_, HealthMonitorState>,
+    settings: State<'_, SettingsStore>,
+    config: HealthMonitorConfig,
+) -> Result<(), String> {
+    settings.set_as(HEALTH_MONITOR_SETTINGS_KEY, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
@@ -0,0 +1,19 @@
+use crate::app::installer::{self, InstalledOpencode, ReleaseInfo};
+
+/// 查询当前平台可安装的最新 opencode 版本
+#[tauri::command]
+pub async fn check_opencode_release(app: tauri::AppHandle) -> Result<ReleaseInfo, String> {
+    installer::check(&app).await
+}
+
+/// 下载、校验并安装最新 opencode，进度通过 `opencode-install-progress` 事件报告
+#[tauri::command]
+pub async fn install_opencode(app: tauri::AppHandle) -> Result<InstalledOpencode, String> {
+    installer::install(&app).await
+}
+
+/// 已通过应用安装的 opencode
+#[tauri::command]
+pub fn get_installed_opencode(app: tauri::AppHandle) -> Option<InstalledOpencode> {
+    installer::installed(&app)
+}
@@ -0,0 +1,65 @@
+use crate::app::{
+    format::FormatValue,
+    i18n::{Language, LanguageState, LANGUAGE_SETTINGS_KEY},
+    settings::SettingsStore,
+};
+use serde::Serialize;
+use tauri::{Emitter, State};
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppLanguage {
+    /// None 表示跟随系统
+    preference: Option<Language>,
+    current: Language,
+}
+
+fn app_language(state: &LanguageState) -> AppLanguage {
+    AppLanguage {
+        preference: state.preference(),
+        current: state.current(),
+    }
+}
+
+/// 用当前语言重新渲染原生菜单 / 托盘等
+pub(crate) fn refresh_native_ui(app: &tauri::AppHandle, lang: Language) {
+    #[cfg(target_os = "macos")]
+    crate::app::menu::apply_app_menu(app, lang);
+    crate::app::tray::apply_menu(app, lang);
+}
+
+/// 获取界面语言设置
+#[tauri::command]
+pub fn get_app_language(state: State<'_, LanguageState>) -> AppLanguage {
+    app_language(&state)
+}
+
+/// 设置界面语言（None 跟随系统），立即刷新原生菜单并通知所有窗口
+#[tauri::command]
+pub fn set_app_language(
+    app: tauri::AppHandle,
+    state: State<'_, LanguageState>,
+    settings: State<'_, SettingsStore>,
+    language: Option<Language>,
+) -> Result<AppLanguage, String> {
+    match language {
+        Some(language) => settings.set_as(LANGUAGE_SETTINGS_KEY, &language)?,
+        None => settings.remove(LANGUAGE_SETTINGS_KEY)?,
+    }
+    state.set_preference(language);
+
+    let result = app_language(&state);
+    refresh_native_ui(&app, result.current);
+    let _ = app.emit("app-language-changed", result.clone());
+    Ok(result)
+}
+
+/// 按当前界面语言格式化一组值（时间、数字、大小、金额、时长），供生成导出文件与报告使用
+#[tauri::command]
+pub fn format_values(state: State<'_, LanguageState>, values: Vec<FormatValue>) -> Vec<String> {
+    let language = state.current();
+    values
+        .into_iter()
+        .map(|value| value.format(language))
+        .collect()
+}
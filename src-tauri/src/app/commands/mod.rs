@@ -1,5 +1,81 @@
+#[cfg(not(target_os = "android"))]
+pub mod appearance;
 pub mod bridge;
 #[cfg(not(target_os = "android"))]
+pub mod checkpoints;
+#[cfg(not(target_os = "android"))]
+pub mod clipboard;
+#[cfg(not(target_os = "android"))]
+pub mod conflicts;
+#[cfg(not(target_os = "android"))]
+pub mod credentials;
+#[cfg(not(target_os = "android"))]
+pub mod diagnostics;
+#[cfg(not(target_os = "android"))]
+pub mod doctor;
+#[cfg(not(target_os = "android"))]
+pub mod health_monitor;
+#[cfg(not(target_os = "android"))]
+pub mod installer;
+#[cfg(not(target_os = "android"))]
+pub mod language;
+#[cfg(not(target_os = "android"))]
+pub mod models;
+#[cfg(not(target_os = "android"))]
+pub mod network;
+#[cfg(not(target_os = "android"))]
+pub mod onboarding;
+#[cfg(not(target_os = "android"))]
 pub mod opencode;
 #[cfg(not(target_os = "android"))]
+pub mod palette;
+#[cfg(not(target_os = "android"))]
+pub mod permissions;
+#[cfg(not(target_os = "android"))]
+pub mod presence;
+#[cfg(not(target_os = "android"))]
+pub mod presentation;
+#[cfg(not(target_os = "android"))]
+pub mod profile;
+#[cfg(not(target_os = "android"))]
+pub mod projects;
+#[cfg(not(target_os = "android"))]
+pub mod run_timers;
+#[cfg(not(target_os = "android"))]
+pub mod safe_mode;
+#[cfg(not(target_os = "android"))]
+pub mod scheduler;
+#[cfg(not(target_os = "android"))]
+pub mod server_profiles;
+#[cfg(not(target_os = "android"))]
+pub mod service_autostart;
+#[cfg(not(target_os = "android"))]
+pub mod service_stats;
+#[cfg(not(target_os = "android"))]
+pub mod service_validation;
+#[cfg(not(target_os = "android"))]
+pub mod session_branches;
+#[cfg(not(target_os = "android"))]
+pub mod session_marks;
+#[cfg(not(target_os = "android"))]
+pub mod session_tree;
+#[cfg(not(target_os = "android"))]
+pub mod share;
+#[cfg(not(target_os = "android"))]
+pub mod shortcuts;
+#[cfg(not(target_os = "android"))]
+pub mod split_view;
+#[cfg(not(target_os = "android"))]
+pub mod storage;
+#[cfg(not(target_os = "android"))]
+pub mod transcripts;
+#[cfg(not(target_os = "android"))]
+pub mod transfer;
+#[cfg(not(target_os = "android"))]
 pub mod utils;
+#[cfg(not(target_os = "android"))]
+pub mod watch;
+#[cfg(not(target_os = "android"))]
+pub mod window_context;
+#[cfg(not(target_os = "android"))]
+pub mod workspace_autolaunch;
@@ -0,0 +1,21 @@
+use crate::app::{
+    api::ServerTarget,
+    model_catalog::{ModelCatalog, ModelCatalogState},
+};
+use tauri::{Emitter, State};
+
+/// 列出 provider / 模型目录（带缓存）。目录内容变化时额外发出
+/// `model-catalog-changed` 事件，方便其他窗口刷新模型选择器。
+#[tauri::command]
+pub async fn list_models(
+    app: tauri::AppHandle,
+    state: State<'_, ModelCatalogState>,
+    target: ServerTarget,
+    force_refresh: Option<bool>,
+) -> Result<ModelCatalog, String> {
+    let result = state.list(&target, force_refresh.unwrap_or(false)).await?;
+    if result.changed {
+        let _ = app.emit("model-catalog-changed", &result);
+    }
+    Ok(result)
+}
@@ -0,0 +1,71 @@
+// ============================================
+// Network Settings (desktop only)
+// ============================================
+
+use crate::app::{
+    dns::{self, DohConfig, DOH_SETTINGS_KEY},
+    network::{
+        self, ClientIdentity, NetworkConfig, CLIENT_IDENTITIES_SETTINGS_KEY, NETWORK_SETTINGS_KEY,
+    },
+    settings::SettingsStore,
+};
+use std::collections::HashMap;
+use tauri::State;
+
+/// 读取 DNS-over-HTTPS 设置
+#[tauri::command]
+pub fn get_doh_config(settings: State<'_, SettingsStore>) -> DohConfig {
+    settings.get_as(DOH_SETTINGS_KEY).unwrap_or_default()
+}
+
+/// 更新 DNS-over-HTTPS 设置，立即对新建的连接生效
+#[tauri::command]
+pub fn set_doh_config(settings: State<'_, SettingsStore>, config: DohConfig) -> Result<(), String> {
+    dns::configure(&config)?;
+    settings.set_as(DOH_SETTINGS_KEY, &config)
+}
+
+/// 读取网络设置（代理、额外根证书、证书校验）
+#[tauri::command]
+pub fn get_network_config(settings: State<'_, SettingsStore>) -> NetworkConfig {
+    settings.get_as(NETWORK_SETTINGS_KEY).unwrap_or_default()
+}
+
+/// 更新网络设置，立即对新建的连接（bridge、健康检查、API 调用）生效
+#[tauri::command]
+pub fn set_network_config(
+    settings: State<'_, SettingsStore>,
+    config: NetworkConfig,
+) -> Result<(), String> {
+    network::configure(&config)?;
+    settings.set_as(NETWORK_SETTINGS_KEY, &config)
+}
+
+/// 读取某个服务器的客户端证书设置
+#[tauri::command]
+pub fn get_client_identity(
+    settings: State<'_, SettingsStore>,
+    server_id: String,
+) -> Option<ClientIdentity> {
+    settings
+        .get_as::<HashMap<String, ClientIdentity>>(CLIENT_IDENTITIES_SETTINGS_KEY)
+        .and_then(|mut identities| identities.remove(&server_id))
+}
+
+/// 设置（`None` 为移除）某个服务器的 mTLS 客户端证书；证书无法加载时报错，不保存
+#[tauri::command]
+pub fn set_client_identity(
+    settings: State<'_, SettingsStore>,
+    server_id: String,
+    identity: Option<ClientIdentity>,
+) -> Result<(), String> {
+    network::set_identity(&server_id, identity.as_ref())?;
+    let mut identities: HashMap<String, ClientIdentity> = settings
+        .get_as(CLIENT_IDENTITIES_SETTINGS_KEY)
+        .unwrap_or_default();
+    match identity {
+        Some(identity) => identities.insert(server_id, identity),
+        None => identities.remove(&server_id),
+    };
+    settings.set_as(CLIENT_IDENTITIES_SETTINGS_KEY, &identities)
+}
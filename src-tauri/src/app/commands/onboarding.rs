@@ -0,0 +1,292 @@
+// ============================================
+// First-run Onboarding (desktop only)
+// 探测已有 opencode 安装与配置，引导新用户完成首次设置
+// ============================================
+
+use crate::app::{
+    commands::opencode::{find_opencode_binary, is_service_running, probe_version},
+    server_probe::{self, ProbeCandidate},
+    settings::SettingsStore,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tauri::{Manager, State};
+
+const ONBOARDING_KEY: &str = "onboarding";
+const DEFAULT_SERVER_URL: &str = "http://127.0.0.1:4096";
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct OnboardingRecord {
+    completed: bool,
+    completed_at: Option<u64>,
+    /// 完成引导时在本机配置中探测到、且用户选择沿用的 provider。
+    /// opencode 自行读取这些配置与凭据，应用不复制任何内容，此列表仅供展示
+    imported_providers: Vec<String>,
+    managed_service: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedInstall {
+    binary_path: Option<String>,
+    version: Option<String>,
+    config_files: Vec<String>,
+    /// 在 opencode 配置或 auth.json 中已配置的 provider id
+    providers: Vec<String>,
+    server_running: bool,
+    /// 已安装但没有运行中的服务时，建议由应用托管启动
+    suggest_managed_service: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OnboardingState {
+    completed: bool,
+    detected: Option<DetectedInstall>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompleteOnboardingArgs {
+    /// 用户勾选的 provider；未在本机配置中探测到的名称会被忽略
+    #[serde(default)]
+    imported_providers: Vec<String>,
+    #[serde(default)]
+    managed_service: bool,
+}
+
+/// opencode 全局配置文件可能的位置
+pub(crate) fn config_candidates(home: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(xdg) = std::env::var_os("XDG_CONFIG_HOME").filter(|value| !value.is_empty()) {
+        dirs.push(PathBuf::from(xdg).join("opencode"));
+    }
+    dirs.push(home.join(".config").join("opencode"));
+
+    let mut candidates: Vec<PathBuf> = dirs
+        .iter()
+        .flat_map(|dir| {
+            ["opencode.json", "opencode.jsonc", "config.json"]
+                .into_iter()
+                .map(move |name| dir.join(name))
+        })
+        .collect();
+
+    if let Some(custom) = std::env::var_os("OPENCODE_CONFIG").filter(|value| !value.is_empty()) {
+        candidates.insert(0, PathBuf::from(custom));
+    }
+    candidates
+}
+
+fn auth_candidates(home: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let Some(xdg) = std::env::var_os("XDG_DATA_HOME").filter(|value| !value.is_empty()) {
+        candidates.push(PathBuf::from(xdg).join("opencode").join("auth.json"));
+    }
+    candidates.push(
+        home.join(".local")
+            .join("share")
+            .join("opencode")
+            .join("auth.json"),
+    );
+    candidates
+}
+
+/// 去掉 JSONC 中的 `//` 与 `/* */` 注释（忽略字符串内的内容）
+pub(crate) fn strip_json_comments(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = '\0';
+                for next in chars.by_ref() {
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn read_json_object(path: &Path) -> Option<serde_json::Map<String, serde_json::Value>> {
+    let data = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&strip_json_comments(&data)).ok()
+}
+
+/// 读取 opencode 配置与 auth.json，返回找到的配置文件和已配置的 provider id
+fn detect_providers(home: &Path) -> (Vec<String>, BTreeSet<String>) {
+    let mut providers = BTreeSet::new();
+    let mut config_files = Vec::new();
+
+    for path in config_candidates(home) {
+        let Some(config) = read_json_object(&path) else {
+            continue;
+        };
+        if let Some(provider) = config.get("provider").and_then(|value| value.as_object()) {
+            providers.extend(provider.keys().cloned());
+        }
+        config_files.push(path.to_string_lossy().to_string());
+    }
+
+    for path in auth_candidates(home) {
+        if let Some(auth) = read_json_object(&path) {
+            providers.extend(auth.keys().cloned());
+        }
+    }
+
+    (config_files, providers)
+}
+
+fn detect_install(home: &Path) -> DetectedInstall {
+    let binary_path = find_opencode_binary(&HashMap::new());
+    let version = binary_path
+        .as_ref()
+        .and_then(|path| probe_version(&path.to_string_lossy(), Duration::from_secs(5)));
+    let (config_files, providers) = detect_providers(home);
+
+    DetectedInstall {
+        binary_path: binary_path.map(|path| path.to_string_lossy().to_string()),
+        version,
+        config_files,
+        providers: providers.into_iter().collect(),
+        server_running: false,
+        suggest_managed_service: false,
+    }
+}
+
+/// 获取首次启动引导状态；未完成时附带环境探测结果
+#[tauri::command]
+pub async fn get_onboarding_state(
+    app: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+) -> Result<OnboardingState, String> {
+    let record: OnboardingRecord = settings.get_as(ONBOARDING_KEY).unwrap_or_default();
+    if record.completed {
+        return Ok(OnboardingState {
+            completed: true,
+            detected: None,
+        });
+    }
+
+    let home = app.path().home_dir().map_err(|e| e.to_string())?;
+    let mut detected = tauri::async_runtime::spawn_blocking(move || detect_install(&home))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    detected.server_running = is_service_running(DEFAULT_SERVER_URL).await;
+    detected.suggest_managed_service = detected.binary_path.is_some() && !detected.server_running;
+
+    Ok(OnboardingState {
+        completed: false,
+        detected: Some(detected),
+    })
+}
+
+/// 记录用户在引导中的选择并标记完成。
+/// 沿用的 provider 以重新探测的结果为准，只保留本机配置中确实存在的
+#[tauri::command]
+pub async fn complete_onboarding(
+    app: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+    args: CompleteOnboardingArgs,
+) -> Result<(), String> {
+    let home = app.path().home_dir().map_err(|e| e.to_string())?;
+    let (_, detected) = tauri::async_runtime::spawn_blocking(move || detect_providers(&home))
+        .await
+        .map_err(|e| e.to_string())?;
+    let imported_providers = args
+        .imported_providers
+        .into_iter()
+        .filter(|provider| detected.contains(provider))
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let record = OnboardingRecord {
+        completed: true,
+        completed_at: Some(crate::app::now_millis()),
+        imported_providers,
+        managed_service: args.managed_service,
+    };
+    log::info!(
+        "Onboarding completed ({} existing provider(s) kept, managed service: {})",
+        record.imported_providers.len(),
+        record.managed_service
+    );
+    settings.set_as(ONBOARDING_KEY, &record)
+}
+
+/// 重新显示引导（设置页中的“重新运行向导”）
+#[tauri::command]
+pub fn reset_onboarding(settings: State<'_, SettingsStore>) -> Result<(), String> {
+    settings.remove(ONBOARDING_KEY)
+}
+
+/// 远程服务器连接向导：探测主机上可用的 opencode 服务地址，按可用性排序
+#[tauri::command]
+pub async fn probe_remote_server(
+    host: String,
+    auth_header: Option<String>,
+) -> Result<Vec<ProbeCandidate>, String> {
+    let candidates = server_probe::probe_host(&host, auth_header.as_deref()).await?;
+    log::info!(
+        "Probed '{}': {} candidate(s) responded",
+        host,
+        candidates.len()
+    );
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::strip_json_comments;
+
+    #[test]
+    fn strip_json_comments_keeps_urls_inside_strings() {
+        let input = "{\n  // provider list\n  \"url\": \"http://a//b\", /* inline */ \"x\": 1\n}";
+        let value: serde_json::Value = serde_json::from_str(&strip_json_comments(input)).unwrap();
+
+        assert_eq!(value["url"], "http://a//b");
+        assert_eq!(value["x"], 1);
+    }
+}
@@ -3,27 +3,86 @@
 // Android 不支持子进程管理和 window.destroy()
 // ============================================
 
-use crate::app::service::ServiceState;
+use crate::app::{
+    arch::{self, BinaryArch, Compatibility},
+    container::{self, ContainerTarget},
+    dotenv,
+    idle::{self, IDLE_SETTINGS_KEY},
+    installer,
+    server_version::{self, OpencodeVersion, VersionSource},
+    service::{
+        IdleSuspendConfig, LogStream, ServiceInstance, ServiceLaunch, ServiceLog, ServiceLogLine,
+        ServiceState, WatchdogConfig,
+    },
+    service_exit::{self, ExitReason, ServiceExit},
+    service_lock::{self, SpawnLock},
+    service_pid,
+    service_watchdog::WATCHDOG_SETTINGS_KEY,
+    settings::SettingsStore,
+    shutdown::{ShutdownServicePolicy, SHUTDOWN_SETTINGS_KEY},
+    sidecar,
+    terminate::{self, TerminationConfig, TERMINATION_SETTINGS_KEY},
+    wsl::{self, WslTarget},
+};
 use serde::Serialize;
 use std::{
     collections::VecDeque,
     env,
     ffi::OsString,
     io::{BufRead, BufReader, Read},
+    net::TcpListener,
     path::{Path, PathBuf},
     process::{Child, Command, Stdio},
-    sync::{atomic::Ordering, mpsc},
+    sync::{atomic::Ordering, mpsc, Arc},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
-use tauri::State;
+use tauri::{ipc::Channel, Emitter, State};
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct StartOpencodeServiceResult {
     started: bool,
     started_by_us: bool,
-    url: Option<String>,
+    pub(crate) url: Option<String>,
+}
+
+/// 检测到的 opencode 可执行文件及其架构信息
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryDiscovery {
+    path: String,
+    arch: BinaryArch,
+    host_arch: BinaryArch,
+    compatibility: Compatibility,
+    warning: Option<String>,
+}
+
+impl BinaryDiscovery {
+    pub(crate) fn inspect(path: &Path) -> Self {
+        let binary_arch = arch::binary_arch(path);
+        let host_arch = arch::host_arch();
+        let compatibility = arch::compatibility(host_arch, binary_arch);
+        Self {
+            path: path.to_string_lossy().to_string(),
+            arch: binary_arch,
+            host_arch,
+            compatibility,
+            warning: arch::compatibility_warning(host_arch, binary_arch, compatibility),
+        }
+    }
+
+    pub(crate) fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub(crate) fn compatibility(&self) -> Compatibility {
+        self.compatibility
+    }
+
+    pub(crate) fn warning(&self) -> Option<&str> {
+        self.warning.as_deref()
+    }
 }
 
 struct SpawnedOpencodeServe {
@@ -34,7 +93,7 @@ struct SpawnedOpencodeServe {
 /// 检查 opencode 服务是否在运行（通过 health endpoint）
 pub async fn is_service_running(url: &str) -> bool {
     let health_url = format!("{}/global/health", url.trim_end_matches('/'));
-    match reqwest::Client::builder()
+    match crate::app::dns::client_builder()
         .connect_timeout(Duration::from_secs(3))
         .build()
     {
@@ -49,19 +108,184 @@ pub async fn is_service_running(url: &str) -> bool {
     }
 }
 
-/// 启动 opencode serve 进程
+/// 托管服务的监听地址
+struct ListenUrl {
+    url: String,
+    hostname: String,
+    port: u16,
+}
+
+pub(crate) fn port_available(hostname: &str, port: u16) -> bool {
+    TcpListener::bind((hostname, port)).is_ok()
+}
+
+/// 解析服务 URL 中的主机名与端口
+pub(crate) fn parse_listen_address(url: &str) -> Result<(reqwest::Url, String, u16), String> {
+    let parsed =
+        reqwest::Url::parse(url).map_err(|e| format!("invalid service URL '{}': {}", url, e))?;
+    let hostname = parsed
+        .host_str()
+        .ok_or_else(|| format!("service URL '{}' has no host", url))?
+        .trim_matches(|c| c == '[' || c == ']')
+        .to_string();
+    let port = parsed
+        .port_or_known_default()
+        .ok_or_else(|| format!("service URL '{}' has no port", url))?;
+    Ok((parsed, hostname, port))
+}
+
+pub(crate) fn bind_host(hostname: &str) -> &str {
+    match hostname {
+        "localhost" => "127.0.0.1",
+        host => host,
+    }
+}
+
+/// 按服务 URL 确定监听地址；URL 中的端口已被占用时改用系统分配的空闲端口
+fn choose_listen_url(url: &str) -> Result<ListenUrl, String> {
+    let (mut parsed, hostname, mut port) = parse_listen_address(url)?;
+    let bind_host = bind_host(&hostname);
+    if !port_available(bind_host, port) {
+        let free = TcpListener::bind((bind_host, 0))
+            .and_then(|listener| listener.local_addr())
+            .map_err(|e| format!("no free port on {}: {}", hostname, e))?
+            .port();
+        log::info!(
+            "Port {} is in use, starting opencode serve on {}",
+            port,
+            free
+        );
+        port = free;
+        parsed
+            .set_port(Some(port))
+            .map_err(|_| format!("cannot set port on '{}'", url))?;
+    }
+
+    Ok(ListenUrl {
+        url: parsed.as_str().trim_end_matches('/').to_string(),
+        hostname,
+        port,
+    })
+}
+
+/// 由我们管理、不允许通过额外参数覆盖的选项
+const RESERVED_SERVE_ARGS: &[&str] = &["--port", "--hostname"];
+
+/// 校验追加给 `opencode serve` 的参数。参数直接传给进程、不经 shell 解析；
+/// Windows 上 .cmd / .bat 需经 cmd.exe 启动，因此同时拒绝 cmd 的元字符
+pub(crate) fn validate_extra_args(args: &[String]) -> Result<(), String> {
+    for arg in args {
+        if arg.is_empty() {
+            return Err("empty argument".to_string());
+        }
+        if arg.chars().any(char::is_control) {
+            return Err(format!(
+                "argument '{}' contains control characters",
+                arg.escape_debug()
+            ));
+        }
+        if cfg!(windows) && arg.contains(['&', '|', '<', '>', '^', '%', '"']) {
+            return Err(format!("argument '{}' contains shell metacharacters", arg));
+        }
+        let name = arg.split('=').next().unwrap_or_default();
+        if RESERVED_SERVE_ARGS.contains(&name) {
+            return Err(format!(
+                "'{}' is set by the app; change the service URL instead",
+                name
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 启动 opencode serve 进程；输出行写入 `logs`，启动期间同时转发给调用方
 fn spawn_opencode_serve(
-    binary_path: &str,
-    env_vars: &std::collections::HashMap<String, String>,
+    instance_id: &str,
+    launch: &ServiceLaunch,
+    listen: &ListenUrl,
+    logs: &Arc<ServiceLog>,
 ) -> Result<SpawnedOpencodeServe, String> {
+    let ServiceLaunch {
+        binary_path,
+        env_vars,
+        extra_args,
+        cwd,
+        wsl,
+        container,
+        ..
+    } = launch;
     log::info!("Starting opencode serve with binary: {}", binary_path);
+    // WSL 与容器中的二进制是 Linux 程序，不做宿主架构检查
+    if wsl.is_none() && container.is_none() {
+        let discovery = BinaryDiscovery::inspect(Path::new(binary_path));
+        match discovery.compatibility() {
+            Compatibility::Incompatible => {
+                return Err(discovery
+                    .warning()
+                    .unwrap_or("opencode binary is built for a different architecture")
+                    .to_string());
+            }
+            Compatibility::Emulated => {
+                log::warn!("{}", discovery.warning().unwrap_or_default());
+            }
+            Compatibility::Native | Compatibility::Unknown => {}
+        }
+    }
     if !env_vars.is_empty() {
         log::info!("Injecting {} environment variable(s)", env_vars.len());
     }
 
-    let serve_args = ["serve".to_string()];
+    let mut serve_args = vec!["serve".to_string()];
+    serve_args.extend(extra_args.iter().cloned());
+    serve_args.extend([
+        "--port".to_string(),
+        listen.port.to_string(),
+        "--hostname".to_string(),
+        // 容器内须监听所有接口，端口发布才能转发进来
+        if container.is_some() {
+            "0.0.0.0".to_string()
+        } else {
+            listen.hostname.clone()
+        },
+    ]);
 
-    let mut cmd = build_opencode_command(binary_path, &serve_args);
+    let cwd = cwd.as_deref();
+    if let Some(cwd) = cwd {
+        log::info!("Working directory: {}", cwd.display());
+    }
+    let keys: Vec<&str> = env_vars.keys().map(String::as_str).collect();
+    let mut cmd = match (wsl, container) {
+        (Some(target), _) => {
+            log::info!(
+                "Running inside WSL distribution: {}",
+                target.distro.as_deref().unwrap_or("(default)")
+            );
+            wsl::command(target, binary_path, &serve_args, cwd, &keys)?
+        }
+        (None, Some(target)) => {
+            let name = container::container_name(instance_id, listen.port);
+            log::info!("Running in container: {}", name);
+            // 上次异常退出可能留下同名容器
+            container::remove(target, &name);
+            container::command(
+                target,
+                &name,
+                binary_path,
+                &serve_args,
+                &listen.hostname,
+                listen.port,
+                cwd,
+                &keys,
+            )
+        }
+        (None, None) => {
+            let mut cmd = build_opencode_command(binary_path, &serve_args);
+            if let Some(cwd) = cwd {
+                cmd.current_dir(cwd);
+            }
+            cmd
+        }
+    };
     cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
 
     // 注入用户配置的环境变量
@@ -69,43 +293,50 @@ fn spawn_opencode_serve(
         cmd.env(key, value);
     }
 
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
-    }
+    // 独立进程组，停止时连同 LSP / 工具子进程一起结束
+    terminate::isolate(&mut cmd);
 
-    let mut child = cmd.spawn().map_err(|e| {
+    // WSL / 容器启动失败时，出错的是 wsl.exe 或容器引擎本身
+    let program = match (wsl, container) {
+        (None, None) => binary_path.clone(),
+        _ => cmd.get_program().to_string_lossy().into_owned(),
+    };
+    let mut child = terminate::spawn(&mut cmd).map_err(|e| {
         format!(
             "Failed to start '{}': {}. Check that the path is correct.",
-            binary_path, e
+            program, e
         )
     })?;
 
     let (tx, output) = mpsc::channel();
     if let Some(stdout) = child.stdout.take() {
-        spawn_output_reader(stdout, tx.clone());
+        spawn_output_reader(stdout, LogStream::Stdout, logs.clone(), tx.clone());
     }
     if let Some(stderr) = child.stderr.take() {
-        spawn_output_reader(stderr, tx);
+        spawn_output_reader(stderr, LogStream::Stderr, logs.clone(), tx);
     }
 
     Ok(SpawnedOpencodeServe { child, output })
 }
 
-fn spawn_output_reader<R>(reader: R, tx: mpsc::Sender<String>)
-where
+/// 持续读取管道直到进程退出；启动完成、接收端关闭后只写入 `logs`
+fn spawn_output_reader<R>(
+    reader: R,
+    stream: LogStream,
+    logs: Arc<ServiceLog>,
+    tx: mpsc::Sender<String>,
+) where
     R: Read + Send + 'static,
 {
     thread::spawn(move || {
         let mut tx = Some(tx);
         for line in BufReader::new(reader).lines().map_while(Result::ok) {
             if let Some(sender) = tx.as_ref() {
-                if sender.send(line).is_err() {
+                if sender.send(line.clone()).is_err() {
                     tx = None;
                 }
             }
+            logs.push(stream, line);
         }
     });
 }
@@ -170,7 +401,7 @@ fn build_opencode_command(binary_path: &str, args: &[String]) -> Command {
     cmd
 }
 
-fn patched_env_var(
+pub(crate) fn patched_env_var(
     env_vars: &std::collections::HashMap<String, String>,
     key: &str,
 ) -> Option<OsString> {
@@ -182,95 +413,437 @@ fn patched_env_var(
     env::var_os(key)
 }
 
-fn path_candidates(env_vars: &std::collections::HashMap<String, String>) -> Vec<PathBuf> {
+/// 候选可执行文件的来源
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CandidateSource {
+    /// `OPENCODE_BIN`
+    Override,
+    /// 随应用打包的 sidecar
+    Bundled,
+    /// 通过应用内安装器安装
+    Installed,
+    Path,
+    /// 常见安装位置（安装脚本、Homebrew、npm / bun 全局、Scoop、WinGet）
+    InstallDir,
+}
+
+/// 不在 PATH 中时也值得查找的常见安装目录（桌面应用启动时 PATH 往往不含 shell 配置里追加的目录）
+fn install_dirs(env_vars: &std::collections::HashMap<String, String>) -> Vec<PathBuf> {
+    let var = |key: &str| {
+        patched_env_var(env_vars, key)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+    };
+    let home = var(if cfg!(windows) { "USERPROFILE" } else { "HOME" });
+    let mut dirs = Vec::new();
+
+    if let Some(home) = &home {
+        dirs.push(home.join(".opencode").join("bin"));
+        dirs.push(home.join(".bun").join("bin"));
+    }
+    if cfg!(windows) {
+        if let Some(appdata) = var("APPDATA") {
+            dirs.push(appdata.join("npm"));
+        }
+        let scoop = var("SCOOP").or_else(|| home.as_ref().map(|home| home.join("scoop")));
+        if let Some(scoop) = scoop {
+            dirs.push(scoop.join("shims"));
+        }
+        if let Some(local) = var("LOCALAPPDATA") {
+            dirs.push(local.join("Microsoft").join("WinGet").join("Links"));
+        }
+    } else {
+        if let Some(home) = &home {
+            dirs.push(home.join(".local").join("bin"));
+            dirs.push(home.join("bin"));
+            dirs.push(home.join(".npm-global").join("bin"));
+            dirs.push(home.join(".volta").join("bin"));
+        }
+        if let Some(prefix) = var("NPM_CONFIG_PREFIX") {
+            dirs.push(prefix.join("bin"));
+        }
+        dirs.push(PathBuf::from("/opt/homebrew/bin"));
+        dirs.push(PathBuf::from("/usr/local/bin"));
+        dirs.push(PathBuf::from("/home/linuxbrew/.linuxbrew/bin"));
+    }
+    dirs
+}
+
+fn labeled_candidates(
+    env_vars: &std::collections::HashMap<String, String>,
+) -> Vec<(PathBuf, CandidateSource)> {
     let mut candidates = Vec::new();
 
     if let Some(bin) = patched_env_var(env_vars, "OPENCODE_BIN") {
         if !bin.is_empty() {
-            candidates.push(PathBuf::from(bin));
+            candidates.push((PathBuf::from(bin), CandidateSource::Override));
         }
     }
 
-    let Some(path) = patched_env_var(env_vars, "PATH") else {
-        return candidates;
-    };
-
     let names: Vec<&str> = if cfg!(windows) {
         vec!["opencode.exe", "opencode.cmd", "opencode.bat", "opencode"]
     } else {
         vec!["opencode"]
     };
 
-    for dir in env::split_paths(&path) {
+    let path_dirs = patched_env_var(env_vars, "PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+    let dirs = path_dirs
+        .into_iter()
+        .map(|dir| (dir, CandidateSource::Path))
+        .chain(
+            install_dirs(env_vars)
+                .into_iter()
+                .map(|dir| (dir, CandidateSource::InstallDir)),
+        );
+    for (dir, source) in dirs {
         for name in &names {
-            candidates.push(dir.join(name));
+            candidates.push((dir.join(name), source));
         }
     }
 
     candidates
 }
 
+fn path_candidates(env_vars: &std::collections::HashMap<String, String>) -> Vec<PathBuf> {
+    labeled_candidates(env_vars)
+        .into_iter()
+        .map(|(path, _)| path)
+        .collect()
+}
+
 fn is_runnable_file(path: &Path) -> bool {
     path.is_file()
 }
 
+/// 运行 `<program> --version` 并返回第一行非空输出，超时视为失败
+pub(crate) fn probe_version(program: &str, timeout: Duration) -> Option<String> {
+    let mut cmd = build_opencode_command(program, &["--version".to_string()]);
+    cmd.stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = cmd.spawn().ok()?;
+    let started = std::time::Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if started.elapsed() < timeout => thread::sleep(Duration::from_millis(50)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    child.stdout.take()?.read_to_string(&mut output).ok()?;
+    output
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+}
+
+/// 查找 opencode：未显式设置 OPENCODE_BIN 时优先使用校验通过的内置 sidecar，其次是应用内安装的版本；
+/// 否则按 PATH 与常见安装目录查找，存在多个时优先原生架构，其次是无法判断的 shim，再次是需要模拟运行的构建
+pub(crate) fn discover_opencode_binary(
+    env_vars: &std::collections::HashMap<String, String>,
+) -> Option<BinaryDiscovery> {
+    let has_override = patched_env_var(env_vars, "OPENCODE_BIN").is_some_and(|bin| !bin.is_empty());
+    if !has_override {
+        if let Some(bundled) = sidecar::bundled_opencode() {
+            return Some(BinaryDiscovery::inspect(bundled));
+        }
+        if let Some(installed) = installer::installed_opencode().filter(|path| path.is_file()) {
+            return Some(BinaryDiscovery::inspect(&installed));
+        }
+    }
+
+    path_candidates(env_vars)
+        .into_iter()
+        .filter(|candidate| is_runnable_file(candidate))
+        .map(|candidate| BinaryDiscovery::inspect(&candidate))
+        .min_by_key(BinaryDiscovery::compatibility)
+}
+
+pub(crate) fn find_opencode_binary(
+    env_vars: &std::collections::HashMap<String, String>,
+) -> Option<PathBuf> {
+    discover_opencode_binary(env_vars).map(|discovery| PathBuf::from(discovery.path))
+}
+
 /// 自动检测 opencode 可执行文件，行为接近直接在终端输入 `opencode`。
 #[tauri::command]
 pub async fn detect_opencode_binary(
     env_vars: std::collections::HashMap<String, String>,
 ) -> Result<Option<String>, String> {
-    for candidate in path_candidates(&env_vars) {
-        if is_runnable_file(&candidate) {
-            return Ok(Some(candidate.to_string_lossy().to_string()));
-        }
-    }
+    Ok(find_opencode_binary(&env_vars).map(|path| path.to_string_lossy().to_string()))
+}
 
-    Ok(None)
+/// 找到的一个 opencode 可执行文件
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BinaryCandidate {
+    #[serde(flatten)]
+    discovery: BinaryDiscovery,
+    source: CandidateSource,
+    /// `--version` 的输出；无法运行或超时时为 None
+    version: Option<String>,
 }
 
-/// 跨平台杀进程
-pub fn kill_process_by_pid(pid: u32) {
-    #[cfg(target_os = "windows")]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        let _ = Command::new("taskkill")
-            .args(["/PID", &pid.to_string(), "/F", "/T"])
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .creation_flags(CREATE_NO_WINDOW)
-            .spawn();
+/// 列出所有找到的 opencode（内置、应用内安装、OPENCODE_BIN、PATH 与常见安装目录），附带版本，
+/// 供设置界面选择；指向同一文件的路径只保留第一个
+#[tauri::command]
+pub async fn list_opencode_binaries(
+    env_vars: std::collections::HashMap<String, String>,
+) -> Result<Vec<BinaryCandidate>, String> {
+    const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+    tauri::async_runtime::spawn_blocking(move || {
+        let bundled =
+            sidecar::bundled_opencode().map(|path| (path.to_path_buf(), CandidateSource::Bundled));
+        let installed =
+            installer::installed_opencode().map(|path| (path, CandidateSource::Installed));
+        let mut seen = std::collections::HashSet::new();
+        bundled
+            .into_iter()
+            .chain(installed)
+            .chain(labeled_candidates(&env_vars))
+            .filter(|(path, _)| is_runnable_file(path))
+            .filter(|(path, _)| seen.insert(path.canonicalize().unwrap_or_else(|_| path.clone())))
+            .map(|(path, source)| BinaryCandidate {
+                version: probe_version(&path.to_string_lossy(), VERSION_TIMEOUT),
+                discovery: BinaryDiscovery::inspect(&path),
+                source,
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 查询 opencode 版本并判断与界面是否兼容：优先询问 url 指向的服务，
+/// 未给出 url 或服务不可达时运行 binary_path（或自动检测到的 opencode）的 `--version`
+#[tauri::command]
+pub async fn get_opencode_version(
+    url: Option<String>,
+    auth_header: Option<String>,
+    binary_path: Option<String>,
+    env_vars: Option<std::collections::HashMap<String, String>>,
+) -> Result<OpencodeVersion, String> {
+    const VERSION_TIMEOUT: Duration = Duration::from_secs(3);
+    let mut server_error = None;
+    if let Some(url) = url.as_deref().filter(|url| !url.trim().is_empty()) {
+        match server_version::server_version(url, auth_header.as_deref()).await {
+            Ok(raw) => return Ok(OpencodeVersion::new(raw, VersionSource::Server)),
+            Err(e) => server_error = Some(e),
+        }
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        let _ = Command::new("kill")
-            .arg(pid.to_string())
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .spawn();
+    let raw = tauri::async_runtime::spawn_blocking(move || {
+        let binary = match binary_path.filter(|path| !path.trim().is_empty()) {
+            Some(path) => PathBuf::from(path),
+            None => find_opencode_binary(&env_vars.unwrap_or_default())?,
+        };
+        probe_version(&binary.to_string_lossy(), VERSION_TIMEOUT)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    match raw {
+        Some(raw) => Ok(OpencodeVersion::new(raw, VersionSource::Binary)),
+        None => Err(server_error
+            .unwrap_or_else(|| "opencode not found or did not respond to --version".to_string())),
     }
 }
 
+/// 列出已安装的 WSL 发行版（仅 Windows），默认发行版在前
+#[tauri::command]
+pub async fn list_wsl_distros() -> Result<Vec<String>, String> {
+    tauri::async_runtime::spawn_blocking(wsl::list_distros)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 检测 opencode 并返回架构信息；传入 binary_path 时只检查该文件
+#[tauri::command]
+pub async fn inspect_opencode_binary(
+    binary_path: Option<String>,
+    env_vars: std::collections::HashMap<String, String>,
+) -> Result<Option<BinaryDiscovery>, String> {
+    tauri::async_runtime::spawn_blocking(move || match binary_path {
+        Some(path) if !path.trim().is_empty() => {
+            let path = PathBuf::from(path);
+            is_runnable_file(&path).then(|| BinaryDiscovery::inspect(&path))
+        }
+        _ => discover_opencode_binary(&env_vars),
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
 /// 检查 opencode 服务是否在运行
 #[tauri::command]
 pub async fn check_opencode_service(url: String) -> Result<bool, String> {
     Ok(is_service_running(&url).await)
 }
 
-/// 启动 opencode serve
+/// 启动 opencode serve；`instance_id` 区分同时运行的多个实例，缺省为默认实例
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn start_opencode_service(
     state: State<'_, ServiceState>,
     url: String,
     binary_path: String,
     env_vars: std::collections::HashMap<String, String>,
+    instance_id: Option<String>,
+    extra_args: Option<Vec<String>>,
+    env_file: Option<String>,
+    cwd: Option<String>,
+    wsl: Option<WslTarget>,
+    container: Option<ContainerTarget>,
+) -> Result<StartOpencodeServiceResult, String> {
+    let launch = prepare_launch(
+        url,
+        binary_path,
+        env_vars,
+        extra_args.unwrap_or_default(),
+        env_file,
+        cwd,
+        wsl,
+        container,
+    )?;
+    let instance = state.instance(instance_id.as_deref());
+    launch_service(&state, &instance, launch).await
+}
+
+/// 校验启动参数并合并 .env 文件，得到 launch_service 所需的 ServiceLaunch
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn prepare_launch(
+    url: String,
+    binary_path: String,
+    env_vars: std::collections::HashMap<String, String>,
+    extra_args: Vec<String>,
+    env_file: Option<String>,
+    cwd: Option<String>,
+    wsl: Option<WslTarget>,
+    container: Option<ContainerTarget>,
+) -> Result<ServiceLaunch, String> {
+    validate_extra_args(&extra_args)?;
+    if wsl.is_some() && !cfg!(target_os = "windows") {
+        return Err("WSL is only available on Windows".to_string());
+    }
+    if wsl.is_some() && container.is_some() {
+        return Err("wsl and container cannot be used together".to_string());
+    }
+    let cwd = cwd
+        .map(|cwd| PathBuf::from(cwd.trim()))
+        .filter(|cwd| !cwd.as_os_str().is_empty());
+    if let Some(cwd) = &cwd {
+        // WSL 的工作目录可以是 Linux 路径，宿主上无法检查，只要求能够转换
+        let valid = match wsl {
+            Some(_) => wsl::to_wsl_path(&cwd.to_string_lossy()).is_some(),
+            None => cwd.is_dir(),
+        };
+        if !valid {
+            return Err(format!(
+                "working directory '{}' does not exist",
+                cwd.display()
+            ));
+        }
+    }
+    // .env 文件中的变量先载入，显式传入的同名变量优先
+    let env_vars = match env_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        Some(path) => {
+            let mut merged: std::collections::HashMap<String, String> =
+                dotenv::load(Path::new(path))?.into_iter().collect();
+            merged.extend(env_vars);
+            merged
+        }
+        None => env_vars,
+    };
+    Ok(ServiceLaunch {
+        url,
+        binary_path,
+        env_vars,
+        extra_args,
+        cwd,
+        wsl,
+        container,
+    })
+}
+
+/// 等待获取跨进程启动锁。返回 None 表示服务已由其他窗口 / 实例启动，应直接复用
+async fn acquire_spawn_lock(url: &str) -> Result<Option<SpawnLock>, String> {
+    const LOCK_WAIT: Duration = Duration::from_secs(30);
+    let started = std::time::Instant::now();
+
+    loop {
+        if is_service_running(url).await {
+            return Ok(None);
+        }
+        if let Some(lock) = service_lock::try_acquire(url)? {
+            // 持锁后再确认一次，对方可能刚启动完成并释放了锁
+            if is_service_running(url).await {
+                return Ok(None);
+            }
+            return Ok(Some(lock));
+        }
+        if started.elapsed() > LOCK_WAIT {
+            return Err(format!(
+                "Another instance is still starting opencode serve for {}",
+                url
+            ));
+        }
+        log::info!(
+            "Waiting for another instance to start opencode serve at {}",
+            url
+        );
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+/// 按给定参数启动（或复用）托管服务，供命令与后台任务共用
+pub(crate) async fn launch_service(
+    state: &ServiceState,
+    instance: &ServiceInstance,
+    launch: ServiceLaunch,
 ) -> Result<StartOpencodeServiceResult, String> {
-    if state.we_started.load(Ordering::SeqCst) {
-        let current_url = state.service_url.lock().map_err(|e| e.to_string())?.clone();
+    let url = launch.url.clone();
+    state.touch_activity();
+    let _launching = instance.launch_lock.lock().await;
+
+    if instance.we_started.load(Ordering::SeqCst) {
+        let current_url = instance
+            .service_url
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone();
         if let Some(current_url) = current_url {
             if is_service_running(&current_url).await {
                 log::info!("opencode service already running at {}", current_url);
+                // 接管的进程没有启动参数，记下本次的，之后重启时使用
+                instance
+                    .launch
+                    .lock()
+                    .map_err(|e| e.to_string())?
+                    .get_or_insert(ServiceLaunch {
+                        url: current_url.clone(),
+                        ..launch
+                    });
                 return Ok(StartOpencodeServiceResult {
                     started: false,
                     started_by_us: true,
@@ -280,22 +853,34 @@ pub async fn start_opencode_service(
         }
     }
 
-    if is_service_running(&url).await {
-        log::info!("opencode service already running at {}", url);
-        return Ok(StartOpencodeServiceResult {
-            started: false,
-            started_by_us: false,
-            url: Some(url),
-        });
-    }
+    let _spawn_lock = match acquire_spawn_lock(&url).await? {
+        Some(lock) => lock,
+        None => {
+            log::info!("opencode service already running at {}", url);
+            return Ok(StartOpencodeServiceResult {
+                started: false,
+                started_by_us: false,
+                url: Some(url),
+            });
+        }
+    };
 
-    let mut spawned = spawn_opencode_serve(&binary_path, &env_vars)?;
+    let listen = choose_listen_url(&url)?;
+    let url = listen.url.clone();
+    let mut spawned = spawn_opencode_serve(&instance.id, &launch, &listen, &instance.logs)?;
     let pid = spawned.child.id();
-    log::info!("Started opencode serve, PID: {}", pid);
+    log::info!("Started opencode serve '{}', PID: {}", instance.id, pid);
 
-    state.child_pid.store(pid, Ordering::SeqCst);
-    state.we_started.store(true, Ordering::SeqCst);
-    *state.service_url.lock().map_err(|e| e.to_string())? = None;
+    instance.child_pid.store(pid, Ordering::SeqCst);
+    instance.we_started.store(true, Ordering::SeqCst);
+    instance.adopted.store(false, Ordering::SeqCst);
+    *instance.service_url.lock().map_err(|e| e.to_string())? = None;
+    // 记录实际使用的地址，重启时沿用同一端口
+    *instance.launch.lock().map_err(|e| e.to_string())? = Some(ServiceLaunch {
+        url: url.clone(),
+        ..launch
+    });
+    service_pid::record(instance);
 
     let mut detected_url: Option<String> = None;
     let mut recent_output = VecDeque::new();
@@ -304,16 +889,19 @@ pub async fn start_opencode_service(
         while let Ok(line) = spawned.output.try_recv() {
             if let Some(parsed_url) = parse_listening_url(&line) {
                 log::info!("Detected opencode serve URL: {}", parsed_url);
-                *state.service_url.lock().map_err(|e| e.to_string())? = Some(parsed_url.clone());
+                *instance.service_url.lock().map_err(|e| e.to_string())? = Some(parsed_url.clone());
                 detected_url = Some(parsed_url);
             }
             remember_recent_output(&mut recent_output, line);
         }
 
         if let Some(status) = spawned.child.try_wait().map_err(|e| e.to_string())? {
-            state.child_pid.store(0, Ordering::SeqCst);
-            state.we_started.store(false, Ordering::SeqCst);
-            *state.service_url.lock().map_err(|e| e.to_string())? = None;
+            instance.child_pid.store(0, Ordering::SeqCst);
+            instance.we_started.store(false, Ordering::SeqCst);
+            *instance.service_url.lock().map_err(|e| e.to_string())? = None;
+            service_pid::record(instance);
+            terminate::reap(pid);
+            service_exit::report(instance, pid, Some(status), ExitReason::StartupFailed);
             return Err(format!(
                 "opencode serve exited during startup with status {}.{}",
                 status,
@@ -324,7 +912,8 @@ pub async fn start_opencode_service(
         let health_url = detected_url.as_deref().unwrap_or(&url);
         if is_service_running(health_url).await {
             log::info!("opencode service is ready at {}", health_url);
-            *state.service_url.lock().map_err(|e| e.to_string())? = Some(health_url.to_string());
+            *instance.service_url.lock().map_err(|e| e.to_string())? = Some(health_url.to_string());
+            *instance.child.lock().map_err(|e| e.to_string())? = Some(spawned.child);
             return Ok(StartOpencodeServiceResult {
                 started: true,
                 started_by_us: true,
@@ -336,6 +925,7 @@ pub async fn start_opencode_service(
     }
 
     log::warn!("opencode service started but health check not passing yet");
+    *instance.child.lock().map_err(|e| e.to_string())? = Some(spawned.child);
     Ok(StartOpencodeServiceResult {
         started: true,
         started_by_us: true,
@@ -343,25 +933,195 @@ pub async fn start_opencode_service(
     })
 }
 
-/// 停止 opencode serve
-#[tauri::command]
-pub async fn stop_opencode_service(state: State<'_, ServiceState>) -> Result<(), String> {
-    let pid = state.child_pid.swap(0, Ordering::SeqCst);
-    state.we_started.store(false, Ordering::SeqCst);
-    *state.service_url.lock().map_err(|e| e.to_string())? = None;
+/// 停止实例；主动停止先清零 PID，watchdog 不会视为崩溃。
+/// 阻塞到进程退出（最多一个宽限期），异步上下文中应放到 `spawn_blocking` 里调用
+pub(crate) fn stop_instance(instance: &ServiceInstance) -> Result<(), String> {
+    let pid = instance.child_pid.swap(0, Ordering::SeqCst);
+    instance.we_started.store(false, Ordering::SeqCst);
+    instance.adopted.store(false, Ordering::SeqCst);
+    *instance.service_url.lock().map_err(|e| e.to_string())? = None;
+    service_pid::record(instance);
 
     if pid > 0 {
-        log::info!("Stopping opencode serve, PID: {}", pid);
-        kill_process_by_pid(pid);
+        log::info!("Stopping opencode serve '{}', PID: {}", instance.id, pid);
+        let child = instance.child.lock().map_err(|e| e.to_string())?.take();
+        let status = terminate::terminate(pid, child);
+        service_exit::report(instance, pid, status, ExitReason::Stopped);
+    }
+
+    // 引擎客户端被强制结束时容器仍在运行，按名称删除
+    let launch = instance.launch.lock().map_err(|e| e.to_string())?.clone();
+    if let Some(launch) = launch {
+        let port = reqwest::Url::parse(&launch.url)
+            .ok()
+            .and_then(|url| url.port_or_known_default());
+        if let (Some(target), Some(port)) = (&launch.container, port) {
+            container::remove(target, &container::container_name(&instance.id, port));
+        }
     }
 
     Ok(())
 }
 
+/// 并行停止多个实例，总耗时不超过一个宽限期
+pub(crate) fn stop_instances(instances: &[Arc<ServiceInstance>]) {
+    thread::scope(|scope| {
+        for instance in instances {
+            scope.spawn(move || {
+                if let Err(e) = stop_instance(instance) {
+                    log::warn!("Failed to stop opencode serve '{}': {}", instance.id, e);
+                }
+            });
+        }
+    });
+}
+
+/// 重启时等待旧进程释放端口的上限
+const PORT_RELEASE_TIMEOUT: Duration = Duration::from_secs(10);
+/// 重启后等待健康检查通过的上限（不含启动阶段已等待的时间）
+const RESTART_HEALTH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// 重启托管服务：停止进程并等待端口释放，按上次的地址、环境变量与参数重新启动，
+/// 健康检查通过后才返回并发出 `service-restarted`，前端此时重连 SSE 不会落空
+#[tauri::command]
+pub async fn restart_opencode_service(
+    app: tauri::AppHandle,
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+) -> Result<StartOpencodeServiceResult, String> {
+    let instance = state.instance(instance_id.as_deref());
+    let launch = instance
+        .launch
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or_else(|| {
+            format!(
+                "opencode serve '{}' was not started by the app",
+                instance.id
+            )
+        })?;
+    let (_, hostname, port) = parse_listen_address(&launch.url)?;
+
+    log::info!("Restarting opencode serve '{}'", instance.id);
+    instance.suspended.lock().map_err(|e| e.to_string())?.take();
+    let stopping = instance.clone();
+    tauri::async_runtime::spawn_blocking(move || stop_instance(&stopping))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    // 端口释放后再启动，新进程沿用同一地址
+    let deadline = Instant::now() + PORT_RELEASE_TIMEOUT;
+    while !port_available(bind_host(&hostname), port) && Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let result = launch_service(&state, &instance, launch).await?;
+    let url = result
+        .url
+        .clone()
+        .ok_or("opencode serve restarted but did not report its address")?;
+    let deadline = Instant::now() + RESTART_HEALTH_TIMEOUT;
+    while !is_service_running(&url).await {
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "opencode serve restarted but {} did not become healthy",
+                url
+            ));
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+    *instance.service_url.lock().map_err(|e| e.to_string())? = Some(url.clone());
+
+    log::info!("opencode serve '{}' restarted at {}", instance.id, url);
+    let _ = app.emit(
+        "service-restarted",
+        serde_json::json!({ "instanceId": instance.id, "url": url }),
+    );
+    Ok(result)
+}
+
+/// 停止 opencode serve
+#[tauri::command]
+pub async fn stop_opencode_service(
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    let instance = state.instance(instance_id.as_deref());
+    tauri::async_runtime::spawn_blocking(move || stop_instance(&instance))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
 /// 查询是否由我们启动了 opencode 服务
 #[tauri::command]
-pub async fn get_service_started_by_us(state: State<'_, ServiceState>) -> Result<bool, String> {
-    Ok(state.we_started.load(Ordering::SeqCst))
+pub async fn get_service_started_by_us(
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+) -> Result<bool, String> {
+    Ok(state
+        .instance(instance_id.as_deref())
+        .we_started
+        .load(Ordering::SeqCst))
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceInstanceInfo {
+    id: String,
+    url: Option<String>,
+    pid: Option<u32>,
+    started_by_us: bool,
+    /// 上次运行留下、本次启动时接管的进程
+    adopted: bool,
+    suspended: bool,
+    healthy: bool,
+    binary_path: Option<String>,
+    extra_args: Vec<String>,
+    cwd: Option<String>,
+    wsl: Option<WslTarget>,
+    container: Option<ContainerTarget>,
+}
+
+/// 列出所有托管实例及其健康状态
+#[tauri::command]
+pub async fn list_opencode_services(
+    state: State<'_, ServiceState>,
+) -> Result<Vec<ServiceInstanceInfo>, String> {
+    let mut infos = Vec::new();
+    for instance in state.instances() {
+        let launch = instance.launch.lock().map_err(|e| e.to_string())?.clone();
+        let url = instance
+            .service_url
+            .lock()
+            .map_err(|e| e.to_string())?
+            .clone()
+            .or_else(|| launch.as_ref().map(|launch| launch.url.clone()));
+        let suspended = instance.is_suspended();
+        let healthy = match url.as_deref() {
+            Some(url) if !suspended => is_service_running(url).await,
+            _ => false,
+        };
+        let pid = instance.child_pid.load(Ordering::SeqCst);
+        infos.push(ServiceInstanceInfo {
+            id: instance.id.clone(),
+            url,
+            pid: (pid > 0).then_some(pid),
+            started_by_us: instance.we_started.load(Ordering::SeqCst),
+            adopted: instance.adopted.load(Ordering::SeqCst),
+            suspended,
+            healthy,
+            binary_path: launch.as_ref().map(|launch| launch.binary_path.clone()),
+            cwd: launch
+                .as_ref()
+                .and_then(|launch| launch.cwd.as_ref())
+                .map(|cwd| cwd.to_string_lossy().into_owned()),
+            wsl: launch.as_ref().and_then(|launch| launch.wsl.clone()),
+            container: launch.as_ref().and_then(|launch| launch.container.clone()),
+            extra_args: launch.map(|launch| launch.extra_args).unwrap_or_default(),
+        });
+    }
+    Ok(infos)
 }
 
 /// 确认关闭应用（前端调用，可选择是否同时停止服务）
@@ -371,17 +1131,180 @@ pub async fn confirm_close_app(
     state: State<'_, ServiceState>,
     stop_service: bool,
 ) -> Result<(), String> {
+    crate::app::shutdown::mark_service_handled();
     if stop_service {
-        let pid = state.child_pid.swap(0, Ordering::SeqCst);
-        if pid > 0 {
-            log::info!("Closing app and stopping opencode serve, PID: {}", pid);
-            kill_process_by_pid(pid);
-        }
-        state.we_started.store(false, Ordering::SeqCst);
-        *state.service_url.lock().map_err(|e| e.to_string())? = None;
+        log::info!("Closing app and stopping opencode serve");
+        let instances = state.instances();
+        tauri::async_runtime::spawn_blocking(move || stop_instances(&instances))
+            .await
+            .map_err(|e| e.to_string())?;
     } else {
         log::info!("Closing app, keeping opencode serve running");
+        terminate::release_all();
     }
 
     window.destroy().map_err(|e| e.to_string())
 }
+
+/// 读取空闲挂起设置
+#[tauri::command]
+pub fn get_idle_suspend_config(
+    state: State<'_, ServiceState>,
+) -> Result<IdleSuspendConfig, String> {
+    Ok(state.idle_config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// 更新空闲挂起设置（持久化到设置存储）
+#[tauri::command]
+pub fn set_idle_suspend_config(
+    state: State<'_, ServiceState>,
+    settings: State<'_, SettingsStore>,
+    config: IdleSuspendConfig,
+) -> Result<(), String> {
+    settings.set_as(IDLE_SETTINGS_KEY, &config)?;
+    *state.idle_config.lock().map_err(|e| e.to_string())? = config;
+    state.touch_activity();
+    Ok(())
+}
+
+/// 读取服务崩溃后的自动重启设置
+#[tauri::command]
+pub fn get_service_watchdog_config(
+    state: State<'_, ServiceState>,
+) -> Result<WatchdogConfig, String> {
+    Ok(state
+        .watchdog_config
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone())
+}
+
+/// 更新服务崩溃后的自动重启设置（持久化到设置存储）
+#[tauri::command]
+pub fn set_service_watchdog_config(
+    state: State<'_, ServiceState>,
+    settings: State<'_, SettingsStore>,
+    config: WatchdogConfig,
+) -> Result<(), String> {
+    settings.set_as(WATCHDOG_SETTINGS_KEY, &config)?;
+    *state.watchdog_config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
+
+/// 读取停止服务时的宽限期设置
+#[tauri::command]
+pub fn get_service_termination_config() -> TerminationConfig {
+    terminate::config()
+}
+
+/// 更新停止服务时的宽限期设置（持久化到设置存储）
+#[tauri::command]
+pub fn set_service_termination_config(
+    settings: State<'_, SettingsStore>,
+    config: TerminationConfig,
+) -> Result<(), String> {
+    settings.set_as(TERMINATION_SETTINGS_KEY, &config)?;
+    terminate::configure(&config);
+    Ok(())
+}
+
+/// 读取系统关机 / 注销时对托管服务的处理方式
+#[tauri::command]
+pub fn get_shutdown_service_policy(settings: State<'_, SettingsStore>) -> ShutdownServicePolicy {
+    settings.get_as(SHUTDOWN_SETTINGS_KEY).unwrap_or_default()
+}
+
+/// 更新系统关机 / 注销时对托管服务的处理方式
+#[tauri::command]
+pub fn set_shutdown_service_policy(
+    settings: State<'_, SettingsStore>,
+    policy: ShutdownServicePolicy,
+) -> Result<(), String> {
+    settings.set_as(SHUTDOWN_SETTINGS_KEY, &policy)
+}
+
+/// 读取托管服务最近的 stdout / stderr 输出
+#[tauri::command]
+pub fn get_service_logs(
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+    limit: Option<usize>,
+) -> Vec<ServiceLogLine> {
+    state.instance(instance_id.as_deref()).logs.lines(limit)
+}
+
+/// 订阅托管服务的输出，返回订阅前已有的输出；窗口关闭后自动退订
+#[tauri::command]
+pub fn follow_service_logs(
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+    on_line: Channel<ServiceLogLine>,
+) -> Vec<ServiceLogLine> {
+    state.instance(instance_id.as_deref()).logs.follow(on_line)
+}
+
+/// 实例最近一次退出的退出码、信号与原因；尚未退出过时为 None
+#[tauri::command]
+pub fn get_service_exit(
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+) -> Result<Option<ServiceExit>, String> {
+    Ok(state
+        .instance(instance_id.as_deref())
+        .last_exit
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone())
+}
+
+#[tauri::command]
+pub fn clear_service_logs(state: State<'_, ServiceState>, instance_id: Option<String>) {
+    state.instance(instance_id.as_deref()).logs.clear();
+}
+
+/// 前端报告用户活动（输入、切换会话等），重置空闲计时
+#[tauri::command]
+pub fn report_service_activity(state: State<'_, ServiceState>) {
+    state.touch_activity();
+}
+
+/// 发送 prompt 前调用：服务被挂起时先唤醒，返回是否执行了唤醒
+#[tauri::command]
+pub async fn ensure_service_awake(
+    app: tauri::AppHandle,
+    state: State<'_, ServiceState>,
+    instance_id: Option<String>,
+) -> Result<bool, String> {
+    idle::wake(&app, &state, instance_id.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_reserved_and_control_arguments() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(validate_extra_args(&args(&["--log-level", "debug", "--print-logs"])).is_ok());
+        assert!(validate_extra_args(&args(&["--port", "4000"])).is_err());
+        assert!(validate_extra_args(&args(&["--hostname=0.0.0.0"])).is_err());
+        assert!(validate_extra_args(&args(&["--config", "a\nb"])).is_err());
+        assert!(validate_extra_args(&args(&[""])).is_err());
+    }
+
+    #[test]
+    fn moves_to_a_free_port_when_busy() {
+        let busy = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let port = busy.local_addr().unwrap().port();
+
+        let listen = choose_listen_url(&format!("http://127.0.0.1:{}", port)).unwrap();
+        assert_ne!(listen.port, port);
+        assert_eq!(listen.hostname, "127.0.0.1");
+        assert_eq!(listen.url, format!("http://127.0.0.1:{}", listen.port));
+
+        drop(busy);
+        let listen = choose_listen_url(&format!("http://localhost:{}/", port)).unwrap();
+        assert_eq!(listen.port, port);
+        assert_eq!(listen.hostname, "localhost");
+    }
+}
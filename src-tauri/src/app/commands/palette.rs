@@ -0,0 +1,26 @@
+use crate::app::palette::{EntryKind, PaletteEntry, PaletteIndex, PaletteMatch, DEFAULT_LIMIT};
+use tauri::State;
+
+/// 命令面板模糊搜索；空查询返回最近使用的条目
+#[tauri::command]
+pub fn palette_query(
+    state: State<'_, PaletteIndex>,
+    text: String,
+    limit: Option<usize>,
+) -> Vec<PaletteMatch> {
+    state.query(&text, limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+/// 前端注册某一类条目（应用命令、prompt 模板），整体替换同类旧条目
+#[tauri::command]
+pub fn palette_register(
+    state: State<'_, PaletteIndex>,
+    kind: EntryKind,
+    entries: Vec<PaletteEntry>,
+) -> Result<(), String> {
+    if matches!(kind, EntryKind::Project | EntryKind::Session) {
+        return Err("projects and sessions are indexed automatically".to_string());
+    }
+    state.replace_kind(kind, entries);
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use crate::app::{
+    permission_relay::{
+        AuditEntry, PendingPermission, PermissionPolicy, PermissionRelayState,
+        PERMISSION_SETTINGS_KEY,
+    },
+    settings::SettingsStore,
+};
+use tauri::State;
+
+/// 审计日志默认返回条数
+const DEFAULT_AUDIT_LIMIT: usize = 100;
+
+/// 尚未答复的权限请求（按请求时间排序）
+#[tauri::command]
+pub fn list_pending_permissions(state: State<'_, PermissionRelayState>) -> Vec<PendingPermission> {
+    state.pending()
+}
+
+/// 读取超时与自动批准设置
+#[tauri::command]
+pub fn get_permission_policy(settings: State<'_, SettingsStore>) -> PermissionPolicy {
+    settings.get_as(PERMISSION_SETTINGS_KEY).unwrap_or_default()
+}
+
+/// 更新超时与自动批准设置，下一次检查时生效
+#[tauri::command]
+pub fn set_permission_policy(
+    settings: State<'_, SettingsStore>,
+    policy: PermissionPolicy,
+) -> Result<(), String> {
+    settings.set_as(PERMISSION_SETTINGS_KEY, &policy)
+}
+
+/// 最近的权限决定，最新的在前
+#[tauri::command]
+pub fn list_permission_audit(
+    state: State<'_, PermissionRelayState>,
+    limit: Option<usize>,
+) -> Vec<AuditEntry> {
+    state.audit(limit.unwrap_or(DEFAULT_AUDIT_LIMIT))
+}
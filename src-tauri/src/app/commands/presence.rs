@@ -0,0 +1,29 @@
+use crate::app::{
+    presence::{PresenceConfig, PresenceState, UserPresence, PRESENCE_SETTINGS_KEY},
+    settings::SettingsStore,
+};
+use tauri::State;
+
+/// 当前用户是否离开（系统级空闲时间超过阈值）
+#[tauri::command]
+pub fn get_user_presence(state: State<'_, PresenceState>) -> UserPresence {
+    state.snapshot()
+}
+
+/// 读取离开判定设置
+#[tauri::command]
+pub fn get_presence_config(state: State<'_, PresenceState>) -> Result<PresenceConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// 更新离开判定设置（持久化到设置存储，下一轮检测生效）
+#[tauri::command]
+pub fn set_presence_config(
+    state: State<'_, PresenceState>,
+    settings: State<'_, SettingsStore>,
+    config: PresenceConfig,
+) -> Result<(), String> {
+    settings.set_as(PRESENCE_SETTINGS_KEY, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
@@ -0,0 +1,96 @@
+use crate::app::{
+    presentation::{PresentationState, SavedWindowGeometry},
+    window_context,
+};
+use serde::Serialize;
+use tauri::{Emitter, Manager, State};
+
+/// 演示模式默认缩放比例
+const DEFAULT_PRESENTATION_ZOOM: f64 = 1.25;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PresentationChanged {
+    active: bool,
+}
+
+/// 进入演示模式：记录当前窗口状态，全屏并放大页面
+#[tauri::command]
+pub fn enter_presentation_mode(
+    window: tauri::WebviewWindow,
+    state: State<'_, PresentationState>,
+    zoom: Option<f64>,
+) -> Result<(), String> {
+    let geometry = SavedWindowGeometry {
+        size: window.outer_size().map_err(|e| e.to_string())?,
+        position: window.outer_position().map_err(|e| e.to_string())?,
+        maximized: window.is_maximized().unwrap_or(false),
+        fullscreen: window.is_fullscreen().unwrap_or(false),
+    };
+
+    if !state.enter(window.label(), geometry) {
+        return Ok(());
+    }
+
+    window.set_fullscreen(true).map_err(|e| e.to_string())?;
+    let zoom = zoom
+        .filter(|value| value.is_finite() && *value > 0.0)
+        .unwrap_or(DEFAULT_PRESENTATION_ZOOM);
+    let _ = window.set_zoom(zoom);
+
+    window_context::refresh_badge(window.app_handle());
+    log::info!("Window '{}' entered presentation mode", window.label());
+    let _ = window.emit_to(
+        window.label(),
+        "presentation-mode-changed",
+        PresentationChanged { active: true },
+    );
+    Ok(())
+}
+
+/// 退出演示模式并恢复进入前的窗口状态
+#[tauri::command]
+pub fn exit_presentation_mode(
+    window: tauri::WebviewWindow,
+    state: State<'_, PresentationState>,
+) -> Result<(), String> {
+    let Some(geometry) = state.exit(window.label()) else {
+        return Ok(());
+    };
+
+    let _ = window.set_zoom(1.0);
+    window
+        .set_fullscreen(geometry.fullscreen)
+        .map_err(|e| e.to_string())?;
+
+    if !geometry.fullscreen {
+        if geometry.maximized {
+            let _ = window.maximize();
+        } else {
+            let _ = window.unmaximize();
+            let _ = window.set_size(geometry.size);
+            let _ = window.set_position(geometry.position);
+        }
+    }
+
+    window_context::refresh_badge(window.app_handle());
+    log::info!("Window '{}' left presentation mode", window.label());
+    let _ = window.emit_to(
+        window.label(),
+        "presentation-mode-changed",
+        PresentationChanged { active: false },
+    );
+    Ok(())
+}
+
+/// 查询当前窗口是否处于演示模式
+#[tauri::command]
+pub fn is_presentation_mode(window: tauri::Window, state: State<'_, PresentationState>) -> bool {
+    state.is_presenting(window.label())
+}
+
+/// 是否应抑制系统通知（任一窗口处于演示模式）
+#[tauri::command]
+pub fn should_suppress_notifications(state: State<'_, PresentationState>) -> bool {
+    state.any_presenting()
+}
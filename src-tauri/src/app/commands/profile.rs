@@ -0,0 +1,44 @@
+use crate::app::profile::{self, ActiveProfile};
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileInfo {
+    active: String,
+    profiles: Vec<String>,
+}
+
+/// 当前 profile 与所有已存在的 profile
+#[tauri::command]
+pub fn list_app_profiles(app: tauri::AppHandle, state: State<'_, ActiveProfile>) -> ProfileInfo {
+    let mut profiles = profile::list_profiles(&app);
+    if !profiles.iter().any(|name| name == state.name()) {
+        profiles.push(state.name().to_string());
+    }
+    ProfileInfo {
+        active: state.name().to_string(),
+        profiles,
+    }
+}
+
+/// 切换 profile：保存选择并重启应用（profile 在启动时确定）
+#[tauri::command]
+pub fn switch_app_profile(
+    app: tauri::AppHandle,
+    state: State<'_, ActiveProfile>,
+    name: String,
+) -> Result<(), String> {
+    profile::validate_profile_name(&name)?;
+    if name == state.name() {
+        return Ok(());
+    }
+
+    profile::save_active_profile(&app, &name)?;
+    log::info!(
+        "Switching profile '{}' -> '{}', restarting",
+        state.name(),
+        name
+    );
+    app.restart()
+}
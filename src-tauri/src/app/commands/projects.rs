@@ -0,0 +1,181 @@
+use crate::app::{
+    api::ServerTarget,
+    dir_browse::{self, DirListing},
+    discovery::{self, DiscoveredRepo},
+    launch_args::LaunchOptions,
+    palette,
+    projects::{self, normalize_path, Project, ProjectsState},
+    scaffold::{self, CreateProgress, CreateStage, ProjectTemplate},
+};
+use std::{collections::HashSet, path::PathBuf};
+use tauri::{Emitter, Manager, State};
+
+/// 列出已注册的项目
+#[tauri::command]
+pub fn list_projects(state: State<'_, ProjectsState>) -> Vec<Project> {
+    state.list()
+}
+
+/// 从项目列表移除（不删除文件）
+#[tauri::command]
+pub fn remove_project(
+    app: tauri::AppHandle,
+    state: State<'_, ProjectsState>,
+    id: String,
+) -> Result<bool, String> {
+    let removed = state.remove(&id)?;
+    palette::sync_projects(&app);
+    Ok(removed)
+}
+
+/// 从模板创建项目：克隆或脚手架 → 注册 → 打开新窗口。
+/// 过程中发出 `create-project-progress` 事件。
+#[tauri::command]
+pub async fn create_project(
+    app: tauri::AppHandle,
+    template: ProjectTemplate,
+    destination: String,
+    open_window: Option<bool>,
+) -> Result<Project, String> {
+    let dest = PathBuf::from(destination.trim());
+    if !dest.is_absolute() {
+        return Err("destination must be an absolute path".to_string());
+    }
+
+    let emitter = app.clone();
+    let destination = dest.to_string_lossy().to_string();
+    let emit = move |stage: CreateStage, message: String, percent: Option<u8>| {
+        let _ = emitter.emit(
+            "create-project-progress",
+            CreateProgress {
+                destination: destination.clone(),
+                stage,
+                message,
+                percent,
+            },
+        );
+    };
+
+    let mut progress = emit.clone();
+    let task_template = template.clone();
+    let task_dest = dest.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        scaffold::create_project(&task_template, &task_dest, &mut progress)
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    emit(
+        CreateStage::Registering,
+        "Registering project".to_string(),
+        None,
+    );
+    let project = app
+        .state::<ProjectsState>()
+        .register(&dest, Some(template.label()))?;
+    palette::sync_projects(&app);
+    emit(CreateStage::Done, project.path.clone(), None);
+
+    if open_window.unwrap_or(true) {
+        crate::app::create_new_window(&app, Some(project.path.clone()), LaunchOptions::default());
+    }
+    log::info!("Created project '{}' at {}", project.name, project.path);
+    Ok(project)
+}
+
+/// 切换项目只读模式（只读时 agent 的写入 / 编辑 / 执行请求会被自动拒绝，
+/// 前端也不能通过 fs 插件访问项目文件；取消只读后 fs 访问在重启后恢复）
+#[tauri::command]
+pub fn set_project_read_only(
+    app: tauri::AppHandle,
+    state: State<'_, ProjectsState>,
+    id: String,
+    read_only: bool,
+) -> Result<Project, String> {
+    let project = state.set_read_only(&id, read_only)?;
+    palette::sync_projects(&app);
+    if read_only {
+        projects::forbid_in_fs_scope(&app, &project);
+        log::info!("Project '{}' is now read-only", project.name);
+    } else {
+        log::info!(
+            "Project '{}' is now writable; file access through the fs plugin resumes after a restart",
+            project.name
+        );
+    }
+    Ok(project)
+}
+
+/// 扫描根目录下的 git 仓库（默认最多向下 4 层），已注册的会标记出来
+#[tauri::command]
+pub async fn discover_projects(
+    state: State<'_, ProjectsState>,
+    root: String,
+    max_depth: Option<usize>,
+) -> Result<Vec<DiscoveredRepo>, String> {
+    let root = normalize_path(&PathBuf::from(root.trim()));
+    if !root.is_dir() {
+        return Err(format!("'{}' is not a directory", root.display()));
+    }
+    let registered: HashSet<String> = state.list().into_iter().map(|p| p.path).collect();
+    let max_depth = max_depth.unwrap_or(discovery::DEFAULT_MAX_DEPTH);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        discovery::find_repos(&root, max_depth)
+            .iter()
+            .map(|repo| {
+                let path = normalize_path(repo);
+                let registered = registered.contains(path.to_string_lossy().as_ref());
+                discovery::describe(&path, registered)
+            })
+            .collect()
+    })
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 批量注册选中的目录，返回注册后的项目；单个目录失败时记录日志并跳过
+#[tauri::command]
+pub fn register_projects(
+    app: tauri::AppHandle,
+    state: State<'_, ProjectsState>,
+    paths: Vec<String>,
+) -> Vec<Project> {
+    let projects: Vec<Project> = paths
+        .iter()
+        .filter_map(|path| match state.register(&PathBuf::from(path), None) {
+            Ok(project) => Some(project),
+            Err(e) => {
+                log::warn!("Failed to register project {}: {}", path, e);
+                None
+            }
+        })
+        .collect();
+    palette::sync_projects(&app);
+    log::info!("Registered {} discovered projects", projects.len());
+    projects
+}
+
+/// 列出本地目录（未指定路径时从主目录开始），用于选择项目目录
+#[tauri::command]
+pub async fn browse_local_directory(
+    app: tauri::AppHandle,
+    path: Option<String>,
+    dirs_only: Option<bool>,
+) -> Result<DirListing, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        dir_browse::list_local(&app, path.as_deref(), dirs_only.unwrap_or(true))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// 通过服务器 API 列出远程 opencode 服务器上的目录，返回结构与本地浏览相同
+#[tauri::command]
+pub async fn browse_remote_directory(
+    target: ServerTarget,
+    path: Option<String>,
+    dirs_only: Option<bool>,
+) -> Result<DirListing, String> {
+    dir_browse::list_remote(&target, path.as_deref(), dirs_only.unwrap_or(true)).await
+}
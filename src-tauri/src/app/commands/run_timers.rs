@@ -0,0 +1,14 @@
+use crate::app::run_timers::{RunElapsed, RunTimersState};
+use tauri::State;
+
+/// 会话最近一次运行的起止时间与耗时（运行中时为当前耗时）
+#[tauri::command]
+pub fn get_run_elapsed(state: State<'_, RunTimersState>, session_id: String) -> Option<RunElapsed> {
+    state.get(&session_id)
+}
+
+/// 当前正在运行的全部会话计时，页面重载后用于恢复显示
+#[tauri::command]
+pub fn list_running_timers(state: State<'_, RunTimersState>) -> Vec<RunElapsed> {
+    state.running()
+}
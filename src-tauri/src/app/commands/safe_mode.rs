@@ -0,0 +1,14 @@
+use crate::app::safe_mode::{SafeModeInfo, SafeModeState};
+use tauri::State;
+
+/// 是否处于安全模式，以及最近一次崩溃 / 非正常退出的信息；安全模式下前端不自动启动服务
+#[tauri::command]
+pub fn get_safe_mode_info(state: State<'_, SafeModeState>) -> SafeModeInfo {
+    state.info()
+}
+
+/// 用户查看后清除崩溃记录
+#[tauri::command]
+pub fn clear_crash_report(state: State<'_, SafeModeState>) -> Result<(), String> {
+    state.clear_crash()
+}
@@ -0,0 +1,35 @@
+use crate::app::scheduler::{run_schedule, Schedule, ScheduleRun, SchedulerState};
+use tauri::State;
+
+/// 列出所有定时 prompt
+#[tauri::command]
+pub fn list_schedules(state: State<'_, SchedulerState>) -> Vec<Schedule> {
+    state.list()
+}
+
+/// 新建或更新定时 prompt（按 id），返回计算好下一次触发时间的计划
+#[tauri::command]
+pub fn save_schedule(
+    state: State<'_, SchedulerState>,
+    schedule: Schedule,
+) -> Result<Schedule, String> {
+    state.upsert(schedule)
+}
+
+#[tauri::command]
+pub fn delete_schedule(state: State<'_, SchedulerState>, id: String) -> Result<bool, String> {
+    state.remove(&id)
+}
+
+/// 立即运行一次（不影响原定计划）
+#[tauri::command]
+pub async fn run_schedule_now(
+    app: tauri::AppHandle,
+    state: State<'_, SchedulerState>,
+    id: String,
+) -> Result<ScheduleRun, String> {
+    let schedule = state
+        .get(&id)
+        .ok_or_else(|| format!("schedule '{}' not found", id))?;
+    Ok(run_schedule(&app, schedule, false).await)
+}
@@ -0,0 +1,69 @@
+use crate::app::{
+    commands::opencode::{launch_service, prepare_launch, StartOpencodeServiceResult},
+    credentials::{self, ServerCredential},
+    server_profiles::{ServerProfile, ServerProfilesState},
+    service::ServiceState,
+};
+use tauri::State;
+
+/// 列出所有服务器配置档（不含凭据）
+#[tauri::command]
+pub fn list_server_profiles(state: State<'_, ServerProfilesState>) -> Vec<ServerProfile> {
+    state.list()
+}
+
+/// 新建或更新配置档；传入 `credential` 时一并存入系统钥匙串
+#[tauri::command]
+pub fn save_server_profile(
+    state: State<'_, ServerProfilesState>,
+    profile: ServerProfile,
+    credential: Option<ServerCredential>,
+) -> Result<ServerProfile, String> {
+    let profile = state.save(profile)?;
+    if let Some(credential) = credential {
+        credentials::store(&profile.id, &credential)?;
+    }
+    log::info!("Saved server profile '{}'", profile.id);
+    Ok(profile)
+}
+
+/// 删除配置档及其凭据，返回之前是否存在
+#[tauri::command]
+pub fn delete_server_profile(
+    state: State<'_, ServerProfilesState>,
+    id: String,
+) -> Result<bool, String> {
+    state.delete(&id)
+}
+
+/// 按配置档启动托管服务，实例 id 即配置档 id
+#[tauri::command]
+pub async fn start_profile_service(
+    profiles: State<'_, ServerProfilesState>,
+    state: State<'_, ServiceState>,
+    profile_id: String,
+) -> Result<StartOpencodeServiceResult, String> {
+    start_profile(&state, profiles.get(&profile_id)?).await
+}
+
+/// 按配置档的托管服务参数启动（或复用）服务，等待其健康检查通过
+pub(crate) async fn start_profile(
+    state: &ServiceState,
+    profile: ServerProfile,
+) -> Result<StartOpencodeServiceResult, String> {
+    let managed = profile
+        .managed
+        .ok_or_else(|| format!("server profile '{}' has no managed service", profile.id))?;
+    let launch = prepare_launch(
+        profile.url,
+        managed.binary_path,
+        managed.env_vars,
+        managed.extra_args,
+        managed.env_file,
+        managed.cwd,
+        managed.wsl,
+        managed.container,
+    )?;
+    let instance = state.instance(Some(&profile.id));
+    launch_service(state, &instance, launch).await
+}
@@ -0,0 +1,31 @@
+use crate::app::{service::ServiceState, service_autostart};
+use tauri::State;
+
+/// 登录系统时是否自动启动 opencode serve
+#[tauri::command]
+pub fn get_service_autostart(app: tauri::AppHandle) -> bool {
+    service_autostart::is_enabled(&app)
+}
+
+/// 开启或关闭登录时自动启动；开启时沿用实例上次的启动参数，需先启动过一次
+#[tauri::command]
+pub async fn set_service_autostart(
+    app: tauri::AppHandle,
+    state: State<'_, ServiceState>,
+    enabled: bool,
+    instance_id: Option<String>,
+) -> Result<(), String> {
+    let launch = if enabled {
+        let instance = state.instance(instance_id.as_deref());
+        let launch = instance.launch.lock().map_err(|e| e.to_string())?.clone();
+        Some(launch.ok_or("start the service once before enabling autostart")?)
+    } else {
+        None
+    };
+    tauri::async_runtime::spawn_blocking(move || match launch {
+        Some(launch) => service_autostart::enable(&app, &launch),
+        None => service_autostart::disable(&app),
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,42 @@
+use crate::app::{
+    service::ServiceState,
+    service_stats::{
+        ServiceStats, ServiceStatsConfig, ServiceStatsState, SERVICE_STATS_SETTINGS_KEY,
+    },
+    settings::SettingsStore,
+};
+use tauri::State;
+
+/// 托管服务的 CPU、内存与运行时长；指定 `instance_id` 时只返回该实例，未运行时为空
+#[tauri::command]
+pub fn get_service_stats(
+    state: State<'_, ServiceStatsState>,
+    services: State<'_, ServiceState>,
+    instance_id: Option<String>,
+) -> Vec<ServiceStats> {
+    let instances = match instance_id {
+        Some(id) => vec![services.instance(Some(&id))],
+        None => services.instances(),
+    };
+    state.sample(&instances)
+}
+
+/// 读取 service-stats 事件的推送设置
+#[tauri::command]
+pub fn get_service_stats_config(
+    state: State<'_, ServiceStatsState>,
+) -> Result<ServiceStatsConfig, String> {
+    Ok(state.config.lock().map_err(|e| e.to_string())?.clone())
+}
+
+/// 更新 service-stats 事件的推送设置（持久化到设置存储）
+#[tauri::command]
+pub fn set_service_stats_config(
+    state: State<'_, ServiceStatsState>,
+    settings: State<'_, SettingsStore>,
+    config: ServiceStatsConfig,
+) -> Result<(), String> {
+    settings.set_as(SERVICE_STATS_SETTINGS_KEY, &config)?;
+    *state.config.lock().map_err(|e| e.to_string())? = config;
+    Ok(())
+}
@@ -0,0 +1,553 @@
+// ============================================
+// Service Config Validation (desktop only)
+// 启动 opencode serve 之前检查设置，返回结构化的问题列表，设置界面可逐项提示：
+// - 可执行文件存在且可执行；额外参数、工作目录、.env 文件有效
+// - 必需的环境变量（API key 等）存在且非空
+// - 端口空闲；opencode 配置文件能够解析
+// 同时返回环境变量差异：哪些变量来自 .env 文件或显式设置、是否覆盖了继承的环境（不含值）
+// ============================================
+
+use crate::app::{
+    commands::{
+        onboarding::{config_candidates, strip_json_comments},
+        opencode::{
+            bind_host, is_service_running, parse_listen_address, patched_env_var, port_available,
+            validate_extra_args,
+        },
+    },
+    container::ContainerTarget,
+    dotenv,
+    wsl::{self, WslTarget},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+use tauri::Manager;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProblemSeverity {
+    /// 按当前设置无法启动
+    Error,
+    /// 能启动，但可能不符合预期
+    Warning,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigProblem {
+    /// 对应的设置项：binaryPath、extraArgs、cwd、envFile、envVars、url、config
+    field: &'static str,
+    severity: ProblemSeverity,
+    message: String,
+    fix: Option<String>,
+}
+
+impl ConfigProblem {
+    fn error(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field,
+            severity: ProblemSeverity::Error,
+            message: message.into(),
+            fix: None,
+        }
+    }
+
+    fn warning(field: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            severity: ProblemSeverity::Warning,
+            ..Self::error(field, message)
+        }
+    }
+
+    fn with_fix(mut self, fix: impl Into<String>) -> Self {
+        self.fix = Some(fix.into());
+        self
+    }
+}
+
+/// 环境变量的来源；同名时显式设置优先
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EnvSource {
+    EnvFile,
+    Explicit,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvEntry {
+    name: String,
+    source: EnvSource,
+    /// 覆盖了应用进程继承的同名变量
+    overrides_inherited: bool,
+    empty: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceConfigReport {
+    /// 没有 Error 级别的问题
+    valid: bool,
+    problems: Vec<ConfigProblem>,
+    /// 来自 .env 文件或显式设置的变量
+    env: Vec<EnvEntry>,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServiceConfigArgs {
+    url: String,
+    binary_path: String,
+    env_vars: HashMap<String, String>,
+    env_file: Option<String>,
+    extra_args: Vec<String>,
+    cwd: Option<String>,
+    /// 必须存在且非空的环境变量，例如所用 provider 的 API key
+    required_env: Vec<String>,
+    wsl: Option<WslTarget>,
+    container: Option<ContainerTarget>,
+}
+
+/// 在 PATH 中查找命令；Windows 上补全常见扩展名
+fn find_in_path(program: &str, path_var: Option<std::ffi::OsString>) -> Option<PathBuf> {
+    let extensions: &[&str] = if cfg!(windows) {
+        &["", ".exe", ".cmd", ".bat"]
+    } else {
+        &[""]
+    };
+    std::env::split_paths(&path_var?)
+        .flat_map(|dir| {
+            extensions
+                .iter()
+                .map(move |ext| dir.join(format!("{}{}", program, ext)))
+        })
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|metadata| metadata.permissions().mode() & 0o111 != 0)
+}
+
+/// 没有扩展名的文件与 build_opencode_command 一致，经 cmd.exe 启动
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    match path.extension() {
+        None => true,
+        Some(ext) => ext.to_str().is_some_and(|ext| {
+            ["exe", "cmd", "bat", "com"]
+                .iter()
+                .any(|known| ext.eq_ignore_ascii_case(known))
+        }),
+    }
+}
+
+fn check_binary(args: &ServiceConfigArgs, problems: &mut Vec<ConfigProblem>) {
+    if let Some(target) = &args.container {
+        let engine = target.engine.trim();
+        let engine = if engine.is_empty() { "docker" } else { engine };
+        if find_in_path(engine, patched_env_var(&args.env_vars, "PATH")).is_none() {
+            problems.push(
+                ConfigProblem::error(
+                    "binaryPath",
+                    format!("container engine '{}' not found on PATH", engine),
+                )
+                .with_fix("Install Docker or Podman, or choose another engine"),
+            );
+        }
+        return;
+    }
+    // WSL 内的路径在宿主上无法检查
+    if args.wsl.is_some() {
+        if !cfg!(target_os = "windows") {
+            problems.push(ConfigProblem::error(
+                "binaryPath",
+                "WSL is only available on Windows",
+            ));
+        }
+        return;
+    }
+
+    let binary = args.binary_path.trim();
+    if binary.is_empty() {
+        problems.push(
+            ConfigProblem::error("binaryPath", "no opencode executable configured")
+                .with_fix("Detect opencode automatically or browse to the executable"),
+        );
+        return;
+    }
+    let path = Path::new(binary);
+    let resolved = if path.components().count() == 1 && !path.exists() {
+        find_in_path(binary, patched_env_var(&args.env_vars, "PATH"))
+    } else {
+        Some(path.to_path_buf())
+    };
+    match resolved {
+        Some(path) if path.is_file() => {
+            if !is_executable(&path) {
+                problems.push(
+                    ConfigProblem::error(
+                        "binaryPath",
+                        format!("'{}' is not executable", path.display()),
+                    )
+                    .with_fix(if cfg!(unix) {
+                        format!("Run chmod +x '{}'", path.display())
+                    } else {
+                        "Choose the .exe or .cmd file".to_string()
+                    }),
+                );
+            }
+        }
+        Some(path) if path.is_dir() => problems.push(ConfigProblem::error(
+            "binaryPath",
+            format!("'{}' is a directory", path.display()),
+        )),
+        _ => problems.push(
+            ConfigProblem::error("binaryPath", format!("'{}' not found", binary))
+                .with_fix("Check the path or install opencode (npm i -g opencode-ai)"),
+        ),
+    }
+}
+
+fn check_cwd(args: &ServiceConfigArgs, problems: &mut Vec<ConfigProblem>) -> Option<PathBuf> {
+    let cwd = args
+        .cwd
+        .as_deref()
+        .map(str::trim)
+        .filter(|cwd| !cwd.is_empty())?;
+    let valid = match args.wsl {
+        Some(_) => wsl::to_wsl_path(cwd).is_some(),
+        None => Path::new(cwd).is_dir(),
+    };
+    if !valid {
+        problems.push(ConfigProblem::error(
+            "cwd",
+            format!("working directory '{}' does not exist", cwd),
+        ));
+        return None;
+    }
+    Some(PathBuf::from(cwd))
+}
+
+/// 合并 .env 文件与显式设置的变量，记录每个变量的来源
+fn resolve_env(
+    args: &ServiceConfigArgs,
+    problems: &mut Vec<ConfigProblem>,
+) -> BTreeMap<String, (String, EnvSource)> {
+    let mut env = BTreeMap::new();
+    if let Some(path) = args
+        .env_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    {
+        match dotenv::load(Path::new(path)) {
+            Ok(vars) => {
+                for (key, value) in vars {
+                    env.insert(key, (value, EnvSource::EnvFile));
+                }
+            }
+            Err(e) => problems.push(ConfigProblem::error("envFile", e)),
+        }
+    }
+    for (key, value) in &args.env_vars {
+        if key.trim().is_empty() || key.contains('=') {
+            problems.push(ConfigProblem::error(
+                "envVars",
+                format!("invalid environment variable name '{}'", key),
+            ));
+            continue;
+        }
+        env.insert(key.clone(), (value.clone(), EnvSource::Explicit));
+    }
+    env
+}
+
+/// Windows 环境变量名不区分大小写，其他平台按原样匹配
+#[cfg(windows)]
+fn lookup_env(env: &BTreeMap<String, (String, EnvSource)>, name: &str) -> Option<String> {
+    env.iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, (value, _))| value.clone())
+}
+
+#[cfg(not(windows))]
+fn lookup_env(env: &BTreeMap<String, (String, EnvSource)>, name: &str) -> Option<String> {
+    env.get(name).map(|(value, _)| value.clone())
+}
+
+fn check_required_env(
+    required: &[String],
+    env: &BTreeMap<String, (String, EnvSource)>,
+    problems: &mut Vec<ConfigProblem>,
+) {
+    for name in required
+        .iter()
+        .map(|name| name.trim())
+        .filter(|name| !name.is_empty())
+    {
+        let value = lookup_env(env, name).or_else(|| std::env::var(name).ok());
+        match value {
+            None => problems.push(
+                ConfigProblem::error("envVars", format!("{} is not set", name)).with_fix(format!(
+                    "Add {} to the service environment or .env file",
+                    name
+                )),
+            ),
+            Some(value) if value.trim().is_empty() => problems.push(
+                ConfigProblem::error("envVars", format!("{} is empty", name))
+                    .with_fix(format!("Set a value for {}", name)),
+            ),
+            Some(_) => {}
+        }
+    }
+}
+
+async fn check_port(url: &str, problems: &mut Vec<ConfigProblem>) {
+    let (_, hostname, port) = match parse_listen_address(url) {
+        Ok(address) => address,
+        Err(e) => {
+            problems.push(
+                ConfigProblem::error("url", e).with_fix("Use a URL such as http://127.0.0.1:4096"),
+            );
+            return;
+        }
+    };
+    if port_available(bind_host(&hostname), port) {
+        return;
+    }
+    // 占用端口的就是 opencode 时启动会直接复用
+    if is_service_running(url).await {
+        problems.push(ConfigProblem::warning(
+            "url",
+            format!(
+                "an opencode server is already running at {}; it will be reused",
+                url
+            ),
+        ));
+    } else {
+        problems.push(
+            ConfigProblem::warning(
+                "url",
+                format!(
+                    "port {} is in use by another program; a free port will be chosen",
+                    port
+                ),
+            )
+            .with_fix("Stop the program using this port or configure a different port"),
+        );
+    }
+}
+
+/// 去掉对象与数组中的尾随逗号（忽略字符串内的内容），opencode 接受这种写法
+fn strip_trailing_commas(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut pending_comma: Option<usize> = None;
+    for c in input.chars() {
+        if in_string {
+            output.push(c);
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            ',' => {
+                pending_comma = Some(output.len());
+                output.push(c);
+            }
+            '}' | ']' => {
+                if let Some(index) = pending_comma.take() {
+                    output.remove(index);
+                }
+                output.push(c);
+            }
+            c if c.is_whitespace() => output.push(c),
+            c => {
+                pending_comma = None;
+                in_string = c == '"';
+                output.push(c);
+            }
+        }
+    }
+    output
+}
+
+fn check_config_file(path: &Path, problems: &mut Vec<ConfigProblem>) {
+    let Ok(data) = std::fs::read_to_string(path) else {
+        return;
+    };
+    let data = strip_trailing_commas(&strip_json_comments(&data));
+    match serde_json::from_str::<serde_json::Value>(&data) {
+        Ok(value) if value.is_object() => {}
+        Ok(_) => problems.push(ConfigProblem::error(
+            "config",
+            format!("{}: top level must be an object", path.display()),
+        )),
+        Err(e) => problems.push(
+            ConfigProblem::error("config", format!("{}: {}", path.display(), e))
+                .with_fix("Fix the JSON syntax at the reported line and column"),
+        ),
+    }
+}
+
+/// opencode 会读取的配置：OPENCODE_CONFIG_CONTENT、OPENCODE_CONFIG、全局配置与工作目录下的配置
+fn check_config(
+    home: Option<&Path>,
+    cwd: Option<&Path>,
+    env: &BTreeMap<String, (String, EnvSource)>,
+    problems: &mut Vec<ConfigProblem>,
+) {
+    let var = |name: &str| {
+        env.get(name)
+            .map(|(value, _)| value.clone())
+            .or_else(|| std::env::var(name).ok())
+            .filter(|value| !value.trim().is_empty())
+    };
+    if let Some(content) = var("OPENCODE_CONFIG_CONTENT") {
+        let content = strip_trailing_commas(&strip_json_comments(&content));
+        if let Err(e) = serde_json::from_str::<serde_json::Value>(&content) {
+            problems.push(ConfigProblem::error(
+                "config",
+                format!("OPENCODE_CONFIG_CONTENT: {}", e),
+            ));
+        }
+    }
+    if let Some(path) = var("OPENCODE_CONFIG") {
+        let path = PathBuf::from(path);
+        if path.is_file() {
+            check_config_file(&path, problems);
+        } else {
+            problems.push(ConfigProblem::error(
+                "config",
+                format!(
+                    "OPENCODE_CONFIG points to missing file '{}'",
+                    path.display()
+                ),
+            ));
+        }
+    }
+    let mut paths: Vec<PathBuf> = home.map(config_candidates).unwrap_or_default();
+    if let Some(cwd) = cwd {
+        paths.extend(["opencode.json", "opencode.jsonc"].map(|name| cwd.join(name)));
+    }
+    for path in paths.iter().filter(|path| path.is_file()) {
+        check_config_file(path, problems);
+    }
+}
+
+/// 检查托管服务的启动设置，返回问题列表与环境变量差异
+#[tauri::command]
+pub async fn validate_service_config(
+    app: tauri::AppHandle,
+    args: ServiceConfigArgs,
+) -> Result<ServiceConfigReport, String> {
+    let home = app.path().home_dir().ok();
+    let url = args.url.trim().to_string();
+    let (mut problems, env) = tauri::async_runtime::spawn_blocking(move || {
+        let mut problems = Vec::new();
+        check_binary(&args, &mut problems);
+        if args.wsl.is_some() && args.container.is_some() {
+            problems.push(ConfigProblem::error(
+                "binaryPath",
+                "wsl and container cannot be used together",
+            ));
+        }
+        if let Err(e) = validate_extra_args(&args.extra_args) {
+            problems.push(ConfigProblem::error("extraArgs", e));
+        }
+        let cwd = check_cwd(&args, &mut problems);
+        let env = resolve_env(&args, &mut problems);
+        check_required_env(&args.required_env, &env, &mut problems);
+        // WSL 与容器中的服务读取的是它们自己的配置
+        if args.wsl.is_none() && args.container.is_none() {
+            check_config(home.as_deref(), cwd.as_deref(), &env, &mut problems);
+        }
+        (problems, env)
+    })
+    .await
+    .map_err(|e| e.to_string())?;
+    check_port(&url, &mut problems).await;
+
+    let env = env
+        .into_iter()
+        .map(|(name, (value, source))| EnvEntry {
+            overrides_inherited: std::env::var_os(&name).is_some(),
+            empty: value.is_empty(),
+            name,
+            source,
+        })
+        .collect();
+    let valid = problems
+        .iter()
+        .all(|problem| problem.severity != ProblemSeverity::Error);
+    Ok(ServiceConfigReport {
+        valid,
+        problems,
+        env,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_trailing_commas_outside_strings() {
+        assert_eq!(
+            strip_trailing_commas("{\"a\": [1, 2,], \"b\": \",}\",\n}"),
+            "{\"a\": [1, 2], \"b\": \",}\"\n}"
+        );
+        assert_eq!(strip_trailing_commas("[\"\\\"\", ]"), "[\"\\\"\" ]");
+    }
+
+    #[test]
+    fn required_env_names_match_case_only_on_windows() {
+        let env = BTreeMap::from([
+            (
+                "Opencode_Test_Key".to_string(),
+                ("secret".to_string(), EnvSource::Explicit),
+            ),
+            ("EMPTY".to_string(), (" ".to_string(), EnvSource::EnvFile)),
+        ]);
+        let mut problems = Vec::new();
+        check_required_env(
+            &[
+                "OPENCODE_TEST_KEY".to_string(),
+                "empty".to_string(),
+                "OPENCODE_TEST_SURELY_UNSET_VAR".to_string(),
+            ],
+            &env,
+            &mut problems,
+        );
+
+        let messages: Vec<_> = problems.iter().map(|problem| &problem.message).collect();
+        #[cfg(windows)]
+        assert_eq!(
+            messages,
+            [
+                "empty is empty",
+                "OPENCODE_TEST_SURELY_UNSET_VAR is not set"
+            ]
+        );
+        #[cfg(not(windows))]
+        assert_eq!(
+            messages,
+            [
+                "OPENCODE_TEST_KEY is not set",
+                "empty is not set",
+                "OPENCODE_TEST_SURELY_UNSET_VAR is not set"
+            ]
+        );
+    }
+}
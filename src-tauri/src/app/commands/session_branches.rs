@@ -0,0 +1,140 @@
+use crate::app::{
+    projects::{normalize_path, ProjectsState},
+    session_branches::{self, BranchError, SessionBranch, SessionBranchesState},
+};
+use std::path::PathBuf;
+use tauri::{Emitter, Manager, State};
+
+/// 检出会话关联的分支，失败时发出 `session-branch-error`；只读项目中不切换分支
+fn checkout_or_emit(app: &tauri::AppHandle, link: &SessionBranch) -> Result<(), String> {
+    let directory = PathBuf::from(&link.directory);
+    let result = match app.state::<ProjectsState>().read_only_project(&directory) {
+        Some(project) => Err(format!(
+            "'{}' is a read-only project; branches are not switched there",
+            project.name
+        )),
+        None => session_branches::checkout(&directory, &link.branch),
+    };
+    result.inspect_err(|message| {
+        let _ = app.emit(
+            "session-branch-error",
+            BranchError {
+                session_id: link.session_id.clone(),
+                directory: link.directory.clone(),
+                branch: link.branch.clone(),
+                message: message.clone(),
+            },
+        );
+    })
+}
+
+/// 把会话关联到项目中的分支；`checkout` 为 true 时立即创建 / 检出，
+/// `auto_checkout` 为 true 时会话开始前由 prepare_session_branch 切到该分支
+#[tauri::command]
+pub async fn link_session_branch(
+    app: tauri::AppHandle,
+    session_id: String,
+    directory: String,
+    branch: String,
+    checkout: Option<bool>,
+    auto_checkout: Option<bool>,
+) -> Result<SessionBranch, String> {
+    let dir = normalize_path(&PathBuf::from(directory.trim()));
+    if !dir.is_dir() {
+        return Err(format!("'{}' is not a directory", dir.display()));
+    }
+    let link = SessionBranch {
+        session_id,
+        directory: dir.to_string_lossy().to_string(),
+        branch: branch.trim().to_string(),
+        auto_checkout: auto_checkout.unwrap_or(false),
+        linked_at: crate::app::now_millis(),
+    };
+
+    let task_app = app.clone();
+    let task_link = link.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        session_branches::validate_branch_name(&task_link.branch, &dir)?;
+        if checkout.unwrap_or(false) {
+            checkout_or_emit(&task_app, &task_link)?;
+        }
+        Ok::<_, String>(())
+    })
+    .await
+    .map_err(|e| e.to_string())??;
+
+    app.state::<SessionBranchesState>().set(link.clone())?;
+    let _ = app.emit("session-branch-linked", &link);
+    Ok(link)
+}
+
+/// 取消会话与分支的关联（不删除分支）
+#[tauri::command]
+pub fn unlink_session_branch(
+    state: State<'_, SessionBranchesState>,
+    session_id: String,
+) -> Result<bool, String> {
+    state.remove(&session_id)
+}
+
+/// 会话关联的分支
+#[tauri::command]
+pub fn get_session_branch(
+    state: State<'_, SessionBranchesState>,
+    session_id: String,
+) -> Option<SessionBranch> {
+    state.get(&session_id)
+}
+
+/// 列出会话与分支的关联，指定目录时只返回该项目的
+#[tauri::command]
+pub fn list_session_branches(
+    state: State<'_, SessionBranchesState>,
+    directory: Option<String>,
+) -> Vec<SessionBranch> {
+    let directory = directory.map(|directory| {
+        normalize_path(&PathBuf::from(directory))
+            .to_string_lossy()
+            .to_string()
+    });
+    state.list(directory.as_deref())
+}
+
+/// 会话开始前调用，前端等待完成后再发送第一条 prompt：
+/// 关联了分支且开启自动切换时检出该分支，否则返回 None。之后不再自动切换，
+/// 会话运行中手动切换的分支不会被改回
+#[tauri::command]
+pub async fn prepare_session_branch(
+    app: tauri::AppHandle,
+    session_id: String,
+) -> Result<Option<SessionBranch>, String> {
+    let Some(link) = app
+        .state::<SessionBranchesState>()
+        .get(&session_id)
+        .filter(|link| link.auto_checkout)
+    else {
+        return Ok(None);
+    };
+    let task_link = link.clone();
+    tauri::async_runtime::spawn_blocking(move || checkout_or_emit(&app, &task_link))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(Some(link))
+}
+
+/// 立即检出会话关联的分支，工作区有未提交修改时报错
+#[tauri::command]
+pub async fn checkout_session_branch(
+    app: tauri::AppHandle,
+    session_id: String,
+) -> Result<SessionBranch, String> {
+    let link = app
+        .state::<SessionBranchesState>()
+        .get(&session_id)
+        .ok_or_else(|| format!("session '{}' has no linked branch", session_id))?;
+    let task_link = link.clone();
+    tauri::async_runtime::spawn_blocking(move || checkout_or_emit(&app, &task_link))
+        .await
+        .map_err(|e| e.to_string())??;
+    Ok(link)
+}
@@ -0,0 +1,45 @@
+use crate::app::session_marks::{self, SessionMark, SessionMarksState};
+use tauri::{Emitter, State};
+
+/// 列出有未读或已置顶的会话；指定目录时只返回该项目下的
+#[tauri::command]
+pub fn list_session_marks(
+    state: State<'_, SessionMarksState>,
+    directory: Option<String>,
+) -> Vec<SessionMark> {
+    state.list(directory.as_deref())
+}
+
+/// 清除会话的未读数
+#[tauri::command]
+pub fn mark_session_read(app: tauri::AppHandle, session_id: String) -> Result<(), String> {
+    session_marks::mark_read(&app, &session_id)
+}
+
+/// 清除所有（或某个项目下）会话的未读数
+#[tauri::command]
+pub fn mark_all_sessions_read(
+    app: tauri::AppHandle,
+    state: State<'_, SessionMarksState>,
+    directory: Option<String>,
+) -> Result<(), String> {
+    let cleared = state.mark_all_read(directory.as_deref())?;
+    if !cleared.is_empty() {
+        let _ = app.emit("session-marks-changed", cleared);
+    }
+    Ok(())
+}
+
+/// 置顶 / 取消置顶会话
+#[tauri::command]
+pub fn set_session_pinned(
+    app: tauri::AppHandle,
+    state: State<'_, SessionMarksState>,
+    session_id: String,
+    directory: Option<String>,
+    pinned: bool,
+) -> Result<SessionMark, String> {
+    let mark = state.set_pinned(&session_id, directory.as_deref(), pinned)?;
+    let _ = app.emit("session-marks-changed", vec![mark.clone()]);
+    Ok(mark)
+}
@@ -0,0 +1,23 @@
+use crate::app::session_tree::{ChildrenSummary, SessionTree, SessionTreeState};
+use tauri::State;
+
+/// 以指定会话为根的子会话树；未见过该会话时返回 None
+#[tauri::command]
+pub fn get_session_tree(state: State<'_, SessionTreeState>, root: String) -> Option<SessionTree> {
+    state.tree(&root)
+}
+
+/// 会话直接子会话的状态汇总
+#[tauri::command]
+pub fn get_session_children_summary(
+    state: State<'_, SessionTreeState>,
+    parent_id: String,
+) -> Option<ChildrenSummary> {
+    state.summary(&parent_id)
+}
+
+/// 会话所在树的根会话 id，用于按任务归并通知
+#[tauri::command]
+pub fn get_session_root(state: State<'_, SessionTreeState>, session_id: String) -> String {
+    state.root_of(&session_id)
+}
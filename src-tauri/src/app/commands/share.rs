@@ -0,0 +1,26 @@
+use crate::app::{
+    api::ServerTarget,
+    share::{self, ShareInfo, ShareState},
+};
+use tauri::State;
+
+/// 开始局域网只读分享（需用户显式开启）
+#[tauri::command]
+pub async fn start_session_share(
+    state: State<'_, ShareState>,
+    target: ServerTarget,
+    session_id: String,
+    port: Option<u16>,
+) -> Result<ShareInfo, String> {
+    share::start_share(&state, target, session_id, port).await
+}
+
+#[tauri::command]
+pub fn stop_session_share(state: State<'_, ShareState>, token: String) -> bool {
+    state.stop(&token)
+}
+
+#[tauri::command]
+pub fn list_session_shares(state: State<'_, ShareState>) -> Vec<ShareInfo> {
+    state.list()
+}
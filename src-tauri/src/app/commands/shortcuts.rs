@@ -0,0 +1,30 @@
+use crate::app::{
+    i18n::LanguageState,
+    settings::SettingsStore,
+    shortcuts::{ShortcutInfo, ShortcutsState},
+};
+use tauri::{Emitter, State};
+
+/// 列出已登记的快捷键（默认值、当前绑定、冲突）
+#[tauri::command]
+pub fn list_shortcuts(state: State<'_, ShortcutsState>) -> Vec<ShortcutInfo> {
+    state.list()
+}
+
+/// 改键；`accelerator` 为空表示禁用。与其它快捷键冲突时报错，成功后刷新原生菜单并通知所有窗口
+#[tauri::command]
+pub fn rebind_shortcut(
+    app: tauri::AppHandle,
+    state: State<'_, ShortcutsState>,
+    settings: State<'_, SettingsStore>,
+    language: State<'_, LanguageState>,
+    id: String,
+    accelerator: Option<String>,
+) -> Result<Vec<ShortcutInfo>, String> {
+    state.rebind(&settings, &id, accelerator)?;
+    super::language::refresh_native_ui(&app, language.current());
+
+    let shortcuts = state.list();
+    let _ = app.emit("shortcuts-changed", shortcuts.clone());
+    Ok(shortcuts)
+}
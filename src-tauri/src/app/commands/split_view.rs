@@ -0,0 +1,163 @@
+use crate::app::{
+    bridge::BridgeState,
+    dir_state::OpenDirectoryState,
+    profile,
+    split_view::{apply_layout, SplitLayout, SplitPane, SplitViewState},
+};
+use serde::Deserialize;
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+use tauri::{Manager, State};
+
+static PANE_COUNTER: AtomicU64 = AtomicU64::new(1);
+static SPLIT_WINDOW_COUNTER: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaneArgs {
+    directory: Option<String>,
+    session_id: Option<String>,
+}
+
+fn add_pane_webview(
+    app: &tauri::AppHandle,
+    window: &tauri::Window,
+    state: &SplitViewState,
+    args: PaneArgs,
+) -> Result<SplitLayout, String> {
+    let label = format!("pane-{}", PANE_COUNTER.fetch_add(1, Ordering::SeqCst));
+
+    if let Some(dir) = args.directory.as_deref() {
+        if let Some(dir_state) = app.try_state::<OpenDirectoryState>() {
+            dir_state
+                .pending()
+                .pin()
+                .insert(label.clone(), Arc::from(dir));
+        }
+    }
+
+    let builder =
+        tauri::webview::WebviewBuilder::new(&label, tauri::WebviewUrl::App("index.html".into()));
+    let builder = match profile::webview_data_dir(app) {
+        Some(dir) => builder.data_directory(dir),
+        None => builder,
+    };
+
+    window
+        .add_child(
+            builder,
+            tauri::LogicalPosition::new(0.0, 0.0),
+            tauri::LogicalSize::new(1.0, 1.0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let layout = state.add_pane(
+        window.label(),
+        SplitPane {
+            label,
+            directory: args.directory,
+            session_id: args.session_id,
+        },
+    );
+    apply_layout(window, &layout);
+    Ok(layout)
+}
+
+/// 新建分屏窗口，每个 pane 为独立 webview
+#[tauri::command]
+pub async fn create_split_window(
+    app: tauri::AppHandle,
+    state: State<'_, SplitViewState>,
+    panes: Vec<PaneArgs>,
+) -> Result<String, String> {
+    if panes.len() < 2 {
+        return Err("split view needs at least two panes".to_string());
+    }
+
+    let label = format!(
+        "split-{}",
+        SPLIT_WINDOW_COUNTER.fetch_add(1, Ordering::SeqCst)
+    );
+    let window = tauri::window::WindowBuilder::new(&app, &label)
+        .title("OpenCode")
+        .inner_size(1400.0, 900.0)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    for pane in panes {
+        add_pane_webview(&app, &window, &state, pane)?;
+    }
+
+    log::info!("Created split view window '{}'", label);
+    Ok(label)
+}
+
+/// 在已有分屏窗口中追加一个 pane
+#[tauri::command]
+pub async fn add_split_pane(
+    app: tauri::AppHandle,
+    state: State<'_, SplitViewState>,
+    window_label: String,
+    pane: PaneArgs,
+) -> Result<SplitLayout, String> {
+    let window = app
+        .get_window(&window_label)
+        .ok_or_else(|| format!("window '{}' not found", window_label))?;
+    add_pane_webview(&app, &window, &state, pane)
+}
+
+/// 关闭某个 pane，并清理它的桥接连接；最后一个 pane 关闭时关闭窗口
+#[tauri::command]
+pub async fn remove_split_pane(
+    app: tauri::AppHandle,
+    state: State<'_, SplitViewState>,
+    bridge: State<'_, BridgeState>,
+    pane_label: String,
+) -> Result<Option<SplitLayout>, String> {
+    let (window_label, layout) = state
+        .remove_pane(&pane_label)
+        .ok_or_else(|| format!("pane '{}' not found", pane_label))?;
+
+    bridge.disconnect_window(&pane_label);
+    if let Some(webview) = app.get_webview(&pane_label) {
+        webview.close().map_err(|e| e.to_string())?;
+    }
+
+    let Some(window) = app.get_window(&window_label) else {
+        return Ok(None);
+    };
+    if layout.panes.is_empty() {
+        state.remove_window(&window_label);
+        window.close().map_err(|e| e.to_string())?;
+        return Ok(None);
+    }
+
+    apply_layout(&window, &layout);
+    Ok(Some(layout))
+}
+
+/// 调整各 pane 的宽度占比
+#[tauri::command]
+pub async fn resize_split_panes(
+    app: tauri::AppHandle,
+    state: State<'_, SplitViewState>,
+    window_label: String,
+    ratios: Vec<f64>,
+) -> Result<SplitLayout, String> {
+    let layout = state.set_ratios(&window_label, &ratios)?;
+    if let Some(window) = app.get_window(&window_label) {
+        apply_layout(&window, &layout);
+    }
+    Ok(layout)
+}
+
+/// pane 内的前端查询自己绑定的会话/目录
+#[tauri::command]
+pub fn get_split_pane(
+    webview: tauri::Webview,
+    state: State<'_, SplitViewState>,
+) -> Option<SplitPane> {
+    state.pane(webview.label())
+}
@@ -0,0 +1,51 @@
+use crate::app::{
+    settings::SettingsStore,
+    storage::{self, StorageBreakdown, StorageCategory, StorageConfig, STORAGE_SETTINGS_KEY},
+};
+use tauri::State;
+
+fn load_config(settings: &SettingsStore) -> StorageConfig {
+    settings.get_as(STORAGE_SETTINGS_KEY).unwrap_or_default()
+}
+
+/// 各分类临时文件占用的空间
+#[tauri::command]
+pub async fn get_storage_breakdown(
+    app: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+) -> Result<StorageBreakdown, String> {
+    let config = load_config(&settings);
+    tauri::async_runtime::spawn_blocking(move || storage::breakdown(&app, &config))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 清空一个分类，返回释放的字节数
+#[tauri::command]
+pub async fn clear_category(
+    app: tauri::AppHandle,
+    category: StorageCategory,
+) -> Result<u64, String> {
+    tauri::async_runtime::spawn_blocking(move || storage::clear(&app, category))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// 读取存储预算与保留时间设置
+#[tauri::command]
+pub fn get_storage_config(settings: State<'_, SettingsStore>) -> StorageConfig {
+    load_config(&settings)
+}
+
+/// 更新存储设置并立即按新设置清理一次，返回释放的字节数
+#[tauri::command]
+pub async fn set_storage_config(
+    app: tauri::AppHandle,
+    settings: State<'_, SettingsStore>,
+    config: StorageConfig,
+) -> Result<u64, String> {
+    settings.set_as(STORAGE_SETTINGS_KEY, &config)?;
+    tauri::async_runtime::spawn_blocking(move || storage::run_janitor(&app, &config))
+        .await
+        .map_err(|e| e.to_string())
+}
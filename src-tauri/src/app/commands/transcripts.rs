@@ -0,0 +1,26 @@
+use crate::app::transcript_log::{
+    self, RecoveredTranscript, TranscriptLogState, TranscriptSummary,
+};
+use tauri::State;
+
+/// 上次运行残留、可能包含未保存内容的会话日志
+#[tauri::command]
+pub fn list_pending_transcripts(state: State<'_, TranscriptLogState>) -> Vec<TranscriptSummary> {
+    state.pending()
+}
+
+/// 与服务端对账，返回服务端缺失的消息；全部已保存时日志会被删除
+#[tauri::command]
+pub async fn reconcile_transcript(
+    app: tauri::AppHandle,
+    session_id: String,
+    auth_header: Option<String>,
+) -> Result<RecoveredTranscript, String> {
+    transcript_log::reconcile(&app, &session_id, auth_header).await
+}
+
+/// 前端已把找回的内容写入缓存后，删除该会话的日志
+#[tauri::command]
+pub fn discard_transcript(state: State<'_, TranscriptLogState>, session_id: String) {
+    state.discard(&session_id);
+}
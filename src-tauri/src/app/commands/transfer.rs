@@ -0,0 +1,20 @@
+use crate::app::transfer::{self, TransferSummary};
+
+/// 导出全部设置；`include_secrets` 为 false 时移除认证信息等敏感字段
+#[tauri::command]
+pub async fn export_settings(
+    app: tauri::AppHandle,
+    path: String,
+    include_secrets: bool,
+) -> Result<TransferSummary, String> {
+    transfer::export_settings(&app, &path, include_secrets)
+}
+
+/// 从文件导入设置（覆盖当前 profile 的设置）
+#[tauri::command]
+pub async fn import_settings(
+    app: tauri::AppHandle,
+    path: String,
+) -> Result<TransferSummary, String> {
+    transfer::import_settings(&app, &path)
+}
@@ -1,7 +1,9 @@
-use crate::app::dir_state::OpenDirectoryState;
+use crate::app::{
+    dir_state::OpenDirectoryState, launch_args::LaunchOptions, recovery::RecoveryState,
+};
 use serde::Serialize;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{Emitter, Manager, State};
 
 #[derive(Serialize)]
 pub struct DroppedPathInfo {
@@ -14,17 +16,137 @@ pub struct DroppedPathInfo {
 /// 获取启动时传入的目录路径（一次性读取后清空）
 #[tauri::command]
 pub fn get_cli_directory(
-    window: tauri::Window,
+    webview: tauri::Webview,
     state: State<'_, OpenDirectoryState>,
 ) -> Option<Arc<str>> {
-    state.pending().pin().remove(window.label()).cloned()
+    state.pending().pin().remove(webview.label()).cloned()
 }
 
-/// 新建桌面窗口
+/// 获取命令行传入的启动参数（prompt / model / agent，一次性读取后清空）
+#[tauri::command]
+pub fn get_cli_launch_options(
+    webview: tauri::Webview,
+    state: State<'_, OpenDirectoryState>,
+) -> Option<Arc<LaunchOptions>> {
+    state.launch().pin().remove(webview.label()).cloned()
+}
+
+/// 新建桌面窗口，可绑定一个项目目录，返回新窗口 label。目录须存在，按规范化路径绑定
 #[cfg(not(target_os = "android"))]
 #[tauri::command]
-pub async fn open_new_window(app: tauri::AppHandle, directory: Option<String>) {
-    crate::app::create_new_window(&app, directory);
+pub async fn open_new_window(
+    app: tauri::AppHandle,
+    directory: Option<String>,
+) -> Result<String, String> {
+    let directory = match directory
+        .as_deref()
+        .map(str::trim)
+        .filter(|dir| !dir.is_empty())
+    {
+        Some(dir) => {
+            let path = std::path::Path::new(dir);
+            if !path.is_dir() {
+                return Err(format!("directory '{}' does not exist", dir));
+            }
+            Some(
+                crate::app::projects::normalize_path(path)
+                    .to_string_lossy()
+                    .into_owned(),
+            )
+        }
+        None => None,
+    };
+    crate::app::open_window(&app, directory, None, LaunchOptions::default())
+}
+
+/// 获取窗口待打开的会话（弹出 / 合并窗口时携带，一次性读取后清空）
+#[tauri::command]
+pub fn get_pending_session(
+    webview: tauri::Webview,
+    state: State<'_, OpenDirectoryState>,
+) -> Option<Arc<str>> {
+    state.sessions().pin().remove(webview.label()).cloned()
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SessionMoved {
+    session_id: String,
+    directory: Option<String>,
+    from_window: String,
+}
+
+/// 把会话弹出到新窗口，返回新窗口 label。原窗口的连接不受影响，由前端决定是否关闭该会话
+#[cfg(not(target_os = "android"))]
+#[tauri::command]
+pub async fn pop_out_session(
+    app: tauri::AppHandle,
+    session_id: String,
+    directory: Option<String>,
+) -> Result<String, String> {
+    crate::app::open_window(&app, directory, Some(session_id), LaunchOptions::default())
+}
+
+/// 把会话移入已有窗口：写入目标窗口的待处理状态并通知其读取，
+/// `close_source` 为 true 时关闭来源窗口（其桥接连接随窗口销毁清理）
+#[cfg(not(target_os = "android"))]
+#[tauri::command]
+pub async fn move_session_to_window(
+    app: tauri::AppHandle,
+    window: tauri::Window,
+    state: State<'_, OpenDirectoryState>,
+    target_label: String,
+    session_id: String,
+    directory: Option<String>,
+    close_source: bool,
+) -> Result<(), String> {
+    if target_label == window.label() {
+        return Err("session is already in this window".to_string());
+    }
+    let target = app
+        .get_window(&target_label)
+        .ok_or_else(|| format!("window '{}' not found", target_label))?;
+
+    if let Some(ref dir) = directory {
+        state
+            .pending()
+            .pin()
+            .insert(target_label.clone(), Arc::from(dir.as_str()));
+    }
+    state
+        .sessions()
+        .pin()
+        .insert(target_label.clone(), Arc::from(session_id.as_str()));
+    target
+        .emit_to(
+            target_label.as_str(),
+            "session-pending",
+            SessionMoved {
+                session_id: session_id.clone(),
+                directory,
+                from_window: window.label().to_string(),
+            },
+        )
+        .map_err(|e| e.to_string())?;
+    let _ = target.show();
+    let _ = target.set_focus();
+
+    log::info!(
+        "Moved session {} from window '{}' to '{}'",
+        session_id,
+        window.label(),
+        target_label
+    );
+    if close_source {
+        window.close().map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// 前端定时心跳，用于检测 WebView 渲染进程崩溃。返回 true 表示页面刚被自动恢复。
+#[tauri::command]
+pub fn webview_heartbeat(webview: tauri::Webview, state: State<'_, RecoveryState>) -> bool {
+    state.heartbeat(webview.label())
 }
 
 /// 桌面窗口前端首帧完成后，通知 Rust 显示真实窗口并关闭 loading 窗口
@@ -0,0 +1,45 @@
+use crate::app::watch::{start_watch, WatchJob, WatchState};
+use serde::Serialize;
+use tauri::State;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchJobStatus {
+    #[serde(flatten)]
+    job: WatchJob,
+    /// 当前是否有一次触发的运行尚未结束
+    running: bool,
+}
+
+#[tauri::command]
+pub fn list_watch_jobs(state: State<'_, WatchState>) -> Vec<WatchJobStatus> {
+    state
+        .list()
+        .into_iter()
+        .map(|job| WatchJobStatus {
+            running: state.is_running(&job.id),
+            job,
+        })
+        .collect()
+}
+
+/// 保存 watch job；启用时立即（重新）开始监听
+#[tauri::command]
+pub fn save_watch_job(
+    app: tauri::AppHandle,
+    state: State<'_, WatchState>,
+    job: WatchJob,
+) -> Result<(), String> {
+    let job = state.upsert(job)?;
+    if job.enabled {
+        start_watch(&app, job)
+    } else {
+        state.stop(&job.id);
+        Ok(())
+    }
+}
+
+#[tauri::command]
+pub fn delete_watch_job(state: State<'_, WatchState>, id: String) -> Result<bool, String> {
+    state.remove(&id)
+}
@@ -0,0 +1,34 @@
+use crate::app::{
+    session_marks,
+    window_context::{self, WindowContext, WindowContextState},
+};
+use tauri::State;
+
+/// 前端上报当前窗口的会话标题 / 项目 / 忙碌状态与正在显示的会话：
+/// 更新原生窗口标题与应用徽标，并记录下来供托盘提示与未读计数使用；
+/// 正在显示的会话标记为已读
+#[tauri::command]
+pub fn set_window_context(
+    window: tauri::Window,
+    state: State<'_, WindowContextState>,
+    title: Option<String>,
+    project: Option<String>,
+    busy: bool,
+    session_id: Option<String>,
+) -> Result<(), String> {
+    if let Some(session_id) = session_id.as_deref() {
+        session_marks::mark_read(window.app_handle(), session_id)?;
+    }
+    let context = WindowContext {
+        title,
+        project,
+        busy,
+        session_id,
+    };
+    window
+        .set_title(&window_context::format_title(&context))
+        .map_err(|e| e.to_string())?;
+    state.set(window.label(), context);
+    window_context::refresh_badge(window.app_handle());
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use crate::app::{
+    settings::SettingsStore,
+    workspace_autolaunch::{
+        self, WorkspaceAutolaunchConfig, WorkspaceServiceStatus, WorkspaceState,
+        WORKSPACE_AUTOLAUNCH_SETTINGS_KEY,
+    },
+};
+use tauri::State;
+
+#[tauri::command]
+pub fn get_workspace_autolaunch_config(
+    settings: State<'_, SettingsStore>,
+) -> WorkspaceAutolaunchConfig {
+    settings
+        .get_as(WORKSPACE_AUTOLAUNCH_SETTINGS_KEY)
+        .unwrap_or_default()
+}
+
+/// 更新工作区自动启动设置，下次启动应用时生效
+#[tauri::command]
+pub fn set_workspace_autolaunch_config(
+    settings: State<'_, SettingsStore>,
+    config: WorkspaceAutolaunchConfig,
+) -> Result<(), String> {
+    settings.set_as(WORKSPACE_AUTOLAUNCH_SETTINGS_KEY, &config)
+}
+
+/// 立即启动工作区中的所有服务，全部就绪或失败后返回
+#[tauri::command]
+pub async fn launch_workspace(
+    app: tauri::AppHandle,
+) -> Result<Vec<WorkspaceServiceStatus>, String> {
+    workspace_autolaunch::launch(&app).await
+}
+
+/// 最近一次启动工作区时各服务的状态
+#[tauri::command]
+pub fn get_workspace_status(state: State<'_, WorkspaceState>) -> Vec<WorkspaceServiceStatus> {
+    state.statuses()
+}
@@ -0,0 +1,404 @@
+// ============================================
+// Edit Conflict Detection (desktop only)
+// 结合 agent 写文件事件（file.edited / edit 类工具）与项目目录监听，
+// 发现 agent 与外部编辑器交叉修改同一文件时发出 `file-conflict` 事件
+// ============================================
+
+use crate::app::bridge::event_data;
+use notify::{RecursiveMode, Watcher};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::{Duration, UNIX_EPOCH},
+};
+use tauri::{Emitter, Manager};
+
+/// agent 写入后多久内的外部修改视为冲突
+const OVERLAP_WINDOW_MS: u64 = 10 * 60 * 1000;
+/// 文件变化先等待一会再判断，让 agent 自己写入对应的 file.edited 事件先到达
+const SETTLE: Duration = Duration::from_millis(1500);
+/// 工具开始执行后，在此时间内的变化都归于 agent
+const PENDING_MS: u64 = 30 * 1000;
+/// 同时监听的项目目录上限
+const MAX_WATCHED_ROOTS: usize = 16;
+/// 会写文件的 opencode 工具
+const EDIT_TOOLS: &[&str] = &["edit", "write", "patch", "multiedit"];
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileStamp {
+    /// 内容 sha256；文件已被删除时为空
+    pub hash: Option<String>,
+    pub mtime: Option<u64>,
+    pub observed_at: u64,
+}
+
+impl FileStamp {
+    fn read(path: &Path) -> Self {
+        let mtime = std::fs::metadata(path)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|time| time.as_millis() as u64);
+        let hash = std::fs::read(path)
+            .ok()
+            .map(|bytes| format!("{:x}", Sha256::digest(&bytes)));
+        Self {
+            hash,
+            mtime,
+            observed_at: crate::app::now_millis(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictKind {
+    /// agent 改过之后，文件又被外部修改
+    ExternalAfterAgent,
+    /// 外部修改之后，agent 覆盖写入
+    AgentAfterExternal,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileConflict {
+    pub path: String,
+    pub kind: ConflictKind,
+    pub agent: FileStamp,
+    pub external: FileStamp,
+    pub detected_at: u64,
+}
+
+#[derive(Default)]
+struct Tracked {
+    agent: Option<FileStamp>,
+    external: Option<FileStamp>,
+    pending_until: u64,
+}
+
+#[derive(Default)]
+pub struct ConflictState {
+    files: Mutex<HashMap<PathBuf, Tracked>>,
+    watchers: Mutex<HashMap<PathBuf, notify::RecommendedWatcher>>,
+    /// 正在等待 SETTLE 的路径，避免一次保存触发多次判断
+    settling: Mutex<HashSet<PathBuf>>,
+    conflicts: Mutex<HashMap<PathBuf, FileConflict>>,
+}
+
+impl ConflictState {
+    /// 当前未处理的冲突
+    pub fn list(&self) -> Vec<FileConflict> {
+        self.conflicts
+            .lock()
+            .expect("conflict state poisoned")
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<FileConflict> {
+        self.conflicts
+            .lock()
+            .expect("conflict state poisoned")
+            .get(path)
+            .cloned()
+    }
+
+    /// 用户确认后清除冲突标记
+    pub fn dismiss(&self, path: &Path) -> bool {
+        self.conflicts
+            .lock()
+            .expect("conflict state poisoned")
+            .remove(path)
+            .is_some()
+    }
+
+    fn report(&self, app: &tauri::AppHandle, path: &Path, conflict: FileConflict) {
+        log::warn!("Edit conflict on {} ({:?})", path.display(), conflict.kind);
+        self.conflicts
+            .lock()
+            .expect("conflict state poisoned")
+            .insert(path.to_path_buf(), conflict.clone());
+        let _ = app.emit("file-conflict", conflict);
+    }
+
+    /// agent 即将写入（工具开始执行）
+    fn agent_pending(&self, path: &Path) {
+        let mut files = self.files.lock().expect("conflict state poisoned");
+        files.entry(path.to_path_buf()).or_default().pending_until =
+            crate::app::now_millis() + PENDING_MS;
+    }
+
+    /// agent 完成写入（file.edited）
+    fn agent_wrote(&self, app: &tauri::AppHandle, path: &Path) {
+        let stamp = FileStamp::read(path);
+        let conflict = {
+            let mut files = self.files.lock().expect("conflict state poisoned");
+            let tracked = files.entry(path.to_path_buf()).or_default();
+            // 同一事件可能经多个窗口的连接重复到达
+            if tracked.agent.as_ref().map(|agent| &agent.hash) == Some(&stamp.hash) {
+                return;
+            }
+            let last_agent_at = tracked.agent.as_ref().map_or(0, |agent| agent.observed_at);
+            let conflict = tracked
+                .external
+                .as_ref()
+                .filter(|external| {
+                    external.observed_at > last_agent_at
+                        && stamp.observed_at.saturating_sub(external.observed_at)
+                            < OVERLAP_WINDOW_MS
+                        && external.hash != stamp.hash
+                })
+                .map(|external| FileConflict {
+                    path: path.to_string_lossy().to_string(),
+                    kind: ConflictKind::AgentAfterExternal,
+                    agent: stamp.clone(),
+                    external: external.clone(),
+                    detected_at: stamp.observed_at,
+                });
+            tracked.agent = Some(stamp);
+            tracked.pending_until = 0;
+            conflict
+        };
+        if let Some(conflict) = conflict {
+            self.report(app, path, conflict);
+        }
+    }
+
+    /// 监听到文件变化（SETTLE 之后调用）
+    fn file_changed(&self, app: &tauri::AppHandle, path: &Path) {
+        let stamp = FileStamp::read(path);
+        let conflict = {
+            let mut files = self.files.lock().expect("conflict state poisoned");
+            let Some(tracked) = files.get_mut(path) else {
+                return;
+            };
+            let Some(agent) = tracked.agent.clone() else {
+                return;
+            };
+            // agent 自己的写入，或工具仍在执行
+            if agent.hash == stamp.hash || tracked.pending_until > stamp.observed_at {
+                return;
+            }
+            if tracked.external.as_ref().map(|external| &external.hash) == Some(&stamp.hash) {
+                return;
+            }
+            tracked.external = Some(stamp.clone());
+            (stamp.observed_at.saturating_sub(agent.observed_at) < OVERLAP_WINDOW_MS).then(|| {
+                FileConflict {
+                    path: path.to_string_lossy().to_string(),
+                    kind: ConflictKind::ExternalAfterAgent,
+                    agent,
+                    external: stamp.clone(),
+                    detected_at: stamp.observed_at,
+                }
+            })
+        };
+        if let Some(conflict) = conflict {
+            self.report(app, path, conflict);
+        }
+    }
+
+    /// 丢弃超出重叠窗口的记录
+    fn prune(&self) {
+        let now = crate::app::now_millis();
+        self.files
+            .lock()
+            .expect("conflict state poisoned")
+            .retain(|_, tracked| {
+                let latest = [&tracked.agent, &tracked.external]
+                    .into_iter()
+                    .flatten()
+                    .map(|stamp| stamp.observed_at)
+                    .max()
+                    .unwrap_or(0);
+                tracked.pending_until > now || now.saturating_sub(latest) < OVERLAP_WINDOW_MS
+            });
+    }
+
+    fn ensure_watch(&self, app: &tauri::AppHandle, root: &Path) {
+        let mut watchers = self.watchers.lock().expect("conflict state poisoned");
+        if watchers.keys().any(|watched| root.starts_with(watched)) {
+            return;
+        }
+        if watchers.len() >= MAX_WATCHED_ROOTS {
+            log::warn!(
+                "Not watching {} for edit conflicts: too many projects",
+                root.display()
+            );
+            return;
+        }
+
+        let handle = app.clone();
+        let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            let Ok(event) = res else {
+                return;
+            };
+            if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()) {
+                return;
+            }
+            for path in event.paths {
+                schedule_check(&handle, path);
+            }
+        });
+        let mut watcher = match watcher {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                log::warn!("Failed to create conflict watcher: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = watcher.watch(root, RecursiveMode::Recursive) {
+            log::warn!("Failed to watch {} for conflicts: {}", root.display(), e);
+            return;
+        }
+        log::info!("Watching {} for edit conflicts", root.display());
+        watchers.insert(root.to_path_buf(), watcher);
+    }
+}
+
+fn schedule_check(app: &tauri::AppHandle, path: PathBuf) {
+    let state = app.state::<ConflictState>();
+    let tracked = state
+        .files
+        .lock()
+        .expect("conflict state poisoned")
+        .contains_key(&path);
+    if !tracked
+        || !state
+            .settling
+            .lock()
+            .expect("conflict state poisoned")
+            .insert(path.clone())
+    {
+        return;
+    }
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SETTLE).await;
+        let state = app.state::<ConflictState>();
+        state
+            .settling
+            .lock()
+            .expect("conflict state poisoned")
+            .remove(&path);
+        state.file_changed(&app, &path);
+    });
+}
+
+/// 从 SSE 事件中提取 agent 写文件的信号：(阶段, 项目目录, 文件路径)
+fn agent_edit(event: &Value) -> Option<(bool, Option<String>, String)> {
+    let (event, directory) = match event.get("payload") {
+        Some(payload) => (payload, event["directory"].as_str().map(str::to_string)),
+        None => (event, None),
+    };
+    let properties = &event["properties"];
+    match event["type"].as_str()? {
+        "file.edited" => Some((true, directory, properties["file"].as_str()?.to_string())),
+        "message.part.updated" => {
+            let part = &properties["part"];
+            if part["type"] != "tool" || !EDIT_TOOLS.contains(&part["tool"].as_str()?) {
+                return None;
+            }
+            let state = &part["state"];
+            if !matches!(state["status"].as_str()?, "pending" | "running") {
+                return None;
+            }
+            let file = state["input"]["filePath"].as_str()?;
+            Some((false, directory, file.to_string()))
+        }
+        _ => None,
+    }
+}
+
+/// 注册为 bridge 的事件观察者
+pub fn install(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.state::<crate::app::bridge::BridgeState>()
+        .add_event_tap(Box::new(move |_, block| {
+            // 先做廉价的字符串过滤，绝大多数事件不需要解析
+            if !block.contains("file.edited") && !block.contains("\"tool\"") {
+                return;
+            }
+            let Some(event) =
+                event_data(block).and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            else {
+                return;
+            };
+            let Some((done, directory, file)) = agent_edit(&event) else {
+                return;
+            };
+            let path = match directory.as_deref() {
+                Some(dir) if Path::new(&file).is_relative() => Path::new(dir).join(&file),
+                _ => PathBuf::from(&file),
+            };
+            let root = directory
+                .map(PathBuf::from)
+                .or_else(|| path.parent().map(Path::to_path_buf));
+
+            let app = handle.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let state = app.state::<ConflictState>();
+                state.prune();
+                if let Some(root) = root {
+                    state.ensure_watch(&app, &root);
+                }
+                if done {
+                    state.agent_wrote(&app, &path);
+                } else {
+                    state.agent_pending(&path);
+                }
+            });
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_agent_edits_from_events() {
+        let edited = json!({
+            "directory": "/work/app",
+            "payload": { "type": "file.edited", "properties": { "file": "/work/app/src/main.rs" } }
+        });
+        assert_eq!(
+            agent_edit(&edited),
+            Some((
+                true,
+                Some("/work/app".to_string()),
+                "/work/app/src/main.rs".to_string()
+            ))
+        );
+
+        let running = json!({
+            "type": "message.part.updated",
+            "properties": { "part": {
+                "type": "tool",
+                "tool": "edit",
+                "state": { "status": "running", "input": { "filePath": "/work/app/README.md" } }
+            } }
+        });
+        assert_eq!(
+            agent_edit(&running),
+            Some((false, None, "/work/app/README.md".to_string()))
+        );
+
+        let read = json!({
+            "type": "message.part.updated",
+            "properties": { "part": {
+                "type": "tool",
+                "tool": "read",
+                "state": { "status": "running", "input": { "filePath": "/work/app/README.md" } }
+            } }
+        });
+        assert_eq!(agent_edit(&read), None);
+    }
+}
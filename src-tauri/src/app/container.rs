@@ -0,0 +1,187 @@
+// ============================================
+// Container Launch (desktop only)
+// 在 docker / podman 容器中运行 opencode serve，无需在本机安装 CLI：
+// - 前台运行 `<engine> run --rm`，输出与退出状态与本地进程相同，沿用日志、健康检查与 watchdog
+// - 服务端口发布到宿主的监听地址，容器内监听 0.0.0.0
+// - 工作目录挂载进容器；环境变量以 `-e KEY` 从引擎进程的环境中取值，值不出现在命令行上
+// - 停止时除结束引擎进程外再 `rm -f` 容器，强制结束客户端不会残留容器
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{
+    path::Path,
+    process::{Command, Stdio},
+};
+
+/// 未指定镜像时使用的官方镜像
+pub const DEFAULT_IMAGE: &str = "ghcr.io/sst/opencode:latest";
+
+/// 未挂载到同名路径时，容器内的项目目录
+const CONTAINER_WORKDIR: &str = "/workspace";
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ContainerTarget {
+    /// 容器引擎：`docker`（默认）或 `podman`，也可以是完整路径
+    pub engine: Option<String>,
+    /// 镜像；None 使用 DEFAULT_IMAGE
+    pub image: Option<String>,
+    /// 追加在 `run` 之后、镜像之前的参数，例如额外的 `-v`
+    pub run_args: Vec<String>,
+}
+
+impl ContainerTarget {
+    fn engine(&self) -> &str {
+        self.engine
+            .as_deref()
+            .map(str::trim)
+            .filter(|engine| !engine.is_empty())
+            .unwrap_or("docker")
+    }
+
+    fn image(&self) -> &str {
+        self.image
+            .as_deref()
+            .map(str::trim)
+            .filter(|image| !image.is_empty())
+            .unwrap_or(DEFAULT_IMAGE)
+    }
+}
+
+/// 容器名由实例与端口决定，应用重启后仍可按名称停止
+pub fn container_name(instance_id: &str, port: u16) -> String {
+    let id: String = instance_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("opencodeui-{}-{}", id, port)
+}
+
+/// 工作目录在容器内的路径：Unix 上挂载到同名路径，会话记录的目录与宿主一致；
+/// Windows 路径无法直接用于 Linux 容器，挂载到 /workspace
+fn container_workdir(cwd: &Path) -> String {
+    if cfg!(windows) {
+        CONTAINER_WORKDIR.to_string()
+    } else {
+        cwd.to_string_lossy().into_owned()
+    }
+}
+
+/// 构建 `<engine> run` 命令；`serve_args` 为 `serve` 及其参数，容器内以 `binary` 为入口
+#[allow(clippy::too_many_arguments)]
+pub fn command(
+    target: &ContainerTarget,
+    name: &str,
+    binary: &str,
+    serve_args: &[String],
+    publish_host: &str,
+    port: u16,
+    cwd: Option<&Path>,
+    env_keys: &[&str],
+) -> Command {
+    let publish_host = match publish_host {
+        "localhost" => "127.0.0.1",
+        host => host,
+    };
+    let mut cmd = Command::new(target.engine());
+    cmd.args(["run", "--rm", "--init", "--name", name])
+        .arg("--publish")
+        .arg(format!("{}:{}:{}", publish_host, port, port));
+    if let Some(cwd) = cwd {
+        let workdir = container_workdir(cwd);
+        cmd.arg("--volume")
+            .arg(format!("{}:{}", cwd.display(), workdir))
+            .args(["--workdir", &workdir]);
+    }
+    for key in env_keys {
+        cmd.args(["--env", key]);
+    }
+    let entrypoint = match binary.trim() {
+        "" => "opencode",
+        binary => binary,
+    };
+    cmd.args(["--entrypoint", entrypoint])
+        .args(&target.run_args)
+        .arg(target.image())
+        .args(serve_args);
+    cmd
+}
+
+/// 删除容器（正在运行时强制结束）；容器不存在时忽略
+pub fn remove(target: &ContainerTarget, name: &str) {
+    let mut cmd = Command::new(target.engine());
+    cmd.args(["rm", "--force", name])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    if let Err(e) = cmd.status() {
+        log::warn!("Failed to remove container {}: {}", name, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_run_command() {
+        let target = ContainerTarget {
+            engine: Some("podman".to_string()),
+            image: None,
+            run_args: vec!["--pull=newer".to_string()],
+        };
+        let name = container_name("Work Tree", 4096);
+        assert_eq!(name, "opencodeui-work_tree-4096");
+
+        let serve_args = vec![
+            "serve".to_string(),
+            "--port".to_string(),
+            "4096".to_string(),
+        ];
+        let cmd = command(
+            &target,
+            &name,
+            "",
+            &serve_args,
+            "127.0.0.1",
+            4096,
+            None,
+            &["OPENAI_API_KEY"],
+        );
+        assert_eq!(cmd.get_program(), "podman");
+        let args: Vec<_> = cmd.get_args().map(|a| a.to_string_lossy()).collect();
+        assert_eq!(
+            args,
+            [
+                "run",
+                "--rm",
+                "--init",
+                "--name",
+                "opencodeui-work_tree-4096",
+                "--publish",
+                "127.0.0.1:4096:4096",
+                "--env",
+                "OPENAI_API_KEY",
+                "--entrypoint",
+                "opencode",
+                "--pull=newer",
+                DEFAULT_IMAGE,
+                "serve",
+                "--port",
+                "4096",
+            ]
+        );
+    }
+}
@@ -0,0 +1,180 @@
+// ============================================
+// Server Credentials (desktop only)
+// 按服务器 id 保存认证信息到系统钥匙串（macOS Keychain / Windows 凭据管理器 /
+// Secret Service），bridge 与 API 调用按 server id 取用，前端不再传递原始认证头
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{OnceLock, RwLock},
+};
+
+const KEYRING_SERVICE: &str = "OpenCodeUI";
+
+/// 当前 profile 名，不同 profile 的凭据互相隔离
+static PROFILE: OnceLock<String> = OnceLock::new();
+/// server id → 已解析的认证头，避免每次请求都访问钥匙串（macOS 上可能弹窗）
+static CACHE: RwLock<Option<HashMap<String, Option<String>>>> = RwLock::new(None);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ServerCredential {
+    /// opencode serve 的密码认证
+    Basic { username: String, password: String },
+    /// 反向代理等使用的 bearer token
+    Bearer { token: String },
+}
+
+impl ServerCredential {
+    pub fn header(&self) -> String {
+        match self {
+            Self::Basic { username, password } => {
+                format!(
+                    "Basic {}",
+                    base64(format!("{}:{}", username, password).as_bytes())
+                )
+            }
+            Self::Bearer { token } => format!("Bearer {}", token.trim()),
+        }
+    }
+}
+
+fn base64(input: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let bytes = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (u32::from(bytes[0]) << 16) | (u32::from(bytes[1]) << 8) | u32::from(bytes[2]);
+        for (i, shift) in [18, 12, 6, 0].into_iter().enumerate() {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> shift) as usize & 63] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// 启动时记录 profile 名
+pub fn init(profile: &str) {
+    let _ = PROFILE.set(profile.to_string());
+}
+
+fn entry(server_id: &str) -> Result<keyring::Entry, String> {
+    let server_id = server_id.trim();
+    if server_id.is_empty() {
+        return Err("server id is empty".to_string());
+    }
+    let profile = PROFILE.get().map(String::as_str).unwrap_or("default");
+    keyring::Entry::new(KEYRING_SERVICE, &format!("{}/{}", profile, server_id))
+        .map_err(|e| format!("keychain unavailable: {}", e))
+}
+
+fn cache_put(server_id: &str, header: Option<String>) {
+    CACHE
+        .write()
+        .expect("credential cache poisoned")
+        .get_or_insert_with(HashMap::new)
+        .insert(server_id.trim().to_string(), header);
+}
+
+pub fn store(server_id: &str, credential: &ServerCredential) -> Result<(), String> {
+    let data = serde_json::to_string(credential).map_err(|e| e.to_string())?;
+    entry(server_id)?
+        .set_password(&data)
+        .map_err(|e| format!("failed to save credential: {}", e))?;
+    cache_put(server_id, Some(credential.header()));
+    Ok(())
+}
+
+/// 删除凭据，返回之前是否存在
+pub fn remove(server_id: &str) -> Result<bool, String> {
+    let removed = match entry(server_id)?.delete_credential() {
+        Ok(()) => true,
+        Err(keyring::Error::NoEntry) => false,
+        Err(e) => return Err(format!("failed to delete credential: {}", e)),
+    };
+    cache_put(server_id, None);
+    Ok(removed)
+}
+
+/// 用一次性条目写入、读回再删除，检查钥匙串是否可用（环境检查使用）
+pub fn probe() -> Result<(), String> {
+    let entry = entry(".doctor-probe")?;
+    entry
+        .set_password("ok")
+        .map_err(|e| format!("cannot write to the keychain: {}", e))?;
+    let read = entry
+        .get_password()
+        .map_err(|e| format!("cannot read from the keychain: {}", e));
+    let _ = entry.delete_credential();
+    match read? {
+        value if value == "ok" => Ok(()),
+        _ => Err("the keychain returned a different value than was stored".to_string()),
+    }
+}
+
+/// 读取某个服务器的认证头；未保存凭据时返回 None
+pub fn auth_header(server_id: &str) -> Result<Option<String>, String> {
+    let cached = CACHE
+        .read()
+        .expect("credential cache poisoned")
+        .as_ref()
+        .and_then(|cache| cache.get(server_id.trim()).cloned());
+    if let Some(header) = cached {
+        return Ok(header);
+    }
+
+    let header = match entry(server_id)?.get_password() {
+        Ok(data) => {
+            let credential: ServerCredential = serde_json::from_str(&data)
+                .map_err(|e| format!("stored credential is invalid: {}", e))?;
+            Some(credential.header())
+        }
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => return Err(format!("failed to read credential: {}", e)),
+    };
+    cache_put(server_id, header.clone());
+    Ok(header)
+}
+
+/// 有 server id 时以钥匙串中的凭据为准，否则沿用调用方给出的认证头（兼容旧配置）
+pub fn resolve(server_id: Option<&str>, fallback: Option<&str>) -> Result<Option<String>, String> {
+    match server_id {
+        Some(server_id) => auth_header(server_id),
+        None => Ok(fallback.map(str::to_string)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_auth_headers() {
+        let basic = ServerCredential::Basic {
+            username: "opencode".to_string(),
+            password: "secret".to_string(),
+        };
+        assert_eq!(basic.header(), "Basic b3BlbmNvZGU6c2VjcmV0");
+        let bearer = ServerCredential::Bearer {
+            token: " abc.def ".to_string(),
+        };
+        assert_eq!(bearer.header(), "Bearer abc.def");
+
+        assert_eq!(base64(b""), "");
+        assert_eq!(base64(b"a"), "YQ==");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b"abc"), "YWJj");
+        assert_eq!(
+            resolve(None, Some("Basic x")).unwrap(),
+            Some("Basic x".to_string())
+        );
+    }
+}
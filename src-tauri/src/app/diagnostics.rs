@@ -0,0 +1,314 @@
+// ============================================
+// Diagnostics Bundle (desktop only)
+// 把排查问题所需的信息打包成一个 zip，保存到下载目录，附在问题报告中：
+// - system.json：应用版本、profile、操作系统与硬件概况
+// - settings.json：当前设置，认证信息等敏感字段已清除
+// - services/：各托管实例的启动参数（环境变量只含名称）、opencode 版本、最近退出状态与输出
+// - logs/：应用日志（每个文件只取末尾部分）
+// - sse-recording.ndjson：当前或最近一次的 SSE 录制
+// zip 以不压缩（stored）方式写出，不引入额外依赖
+// ============================================
+
+use crate::app::{
+    bridge::BridgeState,
+    commands::opencode::probe_version,
+    format,
+    profile::ActiveProfile,
+    safe_mode,
+    service::{LogStream, ServiceInstance, ServiceState},
+    settings::SettingsStore,
+    transfer::redact_secrets,
+};
+use serde_json::{json, Value};
+use std::{
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tauri::Manager;
+
+/// 每个日志文件最多收录的字节数（取末尾）
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+// ---------- zip ----------
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= u32::from(*byte);
+        for _ in 0..8 {
+            crc = (crc >> 1) ^ (0xEDB8_8320 & (crc & 1).wrapping_neg());
+        }
+    }
+    !crc
+}
+
+/// Unix 毫秒 → zip 使用的 DOS (时间, 日期)，UTC
+fn dos_datetime(millis: u64) -> (u16, u16) {
+    let secs = millis / 1000;
+    let (year, month, day) = format::civil_from_days((secs / 86_400) as i64);
+    let time = (((secs % 86_400) / 3600) << 11) | (((secs % 3600) / 60) << 5) | ((secs % 60) / 2);
+    let date = (((year.clamp(1980, 2107) - 1980) as u32) << 9) | (month << 5) | day;
+    (time as u16, date as u16)
+}
+
+/// 只写 stored 条目的最小 zip 写入器
+struct ZipWriter {
+    data: Vec<u8>,
+    central: Vec<u8>,
+    entries: u16,
+    modified: (u16, u16),
+}
+
+impl ZipWriter {
+    fn new(millis: u64) -> Self {
+        Self {
+            data: Vec::new(),
+            central: Vec::new(),
+            entries: 0,
+            modified: dos_datetime(millis),
+        }
+    }
+
+    fn add(&mut self, name: &str, content: &[u8]) {
+        let crc = crc32(content);
+        let size = content.len() as u32;
+        let offset = self.data.len() as u32;
+        let (time, date) = self.modified;
+        // 通用字段：需要的版本、标志（bit 11：文件名为 UTF-8）、方法 0、时间、CRC、大小
+        let mut common = Vec::with_capacity(26);
+        common.extend(20u16.to_le_bytes());
+        common.extend(0x0800u16.to_le_bytes());
+        common.extend(0u16.to_le_bytes());
+        common.extend(time.to_le_bytes());
+        common.extend(date.to_le_bytes());
+        common.extend(crc.to_le_bytes());
+        common.extend(size.to_le_bytes());
+        common.extend(size.to_le_bytes());
+        common.extend((name.len() as u16).to_le_bytes());
+        common.extend(0u16.to_le_bytes());
+
+        self.data.extend(0x0403_4b50u32.to_le_bytes());
+        self.data.extend(&common);
+        self.data.extend(name.as_bytes());
+        self.data.extend(content);
+
+        self.central.extend(0x0201_4b50u32.to_le_bytes());
+        self.central.extend(20u16.to_le_bytes());
+        self.central.extend(&common);
+        // 注释长度、磁盘号、内部属性、外部属性、本地头偏移
+        self.central.extend([0u8; 10]);
+        self.central.extend(offset.to_le_bytes());
+        self.central.extend(name.as_bytes());
+        self.entries += 1;
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        let central_offset = self.data.len() as u32;
+        let central_size = self.central.len() as u32;
+        self.data.append(&mut self.central);
+        self.data.extend(0x0605_4b50u32.to_le_bytes());
+        self.data.extend([0u8; 4]);
+        self.data.extend(self.entries.to_le_bytes());
+        self.data.extend(self.entries.to_le_bytes());
+        self.data.extend(central_size.to_le_bytes());
+        self.data.extend(central_offset.to_le_bytes());
+        self.data.extend(0u16.to_le_bytes());
+        self.data
+    }
+}
+
+// ---------- 收集 ----------
+
+fn pretty(value: &Value) -> Vec<u8> {
+    serde_json::to_vec_pretty(value).unwrap_or_default()
+}
+
+fn file_name(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 读取文件末尾最多 `limit` 字节
+fn read_tail(path: &Path, limit: u64) -> std::io::Result<Vec<u8>> {
+    let mut file = std::fs::File::open(path)?;
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(limit)))?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn system_info(app: &tauri::AppHandle) -> Value {
+    let mut system = sysinfo::System::new();
+    system.refresh_memory();
+    json!({
+        "appVersion": app.package_info().version.to_string(),
+        "profile": app.state::<ActiveProfile>().name(),
+        "safeMode": safe_mode::active(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "osVersion": sysinfo::System::long_os_version(),
+        "kernelVersion": sysinfo::System::kernel_version(),
+        "cpus": std::thread::available_parallelism().map(|n| n.get()).ok(),
+        "totalMemoryBytes": system.total_memory(),
+        "generatedAt": crate::app::now_millis(),
+    })
+}
+
+fn service_info(instance: &ServiceInstance) -> Value {
+    let launch = instance
+        .launch
+        .lock()
+        .ok()
+        .and_then(|launch| launch.clone());
+    let last_exit = instance.last_exit.lock().ok().and_then(|exit| exit.clone());
+    let url = instance.service_url.lock().ok().and_then(|url| url.clone());
+    let launch = launch.map(|launch| {
+        let mut env_names: Vec<&String> = launch.env_vars.keys().collect();
+        env_names.sort();
+        // 本地二进制才能直接询问版本
+        let version = (launch.wsl.is_none() && launch.container.is_none())
+            .then(|| probe_version(&launch.binary_path, VERSION_TIMEOUT))
+            .flatten();
+        json!({
+            "url": launch.url,
+            "binaryPath": launch.binary_path,
+            "opencodeVersion": version,
+            "extraArgs": launch.extra_args,
+            "cwd": launch.cwd,
+            "envVarNames": env_names,
+            "wsl": launch.wsl,
+            "container": launch.container,
+        })
+    });
+    json!({
+        "id": instance.id,
+        "pid": instance.child_pid.load(std::sync::atomic::Ordering::SeqCst),
+        "url": url,
+        "adopted": instance.adopted.load(std::sync::atomic::Ordering::SeqCst),
+        "suspended": instance.is_suspended(),
+        "launch": launch,
+        "lastExit": last_exit,
+    })
+}
+
+fn service_log(instance: &ServiceInstance) -> Vec<u8> {
+    let mut out = String::new();
+    for line in instance.logs.lines(None) {
+        let stream = match line.stream {
+            LogStream::Stdout => "out",
+            LogStream::Stderr => "err",
+            LogStream::System => "sys",
+        };
+        out.push_str(&format!("{} [{}] {}\n", line.at, stream, line.line));
+    }
+    out.into_bytes()
+}
+
+/// 导出 SSE 录制；没有录制时返回 None
+fn sse_recording(app: &tauri::AppHandle) -> Option<Vec<u8>> {
+    let bridge = app.state::<BridgeState>();
+    bridge.recorder().status()?;
+    let temp = std::env::temp_dir().join(format!(
+        "opencodeui-sse-{}.ndjson",
+        crate::app::now_millis()
+    ));
+    let data = bridge
+        .recorder()
+        .export(&temp)
+        .ok()
+        .and_then(|_| std::fs::read(&temp).ok());
+    let _ = std::fs::remove_file(&temp);
+    data
+}
+
+/// 生成诊断包并写入下载目录，返回文件路径
+pub fn export(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let now = crate::app::now_millis();
+    let mut zip = ZipWriter::new(now);
+
+    zip.add("system.json", &pretty(&system_info(app)));
+
+    let mut settings = Value::Object(app.state::<SettingsStore>().snapshot());
+    redact_secrets(&mut settings);
+    zip.add("settings.json", &pretty(&settings));
+
+    for instance in app.state::<ServiceState>().instances() {
+        let name = file_name(&instance.id);
+        zip.add(
+            &format!("services/{}.json", name),
+            &pretty(&service_info(&instance)),
+        );
+        zip.add(&format!("services/{}.log", name), &service_log(&instance));
+    }
+
+    if let Ok(entries) = app
+        .path()
+        .app_log_dir()
+        .and_then(|dir| std::fs::read_dir(dir).map_err(Into::into))
+    {
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.is_file() {
+                continue;
+            }
+            match read_tail(&path, MAX_LOG_BYTES) {
+                Ok(data) => {
+                    let name = file_name(&path.file_name().unwrap_or_default().to_string_lossy());
+                    zip.add(&format!("logs/{}", name), &data);
+                }
+                Err(e) => log::warn!("Skipping log {}: {}", path.display(), e),
+            }
+        }
+    }
+
+    if let Some(recording) = sse_recording(app) {
+        zip.add("sse-recording.ndjson", &recording);
+    }
+
+    let dir = app.path().download_dir().map_err(|e| e.to_string())?;
+    let path = dir.join(format!("opencodeui-diagnostics-{}.zip", now));
+    std::fs::write(&path, zip.finish())
+        .map_err(|e| format!("failed to write '{}': {}", path.display(), e))?;
+    log::info!("Exported diagnostics to {}", path.display());
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_stored_zip() {
+        assert_eq!(crc32(b"hello"), 0x3610_a686);
+        // 2024-03-05 13:14:16 UTC
+        assert_eq!(
+            dos_datetime(1_709_644_456_000),
+            ((13 << 11) | (14 << 5) | 8, (44 << 9) | (3 << 5) | 5)
+        );
+
+        let mut zip = ZipWriter::new(0);
+        zip.add("a.txt", b"hello");
+        zip.add("dir/b.json", b"{}");
+        let data = zip.finish();
+
+        assert_eq!(&data[..4], &0x0403_4b50u32.to_le_bytes());
+        let eocd = &data[data.len() - 22..];
+        assert_eq!(&eocd[..4], &0x0605_4b50u32.to_le_bytes());
+        assert_eq!(u16::from_le_bytes([eocd[10], eocd[11]]), 2);
+        let central_offset = u32::from_le_bytes([eocd[16], eocd[17], eocd[18], eocd[19]]) as usize;
+        assert_eq!(
+            &data[central_offset..central_offset + 4],
+            &0x0201_4b50u32.to_le_bytes()
+        );
+    }
+}
@@ -0,0 +1,165 @@
+// ============================================
+// Directory Browsing (desktop only)
+// 供"添加项目"使用的目录树浏览：本地直接读文件系统，连接远程 opencode 服务器时
+// 通过服务器的 `/file` 接口列出服务器上的目录，两者返回同样的结构
+// ============================================
+
+use crate::app::api::{self, ServerTarget};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tauri::Manager;
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirEntry {
+    pub name: String,
+    /// 绝对路径（远程时为服务器上的路径）
+    pub path: String,
+    pub is_dir: bool,
+    pub hidden: bool,
+    /// 目录下有 `.git`；远程列表不提供该信息时为 false
+    pub is_git_repo: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirListing {
+    pub path: String,
+    /// 上级目录；已在根目录时为 None
+    pub parent: Option<String>,
+    /// 目录在前，按名称排序
+    pub entries: Vec<DirEntry>,
+}
+
+/// 上级目录；同时识别 `/` 与 `\`，远程服务器的系统不一定与本机相同
+pub fn parent_of(path: &str) -> Option<String> {
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    let index = trimmed.rfind(['/', '\\'])?;
+    let parent = &trimmed[..index];
+    if parent.is_empty() {
+        // Unix 根目录
+        return Some(trimmed[..1].to_string());
+    }
+    if parent.len() == 2 && parent.ends_with(':') {
+        // Windows 盘符根目录保留分隔符（`C:\`）
+        return Some(trimmed[..3].to_string());
+    }
+    Some(parent.to_string())
+}
+
+fn listing(path: String, mut entries: Vec<DirEntry>, dirs_only: bool) -> DirListing {
+    if dirs_only {
+        entries.retain(|entry| entry.is_dir);
+    }
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    DirListing {
+        parent: parent_of(&path),
+        path,
+        entries,
+    }
+}
+
+/// 列出本地目录；未指定时从用户主目录开始
+pub fn list_local(
+    app: &tauri::AppHandle,
+    path: Option<&str>,
+    dirs_only: bool,
+) -> Result<DirListing, String> {
+    let dir = match path.map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => crate::app::projects::normalize_path(Path::new(path)),
+        None => app.path().home_dir().map_err(|e| e.to_string())?,
+    };
+    let read = std::fs::read_dir(&dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    let entries = read
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let path = entry.path();
+            // 跟随符号链接判断是否为目录
+            let is_dir = path.is_dir();
+            DirEntry {
+                hidden: name.starts_with('.'),
+                is_git_repo: is_dir && path.join(".git").exists(),
+                path: path.to_string_lossy().into_owned(),
+                name,
+                is_dir,
+            }
+        })
+        .collect();
+    Ok(listing(
+        dir.to_string_lossy().into_owned(),
+        entries,
+        dirs_only,
+    ))
+}
+
+/// opencode `/file` 接口返回的节点
+#[derive(Deserialize)]
+struct FileNode {
+    name: String,
+    absolute: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+/// 通过服务器 API 列出服务器上的目录；未指定时从服务器用户的主目录开始
+pub async fn list_remote(
+    target: &ServerTarget,
+    path: Option<&str>,
+    dirs_only: bool,
+) -> Result<DirListing, String> {
+    let path = match path.map(str::trim).filter(|path| !path.is_empty()) {
+        Some(path) => path.to_string(),
+        None => {
+            let info = api::get_json(target, "/path").await?;
+            info["home"]
+                .as_str()
+                .map(str::to_string)
+                .ok_or("server did not report a home directory")?
+        }
+    };
+
+    // `/file` 列出的是请求目录下的相对路径，把要浏览的目录作为工作目录传入
+    let target = ServerTarget {
+        directory: Some(path.clone()),
+        ..target.clone()
+    };
+    let nodes = api::get_json(&target, "/file?path=").await?;
+    let nodes: Vec<FileNode> = match nodes {
+        Value::Null => Vec::new(),
+        nodes => {
+            serde_json::from_value(nodes).map_err(|e| format!("invalid /file response: {}", e))?
+        }
+    };
+    let entries = nodes
+        .into_iter()
+        .map(|node| DirEntry {
+            hidden: node.name.starts_with('.'),
+            is_dir: node.kind == "directory",
+            is_git_repo: false,
+            name: node.name,
+            path: node.absolute,
+        })
+        .collect();
+    Ok(listing(path, entries, dirs_only))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parent_handles_unix_and_windows_paths() {
+        assert_eq!(parent_of("/home/me/src/").as_deref(), Some("/home/me"));
+        assert_eq!(parent_of("/home").as_deref(), Some("/"));
+        assert_eq!(parent_of("/"), None);
+        assert_eq!(parent_of(r"C:\Users\me").as_deref(), Some(r"C:\Users"));
+        assert_eq!(parent_of(r"C:\Users").as_deref(), Some(r"C:\"));
+        assert_eq!(parent_of(r"C:\"), None);
+    }
+}
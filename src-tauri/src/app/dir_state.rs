@@ -1,8 +1,9 @@
 // ============================================
 // Open Directory State (desktop only)
-// 存储启动时传入的目录路径（右键菜单、拖放等）
+// 存储启动时传入的目录路径（右键菜单、拖放等）与 CLI 启动参数
 // ============================================
 
+use crate::app::launch_args::LaunchOptions;
 use papaya::HashMap as PaHashMap;
 use rapidhash::fast::RandomState;
 use std::sync::Arc;
@@ -10,12 +11,18 @@ use std::sync::Arc;
 pub struct OpenDirectoryState {
     /// per-window 待处理目录: window label → directory path
     pending: PaHashMap<String, Arc<str>, RandomState>,
+    /// per-window 待打开会话（弹出 / 合并窗口时携带）: window label → session id
+    sessions: PaHashMap<String, Arc<str>, RandomState>,
+    /// per-window 待处理启动参数（--prompt / --model / --agent）
+    launch: PaHashMap<String, Arc<LaunchOptions>, RandomState>,
 }
 
 impl Default for OpenDirectoryState {
     fn default() -> Self {
         Self {
             pending: PaHashMap::with_hasher(RandomState::new()),
+            sessions: PaHashMap::with_hasher(RandomState::new()),
+            launch: PaHashMap::with_hasher(RandomState::new()),
         }
     }
 }
@@ -24,4 +31,28 @@ impl OpenDirectoryState {
     pub fn pending(&self) -> &PaHashMap<String, Arc<str>, RandomState> {
         &self.pending
     }
+
+    pub fn sessions(&self) -> &PaHashMap<String, Arc<str>, RandomState> {
+        &self.sessions
+    }
+
+    pub fn launch(&self) -> &PaHashMap<String, Arc<LaunchOptions>, RandomState> {
+        &self.launch
+    }
+
+    /// 记录窗口的启动参数，空参数不记录
+    pub fn set_launch(&self, label: &str, options: LaunchOptions) {
+        if !options.is_empty() {
+            self.launch
+                .pin()
+                .insert(label.to_string(), Arc::new(options));
+        }
+    }
+
+    /// 清理窗口未被读取的待处理状态
+    pub fn forget(&self, label: &str) {
+        self.pending.pin().remove(label);
+        self.sessions.pin().remove(label);
+        self.launch.pin().remove(label);
+    }
 }
@@ -0,0 +1,192 @@
+// ============================================
+// Project Discovery (desktop only)
+// 扫描根目录下的 git 仓库（限制深度，跳过依赖 / 构建目录、隐藏目录及 .gitignore 中忽略的目录），
+// 返回名称、远程地址与最近一次提交，供用户勾选后批量注册为项目
+// ============================================
+
+use crate::app::git;
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+pub const DEFAULT_MAX_DEPTH: usize = 4;
+/// 单次扫描最多返回的仓库数
+const MAX_REPOS: usize = 1000;
+/// 不进入的目录（依赖、构建产物等）
+const SKIPPED_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "vendor",
+    "dist",
+    "build",
+    "out",
+    "venv",
+    "__pycache__",
+    "Library",
+    "AppData",
+];
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LastCommit {
+    pub hash: String,
+    pub summary: String,
+    pub author: String,
+    /// 提交时间（毫秒时间戳）
+    pub at: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveredRepo {
+    pub name: String,
+    pub path: String,
+    pub remote: Option<String>,
+    pub branch: Option<String>,
+    pub last_commit: Option<LastCommit>,
+    /// 已在项目列表中
+    pub registered: bool,
+}
+
+/// `.gitignore` 中可以按目录名匹配的条目（不含通配符与路径分隔符的简单名字）
+fn ignored_names(gitignore: &str) -> Vec<String> {
+    gitignore
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#') && !line.starts_with('!'))
+        .map(|line| line.trim_start_matches('/').trim_end_matches('/'))
+        .filter(|name| !name.is_empty() && !name.contains(['/', '*', '?', '[']))
+        .map(str::to_string)
+        .collect()
+}
+
+fn walk(
+    dir: &Path,
+    depth: usize,
+    max_depth: usize,
+    ignored: &HashSet<String>,
+    out: &mut Vec<PathBuf>,
+) {
+    if out.len() >= MAX_REPOS {
+        return;
+    }
+    // .git 可能是目录，也可能是 worktree / submodule 的文件
+    if dir.join(".git").exists() {
+        out.push(dir.to_path_buf());
+        return;
+    }
+    if depth >= max_depth {
+        return;
+    }
+
+    let mut ignored = ignored.clone();
+    if let Ok(gitignore) = std::fs::read_to_string(dir.join(".gitignore")) {
+        ignored.extend(ignored_names(&gitignore));
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    let mut children: Vec<PathBuf> = entries
+        .flatten()
+        // 不跟随符号链接，避免环和重复
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .filter(|entry| {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            !name.starts_with('.')
+                && !SKIPPED_DIRS.contains(&name.as_str())
+                && !ignored.contains(&name)
+        })
+        .map(|entry| entry.path())
+        .collect();
+    children.sort();
+    for child in children {
+        walk(&child, depth + 1, max_depth, &ignored, out);
+    }
+}
+
+/// 找出 `root` 下的 git 仓库路径（不进入已找到的仓库内部）
+pub fn find_repos(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    let mut repos = Vec::new();
+    walk(root, 0, max_depth, &HashSet::new(), &mut repos);
+    repos
+}
+
+fn last_commit(repo: &Path) -> Option<LastCommit> {
+    let output = git::run(&["log", "-1", "--format=%H%x1f%s%x1f%an%x1f%ct"], repo).ok()?;
+    let mut fields = output.split('\x1f');
+    Some(LastCommit {
+        hash: fields.next()?.to_string(),
+        summary: fields.next()?.to_string(),
+        author: fields.next()?.to_string(),
+        at: fields.next()?.trim().parse::<u64>().ok()? * 1000,
+    })
+}
+
+fn remote(repo: &Path) -> Option<String> {
+    if let Ok(url) = git::run(&["remote", "get-url", "origin"], repo) {
+        return Some(url);
+    }
+    let remotes = git::run(&["remote"], repo).ok()?;
+    let first = remotes.lines().next()?;
+    git::run(&["remote", "get-url", first], repo).ok()
+}
+
+pub fn describe(repo: &Path, registered: bool) -> DiscoveredRepo {
+    DiscoveredRepo {
+        name: repo
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| repo.to_string_lossy().to_string()),
+        path: repo.to_string_lossy().to_string(),
+        remote: remote(repo),
+        branch: git::run(&["branch", "--show-current"], repo)
+            .ok()
+            .filter(|branch| !branch.is_empty()),
+        last_commit: last_commit(repo),
+        registered,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_repos_within_depth_and_skips_ignored() {
+        let root =
+            std::env::temp_dir().join(format!("opencodeui-discovery-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        for repo in [
+            "a/.git",
+            "a/nested/.git",
+            "group/b/.git",
+            "node_modules/pkg/.git",
+            "generated/c/.git",
+            ".hidden/d/.git",
+            "x/y/z/deep/.git",
+        ] {
+            std::fs::create_dir_all(root.join(repo)).unwrap();
+        }
+        std::fs::write(
+            root.join(".gitignore"),
+            "# build output\n/generated/\n*.log\n",
+        )
+        .unwrap();
+
+        let repos: Vec<String> = find_repos(&root, 3)
+            .iter()
+            .map(|repo| {
+                repo.strip_prefix(&root)
+                    .unwrap()
+                    .to_string_lossy()
+                    .replace('\\', "/")
+            })
+            .collect();
+        assert_eq!(repos, vec!["a", "group/b"]);
+        assert_eq!(find_repos(&root, 4).len(), 3);
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,182 @@
+// ============================================
+// DNS-over-HTTPS Resolver
+// 系统 DNS 损坏或被劫持时，通过 DoH 解析远程服务器地址；失败时回退系统解析
+// ============================================
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex, RwLock},
+    time::{Duration, Instant},
+};
+
+/// 预置提供商使用 IP 地址，避免解析 DoH 服务器本身依赖系统 DNS
+pub const CLOUDFLARE_DOH: &str = "https://1.1.1.1/dns-query";
+pub const GOOGLE_DOH: &str = "https://8.8.8.8/resolve";
+
+pub const DOH_SETTINGS_KEY: &str = "dnsOverHttps";
+
+const MIN_TTL: Duration = Duration::from_secs(30);
+const MAX_TTL: Duration = Duration::from_secs(3600);
+const QUERY_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct DohConfig {
+    pub enabled: bool,
+    /// 支持 JSON API（application/dns-json）的 DoH 端点
+    pub url: String,
+}
+
+impl Default for DohConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: CLOUDFLARE_DOH.to_string(),
+        }
+    }
+}
+
+struct Inner {
+    endpoint: String,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<String, (Vec<IpAddr>, Instant)>>,
+}
+
+#[derive(Clone)]
+pub struct DohResolver(Arc<Inner>);
+
+/// 当前生效的 DoH 解析器，未启用时为 None
+static RESOLVER: RwLock<Option<DohResolver>> = RwLock::new(None);
+
+impl DohResolver {
+    fn new(endpoint: &str) -> Result<Self, String> {
+        let parsed = reqwest::Url::parse(endpoint).map_err(|e| e.to_string())?;
+        if parsed.scheme() != "https" {
+            return Err("DoH endpoint must use https".to_string());
+        }
+        // 查询 DoH 的客户端本身走系统解析，避免递归
+        let client = reqwest::Client::builder()
+            .timeout(QUERY_TIMEOUT)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self(Arc::new(Inner {
+            endpoint: endpoint.to_string(),
+            client,
+            cache: Mutex::new(HashMap::new()),
+        })))
+    }
+
+    async fn query(&self, host: &str, record_type: &str) -> Result<(Vec<IpAddr>, u64), String> {
+        let response = self
+            .0
+            .client
+            .get(&self.0.endpoint)
+            .query(&[("name", host), ("type", record_type)])
+            .header("Accept", "application/dns-json")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        let body = response.bytes().await.map_err(|e| e.to_string())?;
+        let value: Value = serde_json::from_slice(&body).map_err(|e| e.to_string())?;
+
+        let mut ttl = u64::MAX;
+        let ips = value["Answer"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|answer| {
+                let ip = answer["data"].as_str()?.parse::<IpAddr>().ok()?;
+                ttl = ttl.min(answer["TTL"].as_u64().unwrap_or(0));
+                Some(ip)
+            })
+            .collect();
+        Ok((ips, ttl))
+    }
+
+    async fn lookup(&self, host: &str) -> Result<Vec<IpAddr>, String> {
+        if let Some((ips, expires)) = self.0.cache.lock().expect("dns cache poisoned").get(host) {
+            if *expires > Instant::now() {
+                return Ok(ips.clone());
+            }
+        }
+
+        let (v4, v6) = tokio::join!(self.query(host, "A"), self.query(host, "AAAA"));
+        let mut ips = Vec::new();
+        let mut ttl = u64::MAX;
+        for (answer_ips, answer_ttl) in [v4.clone(), v6].into_iter().flatten() {
+            ips.extend(answer_ips);
+            ttl = ttl.min(answer_ttl);
+        }
+        if ips.is_empty() {
+            return Err(v4
+                .err()
+                .unwrap_or_else(|| format!("no DoH records for {}", host)));
+        }
+
+        let ttl = Duration::from_secs(ttl).clamp(MIN_TTL, MAX_TTL);
+        self.0
+            .cache
+            .lock()
+            .expect("dns cache poisoned")
+            .insert(host.to_string(), (ips.clone(), Instant::now() + ttl));
+        Ok(ips)
+    }
+}
+
+fn is_local_name(host: &str) -> bool {
+    host.eq_ignore_ascii_case("localhost") || host.ends_with(".local") || !host.contains('.')
+}
+
+async fn system_lookup(host: &str) -> Result<Vec<SocketAddr>, String> {
+    tokio::net::lookup_host((host, 0))
+        .await
+        .map(Iterator::collect)
+        .map_err(|e| e.to_string())
+}
+
+impl Resolve for DohResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            // 本地 / 局域网名称仍交给系统解析
+            let addrs = if is_local_name(&host) {
+                system_lookup(&host).await?
+            } else {
+                match resolver.lookup(&host).await {
+                    Ok(ips) => ips.into_iter().map(|ip| SocketAddr::new(ip, 0)).collect(),
+                    Err(e) => {
+                        log::warn!("DoH lookup for {} failed ({}), using system DNS", host, e);
+                        system_lookup(&host).await?
+                    }
+                }
+            };
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// 应用 DoH 配置；禁用时恢复系统解析
+pub fn configure(config: &DohConfig) -> Result<(), String> {
+    let resolver = if config.enabled {
+        Some(DohResolver::new(&config.url)?)
+    } else {
+        None
+    };
+    *RESOLVER.write().expect("dns resolver poisoned") = resolver;
+    Ok(())
+}
+
+/// 创建 reqwest ClientBuilder，已启用 DoH 时挂上解析器，并应用代理与 TLS 等网络设置。
+/// bridge、健康检查、API 调用等访问服务器的客户端都应从这里创建。
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let builder = crate::app::network::apply(reqwest::Client::builder());
+    match RESOLVER.read().expect("dns resolver poisoned").clone() {
+        Some(resolver) => builder.dns_resolver(Arc::new(resolver)),
+        None => builder,
+    }
+}
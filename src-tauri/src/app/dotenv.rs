@@ -0,0 +1,163 @@
+// ============================================
+// .env Files (desktop only)
+// 解析 .env 格式的环境变量文件，供启动 opencode serve 时注入：
+// - `KEY=VALUE`，可带 `export ` 前缀；`#` 开头的行与未加引号值中空白后的 `#` 为注释
+// - 单引号内原样保留；双引号内支持 \n \r \t \\ \" 转义；引号内的值可以跨行
+// - 不做 `${VAR}` 变量展开
+// ============================================
+
+use std::path::Path;
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 找到结束引号的位置；双引号内跳过转义字符
+fn closing_quote(value: &str, quote: char) -> Option<usize> {
+    let mut escaped = false;
+    for (index, c) in value.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quote == '"' && c == '\\' {
+            escaped = true;
+        } else if c == quote {
+            return Some(index);
+        }
+    }
+    None
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('t') => out.push('\t'),
+            Some(c @ ('\\' | '"')) => out.push(c),
+            Some(c) => {
+                out.push('\\');
+                out.push(c);
+            }
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+/// 去掉未加引号的值中的行尾注释（前面须有空白，`a#b` 不是注释）
+fn strip_inline_comment(value: &str) -> &str {
+    value
+        .char_indices()
+        .find(|&(index, c)| {
+            c == '#' && (index == 0 || value[..index].ends_with(char::is_whitespace))
+        })
+        .map_or(value, |(index, _)| &value[..index])
+}
+
+/// 按出现顺序返回变量；同名变量后者覆盖前者由调用方处理。错误信息带行号
+pub fn parse(content: &str) -> Result<Vec<(String, String)>, String> {
+    let mut vars = Vec::new();
+    let mut lines = content.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let line_no = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line
+            .strip_prefix("export ")
+            .map(str::trim_start)
+            .unwrap_or(line);
+        let (key, rest) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=VALUE", line_no))?;
+        let key = key.trim();
+        if !is_valid_key(key) {
+            return Err(format!("line {}: invalid variable name '{}'", line_no, key));
+        }
+
+        let rest = rest.trim_start();
+        let value = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let mut raw = rest[1..].to_string();
+                let end = loop {
+                    if let Some(end) = closing_quote(&raw, quote) {
+                        break end;
+                    }
+                    let Some((_, next)) = lines.next() else {
+                        return Err(format!("line {}: unterminated {} quote", line_no, quote));
+                    };
+                    raw.push('\n');
+                    raw.push_str(next);
+                };
+                let trailing = raw[end + 1..].trim();
+                if !trailing.is_empty() && !trailing.starts_with('#') {
+                    return Err(format!(
+                        "line {}: unexpected '{}' after closing quote",
+                        line_no, trailing
+                    ));
+                }
+                raw.truncate(end);
+                if quote == '"' {
+                    unescape(&raw)
+                } else {
+                    raw
+                }
+            }
+            _ => strip_inline_comment(rest).trim_end().to_string(),
+        };
+        vars.push((key.to_string(), value));
+    }
+    Ok(vars)
+}
+
+/// 读取并解析 .env 文件
+pub fn load(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read '{}': {}", path.display(), e))?;
+    parse(&content).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_quotes_comments_and_multiline_values() {
+        let content = "# provider keys\n\
+            export OPENAI_API_KEY=sk-test  # inline comment\n\
+            EMPTY=\n\
+            URL=http://host/#anchor\n\
+            SINGLE='raw \\n $HOME'\n\
+            DOUBLE=\"a\\tb \\\"q\\\"\" # note\n\
+            CERT=\"-----BEGIN-----\n\
+            abc\n\
+            -----END-----\"\n";
+        let vars = parse(content).unwrap();
+        let get = |key: &str| vars.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+        assert_eq!(get("OPENAI_API_KEY"), Some("sk-test"));
+        assert_eq!(get("EMPTY"), Some(""));
+        assert_eq!(get("URL"), Some("http://host/#anchor"));
+        assert_eq!(get("SINGLE"), Some("raw \\n $HOME"));
+        assert_eq!(get("DOUBLE"), Some("a\tb \"q\""));
+        assert_eq!(get("CERT"), Some("-----BEGIN-----\nabc\n-----END-----"));
+
+        assert_eq!(
+            parse("A=1\nnot a pair\n").unwrap_err(),
+            "line 2: expected KEY=VALUE"
+        );
+        assert!(parse("1A=x").is_err());
+        assert!(parse("A=\"open\nB=2").unwrap_err().contains("unterminated"));
+        assert!(parse("A='x' y").is_err());
+    }
+}
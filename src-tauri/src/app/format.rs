@@ -0,0 +1,187 @@
+// ============================================
+// Localized Formatting (desktop only)
+// 导出文件、报告等生成内容里的时间、数字、文件大小、金额与时长统一按界面语言格式化
+// ============================================
+
+use crate::app::i18n::Language;
+use serde::Deserialize;
+
+/// 1970-01-01 起的天数 → (年, 月, 日)，Howard Hinnant 的 civil_from_days 算法
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Unix 毫秒时间 → 日期（UTC）
+pub fn date(language: Language, millis: u64) -> String {
+    let (year, month, day) = civil_from_days((millis / 1000 / 86_400) as i64);
+    match language {
+        Language::ZhCn => format!("{}年{}月{}日", year, month, day),
+        Language::En => format!("{} {}, {}", MONTHS[(month - 1) as usize], day, year),
+    }
+}
+
+/// Unix 毫秒时间 → 日期与时间（UTC）
+pub fn timestamp(language: Language, millis: u64) -> String {
+    let secs = millis / 1000;
+    let (hour, minute) = ((secs % 86_400) / 3600, (secs % 3600) / 60);
+    match language {
+        Language::ZhCn => format!("{} {:02}:{:02} (UTC)", date(language, millis), hour, minute),
+        Language::En => format!("{} {:02}:{:02} UTC", date(language, millis), hour, minute),
+    }
+}
+
+/// 整数千分位分组（en 与 zh-CN 都使用逗号）
+pub fn number(value: i64) -> String {
+    let digits = value.unsigned_abs().to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3 + 1);
+    if value < 0 {
+        grouped.push('-');
+    }
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+/// 字节数 → 可读大小（1024 进制）
+pub fn bytes(language: Language, size: u64) -> String {
+    const UNITS: [&str; 5] = ["KB", "MB", "GB", "TB", "PB"];
+    if size < 1024 {
+        return match language {
+            Language::ZhCn => format!("{} 字节", size),
+            Language::En if size == 1 => "1 byte".to_string(),
+            Language::En => format!("{} bytes", size),
+        };
+    }
+    let mut value = size as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    let precision = if value < 10.0 { 1 } else { 0 };
+    format!("{:.*} {}", precision, value, UNITS[unit])
+}
+
+/// 美元金额（模型费用）；不足一美分时保留 4 位小数
+pub fn currency_usd(language: Language, amount: f64) -> String {
+    let precision = if amount != 0.0 && amount.abs() < 0.01 {
+        4
+    } else {
+        2
+    };
+    let sign = if amount < 0.0 { "-" } else { "" };
+    let amount = amount.abs();
+    let whole = number(amount.trunc() as i64);
+    let fraction = format!("{:.*}", precision, amount.fract());
+    // 小数部分四舍五入进位时（如 0.999 → "1.00"）整体重新格式化
+    let (whole, fraction) = match fraction.strip_prefix("0.") {
+        Some(fraction) => (whole, fraction.to_string()),
+        None => (number(amount.round() as i64), "0".repeat(precision)),
+    };
+    let prefix = match language {
+        Language::ZhCn => "US$",
+        Language::En => "$",
+    };
+    format!("{}{}{}.{}", sign, prefix, whole, fraction)
+}
+
+/// 毫秒时长 → 可读时长（如 `1h 5m 3s` / `1小时5分3秒`）
+pub fn duration(language: Language, millis: u64) -> String {
+    if millis < 1000 {
+        return match language {
+            Language::ZhCn => format!("{}毫秒", millis),
+            Language::En => format!("{}ms", millis),
+        };
+    }
+    let secs = millis / 1000;
+    let parts = [
+        (secs / 3600, "h", "小时"),
+        ((secs % 3600) / 60, "m", "分"),
+        (secs % 60, "s", "秒"),
+    ];
+    let mut text = String::new();
+    for (value, en, zh) in parts.into_iter().skip_while(|(value, _, _)| *value == 0) {
+        match language {
+            Language::ZhCn => text.push_str(&format!("{}{}", value, zh)),
+            Language::En => {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&format!("{}{}", value, en));
+            }
+        }
+    }
+    text
+}
+
+/// 前端生成导出文件时请求格式化的值
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "camelCase")]
+pub enum FormatValue {
+    Timestamp(u64),
+    Date(u64),
+    Number(i64),
+    Bytes(u64),
+    CurrencyUsd(f64),
+    Duration(u64),
+}
+
+impl FormatValue {
+    pub fn format(self, language: Language) -> String {
+        match self {
+            Self::Timestamp(millis) => timestamp(language, millis),
+            Self::Date(millis) => date(language, millis),
+            Self::Number(value) => number(value),
+            Self::Bytes(size) => bytes(language, size),
+            Self::CurrencyUsd(amount) => currency_usd(language, amount),
+            Self::Duration(millis) => duration(language, millis),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_per_language() {
+        // 2024-02-29 13:05 UTC
+        let millis = 1_709_211_900_000;
+        assert_eq!(timestamp(Language::En, millis), "Feb 29, 2024 13:05 UTC");
+        assert_eq!(
+            timestamp(Language::ZhCn, millis),
+            "2024年2月29日 13:05 (UTC)"
+        );
+
+        assert_eq!(number(-1_234_567), "-1,234,567");
+        assert_eq!(number(999), "999");
+        assert_eq!(bytes(Language::En, 512), "512 bytes");
+        assert_eq!(bytes(Language::ZhCn, 1536), "1.5 KB");
+        assert_eq!(bytes(Language::En, 300 * 1024 * 1024), "300 MB");
+
+        assert_eq!(currency_usd(Language::En, 1234.5), "$1,234.50");
+        assert_eq!(currency_usd(Language::ZhCn, 0.00123), "US$0.0012");
+        assert_eq!(currency_usd(Language::En, 0.999), "$1.00");
+
+        assert_eq!(duration(Language::En, 3_903_000), "1h 5m 3s");
+        assert_eq!(duration(Language::ZhCn, 63_000), "1分3秒");
+        assert_eq!(duration(Language::En, 250), "250ms");
+    }
+}
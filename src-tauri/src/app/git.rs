@@ -0,0 +1,41 @@
+// ============================================
+// Git CLI Helpers (desktop only)
+// 统一构造 git 子进程：不弹窗口、不提示输入凭据
+// ============================================
+
+use std::{path::Path, process::Command};
+
+pub fn command() -> Command {
+    let mut cmd = Command::new("git");
+    // 禁止弹出凭据输入，需要认证的仓库应通过 credential helper 处理
+    cmd.env("GIT_TERMINAL_PROMPT", "0");
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// 运行 git 并返回 stdout（去掉末尾换行）
+pub fn run(args: &[&str], cwd: &Path) -> Result<String, String> {
+    output(command().args(args).current_dir(cwd), args)
+}
+
+/// 运行已配置好的 git 命令（如设置了环境变量），`args` 仅用于错误信息
+pub fn output(cmd: &mut Command, args: &[&str]) -> Result<String, String> {
+    let output = cmd
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
@@ -0,0 +1,278 @@
+// ============================================
+// Server Health Monitor (desktop only)
+// 后台定期请求各服务器的 /global/health，状态变化时发出 `server-status-changed`，
+// 前端不必再经 IPC 轮询 check_opencode_service：
+// - 监视前端登记的服务器，以及当前运行的托管实例
+// - 新状态连续出现 failure_threshold 次才生效，短暂抖动不会来回切换
+// - 服务器列表（含认证头）只保存在内存中，由前端在启动时登记
+// ============================================
+
+use crate::app::service::ServiceState;
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager};
+
+pub const HEALTH_MONITOR_SETTINGS_KEY: &str = "healthMonitor";
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+/// 未开启检查时检查设置变化的间隔
+const DISABLED_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HealthMonitorConfig {
+    /// 检查间隔（秒），0 表示关闭
+    pub interval_secs: u64,
+    /// 状态切换前需要连续观察到的次数
+    pub failure_threshold: u32,
+}
+
+impl Default for HealthMonitorConfig {
+    fn default() -> Self {
+        Self {
+            interval_secs: 10,
+            failure_threshold: 2,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitoredServer {
+    pub url: String,
+    pub auth_header: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServerStatus {
+    Online,
+    Offline,
+    /// 服务器可达，但返回 401 / 403
+    Unauthorized,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStatusChange {
+    pub url: String,
+    pub status: ServerStatus,
+    /// 最近一次检查的耗时；离线时为 None
+    pub latency_ms: Option<u64>,
+}
+
+/// 单个服务器的去抖状态
+#[derive(Default)]
+struct Tracker {
+    status: Option<ServerStatus>,
+    latency_ms: Option<u64>,
+    /// 尚未生效的新状态及其连续出现次数
+    pending: Option<(ServerStatus, u32)>,
+}
+
+impl Tracker {
+    /// 记录一次检查结果；状态生效切换时返回新状态。首次检查直接生效
+    fn observe(&mut self, status: ServerStatus, threshold: u32) -> Option<ServerStatus> {
+        if self.status.is_none() || self.status == Some(status) {
+            self.pending = None;
+            return (self.status.replace(status) != Some(status)).then_some(status);
+        }
+        let count = match self.pending {
+            Some((pending, count)) if pending == status => count + 1,
+            _ => 1,
+        };
+        if count >= threshold.max(1) {
+            self.pending = None;
+            self.status = Some(status);
+            return Some(status);
+        }
+        self.pending = Some((status, count));
+        None
+    }
+}
+
+#[derive(Default)]
+pub struct HealthMonitorState {
+    servers: Mutex<Vec<MonitoredServer>>,
+    trackers: Mutex<HashMap<String, Tracker>>,
+    pub config: Mutex<HealthMonitorConfig>,
+}
+
+fn normalize_url(url: &str) -> String {
+    url.trim().trim_end_matches('/').to_string()
+}
+
+impl HealthMonitorState {
+    /// 替换前端登记的服务器列表；不再监视的服务器丢弃其状态
+    pub fn set_servers(&self, servers: Vec<MonitoredServer>) {
+        let servers: Vec<MonitoredServer> = servers
+            .into_iter()
+            .map(|server| MonitoredServer {
+                url: normalize_url(&server.url),
+                ..server
+            })
+            .filter(|server| !server.url.is_empty())
+            .collect();
+        if let Ok(mut trackers) = self.trackers.lock() {
+            trackers.retain(|url, _| servers.iter().any(|server| &server.url == url));
+        }
+        if let Ok(mut current) = self.servers.lock() {
+            *current = servers;
+        }
+    }
+
+    /// 已确定状态的服务器
+    pub fn statuses(&self) -> Vec<ServerStatusChange> {
+        let Ok(trackers) = self.trackers.lock() else {
+            return Vec::new();
+        };
+        trackers
+            .iter()
+            .filter_map(|(url, tracker)| {
+                Some(ServerStatusChange {
+                    url: url.clone(),
+                    status: tracker.status?,
+                    latency_ms: tracker.latency_ms,
+                })
+            })
+            .collect()
+    }
+
+    /// 本轮要检查的服务器：登记的服务器加上运行中的托管实例，按 URL 去重
+    fn targets(&self, services: &ServiceState) -> Vec<MonitoredServer> {
+        let mut targets = self
+            .servers
+            .lock()
+            .map(|servers| servers.clone())
+            .unwrap_or_default();
+        for instance in services.instances() {
+            let url = instance.service_url.lock().ok().and_then(|url| url.clone());
+            if let Some(url) = url.map(|url| normalize_url(&url)) {
+                if !targets.iter().any(|server| server.url == url) {
+                    targets.push(MonitoredServer {
+                        url,
+                        auth_header: None,
+                    });
+                }
+            }
+        }
+        targets
+    }
+}
+
+async fn check(client: &reqwest::Client, server: &MonitoredServer) -> (ServerStatus, Option<u64>) {
+    let mut request = client
+        .get(format!("{}/global/health", server.url))
+        .timeout(CHECK_TIMEOUT);
+    if let Some(auth) = server
+        .auth_header
+        .as_deref()
+        .filter(|auth| !auth.is_empty())
+    {
+        request = request.header(reqwest::header::AUTHORIZATION, auth);
+    }
+    let started = Instant::now();
+    match request.send().await {
+        Ok(response) => {
+            let latency = Some(started.elapsed().as_millis() as u64);
+            let status = response.status();
+            if status.is_success() {
+                (ServerStatus::Online, latency)
+            } else if status == reqwest::StatusCode::UNAUTHORIZED
+                || status == reqwest::StatusCode::FORBIDDEN
+            {
+                (ServerStatus::Unauthorized, latency)
+            } else {
+                (ServerStatus::Offline, latency)
+            }
+        }
+        Err(_) => (ServerStatus::Offline, None),
+    }
+}
+
+pub fn spawn_health_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let config = app
+                .state::<HealthMonitorState>()
+                .config
+                .lock()
+                .map(|config| config.clone())
+                .unwrap_or_default();
+            if config.interval_secs == 0 {
+                tokio::time::sleep(DISABLED_CHECK_INTERVAL).await;
+                continue;
+            }
+
+            let state = app.state::<HealthMonitorState>();
+            let targets = state.targets(&app.state::<ServiceState>());
+            if let Ok(client) = crate::app::dns::client_builder()
+                .connect_timeout(Duration::from_secs(3))
+                .build()
+            {
+                let results = join_all(targets.iter().map(|server| check(&client, server))).await;
+                let mut changes = Vec::new();
+                if let Ok(mut trackers) = state.trackers.lock() {
+                    for (server, (status, latency_ms)) in targets.iter().zip(results) {
+                        let tracker = trackers.entry(server.url.clone()).or_default();
+                        tracker.latency_ms = latency_ms;
+                        if let Some(status) = tracker.observe(status, config.failure_threshold) {
+                            changes.push(ServerStatusChange {
+                                url: server.url.clone(),
+                                status,
+                                latency_ms,
+                            });
+                        }
+                    }
+                }
+                for change in changes {
+                    log::info!("Server {} is now {:?}", change.url, change.status);
+                    let _ = app.emit("server-status-changed", change);
+                }
+            }
+
+            tokio::time::sleep(Duration::from_secs(config.interval_secs)).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn debounces_status_flaps() {
+        let mut tracker = Tracker::default();
+        assert_eq!(
+            tracker.observe(ServerStatus::Online, 2),
+            Some(ServerStatus::Online)
+        );
+        assert_eq!(tracker.observe(ServerStatus::Online, 2), None);
+
+        // 单次失败被吸收
+        assert_eq!(tracker.observe(ServerStatus::Offline, 2), None);
+        assert_eq!(tracker.observe(ServerStatus::Online, 2), None);
+
+        // 连续两次失败才切换
+        assert_eq!(tracker.observe(ServerStatus::Offline, 2), None);
+        assert_eq!(
+            tracker.observe(ServerStatus::Offline, 2),
+            Some(ServerStatus::Offline)
+        );
+        assert_eq!(tracker.observe(ServerStatus::Offline, 2), None);
+
+        // 不同的新状态重新计数
+        assert_eq!(tracker.observe(ServerStatus::Unauthorized, 3), None);
+        assert_eq!(tracker.observe(ServerStatus::Online, 3), None);
+        assert_eq!(tracker.observe(ServerStatus::Online, 3), None);
+        assert_eq!(
+            tracker.observe(ServerStatus::Online, 3),
+            Some(ServerStatus::Online)
+        );
+    }
+}
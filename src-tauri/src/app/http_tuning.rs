@@ -0,0 +1,58 @@
+// ============================================
+// Per-server HTTP Client Tuning
+// 某些反向代理只在特定 HTTP 版本 / 连接参数下工作正常，由前端按服务器传入
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HttpVersion {
+    /// TLS 下按 ALPN 协商，明文使用 HTTP/1.1
+    #[default]
+    Auto,
+    Http1,
+    /// 强制 HTTP/2（明文连接使用 prior knowledge）
+    Http2,
+}
+
+/// 未设置的字段保持 reqwest / 调用方的默认值
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HttpTuning {
+    pub version: HttpVersion,
+    pub pool_max_idle_per_host: Option<usize>,
+    pub pool_idle_timeout_secs: Option<u64>,
+    /// 0 表示关闭 TCP keepalive
+    pub tcp_keepalive_secs: Option<u64>,
+    pub tcp_nodelay: Option<bool>,
+}
+
+impl HttpTuning {
+    pub fn apply(&self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        builder = match self.version {
+            HttpVersion::Auto => builder,
+            HttpVersion::Http1 => builder.http1_only(),
+            HttpVersion::Http2 => builder.http2_prior_knowledge(),
+        };
+        if let Some(max) = self.pool_max_idle_per_host {
+            builder = builder.pool_max_idle_per_host(max);
+        }
+        if let Some(secs) = self.pool_idle_timeout_secs {
+            builder = builder.pool_idle_timeout(Duration::from_secs(secs));
+        }
+        if let Some(secs) = self.tcp_keepalive_secs {
+            builder = builder.tcp_keepalive((secs > 0).then(|| Duration::from_secs(secs)));
+        }
+        if let Some(nodelay) = self.tcp_nodelay {
+            builder = builder.tcp_nodelay(nodelay);
+        }
+        builder
+    }
+
+    /// WebSocket 只支持关闭 Nagle；默认与 reqwest 一致为开启 nodelay
+    pub fn ws_disable_nagle(&self) -> bool {
+        self.tcp_nodelay.unwrap_or(true)
+    }
+}
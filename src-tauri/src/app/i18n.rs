@@ -0,0 +1,117 @@
+// ============================================
+// Native UI i18n (desktop only)
+// 原生菜单、托盘、系统对话框等 Rust 侧文案的本地化资源，至少支持 zh-CN / en
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+
+/// 用户选择的界面语言；未设置时跟随系统
+pub const LANGUAGE_SETTINGS_KEY: &str = "appLanguage";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Language {
+    #[serde(rename = "zh-CN")]
+    ZhCn,
+    #[default]
+    #[serde(rename = "en")]
+    En,
+}
+
+/// key → (en, zh-CN)
+const STRINGS: &[(&str, &str, &str)] = &[
+    ("menu.about", "About OpenCode", "关于 OpenCode"),
+    ("menu.hide", "Hide OpenCode", "隐藏 OpenCode"),
+    ("menu.hideOthers", "Hide Others", "隐藏其他"),
+    ("menu.showAll", "Show All", "全部显示"),
+    ("menu.quit", "Quit OpenCode", "退出 OpenCode"),
+    ("menu.file", "File", "文件"),
+    ("menu.newWindow", "New Window", "新建窗口"),
+    ("menu.closeWindow", "Close Window", "关闭窗口"),
+    ("menu.edit", "Edit", "编辑"),
+    ("menu.undo", "Undo", "撤销"),
+    ("menu.redo", "Redo", "重做"),
+    ("menu.cut", "Cut", "剪切"),
+    ("menu.copy", "Copy", "拷贝"),
+    ("menu.paste", "Paste", "粘贴"),
+    ("menu.selectAll", "Select All", "全选"),
+    ("menu.view", "View", "显示"),
+    ("menu.fullscreen", "Enter Full Screen", "进入全屏幕"),
+    ("menu.window", "Window", "窗口"),
+    ("menu.minimize", "Minimize", "最小化"),
+    ("menu.zoom", "Zoom", "缩放"),
+    ("tray.show", "Show OpenCode", "显示 OpenCode"),
+];
+
+impl Language {
+    /// 从 BCP 47 / POSIX locale（如 `zh_CN.UTF-8`、`zh-Hans`）识别语言
+    pub fn from_locale(locale: &str) -> Self {
+        if locale.to_ascii_lowercase().starts_with("zh") {
+            Language::ZhCn
+        } else {
+            Language::En
+        }
+    }
+
+    /// 系统语言：按 POSIX 约定依次读取 LC_ALL / LC_MESSAGES / LANG
+    pub fn system() -> Self {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|key| std::env::var(key).ok())
+            .find(|value| !value.is_empty() && value != "C" && value != "POSIX")
+            .map(|locale| Self::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// 查找文案；缺失时回退英文，再回退 key 本身
+    pub fn t(self, key: &'static str) -> &'static str {
+        STRINGS
+            .iter()
+            .find(|(id, _, _)| *id == key)
+            .map(|(_, en, zh)| match self {
+                Language::En => *en,
+                Language::ZhCn => *zh,
+            })
+            .unwrap_or(key)
+    }
+}
+
+/// 当前生效的语言
+#[derive(Default)]
+pub struct LanguageState {
+    /// None 表示跟随系统
+    preference: Mutex<Option<Language>>,
+}
+
+impl LanguageState {
+    pub fn new(preference: Option<Language>) -> Self {
+        Self {
+            preference: Mutex::new(preference),
+        }
+    }
+
+    pub fn preference(&self) -> Option<Language> {
+        *self.preference.lock().expect("language state poisoned")
+    }
+
+    pub fn set_preference(&self, preference: Option<Language>) {
+        *self.preference.lock().expect("language state poisoned") = preference;
+    }
+
+    pub fn current(&self) -> Language {
+        self.preference().unwrap_or_else(Language::system)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_strings_with_fallback() {
+        assert_eq!(Language::ZhCn.t("menu.copy"), "拷贝");
+        assert_eq!(Language::En.t("menu.copy"), "Copy");
+        assert_eq!(Language::ZhCn.t("missing.key"), "missing.key");
+        assert_eq!(Language::from_locale("zh_TW.UTF-8"), Language::ZhCn);
+    }
+}
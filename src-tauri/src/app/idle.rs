@@ -0,0 +1,217 @@
+// ============================================
+// Idle Auto-suspend (desktop only)
+// 托管服务空闲 N 分钟后停止/暂停，下一次发送 prompt 前自动唤醒
+// ============================================
+
+use crate::app::{
+    api::{self, ServerTarget},
+    commands::opencode::{is_service_running, launch_service, patched_env_var},
+    credentials::ServerCredential,
+    server_profiles::ServerProfilesState,
+    service::{IdleSuspendConfig, ServiceInstance, ServiceState, SuspendMode},
+    service_exit::{self, ExitReason},
+    service_pid, terminate,
+};
+use serde_json::Value;
+use std::{sync::atomic::Ordering, time::Duration};
+use tauri::{Emitter, Manager};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+pub const IDLE_SETTINGS_KEY: &str = "serviceIdleSuspend";
+
+/// 查询托管服务所用的目标：服务以 OPENCODE_SERVER_PASSWORD 启动时使用该密码，
+/// 按配置档启动的服务（实例 id 即配置档 id）使用钥匙串中的凭据与网络设置
+fn service_target(app: &tauri::AppHandle, instance: &ServiceInstance, url: &str) -> ServerTarget {
+    let env_vars = instance
+        .launch
+        .lock()
+        .ok()
+        .and_then(|launch| launch.as_ref().map(|launch| launch.env_vars.clone()))
+        .unwrap_or_default();
+    let mut target = ServerTarget {
+        url: url.to_string(),
+        ..Default::default()
+    };
+    if let Some(password) = patched_env_var(&env_vars, "OPENCODE_SERVER_PASSWORD")
+        .filter(|password| !password.is_empty())
+    {
+        let username = patched_env_var(&env_vars, "OPENCODE_SERVER_USERNAME")
+            .filter(|username| !username.is_empty())
+            .map(|username| username.to_string_lossy().to_string())
+            .unwrap_or_else(|| "opencode".to_string());
+        target.auth_header = Some(
+            ServerCredential::Basic {
+                username,
+                password: password.to_string_lossy().to_string(),
+            }
+            .header(),
+        );
+    } else if app.state::<ServerProfilesState>().get(&instance.id).is_ok() {
+        target.server_id = Some(instance.id.clone());
+    }
+    target
+}
+
+/// 服务端是否仍有忙碌的会话（agent 长时间运行时不算空闲）
+async fn has_busy_sessions(target: &ServerTarget) -> bool {
+    match api::get_json(target, "/session/status").await {
+        Ok(Value::Object(statuses)) => statuses.values().any(|status| {
+            status
+                .get("type")
+                .and_then(|kind| kind.as_str())
+                .is_some_and(|kind| kind != "idle")
+        }),
+        // 查询失败时保守处理，视为忙碌
+        _ => true,
+    }
+}
+
+#[cfg(unix)]
+fn signal_process(pid: u32, signal: &str) -> bool {
+    std::process::Command::new("kill")
+        .args([signal, &pid.to_string()])
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+async fn suspend(app: &tauri::AppHandle, instance: &ServiceInstance, mode: SuspendMode) {
+    let pid = instance.child_pid.load(Ordering::SeqCst);
+    if pid == 0 {
+        return;
+    }
+
+    let mode = match mode {
+        #[cfg(unix)]
+        SuspendMode::Pause if signal_process(pid, "-STOP") => SuspendMode::Pause,
+        _ => {
+            // 先清零，watchdog 才不会把这次退出当作崩溃
+            instance.child_pid.store(0, Ordering::SeqCst);
+            service_pid::record(instance);
+            let child = instance
+                .child
+                .lock()
+                .ok()
+                .and_then(|mut child| child.take());
+            let status =
+                tauri::async_runtime::spawn_blocking(move || terminate::terminate(pid, child))
+                    .await
+                    .ok()
+                    .flatten();
+            service_exit::report(instance, pid, status, ExitReason::Suspended);
+            SuspendMode::Stop
+        }
+    };
+
+    log::info!(
+        "opencode serve '{}' idle, suspended ({:?}), PID: {}",
+        instance.id,
+        mode,
+        pid
+    );
+    if let Ok(mut suspended) = instance.suspended.lock() {
+        *suspended = Some(mode);
+    }
+    let _ = app.emit("service-suspended", mode);
+}
+
+/// 如已挂起则恢复实例，返回是否执行了唤醒
+async fn wake_instance(
+    app: &tauri::AppHandle,
+    state: &ServiceState,
+    instance: &ServiceInstance,
+) -> Result<bool, String> {
+    let mode = instance.suspended.lock().map_err(|e| e.to_string())?.take();
+    let Some(mode) = mode else {
+        return Ok(false);
+    };
+
+    let _ = app.emit("service-waking", ());
+    log::info!("Waking opencode serve '{}' ({:?})", instance.id, mode);
+
+    #[cfg(unix)]
+    if mode == SuspendMode::Pause {
+        let pid = instance.child_pid.load(Ordering::SeqCst);
+        if pid > 0 && signal_process(pid, "-CONT") {
+            let _ = app.emit("service-awake", ());
+            return Ok(true);
+        }
+    }
+
+    let launch = instance
+        .launch
+        .lock()
+        .map_err(|e| e.to_string())?
+        .clone()
+        .ok_or("no previous launch configuration to resume")?;
+
+    // 挂起前服务由我们启动，需清除状态以便重新拉起
+    instance.we_started.store(false, Ordering::SeqCst);
+    match launch_service(state, instance, launch).await {
+        Ok(_) => {
+            let _ = app.emit("service-awake", ());
+            Ok(true)
+        }
+        Err(error) => {
+            let _ = app.emit("service-wake-failed", error.clone());
+            Err(error)
+        }
+    }
+}
+
+/// 恢复被挂起的实例（`None` 为全部），返回是否执行了唤醒
+pub async fn wake(
+    app: &tauri::AppHandle,
+    state: &ServiceState,
+    instance_id: Option<&str>,
+) -> Result<bool, String> {
+    state.touch_activity();
+    let instances = match instance_id {
+        Some(id) => vec![state.instance(Some(id))],
+        None => state.instances(),
+    };
+    let mut woke = false;
+    for instance in instances {
+        woke |= wake_instance(app, state, &instance).await?;
+    }
+    Ok(woke)
+}
+
+pub fn spawn_idle_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let state = app.state::<ServiceState>();
+            let config: IdleSuspendConfig = match state.idle_config.lock() {
+                Ok(config) => config.clone(),
+                Err(_) => continue,
+            };
+            if !config.enabled {
+                continue;
+            }
+
+            let idle_ms =
+                crate::app::now_millis().saturating_sub(state.last_activity.load(Ordering::SeqCst));
+            if idle_ms < u64::from(config.idle_minutes.max(1)) * 60 * 1000 {
+                continue;
+            }
+
+            for instance in state.instances() {
+                if !instance.we_started.load(Ordering::SeqCst) || instance.is_suspended() {
+                    continue;
+                }
+                let url = instance.service_url.lock().ok().and_then(|url| url.clone());
+                let Some(url) = url else {
+                    continue;
+                };
+                if !is_service_running(&url).await
+                    || has_busy_sessions(&service_target(&app, &instance, &url)).await
+                {
+                    continue;
+                }
+                suspend(&app, &instance, config.mode).await;
+            }
+        }
+    });
+}
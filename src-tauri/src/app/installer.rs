@@ -0,0 +1,408 @@
+// ============================================
+// opencode Installer (desktop only)
+// 从 GitHub Release 下载当前平台的 opencode，按 Release 提供的 SHA-256 摘要校验后
+// 安装到应用数据目录（所有 profile 共用），下载过程通过 `opencode-install-progress` 事件报告进度。
+// 解压使用系统自带的 tar / unzip，不引入额外依赖
+// ============================================
+
+use crate::app::arch::{self, BinaryArch};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager};
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/anomalyco/opencode/releases/latest";
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 已安装的 opencode 路径，启动时读取，供查找可执行文件时使用
+static INSTALLED: RwLock<Option<PathBuf>> = RwLock::new(None);
+static INSTALLING: AtomicBool = AtomicBool::new(false);
+
+#[derive(Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    assets: Vec<GithubAsset>,
+}
+
+#[derive(Deserialize)]
+struct GithubAsset {
+    name: String,
+    browser_download_url: String,
+    size: u64,
+    /// `sha256:<hex>`
+    digest: Option<String>,
+}
+
+/// 当前平台可安装的最新版本
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub asset: String,
+    pub size: u64,
+    pub installed_version: Option<String>,
+}
+
+/// 安装记录，保存在安装目录的 `installed.json`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstalledOpencode {
+    pub version: String,
+    pub path: String,
+    pub sha256: String,
+    pub installed_at: u64,
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum Phase {
+    Download,
+    Verify,
+    Extract,
+    Done,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Progress {
+    phase: Phase,
+    downloaded: u64,
+    total: u64,
+}
+
+fn install_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    app.path()
+        .app_data_dir()
+        .map(|dir| dir.join("opencode"))
+        .map_err(|e| e.to_string())
+}
+
+fn binary_name() -> String {
+    format!("opencode{}", std::env::consts::EXE_SUFFIX)
+}
+
+/// 当前平台的 Release 资源名前缀，如 `opencode-darwin-arm64`
+fn asset_prefix() -> Result<String, String> {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        "linux" => "linux",
+        "windows" => "windows",
+        other => return Err(format!("no opencode release for {}", other)),
+    };
+    let arch = match arch::host_arch() {
+        BinaryArch::X86_64 => "x64",
+        BinaryArch::Aarch64 => "arm64",
+        other => return Err(format!("no opencode release for {:?}", other)),
+    };
+    Ok(format!("opencode-{}-{}", os, arch))
+}
+
+/// 选出与前缀完全匹配的压缩包（不选 baseline / musl 等变体）
+fn pick_asset<'a>(assets: &'a [GithubAsset], prefix: &str) -> Option<&'a GithubAsset> {
+    assets.iter().find(|asset| {
+        asset
+            .name
+            .strip_prefix(prefix)
+            .is_some_and(|ext| ext == ".zip" || ext == ".tar.gz")
+    })
+}
+
+fn expected_sha256(asset: &GithubAsset) -> Result<String, String> {
+    asset
+        .digest
+        .as_deref()
+        .and_then(|digest| digest.strip_prefix("sha256:"))
+        .map(str::to_ascii_lowercase)
+        .ok_or_else(|| format!("release asset {} has no SHA-256 digest", asset.name))
+}
+
+fn read_installed(dir: &Path) -> Option<InstalledOpencode> {
+    let data = std::fs::read_to_string(dir.join("installed.json")).ok()?;
+    let installed: InstalledOpencode = serde_json::from_str(&data).ok()?;
+    Path::new(&installed.path).is_file().then_some(installed)
+}
+
+/// 启动时读取安装记录
+pub fn init(app: &tauri::AppHandle) {
+    let installed = install_dir(app).ok().and_then(|dir| read_installed(&dir));
+    *INSTALLED.write().expect("installer state poisoned") =
+        installed.map(|installed| PathBuf::from(installed.path));
+}
+
+/// 通过本模块安装的 opencode
+pub fn installed_opencode() -> Option<PathBuf> {
+    INSTALLED.read().expect("installer state poisoned").clone()
+}
+
+pub fn installed(app: &tauri::AppHandle) -> Option<InstalledOpencode> {
+    read_installed(&install_dir(app).ok()?)
+}
+
+async fn latest_release() -> Result<GithubRelease, String> {
+    let client = crate::app::dns::client_builder()
+        .user_agent(concat!("OpenCodeUI/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(LATEST_RELEASE_URL)
+        .header("Accept", "application/vnd.github+json")
+        .timeout(Duration::from_secs(30))
+        .send()
+        .await
+        .map_err(|e| format!("failed to check for opencode releases: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("GitHub API returned {}", response.status()));
+    }
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    serde_json::from_slice(&body).map_err(|e| format!("unexpected release data: {}", e))
+}
+
+/// 查询当前平台可安装的最新版本
+pub async fn check(app: &tauri::AppHandle) -> Result<ReleaseInfo, String> {
+    let release = latest_release().await?;
+    let prefix = asset_prefix()?;
+    let asset = pick_asset(&release.assets, &prefix)
+        .ok_or_else(|| format!("release {} has no {} build", release.tag_name, prefix))?;
+    Ok(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        asset: asset.name.clone(),
+        size: asset.size,
+        installed_version: installed(app).map(|installed| installed.version),
+    })
+}
+
+/// 下载到 `dest`，同时计算 SHA-256
+async fn download(
+    app: &tauri::AppHandle,
+    asset: &GithubAsset,
+    dest: &Path,
+) -> Result<String, String> {
+    let client = crate::app::dns::client_builder()
+        .user_agent(concat!("OpenCodeUI/", env!("CARGO_PKG_VERSION")))
+        .connect_timeout(Duration::from_secs(10))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let response = client
+        .get(&asset.browser_download_url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .map_err(|e| format!("download failed: {}", e))?;
+    let total = response.content_length().unwrap_or(asset.size);
+
+    let mut file = std::fs::File::create(dest).map_err(|e| e.to_string())?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0;
+    let mut last_report = Instant::now();
+    let mut stream = response.bytes_stream();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|e| format!("download failed: {}", e))?;
+        file.write_all(&chunk).map_err(|e| e.to_string())?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        if last_report.elapsed() >= PROGRESS_INTERVAL {
+            last_report = Instant::now();
+            emit_progress(app, Phase::Download, downloaded, total);
+        }
+    }
+    file.flush().map_err(|e| e.to_string())?;
+    emit_progress(app, Phase::Download, downloaded, total);
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn emit_progress(app: &tauri::AppHandle, phase: Phase, downloaded: u64, total: u64) {
+    let _ = app.emit(
+        "opencode-install-progress",
+        Progress {
+            phase,
+            downloaded,
+            total,
+        },
+    );
+}
+
+/// 用系统工具解压：tar.gz 用 tar；zip 在 Windows / macOS 用 bsdtar，Linux 用 unzip
+fn extract(archive: &Path, dest: &Path) -> Result<(), String> {
+    let name = archive.to_string_lossy();
+    let mut cmd = if name.ends_with(".tar.gz") {
+        let mut cmd = Command::new("tar");
+        cmd.arg("-xzf").arg(archive).arg("-C").arg(dest);
+        cmd
+    } else if cfg!(target_os = "linux") {
+        let mut cmd = Command::new("unzip");
+        cmd.arg("-o").arg("-q").arg(archive).arg("-d").arg(dest);
+        cmd
+    } else {
+        let mut cmd = Command::new("tar");
+        cmd.arg("-xf").arg(archive).arg("-C").arg(dest);
+        cmd
+    };
+    cmd.stdin(Stdio::null()).stdout(Stdio::null());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let output = cmd
+        .output()
+        .map_err(|e| format!("failed to run {:?}: {}", cmd.get_program(), e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "failed to extract {}: {}",
+            archive.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(())
+}
+
+/// 在解压目录中查找可执行文件（可能位于子目录）
+fn find_binary(dir: &Path, name: &str) -> Option<PathBuf> {
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in std::fs::read_dir(&dir).ok()?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if entry.file_name() == name {
+                return Some(path);
+            }
+        }
+    }
+    None
+}
+
+/// 把新的可执行文件放到 `dest`。Windows 无法覆盖正在运行的 exe，但可以重命名，
+/// 因此先把旧文件移开
+fn replace_binary(new: &Path, dest: &Path) -> Result<(), String> {
+    if dest.exists() {
+        let old = dest.with_extension("old");
+        let _ = std::fs::remove_file(&old);
+        std::fs::rename(dest, &old).map_err(|e| format!("failed to replace opencode: {}", e))?;
+    }
+    std::fs::rename(new, dest).map_err(|e| format!("failed to install opencode: {}", e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dest, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+async fn install_latest(app: &tauri::AppHandle) -> Result<InstalledOpencode, String> {
+    let release = latest_release().await?;
+    let prefix = asset_prefix()?;
+    let asset = pick_asset(&release.assets, &prefix)
+        .ok_or_else(|| format!("release {} has no {} build", release.tag_name, prefix))?;
+    let expected = expected_sha256(asset)?;
+
+    let dir = install_dir(app)?;
+    let staging = dir.join("staging");
+    let _ = std::fs::remove_dir_all(&staging);
+    std::fs::create_dir_all(&staging).map_err(|e| e.to_string())?;
+    let archive = staging.join(&asset.name);
+
+    log::info!("Downloading opencode {} ({})", release.tag_name, asset.name);
+    let actual = download(app, asset, &archive).await?;
+    emit_progress(app, Phase::Verify, asset.size, asset.size);
+    if actual != expected {
+        let _ = std::fs::remove_dir_all(&staging);
+        return Err(format!(
+            "checksum mismatch for {} (expected {}, got {})",
+            asset.name, expected, actual
+        ));
+    }
+
+    emit_progress(app, Phase::Extract, asset.size, asset.size);
+    let extracted = staging.join("extracted");
+    std::fs::create_dir_all(&extracted).map_err(|e| e.to_string())?;
+    let (archive_path, extract_dir) = (archive.clone(), extracted.clone());
+    tauri::async_runtime::spawn_blocking(move || extract(&archive_path, &extract_dir))
+        .await
+        .map_err(|e| e.to_string())??;
+    let binary = find_binary(&extracted, &binary_name())
+        .ok_or_else(|| format!("{} does not contain {}", asset.name, binary_name()))?;
+
+    let bin_dir = dir.join("bin");
+    std::fs::create_dir_all(&bin_dir).map_err(|e| e.to_string())?;
+    let dest = bin_dir.join(binary_name());
+    replace_binary(&binary, &dest)?;
+    let _ = std::fs::remove_dir_all(&staging);
+
+    let installed = InstalledOpencode {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        path: dest.to_string_lossy().into_owned(),
+        sha256: expected,
+        installed_at: crate::app::now_millis(),
+    };
+    let data = serde_json::to_string_pretty(&installed).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join("installed.json"), data).map_err(|e| e.to_string())?;
+    *INSTALLED.write().expect("installer state poisoned") = Some(dest);
+
+    log::info!(
+        "Installed opencode {} to {}",
+        installed.version,
+        installed.path
+    );
+    emit_progress(app, Phase::Done, asset.size, asset.size);
+    Ok(installed)
+}
+
+/// 安装（或更新到）最新版本；同一时间只允许一个安装任务
+pub async fn install(app: &tauri::AppHandle) -> Result<InstalledOpencode, String> {
+    if INSTALLING.swap(true, Ordering::SeqCst) {
+        return Err("opencode is already being installed".to_string());
+    }
+    let result = install_latest(app).await;
+    INSTALLING.store(false, Ordering::SeqCst);
+    if let Err(e) = &result {
+        log::warn!("Installing opencode failed: {}", e);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_exact_platform_archive() {
+        let asset = |name: &str| GithubAsset {
+            name: name.to_string(),
+            browser_download_url: String::new(),
+            size: 0,
+            digest: Some("sha256:ABCD".to_string()),
+        };
+        let assets = [
+            asset("opencode-linux-x64-baseline.tar.gz"),
+            asset("opencode-linux-x64-musl.tar.gz"),
+            asset("opencode-linux-x64.tar.gz"),
+            asset("opencode-darwin-arm64.zip"),
+        ];
+        let picked = pick_asset(&assets, "opencode-linux-x64").unwrap();
+        assert_eq!(picked.name, "opencode-linux-x64.tar.gz");
+        assert_eq!(expected_sha256(picked).unwrap(), "abcd");
+        assert!(pick_asset(&assets, "opencode-windows-x64").is_none());
+    }
+}
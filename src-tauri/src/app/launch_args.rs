@@ -0,0 +1,82 @@
+// ============================================
+// CLI Launch Options (desktop only)
+// 解析 `opencode-ui ~/proj --prompt "..." --model provider/model --agent build`
+// 首次启动与 single-instance 转发共用
+// ============================================
+
+use serde::Serialize;
+
+/// 带值的参数；其值不参与目录识别
+pub const VALUE_FLAGS: &[&str] = &["--profile", "--prompt", "--model", "--agent"];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchOptions {
+    /// 窗口打开后自动发送的 prompt
+    pub prompt: Option<String>,
+    /// 模型，如 `anthropic/claude-sonnet-4` 或前端可识别的简称
+    pub model: Option<String>,
+    pub agent: Option<String>,
+}
+
+impl LaunchOptions {
+    /// 支持 `--flag value` 与 `--flag=value` 两种写法，后出现的覆盖先出现的
+    pub fn from_args(args: &[String]) -> Self {
+        let mut options = Self::default();
+        let mut iter = args.iter().skip(1);
+
+        while let Some(arg) = iter.next() {
+            let (flag, inline_value) = match arg.split_once('=') {
+                Some((flag, value)) if flag.starts_with("--") => (flag, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let slot = match flag {
+                "--prompt" => &mut options.prompt,
+                "--model" => &mut options.model,
+                "--agent" => &mut options.agent,
+                _ => continue,
+            };
+            let value = inline_value.or_else(|| iter.next().cloned());
+            *slot = value.filter(|value| !value.trim().is_empty());
+        }
+
+        options
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prompt.is_none() && self.model.is_none() && self.agent.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_prompt_model_and_agent() {
+        let options = LaunchOptions::from_args(&args(&[
+            "opencode-ui",
+            "/tmp/proj",
+            "--prompt",
+            "summarize recent changes",
+            "--model=sonnet",
+            "--agent",
+            "plan",
+        ]));
+        assert_eq!(
+            options,
+            LaunchOptions {
+                prompt: Some("summarize recent changes".to_string()),
+                model: Some("sonnet".to_string()),
+                agent: Some("plan".to_string()),
+            }
+        );
+
+        assert!(LaunchOptions::from_args(&args(&["opencode-ui", "/tmp/proj"])).is_empty());
+        assert!(LaunchOptions::from_args(&args(&["opencode-ui", "--prompt"])).is_empty());
+    }
+}
@@ -0,0 +1,105 @@
+// ============================================
+// macOS Application Menu
+// 用当前语言构建菜单栏；切换语言时重新构建
+// ============================================
+
+use crate::app::{
+    i18n::Language,
+    shortcuts::{self, ShortcutsState},
+};
+use tauri::{
+    menu::{Menu, MenuItem, PredefinedMenuItem, Submenu},
+    Manager,
+};
+
+pub const NEW_WINDOW_ID: &str = shortcuts::NEW_WINDOW;
+
+fn build_app_menu(app: &tauri::AppHandle, lang: Language) -> tauri::Result<Menu<tauri::Wry>> {
+    // 加速键取自快捷键登记处，用户改键后重新构建菜单即可生效
+    let new_window_accelerator = app
+        .try_state::<ShortcutsState>()
+        .and_then(|state| state.accelerator(shortcuts::NEW_WINDOW));
+
+    let app_menu = Submenu::with_items(
+        app,
+        "OpenCode",
+        true,
+        &[
+            &PredefinedMenuItem::about(app, Some(lang.t("menu.about")), None)?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::hide(app, Some(lang.t("menu.hide")))?,
+            &PredefinedMenuItem::hide_others(app, Some(lang.t("menu.hideOthers")))?,
+            &PredefinedMenuItem::show_all(app, Some(lang.t("menu.showAll")))?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::quit(app, Some(lang.t("menu.quit")))?,
+        ],
+    )?;
+
+    let file_menu = Submenu::with_items(
+        app,
+        lang.t("menu.file"),
+        true,
+        &[
+            &MenuItem::with_id(
+                app,
+                NEW_WINDOW_ID,
+                lang.t("menu.newWindow"),
+                true,
+                new_window_accelerator.as_deref(),
+            )?,
+            &PredefinedMenuItem::close_window(app, Some(lang.t("menu.closeWindow")))?,
+        ],
+    )?;
+
+    let edit_menu = Submenu::with_items(
+        app,
+        lang.t("menu.edit"),
+        true,
+        &[
+            &PredefinedMenuItem::undo(app, Some(lang.t("menu.undo")))?,
+            &PredefinedMenuItem::redo(app, Some(lang.t("menu.redo")))?,
+            &PredefinedMenuItem::separator(app)?,
+            &PredefinedMenuItem::cut(app, Some(lang.t("menu.cut")))?,
+            &PredefinedMenuItem::copy(app, Some(lang.t("menu.copy")))?,
+            &PredefinedMenuItem::paste(app, Some(lang.t("menu.paste")))?,
+            &PredefinedMenuItem::select_all(app, Some(lang.t("menu.selectAll")))?,
+        ],
+    )?;
+
+    let view_menu = Submenu::with_items(
+        app,
+        lang.t("menu.view"),
+        true,
+        &[&PredefinedMenuItem::fullscreen(
+            app,
+            Some(lang.t("menu.fullscreen")),
+        )?],
+    )?;
+
+    let window_menu = Submenu::with_items(
+        app,
+        lang.t("menu.window"),
+        true,
+        &[
+            &PredefinedMenuItem::minimize(app, Some(lang.t("menu.minimize")))?,
+            &PredefinedMenuItem::maximize(app, Some(lang.t("menu.zoom")))?,
+        ],
+    )?;
+
+    Menu::with_items(
+        app,
+        &[&app_menu, &file_menu, &edit_menu, &view_menu, &window_menu],
+    )
+}
+
+/// 以指定语言（重新）设置应用菜单
+pub fn apply_app_menu(app: &tauri::AppHandle, lang: Language) {
+    match build_app_menu(app, lang) {
+        Ok(menu) => {
+            if let Err(e) = app.set_menu(menu) {
+                log::warn!("Failed to set app menu: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to build app menu: {}", e),
+    }
+}
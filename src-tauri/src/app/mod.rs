@@ -2,15 +2,140 @@
 // Tauri Application Entry Point
 // Unified Bridge + Plugin Registration + Service Management
 // ============================================
+#[cfg(not(target_os = "android"))]
+mod api;
+#[cfg(not(target_os = "android"))]
+mod appearance;
+#[cfg(not(target_os = "android"))]
+mod arch;
 mod bridge;
+#[cfg(not(target_os = "android"))]
+mod checkpoints;
+#[cfg(not(target_os = "android"))]
+mod clipboard;
 mod commands;
 #[cfg(not(target_os = "android"))]
+mod conflicts;
+#[cfg(not(target_os = "android"))]
+mod container;
+#[cfg(not(target_os = "android"))]
+mod credentials;
+#[cfg(not(target_os = "android"))]
+mod diagnostics;
+#[cfg(not(target_os = "android"))]
+mod dir_browse;
+#[cfg(not(target_os = "android"))]
 mod dir_state;
+#[cfg(not(target_os = "android"))]
+mod discovery;
+mod dns;
+#[cfg(not(target_os = "android"))]
+mod dotenv;
+#[cfg(not(target_os = "android"))]
+mod format;
+#[cfg(not(target_os = "android"))]
+mod git;
+#[cfg(not(target_os = "android"))]
+mod health_monitor;
+mod http_tuning;
+#[cfg(not(target_os = "android"))]
+mod i18n;
+#[cfg(not(target_os = "android"))]
+mod idle;
+#[cfg(not(target_os = "android"))]
+mod installer;
+#[cfg(not(target_os = "android"))]
+mod launch_args;
+#[cfg(target_os = "macos")]
+mod menu;
+#[cfg(not(target_os = "android"))]
+mod model_catalog;
+mod network;
+#[cfg(not(target_os = "android"))]
+mod palette;
+#[cfg(not(target_os = "android"))]
+mod permission_relay;
+#[cfg(not(target_os = "android"))]
+mod presence;
+#[cfg(not(target_os = "android"))]
+mod presentation;
+#[cfg(not(target_os = "android"))]
+mod profile;
+#[cfg(not(target_os = "android"))]
+mod projects;
+#[cfg(not(target_os = "android"))]
+mod recovery;
+#[cfg(not(target_os = "android"))]
+mod run_timers;
+#[cfg(not(target_os = "android"))]
+mod safe_mode;
+#[cfg(not(target_os = "android"))]
+mod scaffold;
+#[cfg(not(target_os = "android"))]
+mod scheduler;
+#[cfg(not(target_os = "android"))]
+mod server_probe;
+#[cfg(not(target_os = "android"))]
+mod server_profiles;
+#[cfg(not(target_os = "android"))]
+mod server_version;
 mod service;
+#[cfg(not(target_os = "android"))]
+mod service_autostart;
+#[cfg(not(target_os = "android"))]
+mod service_exit;
+#[cfg(not(target_os = "android"))]
+mod service_lock;
+#[cfg(not(target_os = "android"))]
+mod service_pid;
+#[cfg(not(target_os = "android"))]
+mod service_stats;
+#[cfg(not(target_os = "android"))]
+mod service_watchdog;
+#[cfg(not(target_os = "android"))]
+mod session_branches;
+#[cfg(not(target_os = "android"))]
+mod session_marks;
+#[cfg(not(target_os = "android"))]
+mod session_tree;
+#[cfg(not(target_os = "android"))]
+mod settings;
+#[cfg(not(target_os = "android"))]
+mod share;
+#[cfg(not(target_os = "android"))]
+mod shortcuts;
+#[cfg(not(target_os = "android"))]
+mod shutdown;
+#[cfg(not(target_os = "android"))]
+mod sidecar;
+#[cfg(not(target_os = "android"))]
+mod split_view;
+#[cfg(not(target_os = "android"))]
+mod storage;
+#[cfg(not(target_os = "android"))]
+mod terminate;
+#[cfg(not(target_os = "android"))]
+mod transcript_log;
+#[cfg(not(target_os = "android"))]
+mod transfer;
+#[cfg(not(target_os = "android"))]
+mod tray;
+#[cfg(not(target_os = "android"))]
+mod watch;
+#[cfg(not(target_os = "android"))]
+mod window_context;
+#[cfg(not(target_os = "android"))]
+mod window_state;
+#[cfg(not(target_os = "android"))]
+mod workspace_autolaunch;
+#[cfg(not(target_os = "android"))]
+mod workspace_diff;
+#[cfg(not(target_os = "android"))]
+mod wsl;
 
 use bridge::BridgeState;
-use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
 use tauri::Manager;
 
 #[cfg(any(windows, target_os = "macos"))]
@@ -24,84 +149,24 @@ use std::sync::Arc;
 #[cfg(not(target_os = "android"))]
 use tauri::Emitter;
 
-#[cfg(not(target_os = "android"))]
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct SavedWindowState {
-    width: u32,
-    height: u32,
-    x: i32,
-    y: i32,
-    maximized: bool,
-}
-
-#[cfg(not(target_os = "android"))]
-fn window_state_path(app: &tauri::AppHandle) -> Option<std::path::PathBuf> {
-    let dir = app.path().app_config_dir().ok()?;
-    Some(dir.join("window-state.json"))
-}
-
-#[cfg(not(target_os = "android"))]
-fn load_window_state(app: &tauri::AppHandle) -> Option<SavedWindowState> {
-    let path = window_state_path(app)?;
-    let data = std::fs::read_to_string(path).ok()?;
-    serde_json::from_str(&data).ok()
-}
-
-#[cfg(not(target_os = "android"))]
-fn save_window_state(window: &tauri::Window) {
-    if window.label() != "main" {
-        return;
-    }
-
-    let Ok(size) = window.outer_size() else {
-        return;
-    };
-    let Ok(position) = window.outer_position() else {
-        return;
-    };
-    let maximized = window.is_maximized().unwrap_or(false);
-
-    let state = SavedWindowState {
-        width: size.width,
-        height: size.height,
-        x: position.x,
-        y: position.y,
-        maximized,
-    };
-
-    let app = window.app_handle();
-    let Some(path) = window_state_path(app) else {
-        return;
-    };
-    if let Some(parent) = path.parent() {
-        let _ = std::fs::create_dir_all(parent);
-    }
-    if let Ok(data) = serde_json::to_string(&state) {
-        let _ = std::fs::write(path, data);
-    }
-}
-
-#[cfg(not(target_os = "android"))]
-fn restore_window_state(window: &tauri::WebviewWindow) {
-    let app = window.app_handle();
-    let Some(state) = load_window_state(app) else {
-        return;
-    };
-
-    if state.width >= 400 && state.height >= 300 {
-        let _ = window.set_size(tauri::PhysicalSize::new(state.width, state.height));
-    }
-    let _ = window.set_position(tauri::PhysicalPosition::new(state.x, state.y));
-
-    if state.maximized {
-        let _ = window.maximize();
-    }
+/// 当前 Unix 时间戳（毫秒）
+pub(crate) fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
 }
 
 /// 从命令行参数中提取目录路径
 #[cfg(not(target_os = "android"))]
 fn extract_directory_from_args(args: &[String]) -> Option<String> {
-    for arg in args.iter().skip(1) {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        // 带值的参数：跳过其值，避免把 profile 名、prompt 等误判为目录
+        if launch_args::VALUE_FLAGS.contains(&arg.as_str()) {
+            iter.next();
+            continue;
+        }
         if arg.starts_with('-') {
             continue;
         }
@@ -127,7 +192,7 @@ fn create_main_window(app: &tauri::AppHandle) -> Result<tauri::WebviewWindow, ta
         .cloned()
         .expect("main window config missing");
 
-    configure_desktop_window_builder(tauri::WebviewWindowBuilder::from_config(app, &config)?)
+    configure_desktop_window_builder(tauri::WebviewWindowBuilder::from_config(app, &config)?, app)
         .visible(false)
         .build()
 }
@@ -155,11 +220,10 @@ fn create_hidden_content_window(
     app: &tauri::AppHandle,
     label: &str,
 ) -> Result<tauri::WebviewWindow, tauri::Error> {
-    let builder = configure_desktop_window_builder(tauri::WebviewWindowBuilder::new(
+    let builder = configure_desktop_window_builder(
+        tauri::WebviewWindowBuilder::new(app, label, tauri::WebviewUrl::App("index.html".into())),
         app,
-        label,
-        tauri::WebviewUrl::App("index.html".into()),
-    ))
+    )
     .title("OpenCode")
     .inner_size(800.0, 600.0);
 
@@ -170,13 +234,16 @@ fn create_hidden_content_window(
 /// 注意：这里通过 decorum 的 `set_traffic_lights_inset` 应用，其内部定位算法与
 /// Tauri 原生 `trafficLightPosition` 不同，y 值需按与自定义标题栏的视觉对齐微调。
 #[cfg(target_os = "macos")]
-const TRAFFIC_LIGHT_INSET: (f32, f32) = (12.0, 14.0);
+pub(crate) const TRAFFIC_LIGHT_INSET: (f32, f32) = (12.0, 14.0);
 
 /// 重新定位 macOS 红绿灯。
 /// macOS 在退出全屏后会把红绿灯重置回系统默认位置，
 /// 因此需要在退出全屏时重新应用偏移，保持与自定义标题栏垂直对齐。
 #[cfg(target_os = "macos")]
 fn reposition_traffic_lights(window: &tauri::WebviewWindow) {
+    if safe_mode::active() {
+        return;
+    }
     let (x, y) = TRAFFIC_LIGHT_INSET;
     let _ = window.set_traffic_lights_inset(x, y);
 }
@@ -191,12 +258,25 @@ fn fullscreen_state() -> &'static std::sync::Mutex<std::collections::HashMap<Str
 
 #[cfg(not(target_os = "android"))]
 fn finish_desktop_window_setup(window: &tauri::WebviewWindow) {
-    #[cfg(windows)]
-    let _ = window.create_overlay_titlebar();
+    let appearance = appearance::load_appearance(window.app_handle());
 
-    // macOS：初始定位红绿灯，使其与自定义标题栏对齐
-    #[cfg(target_os = "macos")]
-    reposition_traffic_lights(window);
+    // 使用原生标题栏时恢复系统装饰，由系统绘制标题与窗口按钮；安全模式下没有 decorum
+    if appearance.native_titlebar || safe_mode::active() {
+        #[cfg(target_os = "macos")]
+        let _ = window.set_title_bar_style(tauri::TitleBarStyle::Visible);
+        #[cfg(not(target_os = "macos"))]
+        let _ = window.set_decorations(true);
+    } else {
+        #[cfg(windows)]
+        let _ = window.create_overlay_titlebar();
+
+        // macOS：初始定位红绿灯，使其与自定义标题栏对齐
+        #[cfg(target_os = "macos")]
+        reposition_traffic_lights(window);
+    }
+
+    appearance::apply_appearance(window, &appearance);
+    shutdown::watch_window(window);
 }
 
 #[cfg(not(target_os = "android"))]
@@ -211,71 +291,168 @@ pub(crate) fn mark_window_ready<R: tauri::Runtime>(
 
 /// 创建新窗口，可选地关联一个目录（多窗口支持）
 #[cfg(not(target_os = "android"))]
-pub(crate) fn create_new_window(app: &tauri::AppHandle, directory: Option<String>) {
+pub(crate) fn create_new_window(
+    app: &tauri::AppHandle,
+    directory: Option<String>,
+    launch: launch_args::LaunchOptions,
+) {
+    let _ = open_window(app, directory, None, launch);
+}
+
+/// 创建新窗口并返回其 label；目录与会话作为该窗口的待处理状态，由前端启动后一次性读取
+#[cfg(not(target_os = "android"))]
+pub(crate) fn open_window(
+    app: &tauri::AppHandle,
+    directory: Option<String>,
+    session_id: Option<String>,
+    launch: launch_args::LaunchOptions,
+) -> Result<String, String> {
     static WIN_COUNTER: AtomicU64 = AtomicU64::new(1);
     let label = format!("win-{}", WIN_COUNTER.fetch_add(1, Ordering::SeqCst));
 
-    if let Some(ref dir) = directory {
-        if let Some(state) = app.try_state::<OpenDirectoryState>() {
+    if let Some(state) = app.try_state::<OpenDirectoryState>() {
+        if let Some(ref dir) = directory {
             state
                 .pending()
                 .pin()
                 .insert(label.clone(), Arc::from(dir.clone()));
         }
+        if let Some(ref session_id) = session_id {
+            state
+                .sessions()
+                .pin()
+                .insert(label.clone(), Arc::from(session_id.as_str()));
+        }
+        state.set_launch(&label, launch);
     }
 
     match create_hidden_content_window(app, &label) {
         Ok(window) => {
             finish_desktop_window_setup(&window);
+            window_state::restore(&window, directory.as_deref());
 
             log::info!(
-                "Created new window '{}' for directory: {:?}",
+                "Created new window '{}' for directory: {:?}, session: {:?}",
                 label,
-                directory
-            )
+                directory,
+                session_id
+            );
+            Ok(label)
+        }
+        Err(e) => {
+            log::error!("Failed to create new window: {}", e);
+            if let Some(state) = app.try_state::<OpenDirectoryState>() {
+                state.forget(&label);
+            }
+            Err(e.to_string())
         }
-        Err(e) => log::error!("Failed to create new window: {}", e),
     }
 }
 
 #[cfg(not(target_os = "android"))]
 fn configure_desktop_window_builder<'a, R: tauri::Runtime, M: tauri::Manager<R>>(
     window_builder: tauri::WebviewWindowBuilder<'a, R, M>,
+    app: &tauri::AppHandle,
 ) -> tauri::WebviewWindowBuilder<'a, R, M> {
-    let window_builder = window_builder;
+    // 安全模式不注册 decorum，使用系统标题栏
+    let native_titlebar = appearance::load_appearance(app).native_titlebar || safe_mode::active();
+
+    // 非默认 profile 使用独立的 webview 数据目录，隔离前端 localStorage
+    let window_builder = match profile::webview_data_dir(app) {
+        Some(dir) => window_builder.data_directory(dir),
+        None => window_builder,
+    };
 
     #[cfg(target_os = "macos")]
-    let window_builder = window_builder
-        .title_bar_style(tauri::TitleBarStyle::Overlay)
-        .hidden_title(true)
-        .traffic_light_position(tauri::LogicalPosition::new(12.0, 14.0));
+    let window_builder = if native_titlebar {
+        window_builder.title_bar_style(tauri::TitleBarStyle::Visible)
+    } else {
+        window_builder
+            .title_bar_style(tauri::TitleBarStyle::Overlay)
+            .hidden_title(true)
+            .traffic_light_position(tauri::LogicalPosition::new(12.0, 14.0))
+    };
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = native_titlebar;
 
     window_builder
 }
 
 pub fn run() {
+    #[cfg(not(target_os = "android"))]
+    safe_mode::set_active(safe_mode::requested(&std::env::args().collect::<Vec<_>>()));
+
     let builder = tauri::Builder::default().manage(BridgeState::default());
 
+    // 安全模式只注册连接服务器、打开项目所需的插件
     #[cfg(not(target_os = "android"))]
-    let builder = builder.plugin(tauri_plugin_decorum::init());
+    let builder = if safe_mode::active() {
+        builder
+    } else {
+        builder
+            .plugin(tauri_plugin_decorum::init())
+            .plugin(tauri_plugin_clipboard_manager::init())
+            .plugin(tauri_plugin_notification::init())
+            .plugin(tauri_plugin_fs::init())
+    };
+    #[cfg(target_os = "android")]
+    let builder = builder
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_fs::init());
 
     // Desktop: 注册 OpenDirectoryState + single-instance 插件（需在 setup 之前）
     #[cfg(not(target_os = "android"))]
     let builder =
         builder
             .manage(OpenDirectoryState::default())
+            .manage(clipboard::ClipboardHistory::default())
+            .manage(presentation::PresentationState::default())
+            .manage(split_view::SplitViewState::default())
+            .manage(share::ShareState::default())
+            .manage(recovery::RecoveryState::default())
+            .manage(conflicts::ConflictState::default())
+            .manage(palette::PaletteIndex::default())
+            .manage(window_context::WindowContextState::default())
+            .manage(tray::TrayState::default())
+            .manage(presence::PresenceState::default())
+            .manage(run_timers::RunTimersState::default())
+            .manage(session_tree::SessionTreeState::default())
+            .manage(service_stats::ServiceStatsState::default())
+            .manage(health_monitor::HealthMonitorState::default())
+            .manage(workspace_autolaunch::WorkspaceState::default())
+            .on_menu_event(|app, event| {
+                if event.id() == "new-window" {
+                    create_new_window(app, None, launch_args::LaunchOptions::default());
+                }
+            })
             .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+                // 单实例下无法同时运行两个 profile，新窗口仍归属当前 profile
+                if let Some(requested) = profile::profile_from_args(&args) {
+                    let active = app.state::<profile::ActiveProfile>();
+                    if requested != active.name() {
+                        log::warn!(
+                            "Profile '{}' requested while '{}' is running; switch profiles from the app instead",
+                            requested,
+                            active.name()
+                        );
+                    }
+                }
+
                 // 始终新建窗口（类似 VSCode：双击图标 = 新窗口）
                 let dir = extract_directory_from_args(&args);
-                log::info!("Single-instance: opening new window, directory: {:?}", dir);
-                create_new_window(app, dir);
+                let launch = launch_args::LaunchOptions::from_args(&args);
+                log::info!(
+                    "Single-instance: opening new window, directory: {:?}, launch: {:?}",
+                    dir,
+                    launch
+                );
+                create_new_window(app, dir, launch);
             }));
 
     let builder = builder
         .plugin(tauri_plugin_http::init())
-        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_dialog::init())
-        .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_opener::init())
         .setup(|app| {
             // 始终启用 log 插件，方便排查问题
@@ -285,11 +462,165 @@ pub fn run() {
                     .build(),
             )?;
 
+            #[cfg(not(target_os = "android"))]
+            {
+                let args: Vec<String> = std::env::args().collect();
+                let name = profile::resolve_profile(app.handle(), &args);
+                log::info!("Using profile: {}", name);
+                credentials::init(&name);
+                app.manage(profile::ActiveProfile::new(name));
+                app.manage(safe_mode::begin_session(app.handle()));
+                app.manage(settings::SettingsStore::load(app.handle()));
+                // 代理与证书设置是连接服务器的前提，安全模式下同样生效
+                let network_config: network::NetworkConfig = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(network::NETWORK_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Err(e) = network::configure(&network_config) {
+                    log::warn!("Invalid network config: {}", e);
+                }
+                network::load_identities(
+                    &app.state::<settings::SettingsStore>()
+                        .get_as(network::CLIENT_IDENTITIES_SETTINGS_KEY)
+                        .unwrap_or_default(),
+                );
+                app.manage(server_profiles::ServerProfilesState::load(app.handle()));
+                let language = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(i18n::LANGUAGE_SETTINGS_KEY);
+                app.manage(i18n::LanguageState::new(language));
+                let shortcuts =
+                    shortcuts::ShortcutsState::load(&app.state::<settings::SettingsStore>());
+                app.manage(shortcuts);
+                commands::language::refresh_native_ui(
+                    app.handle(),
+                    app.state::<i18n::LanguageState>().current(),
+                );
+                app.manage(scheduler::SchedulerState::load(app.handle()));
+                app.manage(watch::WatchState::load(app.handle()));
+                app.manage(projects::ProjectsState::load(app.handle()));
+                app.state::<projects::ProjectsState>()
+                    .forbid_read_only(app.handle());
+                app.manage(checkpoints::CheckpointsState::load(app.handle()));
+                // 安全模式忽略模型目录缓存
+                app.manage(if safe_mode::active() {
+                    model_catalog::ModelCatalogState::default()
+                } else {
+                    model_catalog::ModelCatalogState::load(app.handle())
+                });
+                app.manage(permission_relay::PermissionRelayState::load(app.handle()));
+                app.manage(transcript_log::TranscriptLogState::load(app.handle()));
+                app.manage(session_branches::SessionBranchesState::load(app.handle()));
+                app.manage(session_marks::SessionMarksState::load(app.handle()));
+                // 安全模式不恢复也不保存窗口位置
+                app.manage(if safe_mode::active() {
+                    window_state::WindowGeometryState::default()
+                } else {
+                    window_state::WindowGeometryState::load(app.handle())
+                });
+                terminate::configure(
+                    &app.state::<settings::SettingsStore>()
+                        .get_as(terminate::TERMINATION_SETTINGS_KEY)
+                        .unwrap_or_default(),
+                );
+                installer::init(app.handle());
+                service_exit::init(app.handle());
+                // 先于前端的启动请求接管上次崩溃时遗留的服务，避免重复启动
+                service_pid::adopt_orphans(app.handle());
+                // 安全模式下同样需要回收退出的子进程；未载入设置时不会自动重启
+                service_watchdog::spawn_watchdog(app.handle().clone());
+
+                // DoH 同样是连接服务器的前提
+                let doh_config: dns::DohConfig = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(dns::DOH_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Err(e) = dns::configure(&doh_config) {
+                    log::warn!("Invalid DNS-over-HTTPS config: {}", e);
+                }
+                // 只读项目的自动拒绝与权限超时是安全保证，安全模式下不能关闭
+                permission_relay::install(app.handle());
+
+                recovery::spawn_recovery_monitor(app.handle().clone());
+                #[cfg(unix)]
+                shutdown::spawn_signal_listener(app.handle().clone());
+            }
+
+            // 安全模式下不启动后台任务、事件观察者与托盘
+            #[cfg(not(target_os = "android"))]
+            if !safe_mode::active() {
+                scheduler::spawn_scheduler(app.handle().clone());
+                watch::start_enabled_watches(app.handle());
+
+                let idle_config = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(idle::IDLE_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Ok(mut config) = app.state::<service::ServiceState>().idle_config.lock() {
+                    *config = idle_config;
+                }
+                idle::spawn_idle_monitor(app.handle().clone());
+
+                let watchdog_config = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(service_watchdog::WATCHDOG_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Ok(mut config) = app.state::<service::ServiceState>().watchdog_config.lock()
+                {
+                    *config = watchdog_config;
+                }
+
+                let stats_config = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(service_stats::SERVICE_STATS_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Ok(mut config) = app
+                    .state::<service_stats::ServiceStatsState>()
+                    .config
+                    .lock()
+                {
+                    *config = stats_config;
+                }
+                service_stats::spawn_stats_monitor(app.handle().clone());
+
+                let health_config = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(health_monitor::HEALTH_MONITOR_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Ok(mut config) = app
+                    .state::<health_monitor::HealthMonitorState>()
+                    .config
+                    .lock()
+                {
+                    *config = health_config;
+                }
+                health_monitor::spawn_health_monitor(app.handle().clone());
+                workspace_autolaunch::spawn_autolaunch(app.handle().clone());
+
+                let presence_config = app
+                    .state::<settings::SettingsStore>()
+                    .get_as(presence::PRESENCE_SETTINGS_KEY)
+                    .unwrap_or_default();
+                if let Ok(mut config) = app.state::<presence::PresenceState>().config.lock() {
+                    *config = presence_config;
+                }
+                presence::spawn_presence_monitor(app.handle().clone());
+
+                storage::spawn_janitor(app.handle().clone());
+                conflicts::install(app.handle());
+                palette::install(app.handle());
+                run_timers::install(app.handle());
+                session_tree::install(app.handle());
+                transcript_log::install(app.handle());
+                session_marks::install(app.handle());
+                tray::install(app.handle(), app.state::<i18n::LanguageState>().current());
+            }
+
             #[cfg(not(target_os = "android"))]
             {
                 let main_window = create_main_window(&app.handle())?;
                 finish_desktop_window_setup(&main_window);
-                restore_window_state(&main_window);
+                window_state::restore(&main_window, None);
 
                 #[cfg(debug_assertions)]
                 main_window.open_devtools();
@@ -313,6 +644,9 @@ pub fn run() {
                             .insert("main".to_string(), Arc::from(dir));
                     }
                 }
+                if let Some(state) = app.try_state::<OpenDirectoryState>() {
+                    state.set_launch("main", launch_args::LaunchOptions::from_args(&args));
+                }
             }
 
             Ok(())
@@ -325,19 +659,24 @@ pub fn run() {
         .on_window_event(|window, event| {
             match event {
                 tauri::WindowEvent::CloseRequested { api, .. } => {
-                    save_window_state(window);
+                    window_state::save(window);
 
                     // 只在最后一个窗口关闭时询问是否停止服务
                     let is_last = window.app_handle().webview_windows().len() <= 1;
                     if is_last {
                         let state = window.state::<service::ServiceState>();
-                        if state.we_started.load(Ordering::SeqCst) {
+                        if state.any_started() {
                             api.prevent_close();
                             let _ = window.emit("close-requested", ());
                         }
                     }
                 }
                 tauri::WindowEvent::Resized(_) => {
+                    let split = window.state::<split_view::SplitViewState>();
+                    if let Some(layout) = split.get(window.label()) {
+                        split_view::apply_layout(window, &layout);
+                    }
+
                     // macOS：仅在「退出全屏」时重新对齐红绿灯。
                     // 普通缩放时 overlay 模式会自动把红绿灯锚定在左上角，无需干预；
                     // 若每帧都重算（setFrame 重设标题栏容器）反而会与 AppKit 的 resize
@@ -358,16 +697,33 @@ pub fn run() {
                     }
                 }
                 tauri::WindowEvent::Destroyed => {
-                    save_window_state(window);
+                    window_state::save(window);
 
                     #[cfg(target_os = "macos")]
                     if let Ok(mut states) = fullscreen_state().lock() {
                         states.remove(window.label());
                     }
 
-                    // 窗口销毁时清理该窗口的所有桥接连接
+                    let _ = window
+                        .state::<presentation::PresentationState>()
+                        .exit(window.label());
+                    window
+                        .state::<recovery::RecoveryState>()
+                        .forget(window.label());
+                    window
+                        .state::<window_context::WindowContextState>()
+                        .forget(window.label());
+                    window_context::refresh_badge(window.app_handle());
+
+                    // 窗口销毁时清理该窗口的所有桥接连接（分屏窗口按 pane 清理）
                     let state = window.state::<BridgeState>();
                     state.disconnect_window(window.label());
+                    let split = window.state::<split_view::SplitViewState>();
+                    if let Some(layout) = split.remove_window(window.label()) {
+                        for pane in layout.panes {
+                            state.disconnect_window(&pane.label);
+                        }
+                    }
                 }
                 tauri::WindowEvent::DragDrop(event) => {
                     match event {
@@ -400,6 +756,12 @@ pub fn run() {
                         _ => {}
                     }
                 }
+                tauri::WindowEvent::ThemeChanged(theme) => {
+                    tray::set_theme(window.app_handle(), *theme);
+                }
+                tauri::WindowEvent::Focused(true) => {
+                    tray::clear_errors(window.app_handle());
+                }
                 _ => {}
             }
         })
@@ -407,16 +769,183 @@ pub fn run() {
             commands::bridge::bridge_connect,
             commands::bridge::bridge_send,
             commands::bridge::bridge_disconnect,
+            commands::bridge::sse_pause,
+            commands::bridge::sse_resume,
+            commands::bridge::get_connection_forensics,
+            commands::bridge::dump_connection_forensics,
+            commands::bridge::clear_connection_forensics,
+            commands::bridge::sse_stats,
+            commands::bridge::sse_recording_start,
+            commands::bridge::sse_recording_stop,
+            commands::bridge::sse_recording_status,
+            commands::bridge::sse_recording_export,
             commands::utils::get_cli_directory,
+            commands::utils::get_cli_launch_options,
+            commands::utils::get_pending_session,
+            commands::utils::pop_out_session,
+            commands::utils::move_session_to_window,
             commands::utils::get_dropped_paths_info,
             commands::utils::open_new_window,
             commands::utils::desktop_window_ready,
+            commands::utils::webview_heartbeat,
+            commands::safe_mode::get_safe_mode_info,
+            commands::safe_mode::clear_crash_report,
             commands::opencode::check_opencode_service,
             commands::opencode::detect_opencode_binary,
+            commands::opencode::inspect_opencode_binary,
+            commands::opencode::list_opencode_binaries,
+            commands::opencode::get_opencode_version,
+            commands::opencode::list_wsl_distros,
+            commands::installer::check_opencode_release,
+            commands::installer::install_opencode,
+            commands::installer::get_installed_opencode,
             commands::opencode::start_opencode_service,
             commands::opencode::stop_opencode_service,
+            commands::opencode::restart_opencode_service,
             commands::opencode::get_service_started_by_us,
+            commands::opencode::list_opencode_services,
             commands::opencode::confirm_close_app,
+            commands::opencode::get_idle_suspend_config,
+            commands::opencode::set_idle_suspend_config,
+            commands::opencode::get_service_watchdog_config,
+            commands::opencode::set_service_watchdog_config,
+            commands::opencode::get_service_termination_config,
+            commands::opencode::set_service_termination_config,
+            commands::service_autostart::get_service_autostart,
+            commands::service_autostart::set_service_autostart,
+            commands::service_stats::get_service_stats,
+            commands::service_stats::get_service_stats_config,
+            commands::service_stats::set_service_stats_config,
+            commands::health_monitor::set_monitored_servers,
+            commands::health_monitor::get_server_statuses,
+            commands::health_monitor::get_health_monitor_config,
+            commands::health_monitor::set_health_monitor_config,
+            commands::opencode::report_service_activity,
+            commands::opencode::get_service_logs,
+            commands::opencode::follow_service_logs,
+            commands::opencode::clear_service_logs,
+            commands::opencode::get_service_exit,
+            commands::opencode::ensure_service_awake,
+            commands::opencode::get_shutdown_service_policy,
+            commands::opencode::set_shutdown_service_policy,
+            commands::storage::get_storage_breakdown,
+            commands::storage::clear_category,
+            commands::storage::get_storage_config,
+            commands::storage::set_storage_config,
+            commands::presence::get_user_presence,
+            commands::presence::get_presence_config,
+            commands::presence::set_presence_config,
+            commands::permissions::list_pending_permissions,
+            commands::permissions::get_permission_policy,
+            commands::permissions::set_permission_policy,
+            commands::permissions::list_permission_audit,
+            commands::clipboard::copy_to_clipboard,
+            commands::clipboard::list_clipboard_history,
+            commands::clipboard::recopy_clipboard_entry,
+            commands::clipboard::clear_clipboard_history,
+            commands::clipboard::copy_latest_artifact,
+            commands::appearance::get_window_appearance,
+            commands::appearance::set_window_appearance,
+            commands::appearance::get_titlebar_layout,
+            commands::appearance::start_window_dragging,
+            commands::appearance::titlebar_double_click,
+            commands::presentation::enter_presentation_mode,
+            commands::presentation::exit_presentation_mode,
+            commands::presentation::is_presentation_mode,
+            commands::presentation::should_suppress_notifications,
+            commands::window_context::set_window_context,
+            commands::run_timers::get_run_elapsed,
+            commands::run_timers::list_running_timers,
+            commands::session_tree::get_session_tree,
+            commands::session_tree::get_session_children_summary,
+            commands::session_tree::get_session_root,
+            commands::session_branches::link_session_branch,
+            commands::session_branches::unlink_session_branch,
+            commands::session_branches::get_session_branch,
+            commands::session_branches::list_session_branches,
+            commands::session_branches::checkout_session_branch,
+            commands::session_branches::prepare_session_branch,
+            commands::session_marks::list_session_marks,
+            commands::session_marks::mark_session_read,
+            commands::session_marks::mark_all_sessions_read,
+            commands::session_marks::set_session_pinned,
+            commands::transcripts::list_pending_transcripts,
+            commands::transcripts::reconcile_transcript,
+            commands::transcripts::discard_transcript,
+            commands::split_view::create_split_window,
+            commands::split_view::add_split_pane,
+            commands::split_view::remove_split_pane,
+            commands::split_view::resize_split_panes,
+            commands::split_view::get_split_pane,
+            commands::profile::list_app_profiles,
+            commands::profile::switch_app_profile,
+            commands::doctor::run_doctor,
+            commands::diagnostics::export_diagnostics,
+            commands::service_validation::validate_service_config,
+            commands::onboarding::get_onboarding_state,
+            commands::onboarding::complete_onboarding,
+            commands::onboarding::reset_onboarding,
+            commands::onboarding::probe_remote_server,
+            commands::credentials::set_server_credential,
+            commands::credentials::delete_server_credential,
+            commands::credentials::has_server_credential,
+            commands::server_profiles::list_server_profiles,
+            commands::server_profiles::save_server_profile,
+            commands::server_profiles::delete_server_profile,
+            commands::server_profiles::start_profile_service,
+            commands::workspace_autolaunch::get_workspace_autolaunch_config,
+            commands::workspace_autolaunch::set_workspace_autolaunch_config,
+            commands::workspace_autolaunch::launch_workspace,
+            commands::workspace_autolaunch::get_workspace_status,
+            commands::scheduler::list_schedules,
+            commands::scheduler::save_schedule,
+            commands::scheduler::delete_schedule,
+            commands::scheduler::run_schedule_now,
+            commands::watch::list_watch_jobs,
+            commands::watch::save_watch_job,
+            commands::watch::delete_watch_job,
+            commands::share::start_session_share,
+            commands::share::stop_session_share,
+            commands::share::list_session_shares,
+            commands::transfer::export_settings,
+            commands::transfer::import_settings,
+            // Network
+            commands::network::get_doh_config,
+            commands::network::set_doh_config,
+            commands::network::get_network_config,
+            commands::network::set_network_config,
+            commands::network::get_client_identity,
+            commands::network::set_client_identity,
+            // Language
+            commands::language::get_app_language,
+            commands::language::set_app_language,
+            commands::language::format_values,
+            // Shortcuts
+            commands::shortcuts::list_shortcuts,
+            commands::shortcuts::rebind_shortcut,
+            // Projects
+            commands::projects::list_projects,
+            commands::projects::remove_project,
+            commands::projects::create_project,
+            commands::projects::set_project_read_only,
+            commands::projects::discover_projects,
+            commands::projects::register_projects,
+            commands::projects::browse_local_directory,
+            commands::projects::browse_remote_directory,
+            // Checkpoints
+            commands::checkpoints::list_checkpoints,
+            commands::checkpoints::create_checkpoint,
+            commands::checkpoints::delete_checkpoint,
+            commands::checkpoints::diff_workspace,
+            // Models
+            commands::models::list_models,
+            // Palette
+            commands::palette::palette_query,
+            commands::palette::palette_register,
+            // Conflicts
+            commands::conflicts::list_file_conflicts,
+            commands::conflicts::get_file_conflict,
+            commands::conflicts::dismiss_file_conflict,
         ]);
 
     // Android: 注册 bridge commands
@@ -425,6 +954,16 @@ pub fn run() {
         commands::bridge::bridge_connect,
         commands::bridge::bridge_send,
         commands::bridge::bridge_disconnect,
+        commands::bridge::sse_pause,
+        commands::bridge::sse_resume,
+        commands::bridge::get_connection_forensics,
+        commands::bridge::dump_connection_forensics,
+        commands::bridge::clear_connection_forensics,
+        commands::bridge::sse_stats,
+        commands::bridge::sse_recording_start,
+        commands::bridge::sse_recording_stop,
+        commands::bridge::sse_recording_status,
+        commands::bridge::sse_recording_export,
     ]);
 
     // build + run 分开调用，以支持 macOS RunEvent::Opened
@@ -433,6 +972,13 @@ pub fn run() {
         .unwrap_or_else(|err| panic!("error while building tauri application: {err}"));
 
     app.run(|_app_handle, _event| {
+        // 系统关机 / 注销 / macOS 退出：保存状态并按设置处理托管服务
+        #[cfg(not(target_os = "android"))]
+        if let tauri::RunEvent::Exit = &_event {
+            shutdown::finish(_app_handle, "exit");
+            safe_mode::end_session(_app_handle);
+        }
+
         // macOS: 处理 Finder "Open with" / 拖文件夹到 Dock 图标
         #[cfg(target_os = "macos")]
         if let tauri::RunEvent::Opened { urls } = &_event {
@@ -451,7 +997,11 @@ pub fn run() {
                                     pending.insert("main".to_string(), Arc::from(dir.clone()));
                                     let _ = _app_handle.emit("open-directory", dir);
                             } else {
-                                create_new_window(_app_handle, Some(dir));
+                                create_new_window(
+                                    _app_handle,
+                                    Some(dir),
+                                    launch_args::LaunchOptions::default(),
+                                );
                             }
                         }
                     }
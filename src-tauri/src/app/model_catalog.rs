@@ -0,0 +1,198 @@
+// ============================================
+// Provider Model Catalog Cache (desktop only)
+// 缓存 /config/providers 响应（带 TTL 与变更检测），服务器暂时不可达时返回缓存副本；
+// 缓存持久化到 profile 配置目录，重启后离线也能列出模型
+// ============================================
+
+use crate::app::api::{self, ServerTarget};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+
+/// 缓存有效期，过期后下次请求会重新拉取
+const TTL_MS: u64 = 5 * 60 * 1000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedCatalog {
+    /// `/config/providers` 的原始响应（`providers` + `default`）
+    pub catalog: Value,
+    pub fetched_at: u64,
+    /// 内容摘要，用于判断目录是否变化
+    pub hash: String,
+}
+
+impl CachedCatalog {
+    fn new(catalog: Value, fetched_at: u64) -> Self {
+        let hash = format!("{:x}", Sha256::digest(catalog.to_string().as_bytes()));
+        Self {
+            catalog,
+            fetched_at,
+            hash,
+        }
+    }
+
+    pub fn is_fresh(&self, now: u64) -> bool {
+        now.saturating_sub(self.fetched_at) < TTL_MS
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CatalogSource {
+    Network,
+    Cache,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelCatalog {
+    pub catalog: Value,
+    pub fetched_at: u64,
+    pub source: CatalogSource,
+    /// 本次拉取的内容与之前缓存不同
+    pub changed: bool,
+    /// 拉取失败、返回的是过期缓存
+    pub stale: bool,
+    pub error: Option<String>,
+}
+
+/// 以服务器地址 + 目录区分缓存（不同项目的 opencode.json 可能配置不同 provider）
+fn cache_key(target: &ServerTarget) -> String {
+    format!(
+        "{}|{}",
+        target.url.trim_end_matches('/'),
+        target.directory.as_deref().unwrap_or_default()
+    )
+}
+
+#[derive(Default)]
+pub struct ModelCatalogState {
+    entries: Mutex<HashMap<String, CachedCatalog>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl ModelCatalogState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("model-catalog.json"));
+        let entries = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        Self {
+            entries: Mutex::new(entries),
+            path: Mutex::new(path),
+        }
+    }
+
+    fn cached(&self, key: &str) -> Option<CachedCatalog> {
+        self.entries
+            .lock()
+            .expect("model catalog poisoned")
+            .get(key)
+            .cloned()
+    }
+
+    /// 写入新拉取的目录，返回内容是否变化
+    fn store(&self, key: String, entry: CachedCatalog) -> bool {
+        let mut entries = self.entries.lock().expect("model catalog poisoned");
+        let changed = entries
+            .get(&key)
+            .is_none_or(|previous| previous.hash != entry.hash);
+        entries.insert(key, entry);
+        if let Err(e) = self.persist(&entries) {
+            log::warn!("Failed to persist model catalog: {}", e);
+        }
+        changed
+    }
+
+    fn persist(&self, entries: &HashMap<String, CachedCatalog>) -> Result<(), String> {
+        let path = self.path.lock().expect("model catalog poisoned").clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string(entries).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    /// 返回模型目录：缓存新鲜时直接返回，否则拉取；拉取失败时退回缓存
+    pub async fn list(
+        &self,
+        target: &ServerTarget,
+        force_refresh: bool,
+    ) -> Result<ModelCatalog, String> {
+        let key = cache_key(target);
+        let now = crate::app::now_millis();
+        let cached = self.cached(&key);
+
+        if let Some(cached) = cached
+            .as_ref()
+            .filter(|cached| !force_refresh && cached.is_fresh(now))
+        {
+            return Ok(ModelCatalog {
+                catalog: cached.catalog.clone(),
+                fetched_at: cached.fetched_at,
+                source: CatalogSource::Cache,
+                changed: false,
+                stale: false,
+                error: None,
+            });
+        }
+
+        match api::get_json(target, "/config/providers").await {
+            Ok(catalog) if catalog["providers"].is_array() => {
+                let entry = CachedCatalog::new(catalog, now);
+                let result = ModelCatalog {
+                    catalog: entry.catalog.clone(),
+                    fetched_at: now,
+                    source: CatalogSource::Network,
+                    changed: false,
+                    stale: false,
+                    error: None,
+                };
+                let changed = self.store(key, entry);
+                Ok(ModelCatalog { changed, ..result })
+            }
+            Ok(_) => Err("invalid /config/providers response".to_string()),
+            Err(e) => {
+                let Some(cached) = cached else {
+                    return Err(e);
+                };
+                log::warn!("Model catalog fetch failed, serving cached copy: {}", e);
+                Ok(ModelCatalog {
+                    catalog: cached.catalog,
+                    fetched_at: cached.fetched_at,
+                    source: CatalogSource::Cache,
+                    changed: false,
+                    stale: true,
+                    error: Some(e),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn detects_changes_and_expiry() {
+        let state = ModelCatalogState::default();
+        let catalog = json!({ "providers": [{ "id": "anthropic" }], "default": {} });
+
+        let first = CachedCatalog::new(catalog.clone(), 1_000);
+        assert!(first.is_fresh(1_000 + TTL_MS - 1));
+        assert!(!first.is_fresh(1_000 + TTL_MS));
+
+        assert!(state.store("k".to_string(), first));
+        assert!(!state.store("k".to_string(), CachedCatalog::new(catalog, 2_000)));
+        let updated = json!({ "providers": [{ "id": "openai" }], "default": {} });
+        assert!(state.store("k".to_string(), CachedCatalog::new(updated, 3_000)));
+    }
+}
@@ -0,0 +1,263 @@
+// ============================================
+// Network Settings
+// 访问服务器的 HTTP 客户端共用的网络设置：
+// - 出网代理：HTTP / HTTPS / SOCKS5，可选认证与不走代理的主机列表；本机地址始终直连，
+//   避免本地 opencode 服务的健康检查与 bridge 被代理拦截
+// - TLS：从 PEM 文件加载额外的根证书（内部 CA），以及可选的跳过证书校验（仅限排查问题）
+// - mTLS：按服务器 id 配置客户端证书，连接该服务器的 bridge 与 API 客户端出示该证书
+// - 按服务器 id 覆盖代理与 TLS 设置（服务器配置档），连接该服务器时取代全局设置
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::RwLock};
+
+pub const NETWORK_SETTINGS_KEY: &str = "network";
+/// server id → [`ClientIdentity`]
+pub const CLIENT_IDENTITIES_SETTINGS_KEY: &str = "clientIdentities";
+
+/// 始终直连的主机
+const LOCAL_HOSTS: &[&str] = &["localhost", "127.0.0.1", "::1"];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NetworkConfig {
+    /// 代理地址（`http://` / `https://` / `socks5://` / `socks5h://`），为空表示不使用代理
+    pub proxy_url: String,
+    pub proxy_username: Option<String>,
+    pub proxy_password: Option<String>,
+    /// 不走代理的主机（域名、`.example.com` 后缀、IP 或 CIDR）
+    pub no_proxy: Vec<String>,
+    /// 额外信任的根证书（PEM 文件，可包含多张证书）
+    pub ca_cert_path: Option<String>,
+    /// 不校验服务器证书。会让连接失去中间人攻击防护，只应临时用于排查问题
+    pub accept_invalid_certs: bool,
+}
+
+/// 客户端证书（PEM）。PKCS#12 (.p12 / .pfx) 需先转换：
+/// `openssl pkcs12 -in client.p12 -out client.pem -nodes`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientIdentity {
+    /// 证书（链）PEM 文件；未指定 `key_path` 时私钥也须在该文件中
+    pub cert_path: String,
+    /// 未加密的私钥 PEM 文件（PKCS#8 / RSA / EC）
+    #[serde(default)]
+    pub key_path: Option<String>,
+}
+
+impl ClientIdentity {
+    fn load(&self) -> Result<reqwest::Identity, String> {
+        let read = |path: &str| {
+            std::fs::read(path.trim()).map_err(|e| format!("failed to read '{}': {}", path, e))
+        };
+        let mut pem = read(&self.cert_path)?;
+        if let Some(key_path) = self
+            .key_path
+            .as_deref()
+            .filter(|path| !path.trim().is_empty())
+        {
+            pem.push(b'\n');
+            pem.extend(read(key_path)?);
+        }
+        reqwest::Identity::from_pem(&pem).map_err(|e| format!("invalid client certificate: {}", e))
+    }
+}
+
+/// 已加载的客户端证书：server id → identity
+static IDENTITIES: RwLock<Option<HashMap<String, reqwest::Identity>>> = RwLock::new(None);
+
+/// 设置（`None` 为移除）某个服务器的客户端证书，立即对新建的客户端生效
+pub fn set_identity(server_id: &str, identity: Option<&ClientIdentity>) -> Result<(), String> {
+    let identity = identity.map(ClientIdentity::load).transpose()?;
+    let mut identities = IDENTITIES.write().expect("network config poisoned");
+    let identities = identities.get_or_insert_with(HashMap::new);
+    match identity {
+        Some(identity) => identities.insert(server_id.to_string(), identity),
+        None => identities.remove(server_id),
+    };
+    Ok(())
+}
+
+/// 加载所有已配置的客户端证书；单个证书失败时记录日志并跳过
+pub fn load_identities(configured: &HashMap<String, ClientIdentity>) {
+    for (server_id, identity) in configured {
+        if let Err(e) = set_identity(server_id, Some(identity)) {
+            log::warn!("Client certificate for server {}: {}", server_id, e);
+        }
+    }
+}
+
+/// 连接某个服务器时出示其客户端证书，并应用其网络设置覆盖（已配置时）
+pub fn apply_server(
+    mut builder: reqwest::ClientBuilder,
+    server_id: Option<&str>,
+) -> reqwest::ClientBuilder {
+    let Some(server_id) = server_id else {
+        return builder;
+    };
+    let identity = IDENTITIES
+        .read()
+        .expect("network config poisoned")
+        .as_ref()
+        .and_then(|identities| identities.get(server_id).cloned());
+    if let Some(identity) = identity {
+        builder = builder.identity(identity);
+    }
+    let applied = SERVER_APPLIED
+        .read()
+        .expect("network config poisoned")
+        .as_ref()
+        .and_then(|applied| applied.get(server_id).cloned());
+    match applied {
+        // 先清掉全局代理（以及系统代理），再挂上该服务器自己的
+        Some(applied) => applied.attach(builder.no_proxy()),
+        None => builder,
+    }
+}
+
+/// 由 [`NetworkConfig`] 构建、实际挂到客户端上的设置
+#[derive(Clone, Default)]
+struct Applied {
+    proxy: Option<reqwest::Proxy>,
+    roots: Vec<reqwest::Certificate>,
+    accept_invalid_certs: bool,
+}
+
+impl Applied {
+    fn build(config: &NetworkConfig) -> Result<Self, String> {
+        Ok(Self {
+            proxy: build_proxy(config)?,
+            roots: load_roots(config)?,
+            accept_invalid_certs: config.accept_invalid_certs,
+        })
+    }
+
+    fn attach(self, mut builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        if let Some(proxy) = self.proxy {
+            builder = builder.proxy(proxy);
+        }
+        for root in self.roots {
+            builder = builder.add_root_certificate(root);
+        }
+        builder.danger_accept_invalid_certs(self.accept_invalid_certs)
+    }
+}
+
+/// 当前生效的设置；未配置代理时 reqwest 沿用系统代理环境变量
+static APPLIED: RwLock<Option<Applied>> = RwLock::new(None);
+/// 按服务器 id 覆盖的设置
+static SERVER_APPLIED: RwLock<Option<HashMap<String, Applied>>> = RwLock::new(None);
+
+fn build_proxy(config: &NetworkConfig) -> Result<Option<reqwest::Proxy>, String> {
+    let url = config.proxy_url.trim();
+    if url.is_empty() {
+        return Ok(None);
+    }
+    let parsed = reqwest::Url::parse(url).map_err(|e| format!("invalid proxy URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https" | "socks5" | "socks5h") {
+        return Err(format!(
+            "unsupported proxy scheme '{}' (use http, https or socks5)",
+            parsed.scheme()
+        ));
+    }
+
+    let mut proxy = reqwest::Proxy::all(url).map_err(|e| format!("invalid proxy: {}", e))?;
+    if let Some(username) = config
+        .proxy_username
+        .as_deref()
+        .filter(|username| !username.is_empty())
+    {
+        proxy = proxy.basic_auth(username, config.proxy_password.as_deref().unwrap_or(""));
+    }
+    let no_proxy: Vec<&str> = LOCAL_HOSTS
+        .iter()
+        .copied()
+        .chain(config.no_proxy.iter().map(|host| host.trim()))
+        .filter(|host| !host.is_empty())
+        .collect();
+    Ok(Some(proxy.no_proxy(reqwest::NoProxy::from_string(
+        &no_proxy.join(","),
+    ))))
+}
+
+fn load_roots(config: &NetworkConfig) -> Result<Vec<reqwest::Certificate>, String> {
+    let Some(path) = config
+        .ca_cert_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|path| !path.is_empty())
+    else {
+        return Ok(Vec::new());
+    };
+    let pem = std::fs::read(path).map_err(|e| format!("failed to read '{}': {}", path, e))?;
+    let roots = reqwest::Certificate::from_pem_bundle(&pem)
+        .map_err(|e| format!("invalid certificate in '{}': {}", path, e))?;
+    if roots.is_empty() {
+        return Err(format!("no PEM certificates found in '{}'", path));
+    }
+    Ok(roots)
+}
+
+/// 应用网络设置，立即对新建的客户端生效；出错时保留之前的设置
+pub fn configure(config: &NetworkConfig) -> Result<(), String> {
+    let applied = Applied::build(config)?;
+    if applied.accept_invalid_certs {
+        log::warn!(
+            "TLS certificate verification is DISABLED for server connections; \
+             any network attacker can read and modify this traffic"
+        );
+    }
+    *APPLIED.write().expect("network config poisoned") = Some(applied);
+    Ok(())
+}
+
+/// 设置（`None` 为移除）某个服务器的网络设置覆盖，立即对新建的客户端生效；出错时保留之前的设置
+pub fn set_server_network(server_id: &str, config: Option<&NetworkConfig>) -> Result<(), String> {
+    let applied = config.map(Applied::build).transpose()?;
+    if applied
+        .as_ref()
+        .is_some_and(|applied| applied.accept_invalid_certs)
+    {
+        log::warn!(
+            "TLS certificate verification is DISABLED for server {}",
+            server_id
+        );
+    }
+    let mut servers = SERVER_APPLIED.write().expect("network config poisoned");
+    let servers = servers.get_or_insert_with(HashMap::new);
+    match applied {
+        Some(applied) => servers.insert(server_id.to_string(), applied),
+        None => servers.remove(server_id),
+    };
+    Ok(())
+}
+
+/// 把代理（同时停用系统代理）与 TLS 设置挂到 ClientBuilder 上
+pub fn apply(builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+    match APPLIED.read().expect("network config poisoned").clone() {
+        Some(applied) => applied.attach(builder),
+        None => builder,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_proxy_url() {
+        let config = |url: &str| NetworkConfig {
+            proxy_url: url.to_string(),
+            ..Default::default()
+        };
+        assert!(build_proxy(&config("")).unwrap().is_none());
+        assert!(build_proxy(&config("http://proxy.corp:3128"))
+            .unwrap()
+            .is_some());
+        assert!(build_proxy(&config("socks5://127.0.0.1:1080"))
+            .unwrap()
+            .is_some());
+        assert!(build_proxy(&config("ftp://proxy.corp")).is_err());
+        assert!(build_proxy(&config("not a url")).is_err());
+    }
+}
@@ -0,0 +1,319 @@
+// ============================================
+// Command Palette Index (desktop only)
+// 汇总项目、最近会话、prompt 模板与应用命令，提供模糊搜索；
+// 项目随注册表变化同步，会话随 bridge 上的 session.* 事件增量更新
+// ============================================
+
+use crate::app::{
+    bridge::{event_data, stream_endpoint, BridgeState},
+    projects::ProjectsState,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{collections::HashMap, sync::RwLock};
+use tauri::Manager;
+
+/// 保留的最近会话条数
+const MAX_SESSIONS: usize = 2000;
+pub const DEFAULT_LIMIT: usize = 50;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum EntryKind {
+    Project,
+    Session,
+    PromptTemplate,
+    Command,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteEntry {
+    pub id: String,
+    pub kind: EntryKind,
+    pub title: String,
+    #[serde(default)]
+    pub subtitle: Option<String>,
+    /// 额外参与匹配的词（别名、快捷键说明等）
+    #[serde(default)]
+    pub keywords: Vec<String>,
+    /// 用于空查询排序与同分排序
+    #[serde(default)]
+    pub updated_at: u64,
+    /// 原样返回给前端的附加数据（如会话所在目录）
+    #[serde(default)]
+    pub data: Value,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteMatch {
+    #[serde(flatten)]
+    pub entry: PaletteEntry,
+    pub score: i64,
+    /// 标题中命中的字符下标（按 char 计），用于高亮
+    pub positions: Vec<usize>,
+}
+
+/// 子序列模糊匹配：连续命中、词首命中与前缀命中加分，跳过的字符扣分。
+/// 不匹配返回 None。
+pub fn fuzzy_score(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query.chars().flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some((0, Vec::new()));
+    }
+    let chars: Vec<char> = text.chars().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    let mut score = 0i64;
+    let mut positions: Vec<usize> = Vec::with_capacity(query.len());
+    let mut next = 0;
+    for &wanted in &query {
+        let found = (next..lower.len()).find(|&i| lower[i] == wanted)?;
+        let boundary = found == 0
+            || !chars[found - 1].is_alphanumeric()
+            || (chars[found].is_uppercase() && chars[found - 1].is_lowercase());
+
+        score += 10;
+        if boundary {
+            score += 8;
+        }
+        match positions.last() {
+            Some(&last) if found == last + 1 => score += 6,
+            Some(&last) => score -= (found - last - 1).min(10) as i64,
+            None => score -= found.min(10) as i64,
+        }
+        positions.push(found);
+        next = found + 1;
+    }
+    if positions.first() == Some(&0) {
+        score += 10;
+    }
+    // 同分时更短的文本更相关
+    score -= (chars.len() / 16) as i64;
+    Some((score, positions))
+}
+
+fn kind_bonus(kind: EntryKind) -> i64 {
+    match kind {
+        EntryKind::Command => 4,
+        EntryKind::Project => 3,
+        EntryKind::PromptTemplate => 2,
+        EntryKind::Session => 0,
+    }
+}
+
+#[derive(Default)]
+pub struct PaletteIndex {
+    entries: RwLock<HashMap<(EntryKind, String), PaletteEntry>>,
+}
+
+impl PaletteIndex {
+    pub fn upsert(&self, entry: PaletteEntry) {
+        let mut entries = self.entries.write().expect("palette index poisoned");
+        entries.insert((entry.kind, entry.id.clone()), entry);
+
+        let sessions = entries
+            .keys()
+            .filter(|(kind, _)| *kind == EntryKind::Session)
+            .count();
+        if sessions > MAX_SESSIONS {
+            let oldest = entries
+                .values()
+                .filter(|entry| entry.kind == EntryKind::Session)
+                .min_by_key(|entry| entry.updated_at)
+                .map(|entry| (entry.kind, entry.id.clone()));
+            if let Some(key) = oldest {
+                entries.remove(&key);
+            }
+        }
+    }
+
+    pub fn remove(&self, kind: EntryKind, id: &str) {
+        self.entries
+            .write()
+            .expect("palette index poisoned")
+            .remove(&(kind, id.to_string()));
+    }
+
+    /// 整体替换某一类条目（项目同步、前端注册命令与模板时使用）
+    pub fn replace_kind(&self, kind: EntryKind, new_entries: Vec<PaletteEntry>) {
+        let mut entries = self.entries.write().expect("palette index poisoned");
+        entries.retain(|(entry_kind, _), _| *entry_kind != kind);
+        for mut entry in new_entries {
+            entry.kind = kind;
+            entries.insert((kind, entry.id.clone()), entry);
+        }
+    }
+
+    pub fn query(&self, text: &str, limit: usize) -> Vec<PaletteMatch> {
+        let text = text.trim();
+        let entries = self.entries.read().expect("palette index poisoned");
+        let mut matches: Vec<PaletteMatch> = entries
+            .values()
+            .filter_map(|entry| {
+                let (title_score, positions) = fuzzy_score(text, &entry.title).unwrap_or_default();
+                let title_hit = text.is_empty() || !positions.is_empty();
+                // 副标题与关键词命中只算一半分数，也不提供高亮
+                let other_score = if title_hit {
+                    None
+                } else {
+                    entry
+                        .subtitle
+                        .iter()
+                        .chain(entry.keywords.iter())
+                        .filter_map(|other| fuzzy_score(text, other))
+                        .map(|(score, _)| score / 2)
+                        .max()
+                };
+                if !title_hit && other_score.is_none() {
+                    return None;
+                }
+                Some(PaletteMatch {
+                    score: other_score.unwrap_or(title_score) + kind_bonus(entry.kind),
+                    positions,
+                    entry: entry.clone(),
+                })
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then(b.entry.updated_at.cmp(&a.entry.updated_at))
+        });
+        matches.truncate(limit);
+        matches
+    }
+}
+
+/// 按项目注册表重建项目条目
+pub fn sync_projects(app: &tauri::AppHandle) {
+    let projects = app.state::<ProjectsState>().list();
+    let entries = projects
+        .into_iter()
+        .map(|project| PaletteEntry {
+            id: project.id.clone(),
+            kind: EntryKind::Project,
+            title: project.name.clone(),
+            subtitle: Some(project.path.clone()),
+            keywords: project.template.clone().into_iter().collect(),
+            updated_at: project.last_opened_at.max(project.created_at),
+            data: serde_json::json!({ "path": project.path }),
+        })
+        .collect();
+    app.state::<PaletteIndex>()
+        .replace_kind(EntryKind::Project, entries);
+}
+
+/// session.created / session.updated → 条目；session.deleted → 删除
+fn session_change(event: &Value, stream_directory: Option<&str>) -> Option<(bool, PaletteEntry)> {
+    let (event, directory) = match event.get("payload") {
+        Some(payload) => (payload, event["directory"].as_str()),
+        None => (event, stream_directory),
+    };
+    let upsert = match event["type"].as_str()? {
+        "session.created" | "session.updated" => true,
+        "session.deleted" => false,
+        _ => return None,
+    };
+    let info = &event["properties"]["info"];
+    let id = info["id"].as_str()?.to_string();
+    let directory = info["directory"].as_str().or(directory);
+    let title = info["title"]
+        .as_str()
+        .filter(|title| !title.is_empty())
+        .unwrap_or("Untitled session");
+
+    Some((
+        upsert,
+        PaletteEntry {
+            id: id.clone(),
+            kind: EntryKind::Session,
+            title: title.to_string(),
+            subtitle: directory.map(str::to_string),
+            keywords: Vec::new(),
+            updated_at: info["time"]["updated"].as_f64().unwrap_or(0.0) as u64,
+            data: serde_json::json!({ "sessionId": id, "directory": directory }),
+        },
+    ))
+}
+
+/// 注册为 bridge 的事件观察者，并载入已注册项目
+pub fn install(app: &tauri::AppHandle) {
+    sync_projects(app);
+
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |args, block| {
+            if !block.contains("\"session.") {
+                return;
+            }
+            let Some(event) =
+                event_data(block).and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            else {
+                return;
+            };
+            let stream_directory = stream_endpoint(args.url()).and_then(|(_, _, dir)| dir);
+            let Some((upsert, entry)) = session_change(&event, stream_directory.as_deref()) else {
+                return;
+            };
+            let index = handle.state::<PaletteIndex>();
+            if upsert {
+                index.upsert(entry);
+            } else {
+                index.remove(EntryKind::Session, &entry.id);
+            }
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(kind: EntryKind, id: &str, title: &str, updated_at: u64) -> PaletteEntry {
+        PaletteEntry {
+            id: id.to_string(),
+            kind,
+            title: title.to_string(),
+            subtitle: None,
+            keywords: Vec::new(),
+            updated_at,
+            data: Value::Null,
+        }
+    }
+
+    #[test]
+    fn fuzzy_score_prefers_word_starts_and_runs() {
+        let (prefix, positions) = fuzzy_score("ns", "New Session").unwrap();
+        assert_eq!(positions, vec![0, 4]);
+        let (scattered, _) = fuzzy_score("ns", "Unassigned").unwrap();
+        assert!(prefix > scattered);
+        assert!(fuzzy_score("xyz", "New Session").is_none());
+    }
+
+    #[test]
+    fn query_ranks_and_limits_entries() {
+        let index = PaletteIndex::default();
+        index.upsert(entry(EntryKind::Session, "s1", "Fix login bug", 10));
+        index.upsert(entry(EntryKind::Session, "s2", "Refactor parser", 20));
+        index.replace_kind(
+            EntryKind::Command,
+            vec![entry(EntryKind::Command, "open", "Open Project", 0)],
+        );
+
+        let results = index.query("op", 10);
+        assert_eq!(results[0].entry.id, "open");
+
+        let recent = index.query("", 2);
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0].entry.id, "open");
+        assert_eq!(recent[1].entry.id, "s2");
+
+        index.remove(EntryKind::Session, "s1");
+        assert!(index.query("login", 10).is_empty());
+    }
+}
@@ -0,0 +1,640 @@
+// ============================================
+// Permission Broker (desktop only)
+// 观察 bridge 上的 permission.asked / permission.replied 事件，跟踪未答复的权限请求：
+// - 只读项目中的写入 / 编辑 / 执行请求直接在 Rust 侧拒绝
+// - 安全类别按设置自动批准
+// - 长时间无人答复时逐级提醒，超时后按设置拒绝或批准，避免任务无限挂起
+// 所有决定写入审计日志
+// ============================================
+
+use crate::app::{
+    api::{self, ServerTarget},
+    bridge::{event_data, stream_endpoint, BridgeState, ConnectArgs},
+    projects::ProjectsState,
+    settings::SettingsStore,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::Duration,
+};
+use tauri::{Emitter, Manager};
+
+/// 只读项目中自动拒绝的权限类型
+const WRITE_PERMISSIONS: &[&str] = &["edit", "write", "patch", "multiedit", "bash"];
+/// 记录已处理的请求 id（同一事件会经多个窗口的连接到达）
+const MAX_HANDLED: usize = 1000;
+/// 内存中保留的审计条数（完整记录在审计日志文件中）
+const MAX_AUDIT: usize = 500;
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+const MINUTE_MS: u64 = 60 * 1000;
+const DENY_MESSAGE: &str =
+    "This project is read-only in OpenCodeUI; modifications are not allowed.";
+const TIMEOUT_MESSAGE: &str = "No response from the user in time; rejected by OpenCodeUI.";
+pub const PERMISSION_SETTINGS_KEY: &str = "permissionBroker";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimeoutAction {
+    #[default]
+    Reject,
+    Once,
+}
+
+impl TimeoutAction {
+    fn reply(self) -> &'static str {
+        match self {
+            Self::Reject => "reject",
+            Self::Once => "once",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PermissionPolicy {
+    /// 无人答复多少分钟后自动处理，0 表示一直等待
+    pub timeout_minutes: u32,
+    pub timeout_action: TimeoutAction,
+    /// 直接批准的权限类型（如 read、glob、grep、list）
+    pub auto_approve: Vec<String>,
+    /// 未答复请求的提醒时间点（分钟），逐级发送
+    pub escalate_after_minutes: Vec<u32>,
+}
+
+impl Default for PermissionPolicy {
+    fn default() -> Self {
+        Self {
+            timeout_minutes: 0,
+            timeout_action: TimeoutAction::Reject,
+            auto_approve: Vec::new(),
+            escalate_after_minutes: vec![1, 5],
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PermissionRequest {
+    request_id: String,
+    session_id: String,
+    permission: String,
+    patterns: Vec<String>,
+    directory: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPermission {
+    pub request_id: String,
+    pub session_id: String,
+    pub permission: String,
+    pub patterns: Vec<String>,
+    pub directory: String,
+    pub asked_at: u64,
+    /// 已发送的提醒次数
+    pub escalations: usize,
+    #[serde(skip)]
+    target: ServerTarget,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DecisionSource {
+    /// 用户在任意客户端答复
+    User,
+    ReadOnly,
+    AutoApprove,
+    Timeout,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    pub at: u64,
+    pub request_id: String,
+    pub session_id: String,
+    pub permission: String,
+    pub directory: String,
+    /// `once` / `always` / `reject`
+    pub reply: String,
+    pub source: DecisionSource,
+    /// 从请求到决定的耗时（未跟踪到请求时为空）
+    pub waited_ms: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoDenied {
+    pub request_id: String,
+    pub session_id: String,
+    pub permission: String,
+    pub directory: String,
+    pub project_id: String,
+}
+
+#[derive(Default)]
+pub struct PermissionRelayState {
+    handled: Mutex<HashSet<String>>,
+    pending: Mutex<HashMap<String, PendingPermission>>,
+    audit: Mutex<VecDeque<AuditEntry>>,
+    audit_path: Option<PathBuf>,
+}
+
+impl PermissionRelayState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let audit_path =
+            crate::app::profile::data_dir(app).map(|dir| dir.join("permission-audit.jsonl"));
+        let mut audit: VecDeque<AuditEntry> = audit_path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|data| {
+                data.lines()
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        while audit.len() > MAX_AUDIT {
+            audit.pop_front();
+        }
+
+        Self {
+            audit: Mutex::new(audit),
+            audit_path,
+            ..Default::default()
+        }
+    }
+
+    /// 第一次见到该请求时返回 true
+    fn claim(&self, request_id: &str) -> bool {
+        let mut handled = self.handled.lock().expect("permission relay poisoned");
+        if handled.len() >= MAX_HANDLED {
+            handled.clear();
+        }
+        handled.insert(request_id.to_string())
+    }
+
+    pub fn pending(&self) -> Vec<PendingPermission> {
+        let mut pending: Vec<PendingPermission> = self
+            .pending
+            .lock()
+            .expect("permission relay poisoned")
+            .values()
+            .cloned()
+            .collect();
+        pending.sort_by_key(|request| request.asked_at);
+        pending
+    }
+
+    fn take_pending(&self, request_id: &str) -> Option<PendingPermission> {
+        self.pending
+            .lock()
+            .expect("permission relay poisoned")
+            .remove(request_id)
+    }
+
+    pub fn audit(&self, limit: usize) -> Vec<AuditEntry> {
+        let audit = self.audit.lock().expect("permission relay poisoned");
+        audit.iter().rev().take(limit).cloned().collect()
+    }
+
+    fn record(&self, entry: AuditEntry) {
+        log::info!(
+            "Permission {} ({}) → {} [{:?}]",
+            entry.request_id,
+            entry.permission,
+            entry.reply,
+            entry.source
+        );
+        if let Some(path) = &self.audit_path {
+            let appended = serde_json::to_string(&entry)
+                .map_err(|e| e.to_string())
+                .and_then(|line| {
+                    if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                    }
+                    let mut file = std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)
+                        .map_err(|e| e.to_string())?;
+                    writeln!(file, "{}", line).map_err(|e| e.to_string())
+                });
+            if let Err(e) = appended {
+                log::warn!("Failed to write permission audit log: {}", e);
+            }
+        }
+
+        let mut audit = self.audit.lock().expect("permission relay poisoned");
+        if audit.len() >= MAX_AUDIT {
+            audit.pop_front();
+        }
+        audit.push_back(entry);
+    }
+}
+
+/// 解开 `/global/event` 的 `{ directory, payload }` 包装
+fn unwrap_event<'a>(
+    event: &'a Value,
+    stream_directory: Option<&'a str>,
+) -> (&'a Value, Option<&'a str>) {
+    match event.get("payload") {
+        Some(payload) => (payload, event["directory"].as_str()),
+        None => (event, stream_directory),
+    }
+}
+
+/// permission.asked → 请求信息
+fn permission_request(event: &Value, stream_directory: Option<&str>) -> Option<PermissionRequest> {
+    let (event, directory) = unwrap_event(event, stream_directory);
+    if event["type"] != "permission.asked" {
+        return None;
+    }
+    let properties = &event["properties"];
+    Some(PermissionRequest {
+        request_id: properties["id"].as_str()?.to_string(),
+        session_id: properties["sessionID"].as_str()?.to_string(),
+        permission: properties["permission"].as_str()?.to_string(),
+        patterns: properties["patterns"]
+            .as_array()
+            .map(|patterns| {
+                patterns
+                    .iter()
+                    .filter_map(|pattern| pattern.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        directory: directory?.to_string(),
+    })
+}
+
+/// permission.replied → (请求 id, 答复)
+fn permission_reply(event: &Value) -> Option<(String, String)> {
+    let (event, _) = unwrap_event(event, None);
+    if event["type"] != "permission.replied" {
+        return None;
+    }
+    let properties = &event["properties"];
+    let request_id = properties["requestID"]
+        .as_str()
+        .or(properties["permissionID"].as_str())?;
+    let reply = properties["reply"]
+        .as_str()
+        .or(properties["response"].as_str())
+        .unwrap_or("unknown");
+    Some((request_id.to_string(), reply.to_string()))
+}
+
+fn load_policy(app: &tauri::AppHandle) -> PermissionPolicy {
+    app.state::<SettingsStore>()
+        .get_as(PERMISSION_SETTINGS_KEY)
+        .unwrap_or_default()
+}
+
+/// 在后台答复请求并记入审计日志
+fn reply(
+    app: &tauri::AppHandle,
+    target: ServerTarget,
+    request: PermissionRequest,
+    reply: &'static str,
+    message: Option<&'static str>,
+    source: DecisionSource,
+    asked_at: Option<u64>,
+) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = api::reply_permission(
+            &target,
+            &request.session_id,
+            &request.request_id,
+            reply,
+            message,
+        )
+        .await;
+        if let Err(e) = result {
+            log::error!(
+                "Failed to reply '{}' to permission {}: {}",
+                reply,
+                request.request_id,
+                e
+            );
+            return;
+        }
+
+        let now = crate::app::now_millis();
+        app.state::<PermissionRelayState>().record(AuditEntry {
+            at: now,
+            request_id: request.request_id.clone(),
+            session_id: request.session_id.clone(),
+            permission: request.permission.clone(),
+            directory: request.directory.clone(),
+            reply: reply.to_string(),
+            source,
+            waited_ms: asked_at.map(|asked_at| now.saturating_sub(asked_at)),
+        });
+        match source {
+            DecisionSource::ReadOnly => {
+                let project_id = app
+                    .state::<ProjectsState>()
+                    .read_only_project(Path::new(&request.directory))
+                    .map(|project| project.id)
+                    .unwrap_or_default();
+                let _ = app.emit(
+                    "permission-auto-denied",
+                    AutoDenied {
+                        request_id: request.request_id,
+                        session_id: request.session_id,
+                        permission: request.permission,
+                        directory: request.directory,
+                        project_id,
+                    },
+                );
+            }
+            DecisionSource::Timeout => {
+                let _ = app.emit("permission-timed-out", &request.request_id);
+            }
+            DecisionSource::AutoApprove | DecisionSource::User => {}
+        }
+    });
+}
+
+fn handle_asked(app: &tauri::AppHandle, args: &ConnectArgs, event: &Value) {
+    let Some((base, _, stream_directory)) = stream_endpoint(args.url()) else {
+        return;
+    };
+    let Some(request) = permission_request(event, stream_directory.as_deref()) else {
+        return;
+    };
+    let state = app.state::<PermissionRelayState>();
+    if !state.claim(&request.request_id) {
+        return;
+    }
+
+    let target = ServerTarget {
+        url: base.to_string(),
+        directory: Some(request.directory.clone()),
+        // bridge_connect 已按 server id 解析过凭据
+        auth_header: args.auth_header().map(str::to_string),
+        server_id: None,
+        http: args.http().clone(),
+    };
+
+    let read_only = WRITE_PERMISSIONS.contains(&request.permission.as_str())
+        && app
+            .state::<ProjectsState>()
+            .read_only_project(Path::new(&request.directory))
+            .is_some();
+    if read_only {
+        log::info!(
+            "Auto-denying '{}' permission {} in read-only project {}",
+            request.permission,
+            request.request_id,
+            request.directory
+        );
+        reply(
+            app,
+            target,
+            request,
+            "reject",
+            Some(DENY_MESSAGE),
+            DecisionSource::ReadOnly,
+            None,
+        );
+        return;
+    }
+
+    if load_policy(app)
+        .auto_approve
+        .iter()
+        .any(|permission| permission == &request.permission)
+    {
+        reply(
+            app,
+            target,
+            request,
+            "once",
+            None,
+            DecisionSource::AutoApprove,
+            None,
+        );
+        return;
+    }
+
+    let pending = PendingPermission {
+        request_id: request.request_id.clone(),
+        session_id: request.session_id,
+        permission: request.permission,
+        patterns: request.patterns,
+        directory: request.directory,
+        asked_at: crate::app::now_millis(),
+        escalations: 0,
+        target,
+    };
+    let _ = app.emit("permission-pending", &pending);
+    state
+        .pending
+        .lock()
+        .expect("permission relay poisoned")
+        .insert(request.request_id, pending);
+}
+
+fn handle_replied(app: &tauri::AppHandle, event: &Value) {
+    let Some((request_id, reply)) = permission_reply(event) else {
+        return;
+    };
+    let state = app.state::<PermissionRelayState>();
+    // 只记录经由 broker 跟踪的请求，自动答复已在答复时记录
+    let Some(pending) = state.take_pending(&request_id) else {
+        return;
+    };
+    let now = crate::app::now_millis();
+    state.record(AuditEntry {
+        at: now,
+        request_id,
+        session_id: pending.session_id,
+        permission: pending.permission,
+        directory: pending.directory,
+        reply,
+        source: DecisionSource::User,
+        waited_ms: Some(now.saturating_sub(pending.asked_at)),
+    });
+}
+
+fn handle_event(app: &tauri::AppHandle, args: &ConnectArgs, block: &str) {
+    let asked = block.contains("permission.asked");
+    if !asked && !block.contains("permission.replied") {
+        return;
+    }
+    let Some(event) = event_data(block).and_then(|data| serde_json::from_str::<Value>(&data).ok())
+    else {
+        return;
+    };
+    if asked {
+        handle_asked(app, args, &event);
+    } else {
+        handle_replied(app, &event);
+    }
+    crate::app::tray::refresh(app);
+}
+
+/// 下一次应发送的提醒级别（从 1 开始），没有到期的提醒时返回 None
+fn due_escalation(policy: &PermissionPolicy, waited_ms: u64, sent: usize) -> Option<usize> {
+    let due = policy
+        .escalate_after_minutes
+        .iter()
+        .filter(|minutes| waited_ms >= u64::from(**minutes) * MINUTE_MS)
+        .count();
+    (due > sent).then_some(due)
+}
+
+fn notify(app: &tauri::AppHandle, pending: &PendingPermission, level: usize) {
+    use tauri_plugin_notification::NotificationExt;
+
+    let minutes = crate::app::now_millis().saturating_sub(pending.asked_at) / MINUTE_MS;
+    let body = format!(
+        "'{}' permission has been waiting {} min in {}",
+        pending.permission, minutes, pending.directory
+    );
+    // 安全模式不注册通知插件，只发出事件
+    if !crate::app::safe_mode::active() {
+        let _ = app
+            .notification()
+            .builder()
+            .title("OpenCode is waiting for approval")
+            .body(body)
+            .show();
+    }
+    let _ = app.emit(
+        "permission-escalated",
+        serde_json::json!({ "request": pending, "level": level }),
+    );
+}
+
+fn spawn_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let policy = load_policy(&app);
+            let state = app.state::<PermissionRelayState>();
+            let now = crate::app::now_millis();
+
+            let mut expired = Vec::new();
+            let mut escalate = Vec::new();
+            {
+                let mut pending = state.pending.lock().expect("permission relay poisoned");
+                for request in pending.values_mut() {
+                    let waited = now.saturating_sub(request.asked_at);
+                    if policy.timeout_minutes > 0
+                        && waited >= u64::from(policy.timeout_minutes) * MINUTE_MS
+                    {
+                        expired.push(request.request_id.clone());
+                    } else if let Some(level) = due_escalation(&policy, waited, request.escalations)
+                    {
+                        request.escalations = level;
+                        escalate.push((request.clone(), level));
+                    }
+                }
+            }
+
+            for (request, level) in escalate {
+                notify(&app, &request, level);
+            }
+            let timed_out = !expired.is_empty();
+            for request_id in expired {
+                let Some(pending) = state.take_pending(&request_id) else {
+                    continue;
+                };
+                let action = policy.timeout_action;
+                log::info!(
+                    "Permission {} unanswered for {} min, replying '{}'",
+                    request_id,
+                    policy.timeout_minutes,
+                    action.reply()
+                );
+                reply(
+                    &app,
+                    pending.target,
+                    PermissionRequest {
+                        request_id: pending.request_id,
+                        session_id: pending.session_id,
+                        permission: pending.permission,
+                        patterns: pending.patterns,
+                        directory: pending.directory,
+                    },
+                    action.reply(),
+                    (action == TimeoutAction::Reject).then_some(TIMEOUT_MESSAGE),
+                    DecisionSource::Timeout,
+                    Some(pending.asked_at),
+                );
+            }
+            if timed_out {
+                crate::app::tray::refresh(&app);
+            }
+        }
+    });
+}
+
+/// 注册为 bridge 的事件观察者，并启动超时 / 提醒检查
+pub fn install(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |args, block| {
+            handle_event(&handle, args, block)
+        }));
+    spawn_monitor(app.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn extracts_permission_requests() {
+        let asked = json!({
+            "directory": "/work/app",
+            "payload": { "type": "permission.asked", "properties": {
+                "id": "per_1", "sessionID": "ses_1", "permission": "edit",
+                "patterns": ["src/main.rs"], "metadata": {}, "always": []
+            } }
+        });
+        assert_eq!(
+            permission_request(&asked, None),
+            Some(PermissionRequest {
+                request_id: "per_1".to_string(),
+                session_id: "ses_1".to_string(),
+                permission: "edit".to_string(),
+                patterns: vec!["src/main.rs".to_string()],
+                directory: "/work/app".to_string(),
+            })
+        );
+
+        let unwrapped = asked["payload"].clone();
+        assert_eq!(permission_request(&unwrapped, None), None);
+        assert_eq!(
+            permission_request(&unwrapped, Some("/other")).map(|request| request.directory),
+            Some("/other".to_string())
+        );
+
+        let replied = json!({ "type": "permission.replied", "properties": {
+            "sessionID": "ses_1", "requestID": "per_1", "reply": "always"
+        } });
+        assert_eq!(
+            permission_reply(&replied),
+            Some(("per_1".to_string(), "always".to_string()))
+        );
+    }
+
+    #[test]
+    fn escalates_once_per_threshold() {
+        let policy = PermissionPolicy::default();
+        assert_eq!(due_escalation(&policy, 30_000, 0), None);
+        assert_eq!(due_escalation(&policy, 2 * MINUTE_MS, 0), Some(1));
+        assert_eq!(due_escalation(&policy, 2 * MINUTE_MS, 1), None);
+        assert_eq!(due_escalation(&policy, 6 * MINUTE_MS, 1), Some(2));
+        assert_eq!(due_escalation(&policy, 60 * MINUTE_MS, 2), None);
+    }
+}
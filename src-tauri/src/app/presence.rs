@@ -0,0 +1,230 @@
+// ============================================
+// User Presence (desktop only)
+// 读取系统级的用户空闲时间（最后一次键盘 / 鼠标输入），超过阈值时标记为离开，
+// 前端据此改为批量通知，通知转发（webhook 等）据此决定是否改推到手机
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+use tauri::{Emitter, Manager};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+pub const PRESENCE_SETTINGS_KEY: &str = "userPresence";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PresenceConfig {
+    /// 无输入多少分钟后视为离开
+    pub away_after_minutes: u32,
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            away_after_minutes: 5,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserPresence {
+    pub away: bool,
+    pub idle_ms: u64,
+    /// 当前平台 / 桌面环境能否读取空闲时间
+    pub supported: bool,
+}
+
+#[derive(Default)]
+pub struct PresenceState {
+    away: AtomicBool,
+    idle_ms: AtomicU64,
+    supported: AtomicBool,
+    pub config: Mutex<PresenceConfig>,
+}
+
+impl PresenceState {
+    pub fn is_away(&self) -> bool {
+        self.away.load(Ordering::SeqCst)
+    }
+
+    pub fn snapshot(&self) -> UserPresence {
+        UserPresence {
+            away: self.is_away(),
+            idle_ms: self.idle_ms.load(Ordering::SeqCst),
+            supported: self.supported.load(Ordering::SeqCst),
+        }
+    }
+
+    fn threshold_ms(&self) -> u64 {
+        let minutes = self
+            .config
+            .lock()
+            .map(|config| config.away_after_minutes)
+            .unwrap_or(5);
+        u64::from(minutes.max(1)) * 60 * 1000
+    }
+
+    /// 记录最新空闲时间，离开状态发生切换时返回新状态
+    fn update(&self, idle_ms: u64) -> Option<bool> {
+        self.idle_ms.store(idle_ms, Ordering::SeqCst);
+        let away = idle_ms >= self.threshold_ms();
+        (self.away.swap(away, Ordering::SeqCst) != away).then_some(away)
+    }
+}
+
+/// ioreg 输出中的 `"HIDIdleTime" = <纳秒>`
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_hid_idle_time(output: &str) -> Option<u64> {
+    let line = output
+        .lines()
+        .find(|line| line.contains("\"HIDIdleTime\""))?;
+    let nanos: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+    Some(nanos / 1_000_000)
+}
+
+/// gdbus 输出 `(uint64 12345,)`
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_gdbus_uint64(output: &str) -> Option<u64> {
+    output
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim_end_matches(',')
+        .strip_prefix("uint64 ")?
+        .parse()
+        .ok()
+}
+
+#[cfg(windows)]
+fn system_idle_ms() -> Option<u64> {
+    #[repr(C)]
+    struct LastInputInfo {
+        cb_size: u32,
+        dw_time: u32,
+    }
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn GetLastInputInfo(plii: *mut LastInputInfo) -> i32;
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTickCount() -> u32;
+    }
+
+    let mut info = LastInputInfo {
+        cb_size: std::mem::size_of::<LastInputInfo>() as u32,
+        dw_time: 0,
+    };
+    // SAFETY: info 是按 LASTINPUTINFO 布局初始化的有效指针
+    if unsafe { GetLastInputInfo(&mut info) } == 0 {
+        return None;
+    }
+    // 两者都是开机后的毫秒数（约 49 天回绕一次），用 wrapping_sub 处理回绕
+    let now = unsafe { GetTickCount() };
+    Some(u64::from(now.wrapping_sub(info.dw_time)))
+}
+
+#[cfg(target_os = "macos")]
+fn system_idle_ms() -> Option<u64> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()?;
+    parse_hid_idle_time(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(target_os = "linux")]
+fn system_idle_ms() -> Option<u64> {
+    use std::process::Command;
+
+    // GNOME（X11 与 Wayland 均可）
+    let mutter = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.gnome.Mutter.IdleMonitor",
+            "--object-path",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "--method",
+            "org.gnome.Mutter.IdleMonitor.GetIdletime",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_gdbus_uint64(&String::from_utf8_lossy(&output.stdout)));
+    if mutter.is_some() {
+        return mutter;
+    }
+
+    // 其它 X11 桌面：xprintidle 直接输出毫秒数
+    let output = Command::new("xprintidle").output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+fn system_idle_ms() -> Option<u64> {
+    None
+}
+
+pub fn spawn_presence_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let idle_ms = tauri::async_runtime::spawn_blocking(system_idle_ms)
+                .await
+                .ok()
+                .flatten();
+            let state = app.state::<PresenceState>();
+            let Some(idle_ms) = idle_ms else {
+                if !state.supported.load(Ordering::SeqCst) {
+                    log::info!("System idle time unavailable, away detection disabled");
+                    return;
+                }
+                // 曾经可用（例如桌面会话临时不可达），下一轮再试
+                tokio::time::sleep(CHECK_INTERVAL).await;
+                continue;
+            };
+            state.supported.store(true, Ordering::SeqCst);
+
+            if let Some(away) = state.update(idle_ms) {
+                log::info!(
+                    "User is now {} (idle {}s)",
+                    if away { "away" } else { "back" },
+                    idle_ms / 1000
+                );
+                let _ = app.emit("user-presence-changed", state.snapshot());
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_idle_outputs_and_tracks_transitions() {
+        let ioreg = "    | |   \"HIDIdleTime\" = 4523000000\n    | |   \"HIDKind\" = 1";
+        assert_eq!(parse_hid_idle_time(ioreg), Some(4523));
+        assert_eq!(parse_gdbus_uint64("(uint64 98765,)\n"), Some(98765));
+        assert_eq!(parse_gdbus_uint64("Error: no such name"), None);
+
+        let state = PresenceState::default();
+        assert_eq!(state.update(1_000), None);
+        assert_eq!(state.update(5 * 60 * 1000), Some(true));
+        assert_eq!(state.update(6 * 60 * 1000), None);
+        assert_eq!(state.update(200), Some(false));
+    }
+}
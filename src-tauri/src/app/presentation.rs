@@ -0,0 +1,55 @@
+// ============================================
+// Presentation Mode (desktop only)
+// 演示/kiosk 模式：全屏、放大、抑制通知，退出时恢复原窗口状态
+// ============================================
+
+use std::{collections::HashMap, sync::Mutex};
+
+/// 进入演示模式前的窗口状态
+#[derive(Clone, Debug)]
+pub struct SavedWindowGeometry {
+    pub size: tauri::PhysicalSize<u32>,
+    pub position: tauri::PhysicalPosition<i32>,
+    pub maximized: bool,
+    pub fullscreen: bool,
+}
+
+#[derive(Default)]
+pub struct PresentationState {
+    /// window label → 进入演示模式前的状态
+    saved: Mutex<HashMap<String, SavedWindowGeometry>>,
+}
+
+impl PresentationState {
+    pub fn enter(&self, label: &str, geometry: SavedWindowGeometry) -> bool {
+        let mut saved = self.saved.lock().expect("presentation state poisoned");
+        if saved.contains_key(label) {
+            return false;
+        }
+        saved.insert(label.to_string(), geometry);
+        true
+    }
+
+    pub fn exit(&self, label: &str) -> Option<SavedWindowGeometry> {
+        self.saved
+            .lock()
+            .expect("presentation state poisoned")
+            .remove(label)
+    }
+
+    pub fn is_presenting(&self, label: &str) -> bool {
+        self.saved
+            .lock()
+            .expect("presentation state poisoned")
+            .contains_key(label)
+    }
+
+    /// 任一窗口处于演示模式时，应抑制系统通知
+    pub fn any_presenting(&self) -> bool {
+        !self
+            .saved
+            .lock()
+            .expect("presentation state poisoned")
+            .is_empty()
+    }
+}
@@ -0,0 +1,144 @@
+// ============================================
+// App Profiles (desktop only)
+// `--profile <name>` 隔离设置、缓存、窗口状态与 webview 数据
+// ============================================
+
+use std::path::PathBuf;
+use tauri::Manager;
+
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// 切换器保存的上次选择（位于根配置目录，不随 profile 隔离）
+const ACTIVE_PROFILE_FILE: &str = "active-profile";
+
+/// 当前进程使用的 profile
+pub struct ActiveProfile {
+    name: String,
+}
+
+impl ActiveProfile {
+    pub fn new(name: String) -> Self {
+        Self { name }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_default(&self) -> bool {
+        self.name == DEFAULT_PROFILE
+    }
+}
+
+/// profile 名只允许字母、数字、`-`、`_`，避免路径穿越
+pub fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 32
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("invalid profile name '{}'", name))
+    }
+}
+
+/// 从命令行参数中提取 `--profile <name>` / `--profile=<name>`
+pub fn profile_from_args(args: &[String]) -> Option<String> {
+    let mut iter = args.iter().skip(1);
+    while let Some(arg) = iter.next() {
+        if arg == "--profile" {
+            return iter.next().cloned();
+        }
+        if let Some(name) = arg.strip_prefix("--profile=") {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+fn active_profile_path(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let dir = app.path().app_config_dir().ok()?;
+    Some(dir.join(ACTIVE_PROFILE_FILE))
+}
+
+/// 启动时确定 profile：命令行参数优先，其次是切换器保存的选择
+pub fn resolve_profile(app: &tauri::AppHandle, args: &[String]) -> String {
+    let candidate = profile_from_args(args).or_else(|| {
+        active_profile_path(app)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|name| name.trim().to_string())
+    });
+
+    match candidate {
+        Some(name) if validate_profile_name(&name).is_ok() => name,
+        Some(name) => {
+            log::warn!("Ignoring invalid profile '{}', using default", name);
+            DEFAULT_PROFILE.to_string()
+        }
+        None => DEFAULT_PROFILE.to_string(),
+    }
+}
+
+pub fn save_active_profile(app: &tauri::AppHandle, name: &str) -> Result<(), String> {
+    let path = active_profile_path(app).ok_or("app config dir unavailable")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, name).map_err(|e| e.to_string())
+}
+
+fn current_profile(app: &tauri::AppHandle) -> Option<String> {
+    app.try_state::<ActiveProfile>()
+        .filter(|profile| !profile.is_default())
+        .map(|profile| profile.name().to_string())
+}
+
+/// 当前 profile 的配置目录。默认 profile 保持原有路径，兼容旧数据。
+pub fn config_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let dir = app.path().app_config_dir().ok()?;
+    Some(match current_profile(app) {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    })
+}
+
+/// 当前 profile 的数据目录（缓存、日志等）
+pub fn data_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    let dir = app.path().app_data_dir().ok()?;
+    Some(match current_profile(app) {
+        Some(name) => dir.join("profiles").join(name),
+        None => dir,
+    })
+}
+
+/// 非默认 profile 的 webview 数据目录（localStorage、IndexedDB 等）；安全模式下使用临时目录
+pub fn webview_data_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    if let Some(dir) = crate::app::safe_mode::webview_data_dir(app) {
+        return Some(dir);
+    }
+    current_profile(app)?;
+    Some(data_dir(app)?.join("webview"))
+}
+
+/// 列出已存在的 profile
+pub fn list_profiles(app: &tauri::AppHandle) -> Vec<String> {
+    let mut names = vec![DEFAULT_PROFILE.to_string()];
+    let Ok(root) = app.path().app_config_dir() else {
+        return names;
+    };
+    if let Ok(entries) = std::fs::read_dir(root.join("profiles")) {
+        for entry in entries.flatten() {
+            if entry.path().is_dir() {
+                let name = entry.file_name().to_string_lossy().to_string();
+                if validate_profile_name(&name).is_ok() && name != DEFAULT_PROFILE {
+                    names.push(name);
+                }
+            }
+        }
+    }
+    names.sort();
+    names.dedup();
+    names
+}
@@ -0,0 +1,205 @@
+// ============================================
+// Project Registry (desktop only)
+// 记录通过应用创建/打开过的项目目录，持久化到 profile 配置目录
+// 只读项目同时从 fs 插件的作用域中排除，前端无法通过 fs 插件访问其中的文件；
+// fs 作用域没有撤销接口，取消只读后该限制在下次启动时解除
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+use tauri_plugin_fs::FsExt;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Project {
+    /// 由规范化路径派生，同一目录重复注册得到同一 id
+    pub id: String,
+    pub name: String,
+    pub path: String,
+    pub created_at: u64,
+    #[serde(default)]
+    pub last_opened_at: u64,
+    /// 创建时使用的模板（git URL 或 "scaffold"），手动添加的项目为空
+    #[serde(default)]
+    pub template: Option<String>,
+    /// 只读项目：agent 的写入 / 编辑 / 执行权限请求一律自动拒绝
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+/// 规范化路径（去掉 `..`、统一符号链接），目录不存在时原样返回
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let path = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    // Windows canonicalize 会加上 \\?\ 前缀，opencode 与前端都不认
+    match path.to_str().and_then(|raw| raw.strip_prefix(r"\\?\")) {
+        Some(stripped) => PathBuf::from(stripped),
+        None => path,
+    }
+}
+
+fn project_id(path: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+#[derive(Default)]
+pub struct ProjectsState {
+    projects: Mutex<Vec<Project>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl ProjectsState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("projects.json"));
+        let projects = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        Self {
+            projects: Mutex::new(projects),
+            path: Mutex::new(path),
+        }
+    }
+
+    pub fn list(&self) -> Vec<Project> {
+        self.projects
+            .lock()
+            .expect("projects state poisoned")
+            .clone()
+    }
+
+    pub fn get(&self, id: &str) -> Option<Project> {
+        self.projects
+            .lock()
+            .expect("projects state poisoned")
+            .iter()
+            .find(|project| project.id == id)
+            .cloned()
+    }
+
+    /// 注册目录为项目；已注册时只刷新最近打开时间（及模板信息）
+    pub fn register(&self, dir: &Path, template: Option<String>) -> Result<Project, String> {
+        let dir = normalize_path(dir);
+        if !dir.is_dir() {
+            return Err(format!("'{}' is not a directory", dir.display()));
+        }
+        let path = dir.to_string_lossy().to_string();
+        let id = project_id(&path);
+        let now = crate::app::now_millis();
+
+        let mut projects = self.projects.lock().expect("projects state poisoned");
+        let project = match projects.iter_mut().find(|project| project.id == id) {
+            Some(existing) => {
+                existing.last_opened_at = now;
+                if template.is_some() {
+                    existing.template = template;
+                }
+                existing.clone()
+            }
+            None => {
+                let project = Project {
+                    id,
+                    name: dir
+                        .file_name()
+                        .map(|name| name.to_string_lossy().to_string())
+                        .unwrap_or_else(|| path.clone()),
+                    path,
+                    created_at: now,
+                    last_opened_at: now,
+                    template,
+                    read_only: false,
+                };
+                projects.push(project.clone());
+                project
+            }
+        };
+        self.persist(&projects)?;
+        Ok(project)
+    }
+
+    pub fn set_read_only(&self, id: &str, read_only: bool) -> Result<Project, String> {
+        let mut projects = self.projects.lock().expect("projects state poisoned");
+        let project = projects
+            .iter_mut()
+            .find(|project| project.id == id)
+            .ok_or_else(|| format!("project '{}' not found", id))?;
+        project.read_only = read_only;
+        let project = project.clone();
+        self.persist(&projects)?;
+        Ok(project)
+    }
+
+    /// `path` 位于某个只读项目内时返回该项目
+    pub fn read_only_project(&self, path: &Path) -> Option<Project> {
+        let path = normalize_path(path);
+        self.projects
+            .lock()
+            .expect("projects state poisoned")
+            .iter()
+            .filter(|project| project.read_only)
+            .find(|project| path.starts_with(&project.path))
+            .cloned()
+    }
+
+    /// 整体替换（导入设置时使用）；id 按路径重新计算
+    pub fn replace_all(&self, mut new_projects: Vec<Project>) -> Result<(), String> {
+        for project in &mut new_projects {
+            project.id = project_id(&project.path);
+        }
+        let mut projects = self.projects.lock().expect("projects state poisoned");
+        *projects = new_projects;
+        self.persist(&projects)
+    }
+
+    /// 把所有只读项目从 fs 插件的作用域中排除（启动、导入后调用）
+    pub fn forbid_read_only(&self, app: &tauri::AppHandle) {
+        let projects = self.projects.lock().expect("projects state poisoned");
+        for project in projects.iter().filter(|project| project.read_only) {
+            forbid_in_fs_scope(app, project);
+        }
+    }
+
+    /// 仅从列表移除，不删除磁盘上的文件
+    pub fn remove(&self, id: &str) -> Result<bool, String> {
+        let mut projects = self.projects.lock().expect("projects state poisoned");
+        let before = projects.len();
+        projects.retain(|project| project.id != id);
+        let removed = projects.len() != before;
+        if removed {
+            self.persist(&projects)?;
+        }
+        Ok(removed)
+    }
+
+    fn persist(&self, projects: &[Project]) -> Result<(), String> {
+        let path = self.path.lock().expect("projects state poisoned").clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(projects).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// 禁止前端通过 fs 插件访问项目目录；安全模式下没有注册 fs 插件时跳过
+pub fn forbid_in_fs_scope(app: &tauri::AppHandle, project: &Project) {
+    let Some(scope) = app.try_fs_scope() else {
+        return;
+    };
+    if let Err(e) = scope.forbid_directory(&project.path, true) {
+        log::warn!(
+            "Failed to remove read-only project '{}' from the fs scope: {}",
+            project.name,
+            e
+        );
+    }
+}
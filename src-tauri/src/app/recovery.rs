@@ -0,0 +1,113 @@
+// ============================================
+// WebView Crash Recovery (desktop only)
+// 通过前端心跳检测渲染进程崩溃/卡死，自动 reload 并回放期间的 SSE 事件
+// ============================================
+
+use crate::app::bridge::BridgeState;
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+    time::Duration,
+};
+use tauri::{Emitter, Manager};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// 可见窗口超过该时长没有心跳即视为渲染进程已崩溃
+const HEARTBEAT_TIMEOUT_MS: u64 = 30_000;
+/// reload 后等待新页面恢复心跳的时间，期间不重复 reload
+const RELOAD_GRACE_MS: u64 = 60_000;
+
+#[derive(Default)]
+pub struct RecoveryState {
+    /// webview label → 最近一次心跳时间（只监控发送过心跳的 webview）
+    heartbeats: Mutex<HashMap<String, u64>>,
+    /// 正在恢复中的 webview
+    recovering: Mutex<HashSet<String>>,
+}
+
+impl RecoveryState {
+    /// 记录心跳；返回该 webview 是否刚从崩溃中恢复
+    pub fn heartbeat(&self, label: &str) -> bool {
+        self.heartbeats
+            .lock()
+            .expect("recovery state poisoned")
+            .insert(label.to_string(), crate::app::now_millis());
+        self.recovering
+            .lock()
+            .expect("recovery state poisoned")
+            .remove(label)
+    }
+
+    pub fn forget(&self, label: &str) {
+        self.heartbeats
+            .lock()
+            .expect("recovery state poisoned")
+            .remove(label);
+        self.recovering
+            .lock()
+            .expect("recovery state poisoned")
+            .remove(label);
+    }
+
+    fn stale_webviews(&self, now: u64) -> Vec<String> {
+        let heartbeats = self.heartbeats.lock().expect("recovery state poisoned");
+        heartbeats
+            .iter()
+            .filter(|(_, last)| now.saturating_sub(**last) > HEARTBEAT_TIMEOUT_MS)
+            .map(|(label, _)| label.clone())
+            .collect()
+    }
+}
+
+fn is_window_visible(webview: &tauri::Webview) -> bool {
+    let window = webview.window();
+    window.is_visible().unwrap_or(false) && !window.is_minimized().unwrap_or(false)
+}
+
+fn recover(app: &tauri::AppHandle, label: &str) {
+    let Some(webview) = app.get_webview(label) else {
+        app.state::<RecoveryState>().forget(label);
+        return;
+    };
+    // 隐藏/最小化的窗口会被系统节流定时器，不据此判断崩溃
+    if !is_window_visible(&webview) {
+        return;
+    }
+
+    let state = app.state::<RecoveryState>();
+    state
+        .recovering
+        .lock()
+        .expect("recovery state poisoned")
+        .insert(label.to_string());
+    // 推迟下一次检查，给新页面加载留出时间
+    state
+        .heartbeats
+        .lock()
+        .expect("recovery state poisoned")
+        .insert(
+            label.to_string(),
+            crate::app::now_millis() + RELOAD_GRACE_MS - HEARTBEAT_TIMEOUT_MS,
+        );
+
+    log::error!("WebView '{}' stopped responding, reloading", label);
+    app.state::<BridgeState>().start_buffering(label);
+    if let Err(e) = webview.reload() {
+        log::error!("Failed to reload WebView '{}': {}", label, e);
+    }
+    let _ = app.emit("webview-recovering", label);
+}
+
+pub fn spawn_recovery_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+            let stale = app
+                .state::<RecoveryState>()
+                .stale_webviews(crate::app::now_millis());
+            for label in stale {
+                recover(&app, &label);
+            }
+        }
+    });
+}
@@ -0,0 +1,205 @@
+// ============================================
+// Session Run Timers (desktop only)
+// 根据 bridge 上的 session.status / session.idle 事件记录每个会话运行的起止时间，
+// 用单调时钟计算耗时；后台窗口节流或页面重载都不影响显示的耗时
+// ============================================
+
+use crate::app::bridge::{event_data, BridgeState};
+use serde::Serialize;
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager};
+
+const TICK_INTERVAL: Duration = Duration::from_secs(1);
+/// 保留的已结束计时条数
+const MAX_FINISHED: usize = 500;
+
+#[derive(Clone, Debug)]
+struct RunTimer {
+    started: Instant,
+    /// 开始时的墙钟时间（毫秒时间戳），仅用于展示
+    started_at: u64,
+    /// 结束后固定下来的耗时
+    finished: Option<(u64, Duration)>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunElapsed {
+    pub session_id: String,
+    pub running: bool,
+    pub started_at: u64,
+    pub finished_at: Option<u64>,
+    pub elapsed_ms: u64,
+}
+
+impl RunTimer {
+    fn elapsed(&self, session_id: &str) -> RunElapsed {
+        let (finished_at, elapsed) = match self.finished {
+            Some((finished_at, elapsed)) => (Some(finished_at), elapsed),
+            None => (None, self.started.elapsed()),
+        };
+        RunElapsed {
+            session_id: session_id.to_string(),
+            running: self.finished.is_none(),
+            started_at: self.started_at,
+            finished_at,
+            elapsed_ms: elapsed.as_millis() as u64,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RunTimersState {
+    timers: Mutex<HashMap<String, RunTimer>>,
+}
+
+impl RunTimersState {
+    /// 会话开始运行；已在运行时保持原起点
+    fn start(&self, session_id: &str, now: Instant, wall: u64) {
+        let mut timers = self.timers.lock().expect("run timers poisoned");
+        let running = timers
+            .get(session_id)
+            .is_some_and(|timer| timer.finished.is_none());
+        if !running {
+            timers.insert(
+                session_id.to_string(),
+                RunTimer {
+                    started: now,
+                    started_at: wall,
+                    finished: None,
+                },
+            );
+        }
+    }
+
+    fn stop(&self, session_id: &str, now: Instant, wall: u64) {
+        let mut timers = self.timers.lock().expect("run timers poisoned");
+        if let Some(timer) = timers
+            .get_mut(session_id)
+            .filter(|timer| timer.finished.is_none())
+        {
+            timer.finished = Some((wall, now.duration_since(timer.started)));
+        }
+
+        let finished = timers.values().filter(|t| t.finished.is_some()).count();
+        if finished > MAX_FINISHED {
+            let oldest = timers
+                .iter()
+                .filter_map(|(id, timer)| Some((id, timer.finished?.0)))
+                .min_by_key(|(_, finished_at)| *finished_at)
+                .map(|(id, _)| id.clone());
+            if let Some(id) = oldest {
+                timers.remove(&id);
+            }
+        }
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<RunElapsed> {
+        self.timers
+            .lock()
+            .expect("run timers poisoned")
+            .get(session_id)
+            .map(|timer| timer.elapsed(session_id))
+    }
+
+    pub fn running(&self) -> Vec<RunElapsed> {
+        self.timers
+            .lock()
+            .expect("run timers poisoned")
+            .iter()
+            .filter(|(_, timer)| timer.finished.is_none())
+            .map(|(id, timer)| timer.elapsed(id))
+            .collect()
+    }
+}
+
+/// session.status（busy / retry / idle）与 session.idle → (会话 id, 是否运行中)
+fn run_change(event: &Value) -> Option<(String, bool)> {
+    let event = event.get("payload").unwrap_or(event);
+    let properties = &event["properties"];
+    let session_id = properties["sessionID"].as_str()?.to_string();
+    let running = match event["type"].as_str()? {
+        "session.status" => properties["status"]["type"].as_str()? != "idle",
+        "session.idle" => false,
+        _ => return None,
+    };
+    Some((session_id, running))
+}
+
+/// 注册 bridge 事件观察者，并在有会话运行时每秒发出 `run-timer-tick`
+pub fn install(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |_args, block| {
+            if !block.contains("\"session.status\"") && !block.contains("\"session.idle\"") {
+                return;
+            }
+            let Some((session_id, running)) = event_data(block)
+                .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+                .and_then(|event| run_change(&event))
+            else {
+                return;
+            };
+            let state = handle.state::<RunTimersState>();
+            let wall = crate::app::now_millis();
+            if running {
+                state.start(&session_id, Instant::now(), wall);
+            } else {
+                state.stop(&session_id, Instant::now(), wall);
+                if let Some(elapsed) = state.get(&session_id) {
+                    let _ = handle.emit("run-timer-stopped", elapsed);
+                }
+            }
+        }));
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK_INTERVAL).await;
+            let running = handle.state::<RunTimersState>().running();
+            if !running.is_empty() {
+                let _ = handle.emit("run-timer-tick", running);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tracks_runs_from_status_events() {
+        let busy = json!({
+            "type": "session.status",
+            "properties": { "sessionID": "s1", "status": { "type": "busy" } }
+        });
+        let wrapped_idle = json!({
+            "directory": "/repo",
+            "payload": { "type": "session.idle", "properties": { "sessionID": "s1" } }
+        });
+        assert_eq!(run_change(&busy), Some(("s1".to_string(), true)));
+        assert_eq!(run_change(&wrapped_idle), Some(("s1".to_string(), false)));
+
+        let state = RunTimersState::default();
+        let start = Instant::now();
+        state.start("s1", start, 1_000);
+        // 重复的 busy 不重置起点
+        state.start("s1", start + Duration::from_secs(5), 6_000);
+        assert_eq!(state.running().len(), 1);
+
+        state.stop("s1", start + Duration::from_secs(12), 13_000);
+        let elapsed = state.get("s1").unwrap();
+        assert!(!elapsed.running);
+        assert_eq!(elapsed.started_at, 1_000);
+        assert_eq!(elapsed.finished_at, Some(13_000));
+        assert_eq!(elapsed.elapsed_ms, 12_000);
+        assert!(state.running().is_empty());
+    }
+}
@@ -0,0 +1,151 @@
+// ============================================
+// Safe Mode & Crash Reports (desktop only)
+// `--safe-mode` 启动：不运行后台任务、事件观察者与托盘等扩展功能，不恢复窗口位置、模型目录缓存，
+// 不注册剪贴板、通知、文件系统与 decorum 插件（使用系统标题栏）；
+// 权限中继（只读项目自动拒绝、权限超时）与网络 / DNS 设置照常生效，
+// 前端使用全新的 webview 数据目录（忽略 localStorage 缓存），由前端据此跳过自动启动服务；
+// 同时记录 panic 与非正常退出，供安全模式下展示最近一次崩溃信息
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+pub const SAFE_MODE_FLAG: &str = "--safe-mode";
+const CRASH_FILE: &str = "last-crash.json";
+/// 运行期间存在，正常退出时删除；启动时仍存在说明上次没有正常退出
+const RUNNING_MARKER: &str = "running.marker";
+/// 安全模式下前端使用的临时 webview 数据目录，每次进入安全模式时清空
+const SAFE_WEBVIEW_DIR: &str = "safe-mode-webview";
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn requested(args: &[String]) -> bool {
+    args.iter().skip(1).any(|arg| arg == SAFE_MODE_FLAG)
+}
+
+pub fn set_active(active: bool) {
+    ACTIVE.store(active, Ordering::SeqCst);
+}
+
+pub fn active() -> bool {
+    ACTIVE.load(Ordering::SeqCst)
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub at: u64,
+    pub message: String,
+    pub location: Option<String>,
+    pub thread: Option<String>,
+    pub version: String,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeInfo {
+    pub active: bool,
+    pub last_crash: Option<CrashReport>,
+    /// 上次运行没有正常退出（包括被强制结束、系统崩溃等没有 panic 记录的情况）
+    pub unclean_exit: bool,
+}
+
+pub struct SafeModeState {
+    info: SafeModeInfo,
+    data_dir: Option<PathBuf>,
+}
+
+impl SafeModeState {
+    pub fn info(&self) -> SafeModeInfo {
+        self.info.clone()
+    }
+
+    /// 清除已查看的崩溃记录
+    pub fn clear_crash(&self) -> Result<(), String> {
+        let Some(path) = self.data_dir.as_ref().map(|dir| dir.join(CRASH_FILE)) else {
+            return Ok(());
+        };
+        match std::fs::remove_file(path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|message| message.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// 记录 panic 到数据目录，再交给原有的 hook
+fn install_panic_hook(path: PathBuf, version: String) {
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let report = CrashReport {
+            at: crate::app::now_millis(),
+            message: panic_message(info.payload()),
+            location: info.location().map(|location| location.to_string()),
+            thread: std::thread::current().name().map(str::to_string),
+            version: version.clone(),
+        };
+        if let Ok(data) = serde_json::to_string_pretty(&report) {
+            let _ = std::fs::write(&path, data);
+        }
+        previous(info);
+    }));
+}
+
+/// 读取上次的崩溃信息、写入运行标记并安装 panic hook
+pub fn begin_session(app: &tauri::AppHandle) -> SafeModeState {
+    let data_dir = crate::app::profile::data_dir(app);
+    let mut info = SafeModeInfo {
+        active: active(),
+        last_crash: None,
+        unclean_exit: false,
+    };
+
+    if let Some(dir) = data_dir.as_ref() {
+        info.last_crash = std::fs::read_to_string(dir.join(CRASH_FILE))
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok());
+        let marker = dir.join(RUNNING_MARKER);
+        info.unclean_exit = marker.exists();
+        let _ = std::fs::create_dir_all(dir);
+        let _ = std::fs::write(&marker, crate::app::now_millis().to_string());
+
+        if info.active {
+            let _ = std::fs::remove_dir_all(dir.join(SAFE_WEBVIEW_DIR));
+        }
+        install_panic_hook(dir.join(CRASH_FILE), app.package_info().version.to_string());
+    }
+
+    if info.active {
+        log::warn!(
+            "Starting in safe mode (last crash: {:?}, unclean exit: {})",
+            info.last_crash.as_ref().map(|crash| &crash.message),
+            info.unclean_exit
+        );
+    }
+    SafeModeState { info, data_dir }
+}
+
+/// 正常退出时删除运行标记
+pub fn end_session(app: &tauri::AppHandle) {
+    if let Some(dir) = crate::app::profile::data_dir(app) {
+        let _ = std::fs::remove_file(dir.join(RUNNING_MARKER));
+    }
+}
+
+/// 安全模式下的 webview 数据目录
+pub fn webview_data_dir(app: &tauri::AppHandle) -> Option<PathBuf> {
+    if !active() {
+        return None;
+    }
+    Some(crate::app::profile::data_dir(app)?.join(SAFE_WEBVIEW_DIR))
+}
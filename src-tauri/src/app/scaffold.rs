@@ -0,0 +1,248 @@
+// ============================================
+// Project Scaffolding (desktop only)
+// 从 git 模板仓库克隆，或 git init 并写入起始文件，过程通过回调汇报进度
+// ============================================
+
+use crate::app::git;
+use serde::{Deserialize, Serialize};
+use std::{io::Read, path::Path, process::Stdio};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ProjectTemplate {
+    /// 克隆模板仓库；默认丢弃模板历史，以全新仓库开始
+    Git {
+        url: String,
+        #[serde(default)]
+        branch: Option<String>,
+        #[serde(default)]
+        keep_history: bool,
+    },
+    /// 空项目：git init + README / .gitignore / AGENTS.md
+    Scaffold,
+}
+
+impl ProjectTemplate {
+    /// 记录在项目注册表中的模板描述
+    pub fn label(&self) -> String {
+        match self {
+            Self::Git { url, .. } => url.clone(),
+            Self::Scaffold => "scaffold".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CreateStage {
+    Cloning,
+    Initializing,
+    Writing,
+    Registering,
+    Done,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateProgress {
+    pub destination: String,
+    pub stage: CreateStage,
+    pub message: String,
+    /// git 输出中的百分比（仅克隆阶段）
+    pub percent: Option<u8>,
+}
+
+/// 目标必须不存在或为空目录，避免覆盖已有文件
+pub fn check_destination(dest: &Path) -> Result<(), String> {
+    if !dest.exists() {
+        return Ok(());
+    }
+    if !dest.is_dir() {
+        return Err(format!("'{}' already exists", dest.display()));
+    }
+    let mut entries = std::fs::read_dir(dest).map_err(|e| e.to_string())?;
+    if entries.next().is_some() {
+        return Err(format!("'{}' is not empty", dest.display()));
+    }
+    Ok(())
+}
+
+/// 解析 `git clone --progress` 的一行，如 `Receiving objects:  45% (450/1000)`
+pub fn parse_git_progress(line: &str) -> Option<(String, Option<u8>)> {
+    let line = line.trim().trim_start_matches("remote:").trim();
+    if line.is_empty() {
+        return None;
+    }
+    let percent = line.split_once('%').and_then(|(head, _)| {
+        let digits = head.rsplit(|c: char| !c.is_ascii_digit()).next()?;
+        digits.parse::<u8>().ok()
+    });
+    let message = match line.split_once(':') {
+        Some((phase, _)) if percent.is_some() => phase.trim().to_string(),
+        _ => line.to_string(),
+    };
+    Some((message, percent))
+}
+
+fn clone_template(
+    url: &str,
+    branch: Option<&str>,
+    dest: &Path,
+    progress: &mut dyn FnMut(CreateStage, String, Option<u8>),
+) -> Result<(), String> {
+    let parent = dest
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+
+    let mut cmd = git::command();
+    cmd.args(["clone", "--progress", "--depth", "1"]);
+    if let Some(branch) = branch {
+        cmd.args(["--branch", branch]);
+    }
+    let mut child = cmd
+        .arg(url)
+        .arg(dest)
+        .current_dir(parent)
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+
+    // git 用 \r 刷新同一行进度，按 \r / \n 切分
+    let mut stderr = child.stderr.take().ok_or("git stderr unavailable")?;
+    let mut line = Vec::new();
+    let mut last_line = String::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = stderr.read(&mut buf).map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        for &byte in &buf[..n] {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            let text = String::from_utf8_lossy(&line).to_string();
+            line.clear();
+            if let Some((message, percent)) = parse_git_progress(&text) {
+                progress(CreateStage::Cloning, message, percent);
+                last_line = text;
+            }
+        }
+    }
+
+    let status = child.wait().map_err(|e| e.to_string())?;
+    if !status.success() {
+        let _ = std::fs::remove_dir_all(dest);
+        return Err(format!("git clone failed: {}", last_line.trim()));
+    }
+    Ok(())
+}
+
+/// 删除 .git；Windows 上 git 对象文件是只读的，需先去掉只读属性
+fn remove_git_dir(dir: &Path) -> std::io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            remove_git_dir(&path)?;
+            continue;
+        }
+        let mut permissions = std::fs::metadata(&path)?.permissions();
+        if permissions.readonly() {
+            #[allow(clippy::permissions_set_readonly_false)]
+            permissions.set_readonly(false);
+            std::fs::set_permissions(&path, permissions)?;
+        }
+    }
+    std::fs::remove_dir_all(dir)
+}
+
+fn starter_files(name: &str) -> [(&'static str, String); 3] {
+    [
+        ("README.md", format!("# {}\n", name)),
+        (
+            ".gitignore",
+            "node_modules/\ntarget/\ndist/\n.env\n.DS_Store\n".to_string(),
+        ),
+        (
+            "AGENTS.md",
+            format!(
+                "# {}\n\nDescribe the project, its conventions and how to build and test it here.\nopencode reads this file as project instructions.\n",
+                name
+            ),
+        ),
+    ]
+}
+
+/// 创建项目目录，`progress` 在每个阶段（以及 git 的进度输出）被调用
+pub fn create_project(
+    template: &ProjectTemplate,
+    dest: &Path,
+    progress: &mut dyn FnMut(CreateStage, String, Option<u8>),
+) -> Result<(), String> {
+    check_destination(dest)?;
+
+    match template {
+        ProjectTemplate::Git {
+            url,
+            branch,
+            keep_history,
+        } => {
+            progress(CreateStage::Cloning, format!("Cloning {}", url), None);
+            clone_template(url, branch.as_deref(), dest, progress)?;
+            if !keep_history {
+                progress(
+                    CreateStage::Initializing,
+                    "Starting fresh git history".to_string(),
+                    None,
+                );
+                remove_git_dir(&dest.join(".git")).map_err(|e| e.to_string())?;
+                git::run(&["init"], dest)?;
+            }
+        }
+        ProjectTemplate::Scaffold => {
+            std::fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+            progress(
+                CreateStage::Initializing,
+                "Initializing git repository".to_string(),
+                None,
+            );
+            git::run(&["init"], dest)?;
+
+            let name = dest
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_else(|| "New Project".to_string());
+            for (file, contents) in starter_files(&name) {
+                progress(CreateStage::Writing, format!("Writing {}", file), None);
+                std::fs::write(dest.join(file), contents).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_git_progress_lines() {
+        assert_eq!(
+            parse_git_progress("Receiving objects:  45% (450/1000), 1.2 MiB | 3 MiB/s"),
+            Some(("Receiving objects".to_string(), Some(45)))
+        );
+        assert_eq!(
+            parse_git_progress("remote: Counting objects: 100% (12/12), done."),
+            Some(("Counting objects".to_string(), Some(100)))
+        );
+        assert_eq!(
+            parse_git_progress("Cloning into 'demo'..."),
+            Some(("Cloning into 'demo'...".to_string(), None))
+        );
+        assert_eq!(parse_git_progress("   "), None);
+    }
+}
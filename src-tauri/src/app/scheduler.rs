@@ -0,0 +1,408 @@
+// ============================================
+// Prompt Scheduler (desktop only)
+// 定时向指定项目发送 prompt，持久化计划并记录每次运行结果
+// ============================================
+
+use crate::app::api::{self, ServerTarget};
+use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Mutex, time::Duration};
+use tauri::{Emitter, Manager};
+
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+const MINUTE_MS: u64 = 60 * 1000;
+/// 后台检查间隔；系统休眠唤醒后会在下一次检查时补跑
+const TICK: Duration = Duration::from_secs(30);
+/// 每个计划保留的运行记录条数
+const MAX_HISTORY: usize = 20;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum ScheduleSpec {
+    /// 每隔 N 分钟
+    Interval { minutes: u32 },
+    /// 每天固定时间，可限定星期（0 = 周日）；时间按 `utc_offset_minutes` 换算本地时间
+    Daily {
+        hour: u8,
+        minute: u8,
+        #[serde(default)]
+        weekdays: Vec<u8>,
+        #[serde(default)]
+        utc_offset_minutes: i32,
+    },
+}
+
+impl ScheduleSpec {
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Self::Interval { minutes } if *minutes == 0 => {
+                Err("interval must be at least 1 minute".to_string())
+            }
+            Self::Daily { hour, minute, .. } if *hour > 23 || *minute > 59 => {
+                Err(format!("invalid time {:02}:{:02}", hour, minute))
+            }
+            Self::Daily { weekdays, .. } if weekdays.iter().any(|day| *day > 6) => {
+                Err("weekdays must be between 0 (Sunday) and 6".to_string())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// `after` 之后的下一次触发时间（Unix 毫秒）
+    pub fn next_run(&self, after: u64) -> u64 {
+        match self {
+            Self::Interval { minutes } => after + u64::from(*minutes).max(1) * MINUTE_MS,
+            Self::Daily {
+                hour,
+                minute,
+                weekdays,
+                utc_offset_minutes,
+            } => {
+                let offset = i64::from(*utc_offset_minutes) * MINUTE_MS as i64;
+                let local = (after as i64 + offset).max(0) as u64;
+                let day_start = local - local % DAY_MS;
+                let time_of_day = (u64::from(*hour) * 60 + u64::from(*minute)) * MINUTE_MS;
+
+                let mut candidate = day_start + time_of_day;
+                for _ in 0..8 {
+                    // 1970-01-01 是周四
+                    let weekday = ((candidate / DAY_MS + 4) % 7) as u8;
+                    if candidate > local && (weekdays.is_empty() || weekdays.contains(&weekday)) {
+                        break;
+                    }
+                    candidate += DAY_MS;
+                }
+                (candidate as i64 - offset).max(0) as u64
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleRun {
+    pub started_at: u64,
+    pub ok: bool,
+    pub session_id: Option<String>,
+    pub error: Option<String>,
+    /// 因休眠/关机错过计划时间后补跑
+    pub catch_up: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Schedule {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub spec: ScheduleSpec,
+    pub target: ServerTarget,
+    pub prompt: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// 复用已有会话；为空时每次运行新建会话
+    #[serde(default)]
+    pub session_id: Option<String>,
+    /// 错过的运行（如电脑休眠）是否在唤醒后补跑一次
+    #[serde(default = "default_true")]
+    pub run_missed: bool,
+    #[serde(default)]
+    pub next_run_at: u64,
+    #[serde(default)]
+    pub history: Vec<ScheduleRun>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Schedule {
+    /// 计划以明文保存在 schedules.json，不保存 `auth_header`：
+    /// 需要认证的服务器须设置 `server_id`，运行时从钥匙串取凭据
+    fn strip_credentials(&mut self) -> Result<(), String> {
+        self.target.strip_auth_header()
+    }
+}
+
+#[derive(Default)]
+pub struct SchedulerState {
+    schedules: Mutex<Vec<Schedule>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl SchedulerState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("schedules.json"));
+        let mut schedules: Vec<Schedule> = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        // 旧版本会把 auth_header 写进文件：清除，无法改用钥匙串的计划停用
+        let mut scrubbed = false;
+        for schedule in &mut schedules {
+            if schedule.target.auth_header.is_none() {
+                continue;
+            }
+            scrubbed = true;
+            if let Err(e) = schedule.strip_credentials() {
+                log::warn!("Disabling schedule '{}': {}", schedule.name, e);
+                schedule.enabled = false;
+            }
+        }
+
+        let state = Self {
+            schedules: Mutex::new(schedules),
+            path: Mutex::new(path),
+        };
+        if scrubbed {
+            let schedules = state.schedules.lock().expect("scheduler state poisoned");
+            if let Err(e) = state.persist(&schedules) {
+                log::warn!("Failed to rewrite schedules.json: {}", e);
+            }
+        }
+        state
+    }
+
+    pub fn list(&self) -> Vec<Schedule> {
+        self.schedules
+            .lock()
+            .expect("scheduler state poisoned")
+            .clone()
+    }
+
+    pub fn upsert(&self, mut schedule: Schedule) -> Result<Schedule, String> {
+        schedule.spec.validate()?;
+        if schedule.prompt.trim().is_empty() {
+            return Err("prompt must not be empty".to_string());
+        }
+        schedule.strip_credentials()?;
+        schedule.next_run_at = schedule.spec.next_run(crate::app::now_millis());
+
+        let mut schedules = self.schedules.lock().expect("scheduler state poisoned");
+        match schedules.iter_mut().find(|item| item.id == schedule.id) {
+            Some(existing) => {
+                schedule.history = std::mem::take(&mut existing.history);
+                *existing = schedule.clone();
+            }
+            None => schedules.push(schedule.clone()),
+        }
+        self.persist(&schedules)?;
+        Ok(schedule)
+    }
+
+    /// 整体替换（导入设置时使用），重新计算下一次触发时间
+    pub fn replace_all(&self, mut new_schedules: Vec<Schedule>) -> Result<(), String> {
+        let now = crate::app::now_millis();
+        for schedule in &mut new_schedules {
+            schedule.spec.validate()?;
+            schedule.strip_credentials()?;
+            schedule.next_run_at = schedule.spec.next_run(now);
+        }
+        let mut schedules = self.schedules.lock().expect("scheduler state poisoned");
+        *schedules = new_schedules;
+        self.persist(&schedules)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool, String> {
+        let mut schedules = self.schedules.lock().expect("scheduler state poisoned");
+        let before = schedules.len();
+        schedules.retain(|schedule| schedule.id != id);
+        let removed = schedules.len() != before;
+        if removed {
+            self.persist(&schedules)?;
+        }
+        Ok(removed)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Schedule> {
+        self.schedules
+            .lock()
+            .expect("scheduler state poisoned")
+            .iter()
+            .find(|schedule| schedule.id == id)
+            .cloned()
+    }
+
+    /// 取出到期的计划，并推进它们的下一次触发时间。返回 (计划, 是否补跑)。
+    fn take_due(&self, now: u64) -> Vec<(Schedule, bool)> {
+        let mut schedules = self.schedules.lock().expect("scheduler state poisoned");
+        let mut due = Vec::new();
+
+        for schedule in schedules.iter_mut().filter(|schedule| schedule.enabled) {
+            if schedule.next_run_at == 0 {
+                schedule.next_run_at = schedule.spec.next_run(now);
+                continue;
+            }
+            if schedule.next_run_at > now {
+                continue;
+            }
+
+            // 超过两个检查周期才算“错过”（例如电脑休眠期间）
+            let catch_up = now - schedule.next_run_at > 2 * TICK.as_millis() as u64;
+            schedule.next_run_at = schedule.spec.next_run(now);
+            if catch_up && !schedule.run_missed {
+                log::info!("Skipping missed run of schedule '{}'", schedule.name);
+                continue;
+            }
+            due.push((schedule.clone(), catch_up));
+        }
+
+        if !due.is_empty() {
+            let _ = self.persist(&schedules);
+        }
+        due
+    }
+
+    fn record_run(&self, id: &str, run: ScheduleRun) {
+        let mut schedules = self.schedules.lock().expect("scheduler state poisoned");
+        if let Some(schedule) = schedules.iter_mut().find(|schedule| schedule.id == id) {
+            schedule.history.insert(0, run);
+            schedule.history.truncate(MAX_HISTORY);
+        }
+        let _ = self.persist(&schedules);
+    }
+
+    fn persist(&self, schedules: &[Schedule]) -> Result<(), String> {
+        let path = self.path.lock().expect("scheduler state poisoned").clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(schedules).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// 执行一次计划并记录结果，同时发出 `schedule-run` 事件
+pub async fn run_schedule(
+    app: &tauri::AppHandle,
+    schedule: Schedule,
+    catch_up: bool,
+) -> ScheduleRun {
+    let started_at = crate::app::now_millis();
+    log::info!("Running schedule '{}'", schedule.name);
+
+    let result = api::send_prompt(
+        &schedule.target,
+        schedule.session_id.as_deref(),
+        &schedule.prompt,
+        schedule.model.as_deref(),
+        schedule.agent.as_deref(),
+    )
+    .await;
+
+    let run = match result {
+        Ok(session_id) => ScheduleRun {
+            started_at,
+            ok: true,
+            session_id: Some(session_id),
+            error: None,
+            catch_up,
+        },
+        Err(error) => {
+            log::warn!("Schedule '{}' failed: {}", schedule.name, error);
+            ScheduleRun {
+                started_at,
+                ok: false,
+                session_id: None,
+                error: Some(error),
+                catch_up,
+            }
+        }
+    };
+
+    app.state::<SchedulerState>()
+        .record_run(&schedule.id, run.clone());
+    let _ = app.emit(
+        "schedule-run",
+        serde_json::json!({ "scheduleId": schedule.id, "run": run }),
+    );
+    run
+}
+
+/// 启动后台调度循环
+pub fn spawn_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(TICK).await;
+
+            let due = app
+                .state::<SchedulerState>()
+                .take_due(crate::app::now_millis());
+            for (schedule, catch_up) in due {
+                let app = app.clone();
+                tauri::async_runtime::spawn(async move {
+                    run_schedule(&app, schedule, catch_up).await;
+                });
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Schedule, ScheduleSpec, DAY_MS, MINUTE_MS};
+
+    #[test]
+    fn daily_next_run_rolls_to_next_matching_weekday() {
+        // 1970-01-05 00:00 UTC 是周一
+        let monday = 4 * DAY_MS;
+        let spec = ScheduleSpec::Daily {
+            hour: 2,
+            minute: 0,
+            weekdays: vec![3],
+            utc_offset_minutes: 0,
+        };
+
+        assert_eq!(spec.next_run(monday), monday + 2 * DAY_MS + 120 * MINUTE_MS);
+    }
+
+    #[test]
+    fn daily_next_run_applies_utc_offset() {
+        let spec = ScheduleSpec::Daily {
+            hour: 8,
+            minute: 0,
+            weekdays: Vec::new(),
+            utc_offset_minutes: 8 * 60,
+        };
+
+        // 本地 08:00 (UTC+8) 即 UTC 00:00
+        assert_eq!(spec.next_run(10 * DAY_MS - 1), 10 * DAY_MS);
+    }
+
+    #[test]
+    fn schedules_never_keep_auth_headers() {
+        let schedule = |target: serde_json::Value| -> Schedule {
+            serde_json::from_value(serde_json::json!({
+                "id": "s",
+                "name": "nightly",
+                "enabled": true,
+                "spec": { "kind": "interval", "minutes": 60 },
+                "target": target,
+                "prompt": "run the tests",
+            }))
+            .unwrap()
+        };
+
+        // 有服务器 id 时凭据来自钥匙串，丢弃明文
+        let mut with_id = schedule(serde_json::json!({
+            "url": "http://localhost:4096",
+            "authHeader": "Bearer secret",
+            "serverId": "work",
+        }));
+        assert!(with_id.strip_credentials().is_ok());
+        assert_eq!(with_id.target.auth_header, None);
+        assert!(!serde_json::to_string(&with_id).unwrap().contains("secret"));
+
+        // 只有明文凭据：拒绝
+        let mut without_id = schedule(serde_json::json!({
+            "url": "http://localhost:4096",
+            "authHeader": "Bearer secret",
+        }));
+        assert!(without_id.strip_credentials().is_err());
+        assert_eq!(without_id.target.auth_header, None);
+    }
+}
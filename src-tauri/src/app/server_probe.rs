@@ -0,0 +1,205 @@
+// ============================================
+// Remote Server Probe (desktop only)
+// 给定主机名，尝试常见的协议 / 端口组合，检查健康状态与认证要求，
+// 返回按可用性排序的候选地址，供“连接到我的服务器”向导使用
+// ============================================
+
+use futures_util::future::join_all;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// opencode serve 默认端口，以及反向代理常用端口（None 为协议默认端口）
+const COMMON_PORTS: &[Option<u16>] = &[Some(4096), None, Some(8080), Some(3000), Some(4097)];
+const PROBE_TIMEOUT: Duration = Duration::from_secs(4);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProbeCandidate {
+    pub url: String,
+    pub healthy: bool,
+    pub version: Option<String>,
+    /// 服务器返回 401，需要认证
+    pub requires_auth: bool,
+    /// 提供了认证头时，认证是否通过
+    pub auth_ok: Option<bool>,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub score: i64,
+}
+
+/// 根据用户输入生成候选地址。输入已含协议或端口时只在缺失的部分上展开。
+pub fn candidate_urls(input: &str) -> Vec<String> {
+    let input = input.trim().trim_end_matches('/');
+    let (schemes, rest): (Vec<&str>, &str) = match input.split_once("://") {
+        Some((scheme, rest)) => (vec![scheme], rest),
+        None => (vec!["https", "http"], input),
+    };
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, ""),
+    };
+    if authority.is_empty() {
+        return Vec::new();
+    }
+
+    // IPv6 字面量的冒号不是端口分隔符
+    let has_port = match authority.rfind(':') {
+        Some(index) => !authority.ends_with(']') && authority[index + 1..].parse::<u16>().is_ok(),
+        None => false,
+    };
+
+    let mut urls = Vec::new();
+    for scheme in schemes {
+        if has_port {
+            urls.push(format!("{}://{}{}", scheme, authority, path));
+            continue;
+        }
+        for port in COMMON_PORTS {
+            let url = match port {
+                Some(port) => format!("{}://{}:{}{}", scheme, authority, port, path),
+                None => format!("{}://{}{}", scheme, authority, path),
+            };
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+    }
+    urls
+}
+
+fn score(candidate: &ProbeCandidate) -> i64 {
+    let mut score = 0;
+    if candidate.healthy {
+        score += 100;
+    }
+    match (candidate.requires_auth, candidate.auth_ok) {
+        (false, _) | (true, Some(true)) => score += 50,
+        (true, None) => score += 20,
+        (true, Some(false)) => {}
+    }
+    if candidate.url.starts_with("https://") {
+        score += 10;
+    }
+    score - (candidate.latency_ms / 100) as i64
+}
+
+async fn probe_one(
+    client: &reqwest::Client,
+    url: String,
+    auth_header: Option<&str>,
+) -> Option<ProbeCandidate> {
+    let health = format!("{}/global/health", url);
+    let started = Instant::now();
+    let response = client
+        .get(&health)
+        .timeout(PROBE_TIMEOUT)
+        .send()
+        .await
+        .ok()?;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    let mut candidate = ProbeCandidate {
+        url,
+        healthy: false,
+        version: None,
+        requires_auth: false,
+        auth_ok: None,
+        latency_ms,
+        error: None,
+        score: 0,
+    };
+
+    let mut response = Some(response);
+    if response.as_ref().map(|r| r.status()) == Some(reqwest::StatusCode::UNAUTHORIZED) {
+        candidate.requires_auth = true;
+        response = match auth_header {
+            Some(auth) => {
+                let retry = client
+                    .get(&health)
+                    .header("Authorization", auth)
+                    .timeout(PROBE_TIMEOUT)
+                    .send()
+                    .await
+                    .ok();
+                let ok = retry.as_ref().is_some_and(|r| r.status().is_success());
+                candidate.auth_ok = Some(ok);
+                retry.filter(|_| ok)
+            }
+            None => None,
+        };
+    }
+
+    match response {
+        Some(response) if response.status().is_success() => {
+            let body = response.bytes().await.unwrap_or_default();
+            match serde_json::from_slice::<serde_json::Value>(&body) {
+                Ok(json) if json["healthy"].as_bool().unwrap_or(false) => {
+                    candidate.healthy = true;
+                    candidate.version = json["version"].as_str().map(str::to_string);
+                }
+                // 端口上是别的服务（例如网页服务器返回 HTML）
+                _ => return None,
+            }
+        }
+        Some(response) => {
+            candidate.error = Some(format!("health check returned {}", response.status()));
+        }
+        None if candidate.auth_ok == Some(false) => {
+            candidate.error = Some("authentication failed".to_string());
+        }
+        None => {}
+    }
+
+    candidate.score = score(&candidate);
+    Some(candidate)
+}
+
+/// 并发探测所有候选地址，只返回有 opencode 服务响应的地址（含需要认证的）
+pub async fn probe_host(
+    input: &str,
+    auth_header: Option<&str>,
+) -> Result<Vec<ProbeCandidate>, String> {
+    let urls = candidate_urls(input);
+    if urls.is_empty() {
+        return Err("enter a hostname or URL".to_string());
+    }
+    let client = crate::app::dns::client_builder()
+        .connect_timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| format!("failed to create HTTP client: {}", e))?;
+
+    let mut candidates: Vec<ProbeCandidate> = join_all(
+        urls.into_iter()
+            .map(|url| probe_one(&client, url, auth_header)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+    candidates.sort_by(|a, b| b.score.cmp(&a.score));
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_missing_scheme_and_port() {
+        let urls = candidate_urls("my-server.lan");
+        assert_eq!(urls[0], "https://my-server.lan:4096");
+        assert!(urls.contains(&"http://my-server.lan".to_string()));
+        assert_eq!(urls.len(), 2 * COMMON_PORTS.len());
+
+        assert_eq!(
+            candidate_urls("http://10.0.0.2:5000/opencode/"),
+            vec!["http://10.0.0.2:5000/opencode".to_string()]
+        );
+        assert_eq!(
+            candidate_urls("example.com/oc"),
+            candidate_urls("example.com/oc/")
+        );
+        assert_eq!(candidate_urls("https://[::1]")[0], "https://[::1]:4096");
+        assert!(candidate_urls("  ").is_empty());
+    }
+}
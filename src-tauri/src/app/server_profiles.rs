@@ -0,0 +1,210 @@
+// ============================================
+// Server Profiles (desktop only)
+// 命名的服务器配置档，保存在 profile 配置目录的 server-profiles.json：
+// - 地址、代理 / TLS 覆盖、mTLS 客户端证书与托管服务的启动参数
+// - 配置档 id 即服务器 id：认证信息按该 id 存在系统钥匙串（见 credentials），
+//   bridge 与 API 调用传入该 id 即使用对应的凭据、证书与网络设置
+// - 按配置档启动的托管服务以配置档 id 作为实例 id
+// - 固定（pinned）的配置档组成工作区，可随应用启动一起启动（见 workspace_autolaunch）
+// ============================================
+
+use crate::app::{
+    container::ContainerTarget,
+    credentials,
+    network::{self, ClientIdentity, NetworkConfig},
+    wsl::WslTarget,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+
+/// 托管服务的启动参数，对应 start_opencode_service 的参数
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ManagedServiceProfile {
+    pub binary_path: String,
+    pub env_vars: HashMap<String, String>,
+    pub env_file: Option<String>,
+    pub extra_args: Vec<String>,
+    pub cwd: Option<String>,
+    pub wsl: Option<WslTarget>,
+    pub container: Option<ContainerTarget>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerProfile {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    /// 代理与 TLS 设置；None 沿用全局网络设置
+    #[serde(default)]
+    pub network: Option<NetworkConfig>,
+    #[serde(default)]
+    pub client_identity: Option<ClientIdentity>,
+    /// 由应用启动的服务；None 表示只连接已有的服务器
+    #[serde(default)]
+    pub managed: Option<ManagedServiceProfile>,
+    /// 加入工作区：开启工作区自动启动时随应用启动托管服务
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl ServerProfile {
+    fn validate(&self) -> Result<(), String> {
+        let id = self.id.trim();
+        if id.is_empty() || id.len() > 64 {
+            return Err("profile id must be 1-64 characters".to_string());
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(format!(
+                "profile id '{}' may only contain letters, digits, '-', '_' and '.'",
+                id
+            ));
+        }
+        if self.name.trim().is_empty() {
+            return Err("profile name is empty".to_string());
+        }
+        let url = reqwest::Url::parse(self.url.trim())
+            .map_err(|e| format!("invalid server URL '{}': {}", self.url, e))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(format!("unsupported server URL scheme '{}'", url.scheme()));
+        }
+        Ok(())
+    }
+
+    /// 把网络覆盖与客户端证书挂到该服务器 id 上
+    fn apply_network(&self) -> Result<(), String> {
+        network::set_server_network(&self.id, self.network.as_ref())?;
+        network::set_identity(&self.id, self.client_identity.as_ref())
+    }
+}
+
+#[derive(Default)]
+pub struct ServerProfilesState {
+    profiles: Mutex<Vec<ServerProfile>>,
+    path: Option<PathBuf>,
+}
+
+impl ServerProfilesState {
+    /// 加载配置档并应用其网络设置；单个配置档的证书或代理无效时记录日志并跳过
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("server-profiles.json"));
+        let profiles: Vec<ServerProfile> = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        for profile in &profiles {
+            if let Err(e) = profile.apply_network() {
+                log::warn!("Server profile '{}': {}", profile.id, e);
+            }
+        }
+        Self {
+            profiles: Mutex::new(profiles),
+            path,
+        }
+    }
+
+    pub fn list(&self) -> Vec<ServerProfile> {
+        self.profiles
+            .lock()
+            .expect("server profiles poisoned")
+            .clone()
+    }
+
+    /// 工作区中的配置档：已固定且带托管服务
+    pub fn pinned(&self) -> Vec<ServerProfile> {
+        self.profiles
+            .lock()
+            .expect("server profiles poisoned")
+            .iter()
+            .filter(|profile| profile.pinned && profile.managed.is_some())
+            .cloned()
+            .collect()
+    }
+
+    pub fn get(&self, id: &str) -> Result<ServerProfile, String> {
+        self.profiles
+            .lock()
+            .expect("server profiles poisoned")
+            .iter()
+            .find(|profile| profile.id == id)
+            .cloned()
+            .ok_or_else(|| format!("server profile '{}' not found", id))
+    }
+
+    /// 新建或按 id 替换配置档；网络设置无法应用时报错，不保存
+    pub fn save(&self, mut profile: ServerProfile) -> Result<ServerProfile, String> {
+        profile.id = profile.id.trim().to_string();
+        profile.url = profile.url.trim().trim_end_matches('/').to_string();
+        profile.validate()?;
+        profile.apply_network()?;
+
+        let mut profiles = self.profiles.lock().expect("server profiles poisoned");
+        match profiles
+            .iter_mut()
+            .find(|existing| existing.id == profile.id)
+        {
+            Some(existing) => *existing = profile.clone(),
+            None => profiles.push(profile.clone()),
+        }
+        self.persist(&profiles)?;
+        Ok(profile)
+    }
+
+    /// 整体替换（导入设置时使用）；全部校验通过后才生效。
+    /// 凭据不在设置文件中，钥匙串中已有的凭据保留
+    pub fn replace_all(&self, mut new_profiles: Vec<ServerProfile>) -> Result<(), String> {
+        for profile in &mut new_profiles {
+            profile.id = profile.id.trim().to_string();
+            profile.url = profile.url.trim().trim_end_matches('/').to_string();
+            profile.validate()?;
+        }
+
+        let mut profiles = self.profiles.lock().expect("server profiles poisoned");
+        for old in profiles.iter() {
+            if !new_profiles.iter().any(|profile| profile.id == old.id) {
+                network::set_server_network(&old.id, None)?;
+                network::set_identity(&old.id, None)?;
+            }
+        }
+        for profile in &new_profiles {
+            if let Err(e) = profile.apply_network() {
+                log::warn!("Server profile '{}': {}", profile.id, e);
+            }
+        }
+        *profiles = new_profiles;
+        self.persist(&profiles)
+    }
+
+    /// 删除配置档及其钥匙串中的凭据，返回之前是否存在
+    pub fn delete(&self, id: &str) -> Result<bool, String> {
+        let mut profiles = self.profiles.lock().expect("server profiles poisoned");
+        let before = profiles.len();
+        profiles.retain(|profile| profile.id != id);
+        if profiles.len() == before {
+            return Ok(false);
+        }
+        self.persist(&profiles)?;
+        drop(profiles);
+
+        network::set_server_network(id, None)?;
+        network::set_identity(id, None)?;
+        if let Err(e) = credentials::remove(id) {
+            log::warn!("Failed to remove credential for profile '{}': {}", id, e);
+        }
+        Ok(true)
+    }
+
+    fn persist(&self, profiles: &[ServerProfile]) -> Result<(), String> {
+        let path = self.path.as_ref().ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(profiles).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,199 @@
+// ============================================
+// opencode Version Compatibility (desktop only)
+// 解析 opencode 服务（/global/health）或可执行文件（--version）报告的版本，
+// 与界面支持的最低版本比较，让前端在功能悄悄失效之前给出提示
+// ============================================
+
+use serde::Serialize;
+use std::{cmp::Ordering, time::Duration};
+
+/// 界面依赖的 API（/global/* 端点与 { directory, payload } 事件封装）所需的最低 opencode 版本
+pub const MIN_OPENCODE_VERSION: Version = Version {
+    major: 1,
+    minor: 0,
+    patch: 0,
+    prerelease: None,
+};
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    /// `-` 之后的预发布标记，如 `beta.1`
+    pub prerelease: Option<String>,
+}
+
+impl Version {
+    /// 从 `1.2.3`、`v1.2.3-beta.1`、`opencode 1.2.3` 等输出中解析版本；缺省的次 / 修订号视为 0
+    pub fn parse(text: &str) -> Option<Self> {
+        let token = text
+            .split_whitespace()
+            .map(|token| token.trim_start_matches('v'))
+            .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))?;
+        // 去掉构建元数据
+        let token = token.split('+').next()?;
+        let (core, prerelease) = match token.split_once('-') {
+            Some((core, pre)) if !pre.is_empty() => (core, Some(pre.to_string())),
+            _ => (token, None),
+        };
+
+        let mut parts = core.split('.').map(str::parse::<u64>);
+        let major = parts.next()?.ok()?;
+        let minor = parts.next().unwrap_or(Ok(0)).ok()?;
+        let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(Self {
+            major,
+            minor,
+            patch,
+            prerelease,
+        })
+    }
+}
+
+impl std::fmt::Display for Version {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.prerelease {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    /// 同一版本号的预发布版本早于正式版本
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (&self.prerelease, &other.prerelease) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => a.cmp(b),
+            })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Compatibility {
+    Compatible,
+    /// 低于最低支持版本，部分功能可能无法使用
+    Outdated,
+    /// 未能解析版本号
+    Unknown,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VersionSource {
+    Server,
+    Binary,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpencodeVersion {
+    /// 原始版本字符串
+    pub raw: String,
+    pub version: Option<Version>,
+    pub source: VersionSource,
+    pub minimum: String,
+    pub compatibility: Compatibility,
+    /// 不兼容或无法判断时给用户看的说明
+    pub message: Option<String>,
+}
+
+impl OpencodeVersion {
+    pub fn new(raw: String, source: VersionSource) -> Self {
+        let version = Version::parse(&raw);
+        let (compatibility, message) = match &version {
+            Some(version) if *version >= MIN_OPENCODE_VERSION => (Compatibility::Compatible, None),
+            Some(version) => (
+                Compatibility::Outdated,
+                Some(format!(
+                    "opencode {} is older than the minimum supported version {}; update opencode to avoid missing features",
+                    version, MIN_OPENCODE_VERSION
+                )),
+            ),
+            None => (
+                Compatibility::Unknown,
+                Some(format!("could not parse opencode version '{}'", raw)),
+            ),
+        };
+        Self {
+            raw,
+            version,
+            source,
+            minimum: MIN_OPENCODE_VERSION.to_string(),
+            compatibility,
+            message,
+        }
+    }
+}
+
+/// 读取服务 health endpoint 报告的版本
+pub async fn server_version(url: &str, auth_header: Option<&str>) -> Result<String, String> {
+    let health = format!("{}/global/health", url.trim_end_matches('/'));
+    let client = crate::app::dns::client_builder()
+        .connect_timeout(Duration::from_secs(3))
+        .build()
+        .map_err(|e| e.to_string())?;
+    let mut request = client.get(&health).timeout(HEALTH_TIMEOUT);
+    if let Some(auth) = auth_header {
+        request = request.header("Authorization", auth);
+    }
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("failed to reach {}: {}", url, e))?;
+    if !response.status().is_success() {
+        return Err(format!("health check returned {}", response.status()));
+    }
+    let body = response.bytes().await.map_err(|e| e.to_string())?;
+    let json: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|_| format!("{} is not an opencode server", url))?;
+    json["version"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| format!("{} did not report a version", url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_compares_versions() {
+        let parse = |text| Version::parse(text).unwrap();
+        assert_eq!(parse("opencode v1.2.3\n"), parse("1.2.3"));
+        assert_eq!(parse("1.4").patch, 0);
+        assert_eq!(
+            parse("1.0.0-beta.2+abc").prerelease.as_deref(),
+            Some("beta.2")
+        );
+        assert!(Version::parse("dev").is_none());
+        assert!(Version::parse("1.2.3.4").is_none());
+
+        assert!(parse("1.0.0-beta.2") < parse("1.0.0"));
+        assert!(parse("0.15.31") < MIN_OPENCODE_VERSION);
+        assert!(parse("1.0.10") > parse("1.0.9"));
+        assert_eq!(
+            OpencodeVersion::new("0.9.0".to_string(), VersionSource::Server).compatibility,
+            Compatibility::Outdated
+        );
+    }
+}
@@ -1,24 +1,308 @@
-use std::sync::{
-    atomic::{AtomicBool, AtomicU32},
-    Mutex,
+#[cfg(not(target_os = "android"))]
+use crate::app::{container::ContainerTarget, service_exit::ServiceExit, wsl::WslTarget};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, VecDeque},
+    path::PathBuf,
+    process::Child,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
 };
+use tauri::ipc::Channel;
 
-/// 跟踪我们是否启动了 opencode serve 进程
-pub struct ServiceState {
-    /// 我们启动的子进程 PID
+/// 保留的服务输出行数
+const MAX_LOG_LINES: usize = 2000;
+/// 单行最大长度，超出部分截断
+const MAX_LOG_LINE_BYTES: usize = 8 * 1024;
+
+/// 启动托管服务所用的参数，用于挂起后恢复、重启等场景
+#[derive(Clone, Debug)]
+pub struct ServiceLaunch {
+    pub url: String,
+    pub binary_path: String,
+    pub env_vars: HashMap<String, String>,
+    /// 追加在 `serve` 之后的参数
+    pub extra_args: Vec<String>,
+    /// 工作目录（项目根目录），决定服务读取哪个项目配置；None 时继承应用的工作目录
+    pub cwd: Option<PathBuf>,
+    /// 在 WSL 发行版中运行（仅 Windows）
+    #[cfg(not(target_os = "android"))]
+    pub wsl: Option<WslTarget>,
+    /// 在 docker / podman 容器中运行
+    #[cfg(not(target_os = "android"))]
+    pub container: Option<ContainerTarget>,
+}
+
+/// 空闲挂起方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SuspendMode {
+    /// 结束进程，释放全部内存；恢复时重新启动
+    #[default]
+    Stop,
+    /// SIGSTOP 暂停进程（仅 Unix），恢复更快但不释放内存
+    Pause,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct IdleSuspendConfig {
+    pub enabled: bool,
+    pub idle_minutes: u32,
+    pub mode: SuspendMode,
+}
+
+impl Default for IdleSuspendConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            idle_minutes: 30,
+            mode: SuspendMode::Stop,
+        }
+    }
+}
+
+/// 托管服务意外退出后的处理
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WatchdogConfig {
+    /// 自动重启（指数退避）
+    pub auto_restart: bool,
+    /// 连续重启失败的次数上限；稳定运行一段时间后重新计数
+    pub max_attempts: u32,
+}
+
+impl Default for WatchdogConfig {
+    fn default() -> Self {
+        Self {
+            auto_restart: false,
+            max_attempts: 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+    /// 应用写入的记录，例如进程退出
+    System,
+}
+
+/// 托管服务输出的一行
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceLogLine {
+    /// 递增序号，前端据此去重
+    pub seq: u64,
+    /// Unix 毫秒
+    pub at: u64,
+    pub stream: LogStream,
+    pub line: String,
+}
+
+#[derive(Default)]
+struct LogBuffer {
+    lines: VecDeque<ServiceLogLine>,
+    next_seq: u64,
+    followers: Vec<Channel<ServiceLogLine>>,
+}
+
+/// 托管服务 stdout / stderr 的最近输出，并实时推送给订阅的窗口
+#[derive(Default)]
+pub struct ServiceLog {
+    inner: Mutex<LogBuffer>,
+}
+
+impl ServiceLog {
+    pub fn push(&self, stream: LogStream, mut line: String) {
+        if line.len() > MAX_LOG_LINE_BYTES {
+            let mut end = MAX_LOG_LINE_BYTES;
+            while !line.is_char_boundary(end) {
+                end -= 1;
+            }
+            line.truncate(end);
+            line.push('…');
+        }
+        let mut inner = self.inner.lock().expect("service log poisoned");
+        let entry = ServiceLogLine {
+            seq: inner.next_seq,
+            at: crate::app::now_millis(),
+            stream,
+            line,
+        };
+        inner.next_seq += 1;
+        // 发送失败说明窗口已关闭
+        inner
+            .followers
+            .retain(|channel| channel.send(entry.clone()).is_ok());
+        if inner.lines.len() >= MAX_LOG_LINES {
+            inner.lines.pop_front();
+        }
+        inner.lines.push_back(entry);
+    }
+
+    /// 最近的输出，`limit` 限制为最后若干行
+    pub fn lines(&self, limit: Option<usize>) -> Vec<ServiceLogLine> {
+        let inner = self.inner.lock().expect("service log poisoned");
+        let skip = limit.map_or(0, |limit| inner.lines.len().saturating_sub(limit));
+        inner.lines.iter().skip(skip).cloned().collect()
+    }
+
+    /// 订阅之后的输出；同时返回已有的输出，两者之间不会漏行
+    pub fn follow(&self, channel: Channel<ServiceLogLine>) -> Vec<ServiceLogLine> {
+        let mut inner = self.inner.lock().expect("service log poisoned");
+        inner.followers.push(channel);
+        inner.lines.iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.inner
+            .lock()
+            .expect("service log poisoned")
+            .lines
+            .clear();
+    }
+}
+
+/// 未指定实例 id 时使用的实例
+pub const DEFAULT_INSTANCE: &str = "default";
+
+/// 一个托管的 opencode serve 实例
+pub struct ServiceInstance {
+    pub id: String,
+    /// 我们启动的子进程 PID；主动停止时先置 0，watchdog 据此区分崩溃
     pub child_pid: AtomicU32,
+    /// 启动完成后交给 watchdog 等待退出的子进程
+    pub child: Mutex<Option<Child>>,
     /// 是否由我们启动（用于关闭时判断是否需要询问）
     pub we_started: AtomicBool,
+    /// 由上次运行（应用崩溃前）启动、本次启动时接管的进程
+    pub adopted: AtomicBool,
     /// 我们启动的 opencode serve 实际地址
     pub service_url: Mutex<Option<String>>,
+    /// 最近一次启动参数
+    pub launch: Mutex<Option<ServiceLaunch>>,
+    /// 因空闲被挂起时的方式；None 表示未挂起
+    pub suspended: Mutex<Option<SuspendMode>>,
+    /// 串行化同一进程内多个窗口的启动请求
+    pub launch_lock: tokio::sync::Mutex<()>,
+    /// 托管服务的输出，由读取子进程管道的线程写入
+    pub logs: Arc<ServiceLog>,
+    /// 最近一次退出的状态
+    #[cfg(not(target_os = "android"))]
+    pub last_exit: Mutex<Option<ServiceExit>>,
 }
 
-impl Default for ServiceState {
-    fn default() -> Self {
+impl ServiceInstance {
+    fn new(id: &str) -> Self {
         Self {
+            id: id.to_string(),
             child_pid: AtomicU32::new(0),
+            child: Mutex::new(None),
             we_started: AtomicBool::new(false),
+            adopted: AtomicBool::new(false),
             service_url: Mutex::new(None),
+            launch: Mutex::new(None),
+            suspended: Mutex::new(None),
+            launch_lock: tokio::sync::Mutex::new(()),
+            logs: Arc::default(),
+            #[cfg(not(target_os = "android"))]
+            last_exit: Mutex::new(None),
+        }
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.suspended
+            .lock()
+            .map(|suspended| suspended.is_some())
+            .unwrap_or(false)
+    }
+}
+
+/// 跟踪我们启动的 opencode serve 进程：按实例 id 区分，
+/// 每个实例有独立的 PID、地址、启动参数与输出
+pub struct ServiceState {
+    instances: Mutex<HashMap<String, Arc<ServiceInstance>>>,
+    /// 最近一次用户活动时间（Unix 毫秒），所有实例共用
+    pub last_activity: AtomicU64,
+    pub idle_config: Mutex<IdleSuspendConfig>,
+    pub watchdog_config: Mutex<WatchdogConfig>,
+}
+
+impl Default for ServiceState {
+    fn default() -> Self {
+        Self {
+            instances: Mutex::new(HashMap::new()),
+            last_activity: AtomicU64::new(0),
+            idle_config: Mutex::new(IdleSuspendConfig::default()),
+            watchdog_config: Mutex::new(WatchdogConfig::default()),
+        }
+    }
+}
+
+impl ServiceState {
+    pub fn touch_activity(&self) {
+        self.last_activity
+            .store(crate::app::now_millis(), Ordering::SeqCst);
+    }
+
+    /// 取得实例，不存在时创建；`None` 为默认实例
+    pub fn instance(&self, id: Option<&str>) -> Arc<ServiceInstance> {
+        let id = id.filter(|id| !id.is_empty()).unwrap_or(DEFAULT_INSTANCE);
+        self.instances
+            .lock()
+            .expect("service instances poisoned")
+            .entry(id.to_string())
+            .or_insert_with(|| Arc::new(ServiceInstance::new(id)))
+            .clone()
+    }
+
+    /// 所有已知实例，按 id 排序
+    pub fn instances(&self) -> Vec<Arc<ServiceInstance>> {
+        let mut instances: Vec<_> = self
+            .instances
+            .lock()
+            .expect("service instances poisoned")
+            .values()
+            .cloned()
+            .collect();
+        instances.sort_by(|a, b| a.id.cmp(&b.id));
+        instances
+    }
+
+    /// 是否有任一实例由我们启动
+    pub fn any_started(&self) -> bool {
+        self.instances()
+            .iter()
+            .any(|instance| instance.we_started.load(Ordering::SeqCst))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_recent_lines() {
+        let log = ServiceLog::default();
+        for i in 0..MAX_LOG_LINES + 5 {
+            log.push(LogStream::Stdout, format!("line {}", i));
         }
+        log.push(LogStream::Stderr, "界".repeat(MAX_LOG_LINE_BYTES));
+
+        let lines = log.lines(None);
+        assert_eq!(lines.len(), MAX_LOG_LINES);
+        assert_eq!(lines[0].line, "line 6");
+        let last = log.lines(Some(1)).remove(0);
+        assert_eq!(last.seq, MAX_LOG_LINES as u64 + 5);
+        assert_eq!(last.stream, LogStream::Stderr);
+        assert!(last.line.len() <= MAX_LOG_LINE_BYTES + '…'.len_utf8());
+
+        log.clear();
+        assert!(log.lines(None).is_empty());
     }
 }
@@ -0,0 +1,412 @@
+// ============================================
+// Service Autostart (desktop only)
+// 登录系统时直接启动 opencode serve（不启动界面），打开应用时即可复用已运行的服务：
+// - macOS：~/Library/LaunchAgents 下的 launchd agent
+// - Linux：systemd 用户服务（~/.config/systemd/user），enable 后随登录会话启动
+// - Windows：HKCU\...\Run 项，经 wscript 隐藏窗口运行应用数据目录中的启动脚本
+// 启动参数取自实例上次的启动（地址、二进制、参数、工作目录与环境变量）；
+// 环境变量可能含密钥，写入的文件仅当前用户可读
+// ============================================
+
+use crate::app::service::ServiceLaunch;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+/// 开机自启项的名称；launchd label 与 systemd 单元名由此派生
+const ENTRY_NAME: &str = "opencodeui-serve";
+
+/// 自启时运行的命令
+struct ServeCommand {
+    program: String,
+    args: Vec<String>,
+    cwd: Option<PathBuf>,
+    env: Vec<(String, String)>,
+}
+
+impl ServeCommand {
+    fn from_launch(launch: &ServiceLaunch) -> Result<Self, String> {
+        if launch.wsl.is_some() || launch.container.is_some() {
+            return Err("autostart is not supported for WSL or container services".to_string());
+        }
+        let program = launch.binary_path.trim();
+        if program.is_empty() {
+            return Err("the service has no opencode binary configured".to_string());
+        }
+        let url = reqwest::Url::parse(&launch.url).map_err(|e| e.to_string())?;
+        let hostname = url
+            .host_str()
+            .ok_or_else(|| format!("invalid service URL: {}", launch.url))?
+            .trim_matches(|c| c == '[' || c == ']')
+            .to_string();
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| format!("invalid service URL: {}", launch.url))?;
+
+        let mut args = vec!["serve".to_string()];
+        args.extend(launch.extra_args.iter().cloned());
+        args.extend([
+            "--port".to_string(),
+            port.to_string(),
+            "--hostname".to_string(),
+            hostname,
+        ]);
+        let mut env: Vec<(String, String)> = launch
+            .env_vars
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        env.sort();
+        Ok(Self {
+            program: program.to_string(),
+            args,
+            cwd: launch.cwd.clone(),
+            env,
+        })
+    }
+}
+
+/// 写入仅当前用户可读写的文件
+fn write_private(path: &Path, content: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    std::fs::write(path, content)
+        .map_err(|e| format!("failed to write '{}': {}", path.display(), e))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(any(target_os = "linux", target_os = "windows"))]
+fn run(program: &str, args: &[&str]) -> Result<(), String> {
+    let output = std::process::Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| format!("failed to run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} {} failed: {}",
+            program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+// ---------- macOS ----------
+
+#[cfg(any(target_os = "macos", test))]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn launchd_plist(label: &str, command: &ServeCommand, log_path: &Path) -> String {
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+         <plist version=\"1.0\">\n<dict>\n",
+    );
+    plist.push_str(&format!(
+        "  <key>Label</key>\n  <string>{}</string>\n",
+        xml_escape(label)
+    ));
+    plist.push_str("  <key>ProgramArguments</key>\n  <array>\n");
+    for arg in std::iter::once(&command.program).chain(&command.args) {
+        plist.push_str(&format!("    <string>{}</string>\n", xml_escape(arg)));
+    }
+    plist.push_str("  </array>\n");
+    if let Some(cwd) = &command.cwd {
+        plist.push_str(&format!(
+            "  <key>WorkingDirectory</key>\n  <string>{}</string>\n",
+            xml_escape(&cwd.to_string_lossy())
+        ));
+    }
+    if !command.env.is_empty() {
+        plist.push_str("  <key>EnvironmentVariables</key>\n  <dict>\n");
+        for (key, value) in &command.env {
+            plist.push_str(&format!(
+                "    <key>{}</key>\n    <string>{}</string>\n",
+                xml_escape(key),
+                xml_escape(value)
+            ));
+        }
+        plist.push_str("  </dict>\n");
+    }
+    let log_path = xml_escape(&log_path.to_string_lossy());
+    plist.push_str(&format!(
+        "  <key>RunAtLoad</key>\n  <true/>\n\
+         \x20 <key>StandardOutPath</key>\n  <string>{0}</string>\n\
+         \x20 <key>StandardErrorPath</key>\n  <string>{0}</string>\n\
+         </dict>\n</plist>\n",
+        log_path
+    ));
+    plist
+}
+
+// ---------- Linux ----------
+
+/// ExecStart= 中的引号参数；`%` 为说明符、`$` 为变量展开，均需转义
+#[cfg(any(target_os = "linux", test))]
+fn systemd_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('$', "$$");
+    format!("\"{}\"", escaped)
+}
+
+/// Environment= 的引号赋值；不做变量展开，`$` 原样保留
+#[cfg(any(target_os = "linux", test))]
+fn systemd_env_quote(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('%', "%%")
+        .replace('\n', "\\n");
+    format!("\"{}\"", escaped)
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn systemd_unit(command: &ServeCommand) -> String {
+    let exec: Vec<String> = std::iter::once(&command.program)
+        .chain(&command.args)
+        .map(|arg| systemd_quote(arg))
+        .collect();
+    let mut unit = format!(
+        "[Unit]\nDescription=opencode serve (OpenCode UI)\nAfter=network-online.target\n\n\
+         [Service]\nType=simple\nExecStart={}\nRestart=on-failure\n",
+        exec.join(" ")
+    );
+    if let Some(cwd) = &command.cwd {
+        // WorkingDirectory= 不去除引号，只转义说明符
+        unit.push_str(&format!(
+            "WorkingDirectory={}\n",
+            cwd.to_string_lossy().replace('%', "%%")
+        ));
+    }
+    for (key, value) in &command.env {
+        unit.push_str(&format!(
+            "Environment={}\n",
+            systemd_env_quote(&format!("{}={}", key, value))
+        ));
+    }
+    unit.push_str("\n[Install]\nWantedBy=default.target\n");
+    unit
+}
+
+// ---------- Windows ----------
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// cmd 脚本：设置环境变量、切换目录后运行 serve。
+/// 值都放在双引号内，cmd 无法转义其中的 `"` 与换行，含有它们的值直接拒绝
+#[cfg(target_os = "windows")]
+fn cmd_script(command: &ServeCommand) -> Result<String, String> {
+    let quote = |value: &str| {
+        if value.contains('"') || value.chars().any(char::is_control) {
+            return Err(format!(
+                "'{}' contains a quote or control character and cannot be written to the autostart script",
+                value.escape_debug()
+            ));
+        }
+        Ok(format!("\"{}\"", value.replace('%', "%%")))
+    };
+    let mut script = String::from("@echo off\r\n");
+    for (key, value) in &command.env {
+        script.push_str(&format!(
+            "set {}\r\n",
+            quote(&format!("{}={}", key, value))?
+        ));
+    }
+    if let Some(cwd) = &command.cwd {
+        script.push_str(&format!("cd /d {}\r\n", quote(&cwd.to_string_lossy())?));
+    }
+    let line = std::iter::once(&command.program)
+        .chain(&command.args)
+        .map(|arg| quote(arg))
+        .collect::<Result<Vec<_>, _>>()?;
+    script.push_str(&format!("call {}\r\n", line.join(" ")));
+    Ok(script)
+}
+
+// ---------- 平台入口 ----------
+
+/// 自启项文件所在位置
+fn entry_path(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let path = app.path();
+    #[cfg(target_os = "macos")]
+    let entry = path.home_dir().map(|home| {
+        home.join("Library/LaunchAgents")
+            .join(format!("{}.plist", launchd_label(app)))
+    });
+    #[cfg(target_os = "linux")]
+    let entry = path.config_dir().map(|config| {
+        config
+            .join("systemd/user")
+            .join(format!("{}.service", ENTRY_NAME))
+    });
+    #[cfg(target_os = "windows")]
+    let entry = path
+        .app_data_dir()
+        .map(|dir| dir.join("autostart").join(format!("{}.cmd", ENTRY_NAME)));
+    entry.map_err(|e| e.to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn launchd_label(app: &tauri::AppHandle) -> String {
+    format!("{}.serve", app.config().identifier)
+}
+
+/// 当前是否已启用
+pub fn is_enabled(app: &tauri::AppHandle) -> bool {
+    entry_path(app).is_ok_and(|path| path.is_file())
+}
+
+/// 按实例的启动参数写入并启用自启项
+pub fn enable(app: &tauri::AppHandle, launch: &ServiceLaunch) -> Result<(), String> {
+    let command = ServeCommand::from_launch(launch)?;
+    let path = entry_path(app)?;
+
+    #[cfg(target_os = "macos")]
+    {
+        let log_path = app
+            .path()
+            .app_log_dir()
+            .map_err(|e| e.to_string())?
+            .join(format!("{}.log", ENTRY_NAME));
+        write_private(
+            &path,
+            &launchd_plist(&launchd_label(app), &command, &log_path),
+        )?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        write_private(&path, &systemd_unit(&command))?;
+        let unit = format!("{}.service", ENTRY_NAME);
+        run("systemctl", &["--user", "daemon-reload"])
+            .and_then(|_| run("systemctl", &["--user", "enable", &unit]))
+            .inspect_err(|_| {
+                let _ = std::fs::remove_file(&path);
+            })?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        write_private(&path, &cmd_script(&command)?)?;
+        // wscript 以隐藏窗口运行脚本，登录时不弹出控制台
+        let vbs = path.with_extension("vbs");
+        write_private(
+            &vbs,
+            &format!(
+                "CreateObject(\"WScript.Shell\").Run \"\"\"{}\"\"\", 0, False\r\n",
+                path.display()
+            ),
+        )?;
+        let value = format!("wscript.exe \"{}\"", vbs.display());
+        run(
+            "reg",
+            &[
+                "add", RUN_KEY, "/v", ENTRY_NAME, "/t", "REG_SZ", "/d", &value, "/f",
+            ],
+        )?;
+    }
+
+    log::info!("Enabled opencode serve autostart: {}", path.display());
+    Ok(())
+}
+
+/// 移除自启项；已在运行的服务不受影响
+pub fn disable(app: &tauri::AppHandle) -> Result<(), String> {
+    let path = entry_path(app)?;
+
+    #[cfg(target_os = "linux")]
+    if path.exists() {
+        let unit = format!("{}.service", ENTRY_NAME);
+        if let Err(e) = run("systemctl", &["--user", "disable", &unit]) {
+            log::warn!("{}", e);
+        }
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        let _ = run("reg", &["delete", RUN_KEY, "/v", ENTRY_NAME, "/f"]);
+        let _ = std::fs::remove_file(path.with_extension("vbs"));
+    }
+
+    if path.exists() {
+        std::fs::remove_file(&path)
+            .map_err(|e| format!("failed to remove '{}': {}", path.display(), e))?;
+    }
+
+    #[cfg(target_os = "linux")]
+    let _ = run("systemctl", &["--user", "daemon-reload"]);
+
+    log::info!("Disabled opencode serve autostart");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_autostart_entries() {
+        let command = ServeCommand {
+            program: "/opt/opencode bin/opencode".to_string(),
+            args: vec![
+                "serve".to_string(),
+                "--port".to_string(),
+                "4096".to_string(),
+            ],
+            cwd: Some(PathBuf::from("/home/me/repo")),
+            env: vec![("API_KEY".to_string(), "a$b%c\"d".to_string())],
+        };
+
+        let unit = systemd_unit(&command);
+        assert!(unit
+            .contains("ExecStart=\"/opt/opencode bin/opencode\" \"serve\" \"--port\" \"4096\"\n"));
+        assert!(unit.contains("WorkingDirectory=/home/me/repo\n"));
+        assert!(unit.contains("Environment=\"API_KEY=a$b%%c\\\"d\"\n"));
+
+        let plist = launchd_plist("app.serve", &command, Path::new("/tmp/serve.log"));
+        assert!(plist.contains("<string>/opt/opencode bin/opencode</string>"));
+        assert!(plist.contains("<key>API_KEY</key>\n    <string>a$b%c&quot;d</string>"));
+        assert!(plist.contains("<key>RunAtLoad</key>\n  <true/>\n  <key>StandardOutPath</key>"));
+    }
+
+    #[cfg(target_os = "windows")]
+    #[test]
+    fn cmd_script_rejects_quotes_and_line_breaks() {
+        let command = |env_value: &str, arg: &str| ServeCommand {
+            program: r"C:\Program Files\opencode\opencode.exe".to_string(),
+            args: vec!["serve".to_string(), arg.to_string()],
+            cwd: Some(PathBuf::from(r"C:\repo")),
+            env: vec![("API_KEY".to_string(), env_value.to_string())],
+        };
+
+        let script = cmd_script(&command("a&b%c", "--print-logs")).unwrap();
+        assert!(script.contains("set \"API_KEY=a&b%%c\"\r\n"));
+        assert!(script.contains("cd /d \"C:\\repo\"\r\n"));
+        assert!(script.contains(
+            "call \"C:\\Program Files\\opencode\\opencode.exe\" \"serve\" \"--print-logs\"\r\n"
+        ));
+
+        assert!(cmd_script(&command("x\" & calc & \"", "--print-logs")).is_err());
+        assert!(cmd_script(&command("x\r\ncalc", "--print-logs")).is_err());
+        assert!(cmd_script(&command("x", "\" & calc")).is_err());
+    }
+}
@@ -0,0 +1,92 @@
+// ============================================
+// Service Exit Reporting (desktop only)
+// 托管的 opencode serve 每次退出（主动停止、空闲挂起、启动失败或崩溃）都会：
+// - 记录退出码 / 信号到实例的 last_exit
+// - 在服务输出中追加一行 system 记录，get_service_logs 可见
+// - 发出 `service-exited` 事件
+// 子进程由 watchdog 或停止流程 wait()，不会残留僵尸进程；接管的进程没有 Child 句柄，退出状态未知
+// ============================================
+
+use crate::app::service::{LogStream, ServiceInstance};
+use serde::Serialize;
+use std::{process::ExitStatus, sync::OnceLock};
+use tauri::Emitter;
+
+static APP: OnceLock<tauri::AppHandle> = OnceLock::new();
+
+pub fn init(app: &tauri::AppHandle) {
+    let _ = APP.set(app.clone());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExitReason {
+    /// 用户或应用主动停止（含重启、退出应用）
+    Stopped,
+    /// 空闲挂起时结束
+    Suspended,
+    /// 启动阶段退出
+    StartupFailed,
+    /// 运行中意外退出
+    Crashed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceExit {
+    pub instance_id: String,
+    pub pid: u32,
+    /// 退出码；被信号终止或状态未知时为 None
+    pub code: Option<i32>,
+    /// 终止进程的信号（仅 Unix）
+    pub signal: Option<i32>,
+    /// 可读的退出状态；未能取得时为 None
+    pub status: Option<String>,
+    pub reason: ExitReason,
+    /// Unix 毫秒
+    pub at: u64,
+}
+
+#[cfg(unix)]
+fn exit_signal(status: &ExitStatus) -> Option<i32> {
+    use std::os::unix::process::ExitStatusExt;
+    status.signal()
+}
+
+#[cfg(not(unix))]
+fn exit_signal(_status: &ExitStatus) -> Option<i32> {
+    None
+}
+
+/// 记录一次退出并通知前端
+pub fn report(
+    instance: &ServiceInstance,
+    pid: u32,
+    status: Option<ExitStatus>,
+    reason: ExitReason,
+) {
+    let exit = ServiceExit {
+        instance_id: instance.id.clone(),
+        pid,
+        code: status.and_then(|status| status.code()),
+        signal: status.as_ref().and_then(exit_signal),
+        status: status.map(|status| status.to_string()),
+        reason,
+        at: crate::app::now_millis(),
+    };
+    instance.logs.push(
+        LogStream::System,
+        format!(
+            "opencode serve (PID {}) exited: {} [{:?}]",
+            pid,
+            exit.status.as_deref().unwrap_or("status unknown"),
+            reason
+        ),
+    );
+    if let Ok(mut last_exit) = instance.last_exit.lock() {
+        *last_exit = Some(exit.clone());
+    }
+    if let Some(app) = APP.get() {
+        let _ = app.emit("service-exited", exit);
+    }
+}
@@ -0,0 +1,117 @@
+// ============================================
+// Managed Service Spawn Lock (desktop only)
+// 跨进程锁文件：同一 host:port 同时只允许一个窗口 / 实例启动 opencode serve
+// ============================================
+
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+/// 启动过程不会持续这么久；超过即视为持有者异常退出留下的锁
+const STALE_AFTER_MS: u64 = 120_000;
+
+/// 持有期间其他进程无法启动同一端口的服务；drop 时释放
+pub struct SpawnLock {
+    path: PathBuf,
+}
+
+impl Drop for SpawnLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// 锁按服务地址的 host:port 区分，放在系统临时目录以便跨 profile / 实例共享
+fn lock_path(url: &str) -> PathBuf {
+    let key = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|parsed| {
+            Some(format!(
+                "{}-{}",
+                parsed.host_str()?,
+                parsed.port_or_known_default()?
+            ))
+        })
+        .unwrap_or_else(|| url.to_string());
+    let key: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    std::env::temp_dir().join(format!("opencodeui-serve-{}.lock", key))
+}
+
+pub fn is_process_alive(pid: u32) -> bool {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .stderr(Stdio::null())
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status()
+            .map(|status| status.success())
+            .unwrap_or(false)
+    }
+}
+
+/// 锁文件最后修改距今的时间；读不到修改时间时返回 None
+fn file_age_ms(path: &PathBuf) -> Option<u64> {
+    let modified = fs::metadata(path).ok()?.modified().ok()?;
+    Some(modified.elapsed().unwrap_or_default().as_millis() as u64)
+}
+
+fn is_stale(path: &PathBuf) -> bool {
+    let content = fs::read_to_string(path).unwrap_or_default();
+    let mut fields = content.split_whitespace();
+    let pid = fields.next().and_then(|pid| pid.parse::<u32>().ok());
+    let created = fields.next().and_then(|ms| ms.parse::<u64>().ok());
+
+    match (pid, created) {
+        (Some(pid), Some(created)) => {
+            crate::app::now_millis().saturating_sub(created) > STALE_AFTER_MS
+                || !is_process_alive(pid)
+        }
+        // 内容为空或不完整：持有者可能刚创建、尚未写入，只有文件本身足够旧才视为过期
+        _ => file_age_ms(path).is_some_and(|age| age > STALE_AFTER_MS),
+    }
+}
+
+/// 尝试获取锁；被其他进程持有时返回 None
+pub fn try_acquire(url: &str) -> Result<Option<SpawnLock>, String> {
+    let path = lock_path(url);
+
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                write!(file, "{} {}", std::process::id(), crate::app::now_millis())
+                    .map_err(|e| e.to_string())?;
+                return Ok(Some(SpawnLock { path }));
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                if !is_stale(&path) {
+                    return Ok(None);
+                }
+                log::warn!("Removing stale service lock {}", path.display());
+                let _ = fs::remove_file(&path);
+            }
+            Err(e) => return Err(format!("failed to create {}: {}", path.display(), e)),
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,139 @@
+// ============================================
+// Managed Service PID Files (desktop only)
+// 托管的 opencode serve 启动后把 PID 与地址写入 app 数据目录，停止后删除；
+// 应用崩溃后再次启动时，仍在运行且健康检查通过的旧进程被接管回 ServiceState，
+// 不会再启动第二个实例。接管的进程没有 Child 句柄，watchdog 无法感知其退出
+// ============================================
+
+use crate::app::{
+    commands::opencode::is_service_running,
+    service::{ServiceInstance, ServiceState, DEFAULT_INSTANCE},
+    service_lock::is_process_alive,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::{atomic::Ordering, OnceLock},
+};
+use tauri::Manager;
+
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PidRecord {
+    instance_id: String,
+    pid: u32,
+    url: String,
+    /// 负责该服务的应用进程；它仍在运行时不接管
+    app_pid: u32,
+    started_at: u64,
+}
+
+fn record_path(dir: &Path, instance_id: &str) -> PathBuf {
+    let name: String = instance_id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    dir.join(format!("{}.json", name))
+}
+
+fn write_record(path: &Path, record: &PidRecord) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), std::fs::create_dir_all)
+        .and_then(|_| std::fs::write(path, serde_json::to_vec_pretty(record)?));
+    if let Err(e) = result {
+        log::warn!("Failed to write {}: {}", path.display(), e);
+    }
+}
+
+/// 按实例当前状态写入或删除 PID 文件；在启动、停止、崩溃与挂起后调用
+pub fn record(instance: &ServiceInstance) {
+    let Some(dir) = DIR.get() else {
+        return;
+    };
+    let path = record_path(dir, &instance.id);
+    let pid = instance.child_pid.load(Ordering::SeqCst);
+    let url = instance
+        .launch
+        .lock()
+        .ok()
+        .and_then(|launch| launch.as_ref().map(|launch| launch.url.clone()))
+        .or_else(|| instance.service_url.lock().ok().and_then(|url| url.clone()));
+    match url.filter(|_| pid > 0) {
+        Some(url) => write_record(
+            &path,
+            &PidRecord {
+                instance_id: instance.id.clone(),
+                pid,
+                url,
+                app_pid: std::process::id(),
+                started_at: crate::app::now_millis(),
+            },
+        ),
+        None => {
+            let _ = std::fs::remove_file(&path);
+        }
+    }
+}
+
+fn read_records(dir: &Path) -> Vec<(PathBuf, PidRecord)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let record = serde_json::from_slice(&std::fs::read(&path).ok()?).ok()?;
+            Some((path, record))
+        })
+        .collect()
+}
+
+/// 接管上次运行留下的服务进程；进程已退出或不再响应的记录直接删除。
+/// 需在前端发起启动之前（setup 中）执行，会阻塞到健康检查完成
+pub fn adopt_orphans(app: &tauri::AppHandle) {
+    let Ok(dir) = app.path().app_data_dir().map(|dir| dir.join("services")) else {
+        return;
+    };
+    let records = read_records(&dir);
+    let _ = DIR.set(dir);
+
+    let state = app.state::<ServiceState>();
+    for (path, mut record) in records {
+        if record.app_pid != std::process::id() && is_process_alive(record.app_pid) {
+            continue;
+        }
+        let running = is_process_alive(record.pid)
+            && tauri::async_runtime::block_on(is_service_running(&record.url));
+        if !running {
+            let _ = std::fs::remove_file(&path);
+            continue;
+        }
+
+        let instance_id = Some(record.instance_id.as_str()).filter(|id| *id != DEFAULT_INSTANCE);
+        let instance = state.instance(instance_id);
+        log::info!(
+            "Adopting opencode serve '{}' left running by a previous session, PID: {}",
+            instance.id,
+            record.pid
+        );
+        instance.child_pid.store(record.pid, Ordering::SeqCst);
+        instance.we_started.store(true, Ordering::SeqCst);
+        instance.adopted.store(true, Ordering::SeqCst);
+        if let Ok(mut url) = instance.service_url.lock() {
+            *url = Some(record.url.clone());
+        }
+        record.app_pid = std::process::id();
+        write_record(&path, &record);
+    }
+}
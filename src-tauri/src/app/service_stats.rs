@@ -0,0 +1,106 @@
+// ============================================
+// Service Resource Monitor (desktop only)
+// 采样托管 opencode serve 进程的 CPU、常驻内存与运行时长，供状态栏显示；
+// 设置了推送间隔时周期性发出 service-stats 事件，便于在服务占用过多时主动重启
+// ============================================
+
+use crate::app::service::{ServiceInstance, ServiceState};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{atomic::Ordering, Arc, Mutex},
+    time::Duration,
+};
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System};
+use tauri::{Emitter, Manager};
+
+pub const SERVICE_STATS_SETTINGS_KEY: &str = "serviceStats";
+
+/// 未开启推送时检查设置变化的间隔
+const DISABLED_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ServiceStatsConfig {
+    /// service-stats 事件的推送间隔（秒），0 表示不推送，只按需查询
+    pub interval_secs: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStats {
+    pub instance_id: String,
+    pub pid: u32,
+    /// 占用一个核心为 100，多核时可超过 100；首次采样没有参照，固定为 0
+    pub cpu_percent: f32,
+    /// 常驻内存（RSS）
+    pub memory_bytes: u64,
+    pub uptime_secs: u64,
+}
+
+#[derive(Default)]
+pub struct ServiceStatsState {
+    /// CPU 占用按两次刷新之间计算，需要保留上一次的采样
+    system: Mutex<System>,
+    pub config: Mutex<ServiceStatsConfig>,
+}
+
+impl ServiceStatsState {
+    /// 采样正在运行的实例
+    pub fn sample(&self, instances: &[Arc<ServiceInstance>]) -> Vec<ServiceStats> {
+        let running: Vec<(&str, Pid)> = instances
+            .iter()
+            .filter_map(|instance| {
+                let pid = instance.child_pid.load(Ordering::SeqCst);
+                (pid > 0).then(|| (instance.id.as_str(), Pid::from_u32(pid)))
+            })
+            .collect();
+        if running.is_empty() {
+            return Vec::new();
+        }
+
+        let pids: Vec<Pid> = running.iter().map(|(_, pid)| *pid).collect();
+        let mut system = self.system.lock().expect("service stats poisoned");
+        system.refresh_processes_specifics(
+            ProcessesToUpdate::Some(&pids),
+            true,
+            ProcessRefreshKind::nothing().with_cpu().with_memory(),
+        );
+        running
+            .into_iter()
+            .filter_map(|(instance_id, pid)| {
+                let process = system.process(pid)?;
+                Some(ServiceStats {
+                    instance_id: instance_id.to_string(),
+                    pid: pid.as_u32(),
+                    cpu_percent: process.cpu_usage(),
+                    memory_bytes: process.memory(),
+                    uptime_secs: process.run_time(),
+                })
+            })
+            .collect()
+    }
+}
+
+pub fn spawn_stats_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let interval_secs = app
+                .state::<ServiceStatsState>()
+                .config
+                .lock()
+                .map(|config| config.interval_secs)
+                .unwrap_or(0);
+            if interval_secs == 0 {
+                tokio::time::sleep(DISABLED_CHECK_INTERVAL).await;
+                continue;
+            }
+            tokio::time::sleep(Duration::from_secs(interval_secs)).await;
+
+            let instances = app.state::<ServiceState>().instances();
+            let stats = app.state::<ServiceStatsState>().sample(&instances);
+            if !stats.is_empty() {
+                let _ = app.emit("service-stats", stats);
+            }
+        }
+    });
+}
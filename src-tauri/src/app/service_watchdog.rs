@@ -0,0 +1,234 @@
+// ============================================
+// Service Watchdog (desktop only)
+// 等待我们启动的各个 opencode serve 实例退出：非主动停止的退出视为崩溃，发出 `service-crashed`，
+// 并按设置以指数退避自动重启；实例稳定运行一段时间后重新计算重启次数
+// ============================================
+
+use crate::app::{
+    commands::opencode::launch_service,
+    service::{ServiceInstance, ServiceLogLine, ServiceState, WatchdogConfig},
+    service_exit::{self, ExitReason},
+    service_pid,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    sync::atomic::Ordering,
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager};
+
+pub const WATCHDOG_SETTINGS_KEY: &str = "serviceWatchdog";
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(2);
+/// 运行超过该时长后再崩溃，重启次数从头计算
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// 随崩溃事件附带的最近输出行数
+const CRASH_OUTPUT_LINES: usize = 20;
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ServiceCrash {
+    instance_id: String,
+    pid: u32,
+    /// 退出码；被信号终止时为 None
+    code: Option<i32>,
+    status: String,
+    recent_output: Vec<ServiceLogLine>,
+    will_restart: bool,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RestartAttempt {
+    instance_id: String,
+    attempt: u32,
+    max_attempts: u32,
+    delay_ms: u64,
+}
+
+/// 第 `attempt` 次重启前的等待时间：1s、2s、4s……封顶 60s
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64 << attempt.saturating_sub(1).min(6)).min(MAX_BACKOFF)
+}
+
+/// 取走已退出的子进程，返回 (pid, 退出状态)
+fn reap(instance: &ServiceInstance) -> Option<(u32, std::process::ExitStatus)> {
+    let mut child = instance.child.lock().ok()?;
+    let status = child.as_mut()?.try_wait().ok()??;
+    let pid = child.take()?.id();
+    Some((pid, status))
+}
+
+/// 按退避依次尝试重启，成功或用尽次数后返回
+async fn restart(
+    app: &tauri::AppHandle,
+    state: &ServiceState,
+    instance: &ServiceInstance,
+    attempts: &mut u32,
+    max: u32,
+) {
+    while *attempts < max {
+        *attempts += 1;
+        let delay = backoff(*attempts);
+        let _ = app.emit(
+            "service-restarting",
+            RestartAttempt {
+                instance_id: instance.id.clone(),
+                attempt: *attempts,
+                max_attempts: max,
+                delay_ms: delay.as_millis() as u64,
+            },
+        );
+        tokio::time::sleep(delay).await;
+
+        // 等待期间用户已手动启动
+        if instance.we_started.load(Ordering::SeqCst) {
+            return;
+        }
+        let launch = instance
+            .launch
+            .lock()
+            .ok()
+            .and_then(|launch| launch.clone());
+        let Some(launch) = launch else {
+            return;
+        };
+        match launch_service(state, instance, launch).await {
+            Ok(result) => {
+                log::info!(
+                    "opencode serve '{}' restarted (attempt {})",
+                    instance.id,
+                    attempts
+                );
+                let _ = app.emit(
+                    "service-restarted",
+                    serde_json::json!({ "instanceId": instance.id, "url": result.url }),
+                );
+                return;
+            }
+            Err(error) => {
+                log::warn!("Restarting opencode serve failed: {}", error);
+            }
+        }
+    }
+    log::error!(
+        "opencode serve '{}' keeps crashing, giving up after {} restarts",
+        instance.id,
+        max
+    );
+    let _ = app.emit(
+        "service-restart-failed",
+        serde_json::json!({ "instanceId": instance.id, "attempts": *attempts }),
+    );
+}
+
+/// 单个实例的重启计数
+#[derive(Default)]
+struct Watch {
+    attempts: u32,
+    /// 正在观察的 PID 及其开始运行的时间
+    running: Option<(u32, Instant)>,
+}
+
+async fn check(
+    app: &tauri::AppHandle,
+    state: &ServiceState,
+    instance: &ServiceInstance,
+    watch: &mut Watch,
+) {
+    let pid = instance.child_pid.load(Ordering::SeqCst);
+    if pid != 0 && watch.running.is_none_or(|(watched, _)| watched != pid) {
+        watch.running = Some((pid, Instant::now()));
+    }
+    let Some((pid, status)) = reap(instance) else {
+        return;
+    };
+    // 主动停止或挂起时 child_pid 已先被置 0
+    if instance
+        .child_pid
+        .compare_exchange(pid, 0, Ordering::SeqCst, Ordering::SeqCst)
+        .is_err()
+    {
+        return;
+    }
+    instance.we_started.store(false, Ordering::SeqCst);
+    if let Ok(mut url) = instance.service_url.lock() {
+        *url = None;
+    }
+    service_pid::record(instance);
+
+    if watch
+        .running
+        .is_some_and(|(watched, since)| watched == pid && since.elapsed() >= STABLE_AFTER)
+    {
+        watch.attempts = 0;
+    }
+    watch.running = None;
+
+    let config: WatchdogConfig = state
+        .watchdog_config
+        .lock()
+        .map(|config| config.clone())
+        .unwrap_or_default();
+    log::error!(
+        "opencode serve '{}' (PID {}) exited unexpectedly: {}",
+        instance.id,
+        pid,
+        status
+    );
+    crate::app::terminate::reap(pid);
+    service_exit::report(instance, pid, Some(status), ExitReason::Crashed);
+    let _ = app.emit(
+        "service-crashed",
+        ServiceCrash {
+            instance_id: instance.id.clone(),
+            pid,
+            code: status.code(),
+            status: status.to_string(),
+            recent_output: instance.logs.lines(Some(CRASH_OUTPUT_LINES)),
+            will_restart: config.auto_restart && watch.attempts < config.max_attempts,
+        },
+    );
+
+    if config.auto_restart {
+        restart(
+            app,
+            state,
+            instance,
+            &mut watch.attempts,
+            config.max_attempts,
+        )
+        .await;
+    }
+}
+
+pub fn spawn_watchdog(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut watches: HashMap<String, Watch> = HashMap::new();
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let state = app.state::<ServiceState>();
+            for instance in state.instances() {
+                let watch = watches.entry(instance.id.clone()).or_default();
+                check(&app, &state, &instance, watch).await;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_cap() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(2), Duration::from_secs(2));
+        assert_eq!(backoff(4), Duration::from_secs(8));
+        assert_eq!(backoff(7), MAX_BACKOFF);
+        assert_eq!(backoff(40), MAX_BACKOFF);
+    }
+}
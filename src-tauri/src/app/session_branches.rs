@@ -0,0 +1,145 @@
+// ============================================
+// Session ↔ Branch Links (desktop only)
+// 把会话关联到项目中的一个 git 分支，持久化到 profile 配置目录；
+// 设置了自动切换的会话在开始前（前端发送第一条 prompt 之前调用 prepare_session_branch）
+// 创建 / 检出该分支，工作区有未提交修改时不切换并发出错误事件；只读项目中不切换
+// 分支操作沿用 git.rs 的 git CLI，而不是 git2
+// ============================================
+
+use crate::app::git;
+use serde::{Deserialize, Serialize};
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionBranch {
+    pub session_id: String,
+    pub directory: String,
+    pub branch: String,
+    /// 会话开始时（prepare_session_branch）自动检出该分支
+    #[serde(default)]
+    pub auto_checkout: bool,
+    pub linked_at: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BranchError {
+    pub session_id: String,
+    pub directory: String,
+    pub branch: String,
+    pub message: String,
+}
+
+#[derive(Default)]
+pub struct SessionBranchesState {
+    links: Mutex<Vec<SessionBranch>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl SessionBranchesState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path =
+            crate::app::profile::config_dir(app).map(|dir| dir.join("session-branches.json"));
+        let links = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        Self {
+            links: Mutex::new(links),
+            path: Mutex::new(path),
+        }
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<SessionBranch> {
+        self.links
+            .lock()
+            .expect("session branches poisoned")
+            .iter()
+            .find(|link| link.session_id == session_id)
+            .cloned()
+    }
+
+    /// 列出关联；指定目录时只返回该项目下的
+    pub fn list(&self, directory: Option<&str>) -> Vec<SessionBranch> {
+        self.links
+            .lock()
+            .expect("session branches poisoned")
+            .iter()
+            .filter(|link| directory.is_none_or(|directory| link.directory == directory))
+            .cloned()
+            .collect()
+    }
+
+    pub fn set(&self, link: SessionBranch) -> Result<(), String> {
+        let mut links = self.links.lock().expect("session branches poisoned");
+        links.retain(|existing| existing.session_id != link.session_id);
+        links.push(link);
+        self.persist(&links)
+    }
+
+    pub fn remove(&self, session_id: &str) -> Result<bool, String> {
+        let mut links = self.links.lock().expect("session branches poisoned");
+        let before = links.len();
+        links.retain(|link| link.session_id != session_id);
+        let removed = links.len() != before;
+        if removed {
+            self.persist(&links)?;
+        }
+        Ok(removed)
+    }
+
+    fn persist(&self, links: &[SessionBranch]) -> Result<(), String> {
+        let path = self.path.lock().expect("session branches poisoned").clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(links).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
+
+pub fn validate_branch_name(branch: &str, directory: &Path) -> Result<(), String> {
+    git::run(&["check-ref-format", "--branch", branch], directory)
+        .map(|_| ())
+        .map_err(|_| format!("'{}' is not a valid branch name", branch))
+}
+
+pub fn current_branch(directory: &Path) -> Option<String> {
+    git::run(&["branch", "--show-current"], directory)
+        .ok()
+        .filter(|branch| !branch.is_empty())
+}
+
+/// 检出分支，不存在时从当前 HEAD 创建；工作区有未提交修改时报错，不做任何改动
+pub fn checkout(directory: &Path, branch: &str) -> Result<(), String> {
+    if current_branch(directory).as_deref() == Some(branch) {
+        return Ok(());
+    }
+    let status = git::run(
+        &["status", "--porcelain", "--untracked-files=no"],
+        directory,
+    )?;
+    if !status.is_empty() {
+        return Err(format!(
+            "Working tree has uncommitted changes; commit or stash them before switching to '{}'",
+            branch
+        ));
+    }
+
+    let reference = format!("refs/heads/{}", branch);
+    let exists = git::run(&["rev-parse", "--verify", "--quiet", &reference], directory).is_ok();
+    if exists {
+        git::run(&["switch", branch], directory)?;
+    } else {
+        git::run(&["switch", "-c", branch], directory)?;
+    }
+    log::info!("Checked out branch '{}' in {}", branch, directory.display());
+    Ok(())
+}
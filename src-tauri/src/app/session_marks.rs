@@ -0,0 +1,323 @@
+// ============================================
+// Session Pins & Unread (desktop only)
+// 会话的置顶状态与未读计数，持久化到 profile 配置目录；
+// 未读数由 bridge 上的 message.updated 事件维护（助手消息完成且没有窗口正在显示该会话时 +1），
+// 前端重载后直接查询，不必在 JS 中重放事件
+// ============================================
+
+use crate::app::{
+    bridge::{event_data, BridgeState},
+    window_context::WindowContextState,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{path::PathBuf, sync::Mutex};
+use tauri::{Emitter, Manager};
+
+/// 保留的记录上限；超出时先丢弃最久未更新、未置顶且无未读的记录
+const MAX_MARKS: usize = 2000;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionMark {
+    pub session_id: String,
+    #[serde(default)]
+    pub directory: Option<String>,
+    #[serde(default)]
+    pub unread: u32,
+    #[serde(default)]
+    pub pinned: bool,
+    /// 最近一条计入的消息 id；opencode 的消息 id 按时间递增，用于去重
+    #[serde(default)]
+    pub last_message_id: Option<String>,
+    #[serde(default)]
+    pub updated_at: u64,
+}
+
+impl SessionMark {
+    /// 记录一条已完成的消息；`count` 为 false 时只推进位置（正在显示的会话）。
+    /// 返回是否有变化
+    fn record(&mut self, message_id: &str, count: bool) -> bool {
+        if self
+            .last_message_id
+            .as_deref()
+            .is_some_and(|last| last >= message_id)
+        {
+            return false;
+        }
+        self.last_message_id = Some(message_id.to_string());
+        if count {
+            self.unread += 1;
+        }
+        true
+    }
+}
+
+#[derive(Default)]
+pub struct SessionMarksState {
+    marks: Mutex<Vec<SessionMark>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl SessionMarksState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("session-marks.json"));
+        let marks = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        Self {
+            marks: Mutex::new(marks),
+            path: Mutex::new(path),
+        }
+    }
+
+    /// 列出有未读或已置顶的会话；指定目录时只返回该项目下的
+    pub fn list(&self, directory: Option<&str>) -> Vec<SessionMark> {
+        self.marks
+            .lock()
+            .expect("session marks poisoned")
+            .iter()
+            .filter(|mark| mark.unread > 0 || mark.pinned)
+            .filter(|mark| directory.is_none_or(|dir| mark.directory.as_deref() == Some(dir)))
+            .cloned()
+            .collect()
+    }
+
+    /// 修改（不存在时新建）会话的记录并保存；`f` 返回 false 表示没有变化
+    fn update(
+        &self,
+        session_id: &str,
+        directory: Option<&str>,
+        f: impl FnOnce(&mut SessionMark) -> bool,
+    ) -> Result<Option<SessionMark>, String> {
+        let mut marks = self.marks.lock().expect("session marks poisoned");
+        let index = match marks.iter().position(|mark| mark.session_id == session_id) {
+            Some(index) => index,
+            None => {
+                marks.push(SessionMark {
+                    session_id: session_id.to_string(),
+                    ..Default::default()
+                });
+                marks.len() - 1
+            }
+        };
+        let mark = &mut marks[index];
+        if mark.directory.is_none() {
+            mark.directory = directory.map(str::to_string);
+        }
+        if !f(mark) {
+            return Ok(None);
+        }
+        mark.updated_at = crate::app::now_millis();
+        let mark = mark.clone();
+
+        if marks.len() > MAX_MARKS {
+            if let Some(oldest) = marks
+                .iter()
+                .enumerate()
+                .filter(|(_, mark)| !mark.pinned && mark.unread == 0)
+                .min_by_key(|(_, mark)| mark.updated_at)
+                .map(|(index, _)| index)
+            {
+                marks.remove(oldest);
+            }
+        }
+        self.persist(&marks)?;
+        Ok(Some(mark))
+    }
+
+    pub fn record_message(
+        &self,
+        session_id: &str,
+        directory: Option<&str>,
+        message_id: &str,
+        count: bool,
+    ) -> Result<Option<SessionMark>, String> {
+        self.update(session_id, directory, |mark| mark.record(message_id, count))
+    }
+
+    pub fn mark_read(&self, session_id: &str) -> Result<Option<SessionMark>, String> {
+        // 没有记录的会话不必新建
+        if self.get(session_id).is_none() {
+            return Ok(None);
+        }
+        self.update(session_id, None, |mark| {
+            std::mem::take(&mut mark.unread) > 0
+        })
+    }
+
+    /// 清空所有（或某个项目下）会话的未读数，返回被清空的会话
+    pub fn mark_all_read(&self, directory: Option<&str>) -> Result<Vec<SessionMark>, String> {
+        let mut marks = self.marks.lock().expect("session marks poisoned");
+        let now = crate::app::now_millis();
+        let cleared: Vec<SessionMark> = marks
+            .iter_mut()
+            .filter(|mark| mark.unread > 0)
+            .filter(|mark| directory.is_none_or(|dir| mark.directory.as_deref() == Some(dir)))
+            .map(|mark| {
+                mark.unread = 0;
+                mark.updated_at = now;
+                mark.clone()
+            })
+            .collect();
+        if !cleared.is_empty() {
+            self.persist(&marks)?;
+        }
+        Ok(cleared)
+    }
+
+    pub fn set_pinned(
+        &self,
+        session_id: &str,
+        directory: Option<&str>,
+        pinned: bool,
+    ) -> Result<SessionMark, String> {
+        let changed = self.update(session_id, directory, |mark| {
+            std::mem::replace(&mut mark.pinned, pinned) != pinned
+        })?;
+        match changed {
+            Some(mark) => Ok(mark),
+            None => self
+                .get(session_id)
+                .ok_or_else(|| format!("unknown session '{}'", session_id)),
+        }
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<SessionMark> {
+        self.marks
+            .lock()
+            .expect("session marks poisoned")
+            .iter()
+            .find(|mark| mark.session_id == session_id)
+            .cloned()
+    }
+
+    pub fn remove(&self, session_id: &str) -> Result<bool, String> {
+        let mut marks = self.marks.lock().expect("session marks poisoned");
+        let before = marks.len();
+        marks.retain(|mark| mark.session_id != session_id);
+        let removed = marks.len() != before;
+        if removed {
+            self.persist(&marks)?;
+        }
+        Ok(removed)
+    }
+
+    fn persist(&self, marks: &[SessionMark]) -> Result<(), String> {
+        let path = self.path.lock().expect("session marks poisoned").clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(marks).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// 清除会话未读并通知所有窗口
+pub fn mark_read(app: &tauri::AppHandle, session_id: &str) -> Result<(), String> {
+    if let Some(mark) = app.state::<SessionMarksState>().mark_read(session_id)? {
+        let _ = app.emit("session-marks-changed", vec![mark]);
+    }
+    Ok(())
+}
+
+enum MarkEvent {
+    /// 助手消息完成：(目录, 会话 id, 消息 id)
+    Completed(Option<String>, String, String),
+    Deleted(String),
+}
+
+fn mark_event(event: &Value) -> Option<MarkEvent> {
+    let directory = event["directory"].as_str().map(str::to_string);
+    let event = event.get("payload").unwrap_or(event);
+    let info = &event["properties"]["info"];
+    match event["type"].as_str()? {
+        "message.updated" => {
+            if info["role"] != "assistant" || !info["time"]["completed"].is_number() {
+                return None;
+            }
+            Some(MarkEvent::Completed(
+                directory,
+                info["sessionID"].as_str()?.to_string(),
+                info["id"].as_str()?.to_string(),
+            ))
+        }
+        "session.deleted" => Some(MarkEvent::Deleted(info["id"].as_str()?.to_string())),
+        _ => None,
+    }
+}
+
+/// 注册 bridge 事件观察者，维护未读数并发出 `session-marks-changed`
+pub fn install(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |_args, block| {
+            if !block.contains("\"message.updated\"") && !block.contains("\"session.deleted\"") {
+                return;
+            }
+            let Some(event) = event_data(block)
+                .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+                .and_then(|event| mark_event(&event))
+            else {
+                return;
+            };
+
+            let state = handle.state::<SessionMarksState>();
+            let result = match event {
+                MarkEvent::Completed(directory, session_id, message_id) => {
+                    let showing = handle.state::<WindowContextState>().showing(&session_id);
+                    state
+                        .record_message(&session_id, directory.as_deref(), &message_id, !showing)
+                        .map(|mark| mark.filter(|mark| mark.unread > 0))
+                }
+                MarkEvent::Deleted(session_id) => state.remove(&session_id).map(|_| None),
+            };
+            match result {
+                Ok(Some(mark)) => {
+                    let _ = handle.emit("session-marks-changed", vec![mark]);
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Failed to save session marks: {}", e),
+            }
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_each_completed_message_once() {
+        let mut mark = SessionMark::default();
+        assert!(mark.record("msg_01", true));
+        assert!(!mark.record("msg_01", true));
+        assert!(mark.record("msg_03", true));
+        // 较早的消息再次更新不重复计数
+        assert!(!mark.record("msg_02", true));
+        assert_eq!(mark.unread, 2);
+        // 正在显示的会话只推进位置
+        assert!(mark.record("msg_04", false));
+        assert_eq!(mark.unread, 2);
+        assert_eq!(mark.last_message_id.as_deref(), Some("msg_04"));
+
+        let event = serde_json::json!({
+            "directory": "/p",
+            "payload": {
+                "type": "message.updated",
+                "properties": { "info": {
+                    "id": "msg_05", "sessionID": "ses_1", "role": "assistant",
+                    "time": { "created": 1, "completed": 2 }
+                }}
+            }
+        });
+        assert!(matches!(
+            mark_event(&event),
+            Some(MarkEvent::Completed(Some(dir), session, message))
+                if dir == "/p" && session == "ses_1" && message == "msg_05"
+        ));
+    }
+}
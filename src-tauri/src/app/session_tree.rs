@@ -0,0 +1,350 @@
+// ============================================
+// Sub-session Topology (desktop only)
+// 根据 bridge 上的 session.created / updated / deleted、session.status 与 session.error 事件
+// 维护父子会话图，父会话的子会话状态变化时发出汇总事件（如 "5 个子会话中 3 个完成、1 个失败"）
+// ============================================
+
+use crate::app::bridge::{event_data, BridgeState};
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::HashMap, sync::Mutex};
+use tauri::{Emitter, Manager};
+
+/// 保留的会话节点上限，超出时丢弃最久未更新的根会话
+const MAX_NODES: usize = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionRunStatus {
+    /// 已创建，尚未开始运行
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+#[derive(Clone, Debug)]
+struct SessionNode {
+    parent_id: Option<String>,
+    title: String,
+    status: SessionRunStatus,
+    children: Vec<String>,
+    updated_at: u64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionTree {
+    pub id: String,
+    pub title: String,
+    pub status: SessionRunStatus,
+    pub children: Vec<SessionTree>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChildrenSummary {
+    pub parent_id: String,
+    pub total: usize,
+    pub pending: usize,
+    pub running: usize,
+    pub done: usize,
+    pub failed: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum SessionChange {
+    Upsert {
+        id: String,
+        parent_id: Option<String>,
+        title: String,
+    },
+    Removed(String),
+    Status(String, SessionRunStatus),
+}
+
+#[derive(Default)]
+pub struct SessionTreeState {
+    nodes: Mutex<HashMap<String, SessionNode>>,
+}
+
+impl SessionTreeState {
+    /// 应用一条变化，返回受影响的父会话 id
+    fn apply(&self, change: SessionChange, now: u64) -> Option<String> {
+        let mut nodes = self.nodes.lock().expect("session tree poisoned");
+        let parent = match change {
+            SessionChange::Upsert {
+                id,
+                parent_id,
+                title,
+            } => {
+                let node = nodes.entry(id.clone()).or_insert_with(|| SessionNode {
+                    parent_id: None,
+                    title: String::new(),
+                    status: SessionRunStatus::Pending,
+                    children: Vec::new(),
+                    updated_at: now,
+                });
+                node.title = title;
+                node.updated_at = now;
+                let previous = std::mem::replace(&mut node.parent_id, parent_id.clone());
+                if previous != parent_id {
+                    if let Some(previous) = previous.and_then(|p| nodes.get_mut(&p)) {
+                        previous.children.retain(|child| child != &id);
+                    }
+                    if let Some(parent_id) = &parent_id {
+                        let parent =
+                            nodes
+                                .entry(parent_id.clone())
+                                .or_insert_with(|| SessionNode {
+                                    parent_id: None,
+                                    title: String::new(),
+                                    status: SessionRunStatus::Pending,
+                                    children: Vec::new(),
+                                    updated_at: now,
+                                });
+                        parent.children.push(id);
+                    }
+                }
+                parent_id
+            }
+            SessionChange::Removed(id) => {
+                let node = nodes.remove(&id)?;
+                for child in &node.children {
+                    if let Some(child) = nodes.get_mut(child) {
+                        child.parent_id = None;
+                    }
+                }
+                if let Some(parent) = node.parent_id.as_ref().and_then(|p| nodes.get_mut(p)) {
+                    parent.children.retain(|child| child != &id);
+                }
+                node.parent_id
+            }
+            SessionChange::Status(id, status) => {
+                let node = nodes.get_mut(&id)?;
+                // 没运行过的会话收到 idle 仍算待运行；失败后的 idle 保留失败状态
+                let status = match (node.status, status) {
+                    (SessionRunStatus::Pending, SessionRunStatus::Done) => return None,
+                    (SessionRunStatus::Failed, SessionRunStatus::Done) => return None,
+                    (_, status) => status,
+                };
+                if node.status == status {
+                    return None;
+                }
+                node.status = status;
+                node.updated_at = now;
+                node.parent_id.clone()
+            }
+        };
+        prune(&mut nodes);
+        parent
+    }
+
+    pub fn tree(&self, root: &str) -> Option<SessionTree> {
+        let nodes = self.nodes.lock().expect("session tree poisoned");
+        build_tree(&nodes, root, 0)
+    }
+
+    pub fn summary(&self, parent_id: &str) -> Option<ChildrenSummary> {
+        let nodes = self.nodes.lock().expect("session tree poisoned");
+        let parent = nodes.get(parent_id)?;
+        let mut summary = ChildrenSummary {
+            parent_id: parent_id.to_string(),
+            ..Default::default()
+        };
+        for status in parent
+            .children
+            .iter()
+            .filter_map(|child| nodes.get(child))
+            .map(|child| child.status)
+        {
+            summary.total += 1;
+            match status {
+                SessionRunStatus::Pending => summary.pending += 1,
+                SessionRunStatus::Running => summary.running += 1,
+                SessionRunStatus::Done => summary.done += 1,
+                SessionRunStatus::Failed => summary.failed += 1,
+            }
+        }
+        Some(summary)
+    }
+
+    /// 会话所在树的根会话 id
+    pub fn root_of(&self, session_id: &str) -> String {
+        let nodes = self.nodes.lock().expect("session tree poisoned");
+        let mut current = session_id;
+        // 深度上限防止异常数据形成环
+        for _ in 0..nodes.len() {
+            match nodes
+                .get(current)
+                .and_then(|node| node.parent_id.as_deref())
+            {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        current.to_string()
+    }
+}
+
+fn build_tree(nodes: &HashMap<String, SessionNode>, id: &str, depth: usize) -> Option<SessionTree> {
+    let node = nodes.get(id)?;
+    let children = if depth < nodes.len() {
+        node.children
+            .iter()
+            .filter_map(|child| build_tree(nodes, child, depth + 1))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    Some(SessionTree {
+        id: id.to_string(),
+        title: node.title.clone(),
+        status: node.status,
+        children,
+    })
+}
+
+/// 节点过多时丢弃最久未更新的无父节点（连同其子会话保留为新的根）
+fn prune(nodes: &mut HashMap<String, SessionNode>) {
+    while nodes.len() > MAX_NODES {
+        let oldest = nodes
+            .iter()
+            .filter(|(_, node)| {
+                node.parent_id.is_none() && node.status != SessionRunStatus::Running
+            })
+            .min_by_key(|(_, node)| node.updated_at)
+            .map(|(id, _)| id.clone());
+        let Some(id) = oldest else {
+            break;
+        };
+        if let Some(node) = nodes.remove(&id) {
+            for child in node.children {
+                if let Some(child) = nodes.get_mut(&child) {
+                    child.parent_id = None;
+                }
+            }
+        }
+    }
+}
+
+fn session_change(event: &Value) -> Option<SessionChange> {
+    let event = event.get("payload").unwrap_or(event);
+    let properties = &event["properties"];
+    match event["type"].as_str()? {
+        "session.created" | "session.updated" => {
+            let info = &properties["info"];
+            Some(SessionChange::Upsert {
+                id: info["id"].as_str()?.to_string(),
+                parent_id: info["parentID"].as_str().map(str::to_string),
+                title: info["title"].as_str().unwrap_or_default().to_string(),
+            })
+        }
+        "session.deleted" => Some(SessionChange::Removed(
+            properties["info"]["id"].as_str()?.to_string(),
+        )),
+        "session.status" => {
+            let status = match properties["status"]["type"].as_str()? {
+                "idle" => SessionRunStatus::Done,
+                _ => SessionRunStatus::Running,
+            };
+            Some(SessionChange::Status(
+                properties["sessionID"].as_str()?.to_string(),
+                status,
+            ))
+        }
+        "session.idle" => Some(SessionChange::Status(
+            properties["sessionID"].as_str()?.to_string(),
+            SessionRunStatus::Done,
+        )),
+        "session.error" => Some(SessionChange::Status(
+            properties["sessionID"].as_str()?.to_string(),
+            SessionRunStatus::Failed,
+        )),
+        _ => None,
+    }
+}
+
+/// 注册 bridge 事件观察者；子会话变化时发出 `session-children-changed`
+pub fn install(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |_args, block| {
+            if !block.contains("\"session.") {
+                return;
+            }
+            let Some(change) = event_data(block)
+                .and_then(|data| serde_json::from_str::<Value>(&data).ok())
+                .and_then(|event| session_change(&event))
+            else {
+                return;
+            };
+            let state = handle.state::<SessionTreeState>();
+            let Some(parent_id) = state.apply(change, crate::app::now_millis()) else {
+                return;
+            };
+            if let Some(summary) = state.summary(&parent_id) {
+                let _ = handle.emit("session-children-changed", summary);
+            }
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn created(id: &str, parent: Option<&str>) -> SessionChange {
+        session_change(&json!({
+            "type": "session.created",
+            "properties": { "info": { "id": id, "parentID": parent, "title": id } }
+        }))
+        .unwrap()
+    }
+
+    fn status(id: &str, status: SessionRunStatus) -> SessionChange {
+        SessionChange::Status(id.to_string(), status)
+    }
+
+    #[test]
+    fn aggregates_child_statuses() {
+        let state = SessionTreeState::default();
+        state.apply(created("root", None), 1);
+        for child in ["a", "b", "c"] {
+            assert_eq!(
+                state.apply(created(child, Some("root")), 2),
+                Some("root".to_string())
+            );
+        }
+        state.apply(created("a1", Some("a")), 3);
+
+        state.apply(status("a", SessionRunStatus::Running), 4);
+        state.apply(status("b", SessionRunStatus::Running), 4);
+        state.apply(status("a", SessionRunStatus::Done), 5);
+        state.apply(status("b", SessionRunStatus::Failed), 5);
+        // 失败后的 idle 不覆盖失败状态
+        assert_eq!(state.apply(status("b", SessionRunStatus::Done), 6), None);
+
+        assert_eq!(
+            state.summary("root"),
+            Some(ChildrenSummary {
+                parent_id: "root".to_string(),
+                total: 3,
+                pending: 1,
+                running: 0,
+                done: 1,
+                failed: 1,
+            })
+        );
+        assert_eq!(state.root_of("a1"), "root");
+
+        let tree = state.tree("root").unwrap();
+        assert_eq!(tree.children.len(), 3);
+        let a = tree.children.iter().find(|child| child.id == "a").unwrap();
+        assert_eq!(a.children[0].id, "a1");
+
+        state.apply(SessionChange::Removed("a".to_string()), 7);
+        assert_eq!(state.summary("root").unwrap().total, 2);
+        assert_eq!(state.root_of("a1"), "a1");
+    }
+}
@@ -0,0 +1,87 @@
+// ============================================
+// Settings Store (desktop only)
+// Rust 侧持久化的键值设置（settings.json，按 profile 隔离）
+// ============================================
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{Map, Value};
+use std::{path::PathBuf, sync::Mutex};
+
+pub struct SettingsStore {
+    path: Option<PathBuf>,
+    values: Mutex<Map<String, Value>>,
+}
+
+impl SettingsStore {
+    /// 从当前 profile 的配置目录加载；文件缺失或损坏时从空设置开始
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("settings.json"));
+        let values = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str::<Map<String, Value>>(&data).ok())
+            .unwrap_or_default();
+
+        Self {
+            path,
+            values: Mutex::new(values),
+        }
+    }
+
+    pub fn get(&self, key: &str) -> Option<Value> {
+        self.values
+            .lock()
+            .expect("settings store poisoned")
+            .get(key)
+            .cloned()
+    }
+
+    /// 读取并反序列化为具体类型，缺失或类型不符时返回 None
+    pub fn get_as<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.get(key)
+            .and_then(|value| serde_json::from_value(value).ok())
+    }
+
+    pub fn set(&self, key: &str, value: Value) -> Result<(), String> {
+        let mut values = self.values.lock().expect("settings store poisoned");
+        values.insert(key.to_string(), value);
+        self.persist(&values)
+    }
+
+    pub fn set_as<T: Serialize>(&self, key: &str, value: &T) -> Result<(), String> {
+        let value = serde_json::to_value(value).map_err(|e| e.to_string())?;
+        self.set(key, value)
+    }
+
+    pub fn remove(&self, key: &str) -> Result<(), String> {
+        let mut values = self.values.lock().expect("settings store poisoned");
+        if values.remove(key).is_some() {
+            self.persist(&values)?;
+        }
+        Ok(())
+    }
+
+    pub fn snapshot(&self) -> Map<String, Value> {
+        self.values.lock().expect("settings store poisoned").clone()
+    }
+
+    /// 整体替换（导入设置时使用）
+    pub fn replace_all(&self, new_values: Map<String, Value>) -> Result<(), String> {
+        let mut values = self.values.lock().expect("settings store poisoned");
+        *values = new_values;
+        self.persist(&values)
+    }
+
+    fn persist(&self, values: &Map<String, Value>) -> Result<(), String> {
+        let path = self.path.as_ref().ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(values).map_err(|e| e.to_string())?;
+
+        // 先写临时文件再改名，避免写到一半时崩溃导致设置损坏
+        let tmp = path.with_extension("json.tmp");
+        std::fs::write(&tmp, data).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, path).map_err(|e| e.to_string())
+    }
+}
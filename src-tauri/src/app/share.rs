@@ -0,0 +1,256 @@
+// ============================================
+// LAN Session Sharing (desktop only)
+// 内置只读 HTTP 服务，局域网内用浏览器凭 token 实时查看会话
+// ============================================
+
+use crate::app::api::{self, ServerTarget};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+    sync::Notify,
+};
+
+const VIEWER_HTML: &str = include_str!("share_viewer.html");
+/// 请求头最大长度，超过直接断开
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+/// 读取请求头的最长时间，防止连接只建立不发送而一直占用
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub token: String,
+    pub session_id: String,
+    pub url: String,
+    pub started_at: u64,
+}
+
+struct ActiveShare {
+    info: ShareInfo,
+    shutdown: Arc<Notify>,
+}
+
+#[derive(Default)]
+pub struct ShareState {
+    shares: Mutex<HashMap<String, ActiveShare>>,
+}
+
+impl ShareState {
+    pub fn list(&self) -> Vec<ShareInfo> {
+        self.shares
+            .lock()
+            .expect("share state poisoned")
+            .values()
+            .map(|share| share.info.clone())
+            .collect()
+    }
+
+    pub fn stop(&self, token: &str) -> bool {
+        let removed = self
+            .shares
+            .lock()
+            .expect("share state poisoned")
+            .remove(token);
+        match removed {
+            Some(share) => {
+                share.shutdown.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// 128 位随机 token，取自操作系统的安全随机源
+pub fn random_token() -> Result<String, String> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| format!("failed to generate share token: {}", e))?;
+    Ok(bytes.iter().map(|byte| format!("{:02x}", byte)).collect())
+}
+
+/// 本机局域网 IP：向外部地址 "connect" 一个 UDP socket 读取本地地址，不会实际发包
+fn lan_ip() -> IpAddr {
+    UdpSocket::bind("0.0.0.0:0")
+        .and_then(|socket| {
+            socket.connect("192.168.0.1:80")?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn query_param<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query.split('&').find_map(|pair| {
+        let (key, value) = pair.split_once('=')?;
+        (key == name).then_some(value)
+    })
+}
+
+/// 逐字节比较全部内容，耗时与第一个不同字节的位置无关
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, content_type: &str, body: &[u8]) {
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nX-Content-Type-Options: nosniff\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    let _ = stream.write_all(header.as_bytes()).await;
+    let _ = stream.write_all(body).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn handle_connection(
+    mut stream: TcpStream,
+    token: &str,
+    target: &ServerTarget,
+    session_id: &str,
+) {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    let read_head = async {
+        while !buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            match stream.read(&mut chunk).await {
+                Ok(0) | Err(_) => return false,
+                Ok(n) => buf.extend_from_slice(&chunk[..n]),
+            }
+            if buf.len() > MAX_REQUEST_BYTES {
+                return false;
+            }
+        }
+        true
+    };
+    if !matches!(
+        tokio::time::timeout(HEADER_TIMEOUT, read_head).await,
+        Ok(true)
+    ) {
+        return;
+    }
+
+    let request = String::from_utf8_lossy(&buf);
+    let mut parts = request.lines().next().unwrap_or("").split_whitespace();
+    let (method, target_path) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let (path, query) = target_path.split_once('?').unwrap_or((target_path, ""));
+
+    if method != "GET" {
+        respond(
+            &mut stream,
+            "405 Method Not Allowed",
+            "text/plain",
+            b"read-only",
+        )
+        .await;
+        return;
+    }
+    if !query_param(query, "token").is_some_and(|given| tokens_match(given, token)) {
+        respond(&mut stream, "403 Forbidden", "text/plain", b"invalid token").await;
+        return;
+    }
+
+    match path {
+        "/" => {
+            respond(
+                &mut stream,
+                "200 OK",
+                "text/html; charset=utf-8",
+                VIEWER_HTML.as_bytes(),
+            )
+            .await
+        }
+        "/api/messages" => {
+            match api::get_json(target, &format!("/session/{}/message", session_id)).await {
+                Ok(messages) => {
+                    respond(
+                        &mut stream,
+                        "200 OK",
+                        "application/json",
+                        messages.to_string().as_bytes(),
+                    )
+                    .await
+                }
+                Err(error) => {
+                    log::warn!("Share proxy failed: {}", error);
+                    respond(
+                        &mut stream,
+                        "502 Bad Gateway",
+                        "text/plain",
+                        b"upstream unavailable",
+                    )
+                    .await
+                }
+            }
+        }
+        _ => respond(&mut stream, "404 Not Found", "text/plain", b"not found").await,
+    }
+}
+
+/// 开始分享会话，返回可在局域网访问的地址
+pub async fn start_share(
+    state: &ShareState,
+    target: ServerTarget,
+    session_id: String,
+    port: Option<u16>,
+) -> Result<ShareInfo, String> {
+    let listener = TcpListener::bind(SocketAddr::from(([0, 0, 0, 0], port.unwrap_or(0))))
+        .await
+        .map_err(|e| format!("failed to bind share server: {}", e))?;
+    let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+
+    let token = random_token()?;
+    let info = ShareInfo {
+        url: format!("http://{}:{}/?token={}", lan_ip(), port, token),
+        token: token.clone(),
+        session_id: session_id.clone(),
+        started_at: crate::app::now_millis(),
+    };
+    let shutdown = Arc::new(Notify::new());
+
+    state.shares.lock().expect("share state poisoned").insert(
+        token.clone(),
+        ActiveShare {
+            info: info.clone(),
+            shutdown: shutdown.clone(),
+        },
+    );
+    log::info!("Sharing session {} on port {}", session_id, port);
+
+    let target = Arc::new(target);
+    let session_id = Arc::<str>::from(session_id);
+    let token = Arc::<str>::from(token);
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::select! {
+                _ = shutdown.notified() => break,
+                accepted = listener.accept() => {
+                    let Ok((stream, _)) = accepted else {
+                        continue;
+                    };
+                    let (token, target, session_id) =
+                        (token.clone(), target.clone(), session_id.clone());
+                    tauri::async_runtime::spawn(async move {
+                        handle_connection(stream, &token, &target, &session_id).await;
+                    });
+                }
+            }
+        }
+        log::info!("Stopped sharing session {}", session_id);
+    });
+
+    Ok(info)
+}
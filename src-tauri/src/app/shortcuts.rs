@@ -0,0 +1,324 @@
+// ============================================
+// Shortcut Registry (desktop only)
+// 全局快捷键、托盘与原生菜单加速键的统一登记处：用户覆盖持久化到设置，
+// 登记与改键时检测冲突，冲突的快捷键保持未绑定并记录冲突对象
+// ============================================
+
+use crate::app::settings::SettingsStore;
+use serde::Serialize;
+use std::{collections::HashMap, sync::Mutex};
+
+pub const SHORTCUTS_SETTINGS_KEY: &str = "shortcuts";
+
+pub const NEW_WINDOW: &str = "new-window";
+
+/// 快捷键生效的范围
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShortcutScope {
+    /// 系统级，应用不在前台时也生效
+    Global,
+    /// 原生菜单栏
+    Menu,
+    /// 托盘菜单
+    Tray,
+    /// 窗口内由前端处理
+    App,
+}
+
+impl ShortcutScope {
+    /// 两个范围的快捷键能否同时被触发
+    fn overlaps(self, other: Self) -> bool {
+        self == Self::Global
+            || other == Self::Global
+            || (self == Self::Tray) == (other == Self::Tray)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ShortcutDef {
+    pub id: &'static str,
+    pub scope: ShortcutScope,
+    pub default: Option<&'static str>,
+}
+
+/// 内置快捷键；托盘、全局快捷键等模块初始化时另行 [`ShortcutsState::register`]
+const BUILTIN: &[ShortcutDef] = &[ShortcutDef {
+    id: NEW_WINDOW,
+    scope: ShortcutScope::Menu,
+    default: Some("CmdOrCtrl+Shift+N"),
+}];
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutInfo {
+    pub id: String,
+    pub scope: ShortcutScope,
+    pub default: Option<String>,
+    /// 实际生效的加速键；None 表示未绑定（用户禁用或登记时冲突）
+    pub accelerator: Option<String>,
+    pub customized: bool,
+    /// 登记时与之冲突而未能绑定的快捷键 id
+    pub conflict: Option<String>,
+}
+
+const MODIFIERS: &[(&str, &[&str])] = &[
+    (
+        "CmdOrCtrl",
+        &["cmdorctrl", "commandorcontrol", "cmdorcontrol"],
+    ),
+    ("Cmd", &["cmd", "command", "super", "meta"]),
+    ("Ctrl", &["ctrl", "control"]),
+    ("Alt", &["alt", "option"]),
+    ("Shift", &["shift"]),
+];
+
+/// 规范化加速键写法（修饰键固定顺序、大小写统一），便于比较冲突
+pub fn normalize(accelerator: &str) -> Result<String, String> {
+    let parts: Vec<&str> = accelerator.split('+').map(str::trim).collect();
+    let invalid = || format!("'{}' is not a valid shortcut", accelerator);
+    let (key, modifiers) = parts.split_last().ok_or_else(invalid)?;
+    if key.is_empty() {
+        return Err(invalid());
+    }
+
+    let mut present = [false; 5];
+    for modifier in modifiers {
+        let lower = modifier.to_ascii_lowercase();
+        let index = MODIFIERS
+            .iter()
+            .position(|(_, aliases)| aliases.contains(&lower.as_str()))
+            .ok_or_else(invalid)?;
+        present[index] = true;
+    }
+    if MODIFIERS
+        .iter()
+        .any(|(_, aliases)| aliases.contains(&key.to_ascii_lowercase().as_str()))
+    {
+        return Err(invalid());
+    }
+
+    let key = match key.chars().count() {
+        1 => key.to_ascii_uppercase(),
+        _ => {
+            let mut chars = key.chars();
+            let first = chars.next().map(|c| c.to_ascii_uppercase());
+            first.into_iter().chain(chars).collect()
+        }
+    };
+    Ok(MODIFIERS
+        .iter()
+        .zip(present)
+        .filter(|(_, present)| *present)
+        .map(|((name, _), _)| *name)
+        .chain(std::iter::once(key.as_str()))
+        .collect::<Vec<_>>()
+        .join("+"))
+}
+
+struct Entry {
+    def: ShortcutDef,
+    conflict: Option<String>,
+}
+
+pub struct ShortcutsState {
+    entries: Mutex<Vec<Entry>>,
+    /// 用户覆盖：id → 加速键（None 表示禁用）
+    overrides: Mutex<HashMap<String, Option<String>>>,
+}
+
+impl ShortcutsState {
+    pub fn load(settings: &SettingsStore) -> Self {
+        let state = Self {
+            entries: Mutex::new(Vec::new()),
+            overrides: Mutex::new(settings.get_as(SHORTCUTS_SETTINGS_KEY).unwrap_or_default()),
+        };
+        for def in BUILTIN {
+            if let Err(e) = state.register(def.clone()) {
+                log::warn!("{}", e);
+            }
+        }
+        state
+    }
+
+    fn effective(&self, entry: &Entry) -> Option<String> {
+        if entry.conflict.is_some() {
+            return None;
+        }
+        let overrides = self.overrides.lock().expect("shortcuts poisoned");
+        match overrides.get(entry.def.id) {
+            Some(accelerator) => accelerator.clone(),
+            None => entry.def.default.map(str::to_string),
+        }
+    }
+
+    /// 与 `accelerator` 冲突的已登记快捷键（排除 `id` 自身）
+    fn conflicting(
+        &self,
+        entries: &[Entry],
+        id: &str,
+        scope: ShortcutScope,
+        accelerator: &str,
+    ) -> Option<String> {
+        let accelerator = normalize(accelerator).ok()?;
+        entries
+            .iter()
+            .filter(|entry| entry.def.id != id && entry.def.scope.overlaps(scope))
+            .find(|entry| {
+                self.effective(entry)
+                    .and_then(|other| normalize(&other).ok())
+                    .is_some_and(|other| other == accelerator)
+            })
+            .map(|entry| entry.def.id.to_string())
+    }
+
+    /// 登记快捷键；生效的加速键与已登记的冲突时仍登记，但保持未绑定并返回错误
+    pub fn register(&self, def: ShortcutDef) -> Result<(), String> {
+        let mut entries = self.entries.lock().expect("shortcuts poisoned");
+        entries.retain(|entry| entry.def.id != def.id);
+        let mut entry = Entry {
+            def,
+            conflict: None,
+        };
+        let result = match self.effective(&entry) {
+            Some(accelerator) => {
+                match self.conflicting(&entries, entry.def.id, entry.def.scope, &accelerator) {
+                    Some(other) => {
+                        let message = format!(
+                            "Shortcut '{}' ({}) conflicts with '{}', left unbound",
+                            entry.def.id, accelerator, other
+                        );
+                        entry.conflict = Some(other);
+                        Err(message)
+                    }
+                    None => Ok(()),
+                }
+            }
+            None => Ok(()),
+        };
+        entries.push(entry);
+        result
+    }
+
+    /// 当前生效的加速键
+    pub fn accelerator(&self, id: &str) -> Option<String> {
+        let entries = self.entries.lock().expect("shortcuts poisoned");
+        entries
+            .iter()
+            .find(|entry| entry.def.id == id)
+            .and_then(|entry| self.effective(entry))
+    }
+
+    pub fn list(&self) -> Vec<ShortcutInfo> {
+        let entries = self.entries.lock().expect("shortcuts poisoned");
+        let overrides = self.overrides.lock().expect("shortcuts poisoned").clone();
+        entries
+            .iter()
+            .map(|entry| ShortcutInfo {
+                id: entry.def.id.to_string(),
+                scope: entry.def.scope,
+                default: entry.def.default.map(str::to_string),
+                accelerator: self.effective(entry),
+                customized: overrides.contains_key(entry.def.id),
+                conflict: entry.conflict.clone(),
+            })
+            .collect()
+    }
+
+    /// 改键（None 表示禁用）；与其它快捷键冲突时报错。与默认值相同时移除覆盖
+    pub fn rebind(
+        &self,
+        settings: &SettingsStore,
+        id: &str,
+        accelerator: Option<String>,
+    ) -> Result<(), String> {
+        let accelerator = accelerator
+            .filter(|accelerator| !accelerator.trim().is_empty())
+            .map(|accelerator| normalize(&accelerator))
+            .transpose()?;
+
+        let mut entries = self.entries.lock().expect("shortcuts poisoned");
+        let index = entries
+            .iter()
+            .position(|entry| entry.def.id == id)
+            .ok_or_else(|| format!("unknown shortcut '{}'", id))?;
+        let scope = entries[index].def.scope;
+        if let Some(ref accelerator) = accelerator {
+            if let Some(other) = self.conflicting(&entries, id, scope, accelerator) {
+                return Err(format!("{} is already used by '{}'", accelerator, other));
+            }
+        }
+
+        let is_default = accelerator.as_deref()
+            == entries[index]
+                .def
+                .default
+                .and_then(|default| normalize(default).ok())
+                .as_deref();
+        let mut overrides = self.overrides.lock().expect("shortcuts poisoned");
+        if is_default {
+            overrides.remove(id);
+        } else {
+            overrides.insert(id.to_string(), accelerator);
+        }
+        settings.set_as(SHORTCUTS_SETTINGS_KEY, &*overrides)?;
+        drop(overrides);
+
+        entries[index].conflict = None;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(overrides: &[(&str, Option<&str>)]) -> ShortcutsState {
+        ShortcutsState {
+            entries: Mutex::new(Vec::new()),
+            overrides: Mutex::new(
+                overrides
+                    .iter()
+                    .map(|(id, accel)| (id.to_string(), accel.map(str::to_string)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn def(id: &'static str, scope: ShortcutScope, default: &'static str) -> ShortcutDef {
+        ShortcutDef {
+            id,
+            scope,
+            default: Some(default),
+        }
+    }
+
+    #[test]
+    fn normalizes_and_detects_conflicts() {
+        assert_eq!(
+            normalize("shift + cmdorctrl + n").unwrap(),
+            "CmdOrCtrl+Shift+N"
+        );
+        assert_eq!(normalize("Option+Space").unwrap(), "Alt+Space");
+        assert!(normalize("Ctrl+").is_err());
+        assert!(normalize("Hyper+K").is_err());
+        assert!(normalize("Ctrl+Shift").is_err());
+
+        let state = state(&[("palette", Some("ctrl+shift+n"))]);
+        state
+            .register(def("new-window", ShortcutScope::Menu, "Ctrl+Shift+N"))
+            .unwrap();
+        // 托盘菜单与窗口内快捷键互不干扰
+        state
+            .register(def("tray-show", ShortcutScope::Tray, "Ctrl+Shift+N"))
+            .unwrap();
+        assert!(state
+            .register(def("palette", ShortcutScope::Global, "Alt+Space"))
+            .is_err());
+        assert_eq!(state.accelerator("palette"), None);
+        assert_eq!(
+            state.accelerator("new-window").as_deref(),
+            Some("Ctrl+Shift+N")
+        );
+    }
+}
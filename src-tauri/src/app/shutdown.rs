@@ -0,0 +1,155 @@
+// ============================================
+// OS Shutdown / Logout Handling (desktop only)
+// 系统关机、注销时不会走 close-requested 流程：在这里保存窗口状态、刷新日志，
+// 并按用户设置停止或保留托管的 opencode serve
+// - Windows: 子类化窗口过程，处理 WM_QUERYENDSESSION / WM_ENDSESSION
+// - macOS: NSApplication 终止会产生 RunEvent::Exit
+// - Linux / macOS: SIGTERM、SIGHUP
+// ============================================
+
+use crate::app::{
+    commands::opencode::stop_instances, service::ServiceState, settings::SettingsStore,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use tauri::Manager;
+
+pub const SHUTDOWN_SETTINGS_KEY: &str = "shutdownService";
+
+/// 会话结束时如何处理由我们启动的服务
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShutdownServicePolicy {
+    #[default]
+    Stop,
+    /// 保留服务继续运行（例如服务在关机后由系统托管重启）
+    Detach,
+}
+
+static PERSISTED: AtomicBool = AtomicBool::new(false);
+static FINISHED: AtomicBool = AtomicBool::new(false);
+/// 用户已在关闭对话框中做出选择，退出时不再按默认策略处理服务
+static SERVICE_HANDLED: AtomicBool = AtomicBool::new(false);
+
+pub fn mark_service_handled() {
+    SERVICE_HANDLED.store(true, Ordering::SeqCst);
+}
+
+/// 第一阶段：保存窗口状态并刷新日志（关机仍可能被其它程序取消，不动服务）
+pub fn persist(app: &tauri::AppHandle) {
+    if PERSISTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    for window in app.webview_windows().values() {
+        crate::app::window_state::save(&window.as_ref().window());
+    }
+    log::logger().flush();
+}
+
+/// 第二阶段：会话确定结束，按设置停止或保留服务
+pub fn finish(app: &tauri::AppHandle, reason: &str) {
+    if FINISHED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("Session ending ({}), shutting down", reason);
+    persist(app);
+
+    let state = app.state::<ServiceState>();
+    if !SERVICE_HANDLED.load(Ordering::SeqCst) && state.any_started() {
+        let policy: ShutdownServicePolicy = app
+            .state::<SettingsStore>()
+            .get_as(SHUTDOWN_SETTINGS_KEY)
+            .unwrap_or_default();
+        match policy {
+            ShutdownServicePolicy::Stop => {
+                log::info!("Stopping opencode serve before exit");
+                stop_instances(&state.instances());
+            }
+            ShutdownServicePolicy::Detach => {
+                log::info!("Leaving opencode serve running after exit");
+                crate::app::terminate::release_all();
+            }
+        }
+    }
+    log::logger().flush();
+}
+
+/// 监听 SIGTERM / SIGHUP（注销、`systemctl stop`、终端关闭等），处理后正常退出
+#[cfg(unix)]
+pub fn spawn_signal_listener(app: tauri::AppHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tauri::async_runtime::spawn(async move {
+        let (Ok(mut term), Ok(mut hup)) = (
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+        ) else {
+            log::warn!("Failed to install shutdown signal handlers");
+            return;
+        };
+        let reason = tokio::select! {
+            _ = term.recv() => "SIGTERM",
+            _ = hup.recv() => "SIGHUP",
+        };
+        finish(&app, reason);
+        app.exit(0);
+    });
+}
+
+#[cfg(windows)]
+static APP: std::sync::OnceLock<tauri::AppHandle> = std::sync::OnceLock::new();
+
+#[cfg(windows)]
+type SubclassProc = unsafe extern "system" fn(isize, u32, usize, isize, usize, usize) -> isize;
+
+#[cfg(windows)]
+#[link(name = "comctl32")]
+extern "system" {
+    fn SetWindowSubclass(hwnd: isize, proc_: SubclassProc, id: usize, data: usize) -> i32;
+    fn DefSubclassProc(hwnd: isize, msg: u32, wparam: usize, lparam: isize) -> isize;
+}
+
+#[cfg(windows)]
+unsafe extern "system" fn end_session_proc(
+    hwnd: isize,
+    msg: u32,
+    wparam: usize,
+    lparam: isize,
+    _id: usize,
+    _data: usize,
+) -> isize {
+    const WM_QUERYENDSESSION: u32 = 0x0011;
+    const WM_ENDSESSION: u32 = 0x0016;
+
+    if let Some(app) = APP.get() {
+        match msg {
+            WM_QUERYENDSESSION => persist(app),
+            WM_ENDSESSION if wparam != 0 => finish(app, "WM_ENDSESSION"),
+            // wparam 为 0 表示关机被取消，下次仍需重新保存
+            WM_ENDSESSION => PERSISTED.store(false, Ordering::SeqCst),
+            _ => {}
+        }
+    }
+    DefSubclassProc(hwnd, msg, wparam, lparam)
+}
+
+/// 为窗口挂上会话结束消息处理（每个窗口都会收到，finish 只执行一次）
+#[cfg(windows)]
+pub fn watch_window(window: &tauri::WebviewWindow) {
+    const SUBCLASS_ID: usize = 0x0C0D_E5E5;
+
+    let _ = APP.set(window.app_handle().clone());
+    let Ok(hwnd) = window.hwnd() else {
+        return;
+    };
+    // SAFETY: hwnd 是当前窗口的有效句柄，回调签名与 SUBCLASSPROC 一致
+    if unsafe { SetWindowSubclass(hwnd.0 as isize, end_session_proc, SUBCLASS_ID, 0) } == 0 {
+        log::warn!(
+            "Failed to watch end-session messages for '{}'",
+            window.label()
+        );
+    }
+}
+
+#[cfg(not(windows))]
+pub fn watch_window(_window: &tauri::WebviewWindow) {}
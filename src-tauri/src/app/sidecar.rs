@@ -0,0 +1,64 @@
+// ============================================
+// Bundled opencode Sidecar (desktop only)
+// sidecar 构建会把 opencode 放在主程序旁边，校验 SHA-256 后优先使用
+// ============================================
+
+use sha2::{Digest, Sha256};
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+/// build.rs 在存在 sidecar 时写入的校验和；普通构建为 None
+const EXPECTED_SHA256: Option<&str> = option_env!("OPENCODE_SIDECAR_SHA256");
+
+fn sha256_file(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+fn locate_and_verify() -> Option<PathBuf> {
+    let expected = EXPECTED_SHA256?;
+    // Tauri 打包时 externalBin 去掉 target triple 后与主程序放在同一目录
+    let path = std::env::current_exe()
+        .ok()?
+        .parent()?
+        .join(format!("opencode{}", std::env::consts::EXE_SUFFIX));
+    if !path.is_file() {
+        log::warn!("Bundled opencode not found at {}", path.display());
+        return None;
+    }
+
+    match sha256_file(&path) {
+        Ok(actual) if actual.eq_ignore_ascii_case(expected) => {
+            log::info!("Using bundled opencode: {}", path.display());
+            Some(path)
+        }
+        Ok(actual) => {
+            log::error!(
+                "Bundled opencode checksum mismatch (expected {}, got {}); falling back to system install",
+                expected,
+                actual
+            );
+            None
+        }
+        Err(e) => {
+            log::error!("Failed to verify bundled opencode: {}", e);
+            None
+        }
+    }
+}
+
+/// 已通过校验的内置 opencode 路径；结果在进程内缓存，避免重复哈希大文件
+pub fn bundled_opencode() -> Option<&'static Path> {
+    static BUNDLED: OnceLock<Option<PathBuf>> = OnceLock::new();
+    BUNDLED.get_or_init(locate_and_verify).as_deref()
+}
@@ -0,0 +1,143 @@
+// ============================================
+// Split View (desktop only)
+// 一个窗口内并排放置多个 webview，每个 pane 绑定各自的项目/会话
+// ============================================
+
+use serde::Serialize;
+use std::{collections::HashMap, sync::Mutex};
+
+/// pane 的最小宽度占比，防止拖到不可见
+const MIN_PANE_RATIO: f64 = 0.15;
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitPane {
+    pub label: String,
+    pub directory: Option<String>,
+    pub session_id: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SplitLayout {
+    pub panes: Vec<SplitPane>,
+    /// 每个 pane 的宽度占比，总和为 1
+    pub ratios: Vec<f64>,
+}
+
+impl SplitLayout {
+    fn reset_ratios(&mut self) {
+        let count = self.panes.len().max(1);
+        self.ratios = vec![1.0 / count as f64; self.panes.len()];
+    }
+}
+
+#[derive(Default)]
+pub struct SplitViewState {
+    /// window label → 该窗口的 pane 布局
+    layouts: Mutex<HashMap<String, SplitLayout>>,
+}
+
+impl SplitViewState {
+    pub fn get(&self, window_label: &str) -> Option<SplitLayout> {
+        self.layouts
+            .lock()
+            .expect("split view state poisoned")
+            .get(window_label)
+            .cloned()
+    }
+
+    pub fn add_pane(&self, window_label: &str, pane: SplitPane) -> SplitLayout {
+        let mut layouts = self.layouts.lock().expect("split view state poisoned");
+        let layout = layouts.entry(window_label.to_string()).or_default();
+        layout.panes.push(pane);
+        layout.reset_ratios();
+        layout.clone()
+    }
+
+    /// 移除 pane，返回所在窗口 label 与剩余布局
+    pub fn remove_pane(&self, pane_label: &str) -> Option<(String, SplitLayout)> {
+        let mut layouts = self.layouts.lock().expect("split view state poisoned");
+        let (window_label, layout) = layouts
+            .iter_mut()
+            .find(|(_, layout)| layout.panes.iter().any(|pane| pane.label == pane_label))?;
+        layout.panes.retain(|pane| pane.label != pane_label);
+        layout.reset_ratios();
+        Some((window_label.clone(), layout.clone()))
+    }
+
+    pub fn set_ratios(&self, window_label: &str, ratios: &[f64]) -> Result<SplitLayout, String> {
+        let mut layouts = self.layouts.lock().expect("split view state poisoned");
+        let layout = layouts
+            .get_mut(window_label)
+            .ok_or_else(|| format!("window '{}' is not a split view", window_label))?;
+
+        if ratios.len() != layout.panes.len() {
+            return Err(format!(
+                "expected {} ratios, got {}",
+                layout.panes.len(),
+                ratios.len()
+            ));
+        }
+        if ratios
+            .iter()
+            .any(|ratio| !ratio.is_finite() || *ratio < MIN_PANE_RATIO)
+        {
+            return Err(format!(
+                "each pane ratio must be at least {}",
+                MIN_PANE_RATIO
+            ));
+        }
+
+        let total: f64 = ratios.iter().sum();
+        layout.ratios = ratios.iter().map(|ratio| ratio / total).collect();
+        Ok(layout.clone())
+    }
+
+    pub fn pane(&self, pane_label: &str) -> Option<SplitPane> {
+        self.layouts
+            .lock()
+            .expect("split view state poisoned")
+            .values()
+            .flat_map(|layout| layout.panes.iter())
+            .find(|pane| pane.label == pane_label)
+            .cloned()
+    }
+
+    pub fn remove_window(&self, window_label: &str) -> Option<SplitLayout> {
+        self.layouts
+            .lock()
+            .expect("split view state poisoned")
+            .remove(window_label)
+    }
+}
+
+/// 按布局占比重新排列窗口内各 pane 的位置与大小
+pub fn apply_layout<R: tauri::Runtime>(window: &tauri::Window<R>, layout: &SplitLayout) {
+    let Ok(size) = window.inner_size() else {
+        return;
+    };
+
+    let mut x = 0u32;
+    let count = layout.panes.len();
+    for (index, (pane, ratio)) in layout.panes.iter().zip(&layout.ratios).enumerate() {
+        let Some(webview) = window
+            .webviews()
+            .into_iter()
+            .find(|webview| webview.label() == pane.label)
+        else {
+            continue;
+        };
+
+        // 最后一个 pane 吃掉取整误差，避免右侧留缝
+        let width = if index + 1 == count {
+            size.width.saturating_sub(x)
+        } else {
+            (size.width as f64 * ratio).round() as u32
+        };
+
+        let _ = webview.set_position(tauri::PhysicalPosition::new(x, 0));
+        let _ = webview.set_size(tauri::PhysicalSize::new(width, size.height));
+        x += width;
+    }
+}
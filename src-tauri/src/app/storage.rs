@@ -0,0 +1,291 @@
+// ============================================
+// Storage Janitor (desktop only)
+// 附件、缩略图、导出暂存与 SSE 录制等临时文件统一放在 profile 数据目录下的分类子目录，
+// 后台任务按分类的最长保留时间清理，并在超出总存储预算时从最旧的文件开始删除
+// ============================================
+
+use crate::app::settings::SettingsStore;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::{Duration, UNIX_EPOCH},
+};
+use tauri::Manager;
+
+pub const STORAGE_SETTINGS_KEY: &str = "storageJanitor";
+const FIRST_RUN_DELAY: Duration = Duration::from_secs(60);
+const RUN_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const DAY_MS: u64 = 24 * 60 * 60 * 1000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StorageCategory {
+    Attachments,
+    Thumbnails,
+    Exports,
+    Recordings,
+}
+
+impl StorageCategory {
+    pub const ALL: [StorageCategory; 4] = [
+        Self::Attachments,
+        Self::Thumbnails,
+        Self::Exports,
+        Self::Recordings,
+    ];
+
+    fn dir_name(self) -> &'static str {
+        match self {
+            Self::Attachments => "attachments",
+            Self::Thumbnails => "thumbnails",
+            Self::Exports => "exports",
+            Self::Recordings => "recordings",
+        }
+    }
+
+    fn default_max_age_days(self) -> u32 {
+        match self {
+            Self::Attachments => 30,
+            Self::Thumbnails => 14,
+            Self::Exports => 2,
+            Self::Recordings => 7,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct StorageConfig {
+    /// 所有分类合计的存储上限（MB），0 表示不限制
+    pub budget_mb: u64,
+    /// 各分类文件的最长保留天数，0 表示不按时间清理；未配置的分类使用默认值
+    pub max_age_days: HashMap<StorageCategory, u32>,
+}
+
+impl Default for StorageConfig {
+    fn default() -> Self {
+        Self {
+            budget_mb: 1024,
+            max_age_days: HashMap::new(),
+        }
+    }
+}
+
+impl StorageConfig {
+    fn max_age_ms(&self, category: StorageCategory) -> Option<u64> {
+        let days = self
+            .max_age_days
+            .get(&category)
+            .copied()
+            .unwrap_or_else(|| category.default_max_age_days());
+        (days > 0).then(|| u64::from(days) * DAY_MS)
+    }
+
+    fn budget_bytes(&self) -> Option<u64> {
+        (self.budget_mb > 0).then(|| self.budget_mb.saturating_mul(1024 * 1024))
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryUsage {
+    pub category: StorageCategory,
+    pub bytes: u64,
+    pub files: usize,
+    /// 最旧文件的修改时间（毫秒时间戳）
+    pub oldest: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageBreakdown {
+    pub categories: Vec<CategoryUsage>,
+    pub total_bytes: u64,
+    pub budget_bytes: Option<u64>,
+}
+
+#[derive(Clone, Debug)]
+struct StoredFile {
+    path: PathBuf,
+    category: StorageCategory,
+    size: u64,
+    modified: u64,
+}
+
+/// 分类目录（不存在时创建），写入临时文件的功能都应从这里取目录
+pub fn category_dir(app: &tauri::AppHandle, category: StorageCategory) -> Option<PathBuf> {
+    let dir = crate::app::profile::data_dir(app)?.join(category.dir_name());
+    std::fs::create_dir_all(&dir).ok()?;
+    Some(dir)
+}
+
+fn collect_files(dir: &Path, category: StorageCategory, out: &mut Vec<StoredFile>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_files(&entry.path(), category, out);
+        } else if metadata.is_file() {
+            let modified = metadata
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|age| age.as_millis() as u64)
+                .unwrap_or(0);
+            out.push(StoredFile {
+                path: entry.path(),
+                category,
+                size: metadata.len(),
+                modified,
+            });
+        }
+    }
+}
+
+fn scan(app: &tauri::AppHandle) -> Vec<StoredFile> {
+    let mut files = Vec::new();
+    for category in StorageCategory::ALL {
+        if let Some(dir) = category_dir(app, category) {
+            collect_files(&dir, category, &mut files);
+        }
+    }
+    files
+}
+
+/// 选出要删除的文件：先按分类保留时间，再在超出预算时从最旧的开始删
+fn plan_cleanup(files: &[StoredFile], config: &StorageConfig, now: u64) -> Vec<usize> {
+    let mut doomed = vec![false; files.len()];
+    for (index, file) in files.iter().enumerate() {
+        if let Some(max_age) = config.max_age_ms(file.category) {
+            doomed[index] = now.saturating_sub(file.modified) > max_age;
+        }
+    }
+
+    if let Some(budget) = config.budget_bytes() {
+        let mut total: u64 = files
+            .iter()
+            .zip(&doomed)
+            .filter(|(_, doomed)| !**doomed)
+            .map(|(file, _)| file.size)
+            .sum();
+        let mut by_age: Vec<usize> = (0..files.len()).filter(|&i| !doomed[i]).collect();
+        by_age.sort_by_key(|&i| files[i].modified);
+        for index in by_age {
+            if total <= budget {
+                break;
+            }
+            doomed[index] = true;
+            total -= files[index].size;
+        }
+    }
+
+    (0..files.len()).filter(|&i| doomed[i]).collect()
+}
+
+pub fn breakdown(app: &tauri::AppHandle, config: &StorageConfig) -> StorageBreakdown {
+    let files = scan(app);
+    let categories: Vec<CategoryUsage> = StorageCategory::ALL
+        .into_iter()
+        .map(|category| {
+            let matching = files.iter().filter(|file| file.category == category);
+            CategoryUsage {
+                category,
+                bytes: matching.clone().map(|file| file.size).sum(),
+                files: matching.clone().count(),
+                oldest: matching.map(|file| file.modified).min(),
+            }
+        })
+        .collect();
+    StorageBreakdown {
+        total_bytes: categories.iter().map(|usage| usage.bytes).sum(),
+        categories,
+        budget_bytes: config.budget_bytes(),
+    }
+}
+
+/// 清空一个分类，返回释放的字节数
+pub fn clear(app: &tauri::AppHandle, category: StorageCategory) -> Result<u64, String> {
+    let dir = category_dir(app, category).ok_or("app data dir unavailable")?;
+    let mut files = Vec::new();
+    collect_files(&dir, category, &mut files);
+    let freed = files.iter().map(|file| file.size).sum();
+    std::fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    log::info!("Cleared {:?} storage, freed {} bytes", category, freed);
+    Ok(freed)
+}
+
+/// 执行一次清理，返回释放的字节数
+pub fn run_janitor(app: &tauri::AppHandle, config: &StorageConfig) -> u64 {
+    let files = scan(app);
+    let mut freed = 0;
+    for index in plan_cleanup(&files, config, crate::app::now_millis()) {
+        let file = &files[index];
+        match std::fs::remove_file(&file.path) {
+            Ok(()) => freed += file.size,
+            Err(e) => log::debug!("Failed to remove {}: {}", file.path.display(), e),
+        }
+    }
+    if freed > 0 {
+        log::info!("Storage janitor freed {} bytes", freed);
+    }
+    freed
+}
+
+pub fn spawn_janitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(FIRST_RUN_DELAY).await;
+        loop {
+            let config: StorageConfig = app
+                .state::<SettingsStore>()
+                .get_as(STORAGE_SETTINGS_KEY)
+                .unwrap_or_default();
+            let handle = app.clone();
+            let _ =
+                tauri::async_runtime::spawn_blocking(move || run_janitor(&handle, &config)).await;
+            tokio::time::sleep(RUN_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(category: StorageCategory, size: u64, modified: u64) -> StoredFile {
+        StoredFile {
+            path: PathBuf::new(),
+            category,
+            size,
+            modified,
+        }
+    }
+
+    #[test]
+    fn plan_cleanup_applies_age_then_budget() {
+        let now = 100 * DAY_MS;
+        let files = vec![
+            // 导出暂存默认保留 2 天
+            file(StorageCategory::Exports, 10, now - 3 * DAY_MS),
+            file(StorageCategory::Attachments, 600 * 1024, now - 10 * DAY_MS),
+            file(StorageCategory::Attachments, 600 * 1024, now - DAY_MS),
+            file(StorageCategory::Thumbnails, 100, now),
+        ];
+        let config = StorageConfig {
+            budget_mb: 1,
+            max_age_days: HashMap::new(),
+        };
+        assert_eq!(plan_cleanup(&files, &config, now), vec![0, 1]);
+
+        let unlimited = StorageConfig {
+            budget_mb: 0,
+            max_age_days: HashMap::from([(StorageCategory::Exports, 0)]),
+        };
+        assert!(plan_cleanup(&files, &unlimited, now).is_empty());
+    }
+}
@@ -0,0 +1,385 @@
+// ============================================
+// Graceful Service Termination (desktop only)
+// 停止托管的 opencode serve：先请求退出（Unix 上 SIGTERM，Windows 上 CTRL_BREAK），
+// 宽限期内未退出再强制结束。serve 运行在独立的进程组中，
+// 信号发给整个进程组，LSP / 工具子进程不会残留
+// Windows 上进程组不包含孙进程（node、LSP），另把 serve 放进带 kill-on-close 的 Job Object：
+// 停止时结束整个作业；应用退出时句柄关闭，系统同样会结束整棵进程树（选择保留服务时先解除该限制）
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::atomic::{AtomicU64, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+pub const TERMINATION_SETTINGS_KEY: &str = "serviceTermination";
+
+const DEFAULT_GRACE_MS: u64 = 5_000;
+const MAX_GRACE_MS: u64 = 60_000;
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TerminationConfig {
+    /// 请求退出后等待的时间，超时强制结束；0 表示直接强制结束
+    pub grace_ms: u64,
+}
+
+impl Default for TerminationConfig {
+    fn default() -> Self {
+        Self {
+            grace_ms: DEFAULT_GRACE_MS,
+        }
+    }
+}
+
+impl TerminationConfig {
+    fn grace(&self) -> Duration {
+        Duration::from_millis(self.grace_ms.min(MAX_GRACE_MS))
+    }
+}
+
+static GRACE_MS: AtomicU64 = AtomicU64::new(DEFAULT_GRACE_MS);
+
+/// 应用宽限期设置，对之后的停止操作生效
+pub fn configure(config: &TerminationConfig) {
+    GRACE_MS.store(config.grace().as_millis() as u64, Ordering::SeqCst);
+}
+
+pub fn config() -> TerminationConfig {
+    TerminationConfig {
+        grace_ms: GRACE_MS.load(Ordering::SeqCst),
+    }
+}
+
+/// 让子进程成为新进程组的组长，之后可以一起结束它派生的进程
+pub fn isolate(cmd: &mut Command) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        cmd.process_group(0);
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        cmd.creation_flags(ISOLATE_FLAGS);
+    }
+}
+
+/// CREATE_NO_WINDOW | CREATE_NEW_PROCESS_GROUP
+#[cfg(target_os = "windows")]
+const ISOLATE_FLAGS: u32 = 0x08000000 | 0x00000200;
+
+/// 启动经 `isolate` 设置的命令。Windows 上尽量脱离应用所在的作业（CREATE_BREAKAWAY_FROM_JOB，
+/// 例如从终端启动时），再放进该服务自己的 Job Object
+pub fn spawn(cmd: &mut Command) -> std::io::Result<Child> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_BREAKAWAY_FROM_JOB: u32 = 0x01000000;
+        cmd.creation_flags(ISOLATE_FLAGS | CREATE_BREAKAWAY_FROM_JOB);
+        let child = match cmd.spawn() {
+            // 所在作业不允许脱离时退回普通启动（Windows 8 起支持嵌套作业）
+            Err(e) if e.kind() == std::io::ErrorKind::PermissionDenied => {
+                cmd.creation_flags(ISOLATE_FLAGS);
+                cmd.spawn()?
+            }
+            result => result?,
+        };
+        job::contain(&child);
+        Ok(child)
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        cmd.spawn()
+    }
+}
+
+/// 进程已退出：结束其作业中残留的子进程并释放作业（Windows），其它平台无需处理
+pub fn reap(pid: u32) {
+    #[cfg(target_os = "windows")]
+    job::close(pid, true);
+    #[cfg(not(target_os = "windows"))]
+    let _ = pid;
+}
+
+/// 应用退出但保留服务时调用：解除 kill-on-close，关闭句柄后服务继续运行
+pub fn release_all() {
+    #[cfg(target_os = "windows")]
+    job::release_all();
+}
+
+/// 结束进程（组）：请求退出，宽限期内未退出则强制结束。阻塞直到完成；
+/// 传入 `child` 时顺带回收，避免留下僵尸进程，并返回其退出状态
+pub fn terminate(pid: u32, mut child: Option<Child>) -> Option<ExitStatus> {
+    let grace = Duration::from_millis(GRACE_MS.load(Ordering::SeqCst));
+    if !grace.is_zero() && request_exit(pid) {
+        let deadline = Instant::now() + grace;
+        while Instant::now() < deadline {
+            if !alive(pid, child.as_mut()) {
+                log::info!("Process {} exited gracefully", pid);
+                reap(pid);
+                // 已由 try_wait 回收，wait 直接返回记下的状态
+                return child.and_then(|mut child| child.wait().ok());
+            }
+            thread::sleep(POLL_INTERVAL);
+        }
+        log::warn!(
+            "Process {} did not exit within {:?}, killing it",
+            pid,
+            grace
+        );
+    }
+
+    force_kill(pid);
+    reap(pid);
+    child.and_then(|mut child| child.wait().ok())
+}
+
+fn quiet(mut cmd: Command) -> bool {
+    cmd.stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// 向进程组发送信号；进程不是组长（例如接管的外部进程）时只发给它本身
+#[cfg(unix)]
+fn signal(pid: u32, signal: &str) -> bool {
+    // 负数 PID 表示进程组；procps 的 kill 需要 `--` 才不会把它当作选项
+    let mut cmd = Command::new("kill");
+    cmd.args([signal, "--", &format!("-{}", pid)]);
+    if quiet(cmd) {
+        return true;
+    }
+    let mut cmd = Command::new("kill");
+    cmd.args([signal, &pid.to_string()]);
+    quiet(cmd)
+}
+
+#[cfg(unix)]
+fn request_exit(pid: u32) -> bool {
+    let sent = signal(pid, "-TERM");
+    // 被空闲挂起（SIGSTOP）的进程需要先恢复才能处理 SIGTERM
+    signal(pid, "-CONT");
+    sent
+}
+
+/// 组长已退出后，只要组里还有进程就视为未结束
+#[cfg(unix)]
+fn alive(pid: u32, child: Option<&mut Child>) -> bool {
+    if let Some(child) = child {
+        if matches!(child.try_wait(), Ok(None)) {
+            return true;
+        }
+    }
+    signal(pid, "-0")
+}
+
+#[cfg(unix)]
+fn force_kill(pid: u32) {
+    signal(pid, "-KILL");
+}
+
+#[cfg(target_os = "windows")]
+#[link(name = "kernel32")]
+extern "system" {
+    fn GetConsoleWindow() -> isize;
+    fn AttachConsole(pid: u32) -> i32;
+    fn FreeConsole() -> i32;
+    fn SetConsoleCtrlHandler(handler: isize, add: i32) -> i32;
+    fn GenerateConsoleCtrlEvent(event: u32, group: u32) -> i32;
+}
+
+/// 附加到子进程的（隐藏）控制台，向其进程组发送 CTRL_BREAK
+#[cfg(target_os = "windows")]
+fn request_exit(pid: u32) -> bool {
+    const CTRL_BREAK_EVENT: u32 = 1;
+    // 附加控制台是进程级状态，同时停止多个实例时需要串行
+    static CONSOLE: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    let _guard = CONSOLE.lock();
+
+    // SAFETY: 以下均为无指针参数的 Win32 调用
+    unsafe {
+        // 带控制台运行（调试构建）时不能附加到别的控制台
+        if GetConsoleWindow() != 0 || AttachConsole(pid) == 0 {
+            return false;
+        }
+        // 忽略同一事件，避免结束自己；GUI 进程本就不处理控制台事件
+        SetConsoleCtrlHandler(0, 1);
+        let sent = GenerateConsoleCtrlEvent(CTRL_BREAK_EVENT, pid) != 0;
+        FreeConsole();
+        sent
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn alive(pid: u32, child: Option<&mut Child>) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    if let Some(child) = child {
+        return matches!(child.try_wait(), Ok(None));
+    }
+    Command::new("tasklist")
+        .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains(&pid.to_string()))
+}
+
+#[cfg(target_os = "windows")]
+fn force_kill(pid: u32) {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    let mut cmd = Command::new("taskkill");
+    cmd.args(["/PID", &pid.to_string(), "/F", "/T"])
+        .creation_flags(CREATE_NO_WINDOW);
+    quiet(cmd);
+}
+
+/// Job Object 管理：按 serve 的 PID 保存作业句柄
+#[cfg(target_os = "windows")]
+mod job {
+    use std::{os::windows::io::AsRawHandle, process::Child, sync::Mutex};
+
+    const JOB_OBJECT_EXTENDED_LIMIT_INFORMATION_CLASS: i32 = 9;
+    const JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE: u32 = 0x2000;
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct BasicLimitInformation {
+        per_process_user_time_limit: i64,
+        per_job_user_time_limit: i64,
+        limit_flags: u32,
+        minimum_working_set_size: usize,
+        maximum_working_set_size: usize,
+        active_process_limit: u32,
+        affinity: usize,
+        priority_class: u32,
+        scheduling_class: u32,
+    }
+
+    #[repr(C)]
+    #[derive(Default)]
+    struct ExtendedLimitInformation {
+        basic: BasicLimitInformation,
+        io_counters: [u64; 6],
+        process_memory_limit: usize,
+        job_memory_limit: usize,
+        peak_process_memory_used: usize,
+        peak_job_memory_used: usize,
+    }
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn CreateJobObjectW(attributes: *const std::ffi::c_void, name: *const u16) -> isize;
+        fn SetInformationJobObject(
+            job: isize,
+            class: i32,
+            info: *const std::ffi::c_void,
+            length: u32,
+        ) -> i32;
+        fn AssignProcessToJobObject(job: isize, process: isize) -> i32;
+        fn TerminateJobObject(job: isize, exit_code: u32) -> i32;
+        fn CloseHandle(handle: isize) -> i32;
+    }
+
+    /// (PID, 作业句柄)
+    static JOBS: Mutex<Vec<(u32, isize)>> = Mutex::new(Vec::new());
+
+    fn set_kill_on_close(job: isize, enabled: bool) -> bool {
+        let mut info = ExtendedLimitInformation::default();
+        if enabled {
+            info.basic.limit_flags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+        }
+        // SAFETY: info 在调用期间有效，长度与结构体一致
+        unsafe {
+            SetInformationJobObject(
+                job,
+                JOB_OBJECT_EXTENDED_LIMIT_INFORMATION_CLASS,
+                &info as *const _ as *const std::ffi::c_void,
+                std::mem::size_of::<ExtendedLimitInformation>() as u32,
+            ) != 0
+        }
+    }
+
+    /// 新建 kill-on-close 作业并把子进程放进去；失败时只记录日志，停止时退回 taskkill /T。
+    /// serve 在放进作业前派生的进程不在作业中，实际上启动后立即执行，不会发生
+    pub(super) fn contain(child: &Child) {
+        // SAFETY: 空指针参数表示默认安全属性、匿名作业
+        let job = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+        if job == 0 {
+            log::warn!(
+                "CreateJobObjectW failed: {}",
+                std::io::Error::last_os_error()
+            );
+            return;
+        }
+        // SAFETY: job 与子进程句柄在调用期间有效
+        let assigned = set_kill_on_close(job, true)
+            && unsafe { AssignProcessToJobObject(job, child.as_raw_handle() as isize) } != 0;
+        if !assigned {
+            log::warn!(
+                "Failed to put PID {} into a job object: {}",
+                child.id(),
+                std::io::Error::last_os_error()
+            );
+            // SAFETY: job 是上面创建的有效句柄
+            unsafe { CloseHandle(job) };
+            return;
+        }
+        if let Ok(mut jobs) = JOBS.lock() {
+            jobs.push((child.id(), job));
+        }
+    }
+
+    /// 关闭 PID 对应的作业；`kill` 时先结束其中所有进程
+    pub(super) fn close(pid: u32, kill: bool) {
+        let job = JOBS.lock().ok().and_then(|mut jobs| {
+            let index = jobs.iter().position(|(job_pid, _)| *job_pid == pid)?;
+            Some(jobs.swap_remove(index).1)
+        });
+        if let Some(job) = job {
+            // SAFETY: job 是 contain 创建、尚未关闭的句柄
+            unsafe {
+                if kill {
+                    TerminateJobObject(job, 1);
+                }
+                CloseHandle(job);
+            }
+        }
+    }
+
+    pub(super) fn release_all() {
+        let jobs = JOBS
+            .lock()
+            .map(|mut jobs| std::mem::take(&mut *jobs))
+            .unwrap_or_default();
+        for (_, job) in jobs {
+            set_kill_on_close(job, false);
+            // SAFETY: job 是 contain 创建、尚未关闭的句柄
+            unsafe { CloseHandle(job) };
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grace_is_capped() {
+        let config = |grace_ms| TerminationConfig { grace_ms };
+        assert_eq!(config(0).grace(), Duration::ZERO);
+        assert_eq!(config(2_500).grace(), Duration::from_millis(2_500));
+        assert_eq!(
+            config(u64::MAX).grace(),
+            Duration::from_millis(MAX_GRACE_MS)
+        );
+    }
+}
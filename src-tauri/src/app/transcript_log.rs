@@ -0,0 +1,519 @@
+// ============================================
+// Transcript Write-Ahead Log (desktop only)
+// 运行中的会话把 bridge 上收到的消息 / 工具事件逐条追加到磁盘上的按会话日志，
+// 会话空闲（服务端已持久化）后删除；应用或系统崩溃后残留的日志在重启时与服务端对比，
+// 找回用户看到过但服务端没有保存下来的内容
+// ============================================
+
+use crate::app::{
+    api::{self, ServerTarget},
+    bridge::{event_data, stream_endpoint, BridgeState},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap, VecDeque},
+    fs::File,
+    hash::{Hash, Hasher},
+    io::Write,
+    path::PathBuf,
+    sync::Mutex,
+    time::Duration,
+};
+use tauri::Manager;
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(2);
+/// 超过该时间的残留日志在启动时直接删除
+const MAX_LOG_AGE: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+/// 去重窗口：同一事件会经多个窗口的连接到达
+const RECENT_EVENTS: usize = 64;
+
+/// 日志中的一行
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LogLine {
+    at: u64,
+    server: String,
+    #[serde(default)]
+    server_id: Option<String>,
+    #[serde(default)]
+    directory: Option<String>,
+    event: Value,
+}
+
+struct OpenLog {
+    file: File,
+    recent: VecDeque<u64>,
+    dirty: bool,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptSummary {
+    pub session_id: String,
+    pub server: String,
+    pub directory: Option<String>,
+    pub events: usize,
+    pub updated_at: u64,
+}
+
+/// 找回的一条消息及其分片（与服务端 `/session/:id/message` 的结构一致）
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredMessage {
+    pub info: Value,
+    pub parts: Vec<Value>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveredTranscript {
+    pub session_id: String,
+    pub directory: Option<String>,
+    /// 服务端缺失或不完整的消息
+    pub messages: Vec<RecoveredMessage>,
+    /// 服务端不可达时为 false，`messages` 为日志中的全部内容
+    pub reconciled: bool,
+}
+
+pub struct TranscriptLogState {
+    dir: Option<PathBuf>,
+    open: Mutex<HashMap<String, OpenLog>>,
+}
+
+/// 会话 id 直接作为文件名，只接受安全字符
+fn valid_session_id(session_id: &str) -> bool {
+    !session_id.is_empty()
+        && session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+fn fingerprint(value: &Value) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.to_string().hash(&mut hasher);
+    hasher.finish()
+}
+
+impl TranscriptLogState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let dir = crate::app::profile::data_dir(app).map(|dir| dir.join("transcripts"));
+        if let Some(entries) = dir.as_ref().and_then(|dir| std::fs::read_dir(dir).ok()) {
+            for entry in entries.flatten() {
+                let expired = entry
+                    .metadata()
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > MAX_LOG_AGE);
+                if expired {
+                    let _ = std::fs::remove_file(entry.path());
+                }
+            }
+        }
+        Self {
+            dir,
+            open: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn path(&self, session_id: &str) -> Option<PathBuf> {
+        if !valid_session_id(session_id) {
+            return None;
+        }
+        Some(self.dir.as_ref()?.join(format!("{}.jsonl", session_id)))
+    }
+
+    fn append(&self, session_id: &str, line: &LogLine) -> Result<(), String> {
+        let path = self.path(session_id).ok_or("invalid transcript path")?;
+        let mut open = self.open.lock().expect("transcript log poisoned");
+        let log = match open.entry(session_id.to_string()) {
+            std::collections::hash_map::Entry::Occupied(entry) => entry.into_mut(),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+                }
+                let file = std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&path)
+                    .map_err(|e| e.to_string())?;
+                entry.insert(OpenLog {
+                    file,
+                    recent: VecDeque::new(),
+                    dirty: false,
+                })
+            }
+        };
+
+        let hash = fingerprint(&line.event);
+        if log.recent.contains(&hash) {
+            return Ok(());
+        }
+        if log.recent.len() >= RECENT_EVENTS {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(hash);
+
+        let text = serde_json::to_string(line).map_err(|e| e.to_string())?;
+        writeln!(log.file, "{}", text).map_err(|e| e.to_string())?;
+        log.dirty = true;
+        Ok(())
+    }
+
+    /// 会话已由服务端持久化，删除日志
+    fn finish(&self, session_id: &str) {
+        let was_open = self
+            .open
+            .lock()
+            .expect("transcript log poisoned")
+            .remove(session_id)
+            .is_some();
+        if was_open {
+            self.discard(session_id);
+        }
+    }
+
+    /// 把写入的内容刷到磁盘，系统崩溃时最多丢失一个同步周期
+    fn sync(&self) {
+        let mut open = self.open.lock().expect("transcript log poisoned");
+        for log in open.values_mut().filter(|log| log.dirty) {
+            if let Err(e) = log.file.sync_data() {
+                log::debug!("Failed to sync transcript log: {}", e);
+            }
+            log.dirty = false;
+        }
+    }
+
+    fn read(&self, session_id: &str) -> Vec<LogLine> {
+        self.path(session_id)
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|data| {
+                data.lines()
+                    // 崩溃时最后一行可能只写了一半
+                    .filter_map(|line| serde_json::from_str(line).ok())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 上次运行残留、尚未对账的日志（不含当前正在写入的）
+    pub fn pending(&self) -> Vec<TranscriptSummary> {
+        let Some(entries) = self
+            .dir
+            .as_ref()
+            .and_then(|dir| std::fs::read_dir(dir).ok())
+        else {
+            return Vec::new();
+        };
+        let open = self.open.lock().expect("transcript log poisoned");
+        let session_ids: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                name.strip_suffix(".jsonl").map(str::to_string)
+            })
+            .filter(|session_id| !open.contains_key(session_id))
+            .collect();
+        drop(open);
+
+        session_ids
+            .into_iter()
+            .filter_map(|session_id| {
+                let lines = self.read(&session_id);
+                let last = lines.last()?;
+                Some(TranscriptSummary {
+                    server: last.server.clone(),
+                    directory: last.directory.clone(),
+                    events: lines.len(),
+                    updated_at: last.at,
+                    session_id,
+                })
+            })
+            .collect()
+    }
+
+    pub fn discard(&self, session_id: &str) {
+        if let Some(path) = self.path(session_id) {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+fn message_entry<'a>(
+    messages: &'a mut HashMap<String, RecoveredMessage>,
+    order: &mut Vec<String>,
+    id: &str,
+) -> &'a mut RecoveredMessage {
+    messages.entry(id.to_string()).or_insert_with(|| {
+        order.push(id.to_string());
+        RecoveredMessage {
+            info: Value::Null,
+            parts: Vec::new(),
+        }
+    })
+}
+
+/// 按 id 合并日志中的事件，得到每条消息及分片的最后状态
+fn compact(events: &[Value]) -> Vec<RecoveredMessage> {
+    let mut order: Vec<String> = Vec::new();
+    let mut messages: HashMap<String, RecoveredMessage> = HashMap::new();
+
+    for event in events {
+        let properties = &event["properties"];
+        match event["type"].as_str() {
+            Some("message.updated") => {
+                let Some(id) = properties["info"]["id"].as_str() else {
+                    continue;
+                };
+                message_entry(&mut messages, &mut order, id).info = properties["info"].clone();
+            }
+            Some("message.part.updated") => {
+                let part = &properties["part"];
+                let (Some(message_id), Some(part_id)) =
+                    (part["messageID"].as_str(), part["id"].as_str())
+                else {
+                    continue;
+                };
+                let parts = &mut message_entry(&mut messages, &mut order, message_id).parts;
+                match parts.iter_mut().find(|p| p["id"] == part_id) {
+                    Some(existing) => *existing = part.clone(),
+                    None => parts.push(part.clone()),
+                }
+            }
+            Some("message.part.removed") => {
+                let (Some(message_id), Some(part_id)) = (
+                    properties["messageID"].as_str(),
+                    properties["partID"].as_str(),
+                ) else {
+                    continue;
+                };
+                if let Some(message) = messages.get_mut(message_id) {
+                    message.parts.retain(|p| p["id"] != part_id);
+                }
+            }
+            Some("message.removed") => {
+                if let Some(message_id) = properties["messageID"].as_str() {
+                    messages.remove(message_id);
+                    order.retain(|id| id != message_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    order
+        .into_iter()
+        .filter_map(|id| messages.remove(&id))
+        .collect()
+}
+
+/// 分片内容的长度，用于判断服务端保存的版本是否比日志里的旧
+fn part_len(part: &Value) -> usize {
+    ["text", "state"]
+        .iter()
+        .map(|key| match &part[key] {
+            Value::String(text) => text.len(),
+            Value::Null => 0,
+            other => other.to_string().len(),
+        })
+        .sum()
+}
+
+/// 只保留服务端缺失或比日志更短的消息 / 分片
+fn missing_from_server(local: Vec<RecoveredMessage>, server: &Value) -> Vec<RecoveredMessage> {
+    let server_messages: HashMap<&str, &Value> = server
+        .as_array()
+        .map(|messages| {
+            messages
+                .iter()
+                .filter_map(|message| Some((message["info"]["id"].as_str()?, message)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    local
+        .into_iter()
+        .filter_map(|mut message| {
+            let id = message.info["id"]
+                .as_str()
+                .or_else(|| message.parts.first()?["messageID"].as_str())?
+                .to_string();
+            let Some(stored) = server_messages.get(id.as_str()) else {
+                return Some(message);
+            };
+            let stored_parts = stored["parts"].as_array();
+            message.parts.retain(|part| {
+                let stored = stored_parts
+                    .and_then(|parts| parts.iter().find(|stored| stored["id"] == part["id"]));
+                stored.is_none_or(|stored| part_len(stored) < part_len(part))
+            });
+            if message.parts.is_empty() {
+                return None;
+            }
+            message.info = stored["info"].clone();
+            Some(message)
+        })
+        .collect()
+}
+
+/// 与服务端对账，返回需要找回的内容；全部已保存时删除日志
+pub async fn reconcile(
+    app: &tauri::AppHandle,
+    session_id: &str,
+    auth_header: Option<String>,
+) -> Result<RecoveredTranscript, String> {
+    let state = app.state::<TranscriptLogState>();
+    let lines = state.read(session_id);
+    let last = lines.last().ok_or("no transcript log for session")?;
+    let target = ServerTarget {
+        url: last.server.clone(),
+        directory: last.directory.clone(),
+        auth_header,
+        server_id: last.server_id.clone(),
+        ..Default::default()
+    };
+    let directory = last.directory.clone();
+    let events: Vec<Value> = lines.into_iter().map(|line| line.event).collect();
+    let local = compact(&events);
+
+    let path = format!("/session/{}/message", session_id);
+    let (messages, reconciled) = match api::get_json(&target, &path).await {
+        Ok(server) => (missing_from_server(local, &server), true),
+        Err(e) => {
+            log::warn!("Failed to reconcile transcript {}: {}", session_id, e);
+            (local, false)
+        }
+    };
+    if reconciled && messages.is_empty() {
+        state.discard(session_id);
+    }
+    Ok(RecoveredTranscript {
+        session_id: session_id.to_string(),
+        directory,
+        messages,
+        reconciled,
+    })
+}
+
+/// 事件所属会话，以及该事件是否表示会话已空闲
+fn classify(event: &Value) -> Option<(&str, bool)> {
+    let properties = &event["properties"];
+    match event["type"].as_str()? {
+        "message.updated" => Some((properties["info"]["sessionID"].as_str()?, false)),
+        "message.part.updated" => Some((properties["part"]["sessionID"].as_str()?, false)),
+        "message.removed" | "message.part.removed" => {
+            Some((properties["sessionID"].as_str()?, false))
+        }
+        "session.idle" => Some((properties["sessionID"].as_str()?, true)),
+        "session.status" if properties["status"]["type"] == "idle" => {
+            Some((properties["sessionID"].as_str()?, true))
+        }
+        _ => None,
+    }
+}
+
+/// 注册 bridge 事件观察者，并定期把日志刷到磁盘
+pub fn install(app: &tauri::AppHandle) {
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |args, block| {
+            if !block.contains("\"message.") && !block.contains("\"session.") {
+                return;
+            }
+            let Some(mut event) =
+                event_data(block).and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            else {
+                return;
+            };
+            let Some((base, _, stream_directory)) = stream_endpoint(args.url()) else {
+                return;
+            };
+            let directory = match event.get_mut("payload").map(Value::take) {
+                Some(payload) => {
+                    let directory = event["directory"].as_str().map(str::to_string);
+                    event = payload;
+                    directory
+                }
+                None => stream_directory,
+            };
+            let Some((session_id, idle)) = classify(&event) else {
+                return;
+            };
+
+            let state = handle.state::<TranscriptLogState>();
+            if idle {
+                state.finish(session_id);
+                return;
+            }
+            let session_id = session_id.to_string();
+            let line = LogLine {
+                at: crate::app::now_millis(),
+                server: base.to_string(),
+                server_id: args.server_id().map(str::to_string),
+                directory,
+                event,
+            };
+            if let Err(e) = state.append(&session_id, &line) {
+                log::debug!("Failed to append transcript log for {}: {}", session_id, e);
+            }
+        }));
+
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SYNC_INTERVAL).await;
+            handle.state::<TranscriptLogState>().sync();
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn part(id: &str, text: &str) -> Value {
+        json!({
+            "type": "message.part.updated",
+            "properties": { "part": {
+                "id": id, "messageID": "msg_1", "sessionID": "ses_1", "type": "text", "text": text
+            } }
+        })
+    }
+
+    #[test]
+    fn recovers_parts_the_server_lost() {
+        let events = vec![
+            json!({ "type": "message.updated", "properties": { "info": {
+                "id": "msg_1", "sessionID": "ses_1", "role": "assistant"
+            } } }),
+            part("prt_1", "Hello"),
+            part("prt_1", "Hello, world"),
+            part("prt_2", "Streaming when it crash"),
+            part("prt_3", "removed"),
+            json!({ "type": "message.part.removed", "properties": {
+                "sessionID": "ses_1", "messageID": "msg_1", "partID": "prt_3"
+            } }),
+        ];
+        let local = compact(&events);
+        assert_eq!(local.len(), 1);
+        assert_eq!(local[0].parts.len(), 2);
+        assert_eq!(local[0].parts[0]["text"], "Hello, world");
+
+        // 服务端只保存了第一个分片的完整内容和第二个分片的前半段
+        let server = json!([{
+            "info": { "id": "msg_1", "sessionID": "ses_1", "role": "assistant" },
+            "parts": [
+                { "id": "prt_1", "type": "text", "text": "Hello, world" },
+                { "id": "prt_2", "type": "text", "text": "Streaming" }
+            ]
+        }]);
+        let missing = missing_from_server(local.clone(), &server);
+        assert_eq!(missing.len(), 1);
+        assert_eq!(missing[0].parts.len(), 1);
+        assert_eq!(missing[0].parts[0]["id"], "prt_2");
+
+        assert_eq!(missing_from_server(local.clone(), &json!([])), local);
+    }
+}
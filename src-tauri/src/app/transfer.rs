@@ -0,0 +1,243 @@
+// ============================================
+// Settings Export / Import (desktop only)
+// 将 Rust 侧所有设置打包为单个文件，便于迁移新机器或分享团队基线配置
+// ============================================
+
+use crate::app::{
+    appearance::{self, WindowAppearance},
+    format,
+    i18n::LanguageState,
+    projects::{Project, ProjectsState},
+    scheduler::{Schedule, SchedulerState},
+    server_profiles::{ServerProfile, ServerProfilesState},
+    settings::SettingsStore,
+    watch::{self, WatchJob, WatchState},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tauri::Manager;
+
+const BUNDLE_FORMAT: &str = "opencodeui-settings";
+const BUNDLE_VERSION: u32 = 1;
+
+/// 视为敏感信息的字段名（不区分大小写，忽略 `_`/`-`）
+const SECRET_KEYS: &[&str] = &[
+    "authheader",
+    "authorization",
+    "password",
+    "token",
+    "apikey",
+    "secret",
+    "cookie",
+];
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsBundle {
+    format: String,
+    version: u32,
+    exported_at: u64,
+    /// 按界面语言格式化的导出时间，方便人工查看
+    #[serde(default)]
+    exported_at_display: String,
+    app_version: String,
+    profile: String,
+    contains_secrets: bool,
+    #[serde(default)]
+    settings: Map<String, Value>,
+    #[serde(default)]
+    appearance: Option<WindowAppearance>,
+    #[serde(default)]
+    schedules: Vec<Schedule>,
+    #[serde(default)]
+    watch_jobs: Vec<WatchJob>,
+    /// 项目列表（路径按原机器记录）
+    #[serde(default)]
+    projects: Vec<Project>,
+    /// 服务器配置档；凭据在钥匙串中，不随设置导出
+    #[serde(default)]
+    server_profiles: Vec<ServerProfile>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferSummary {
+    settings: usize,
+    schedules: usize,
+    watch_jobs: usize,
+    projects: usize,
+    server_profiles: usize,
+    /// 导出时被移除、导入后需要重新填写的敏感字段数
+    redacted_secrets: usize,
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let normalized: String = key
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect();
+    SECRET_KEYS
+        .iter()
+        .any(|secret| normalized.ends_with(secret))
+}
+
+/// 递归清除 JSON 中的敏感字段（置为 null），返回清除的数量
+pub fn redact_secrets(value: &mut Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter_mut()
+            .map(|(key, value)| {
+                if is_secret_key(key) && !value.is_null() {
+                    *value = Value::Null;
+                    1
+                } else {
+                    redact_secrets(value)
+                }
+            })
+            .sum(),
+        Value::Array(items) => items.iter_mut().map(redact_secrets).sum(),
+        _ => 0,
+    }
+}
+
+/// 统计 JSON 中值为 null 的敏感字段（即需要重新填写的项）
+fn count_missing_secrets(value: &Value) -> usize {
+    match value {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| {
+                if is_secret_key(key) && value.is_null() {
+                    1
+                } else {
+                    count_missing_secrets(value)
+                }
+            })
+            .sum(),
+        Value::Array(items) => items.iter().map(count_missing_secrets).sum(),
+        _ => 0,
+    }
+}
+
+pub fn export_settings(
+    app: &tauri::AppHandle,
+    path: &str,
+    include_secrets: bool,
+) -> Result<TransferSummary, String> {
+    let exported_at = crate::app::now_millis();
+    let bundle = SettingsBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        version: BUNDLE_VERSION,
+        exported_at,
+        exported_at_display: format::timestamp(app.state::<LanguageState>().current(), exported_at),
+        app_version: app.package_info().version.to_string(),
+        profile: app
+            .state::<crate::app::profile::ActiveProfile>()
+            .name()
+            .to_string(),
+        contains_secrets: include_secrets,
+        settings: app.state::<SettingsStore>().snapshot(),
+        appearance: Some(appearance::load_appearance(app)),
+        schedules: app.state::<SchedulerState>().list(),
+        watch_jobs: app.state::<WatchState>().list(),
+        projects: app.state::<ProjectsState>().list(),
+        server_profiles: app.state::<ServerProfilesState>().list(),
+    };
+
+    let mut value = serde_json::to_value(&bundle).map_err(|e| e.to_string())?;
+    let redacted_secrets = if include_secrets {
+        0
+    } else {
+        redact_secrets(&mut value)
+    };
+
+    let data = serde_json::to_string_pretty(&value).map_err(|e| e.to_string())?;
+    std::fs::write(path, data).map_err(|e| format!("failed to write '{}': {}", path, e))?;
+    log::info!("Exported settings to {}", path);
+
+    Ok(TransferSummary {
+        settings: bundle.settings.len(),
+        schedules: bundle.schedules.len(),
+        watch_jobs: bundle.watch_jobs.len(),
+        projects: bundle.projects.len(),
+        server_profiles: bundle.server_profiles.len(),
+        redacted_secrets,
+    })
+}
+
+pub fn import_settings(app: &tauri::AppHandle, path: &str) -> Result<TransferSummary, String> {
+    let data =
+        std::fs::read_to_string(path).map_err(|e| format!("failed to read '{}': {}", path, e))?;
+    let value: Value =
+        serde_json::from_str(&data).map_err(|e| format!("invalid settings file: {}", e))?;
+    let missing_secrets = count_missing_secrets(&value);
+    let mut bundle: SettingsBundle =
+        serde_json::from_value(value).map_err(|e| format!("invalid settings file: {}", e))?;
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err("not an OpenCode UI settings file".to_string());
+    }
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "settings file version {} is newer than supported ({})",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+
+    // 与保存时相同，凭据不写回磁盘；在写入任何设置之前检查
+    for target in bundle
+        .schedules
+        .iter_mut()
+        .map(|schedule| &mut schedule.target)
+        .chain(bundle.watch_jobs.iter_mut().map(|job| &mut job.target))
+    {
+        target.strip_auth_header()?;
+    }
+
+    let summary = TransferSummary {
+        settings: bundle.settings.len(),
+        schedules: bundle.schedules.len(),
+        watch_jobs: bundle.watch_jobs.len(),
+        projects: bundle.projects.len(),
+        server_profiles: bundle.server_profiles.len(),
+        redacted_secrets: missing_secrets,
+    };
+
+    app.state::<ServerProfilesState>()
+        .replace_all(bundle.server_profiles)?;
+    app.state::<SettingsStore>().replace_all(bundle.settings)?;
+    if let Some(appearance) = bundle.appearance {
+        appearance::save_appearance(app, &appearance)?;
+        for window in app.webview_windows().values() {
+            appearance::apply_appearance(window, &appearance);
+        }
+    }
+    app.state::<SchedulerState>()
+        .replace_all(bundle.schedules)?;
+    app.state::<WatchState>().replace_all(bundle.watch_jobs)?;
+    watch::start_enabled_watches(app);
+    app.state::<ProjectsState>().replace_all(bundle.projects)?;
+    app.state::<ProjectsState>().forbid_read_only(app);
+
+    log::info!("Imported settings from {}", path);
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::redact_secrets;
+
+    #[test]
+    fn redact_secrets_clears_nested_credentials() {
+        let mut value = serde_json::json!({
+            "schedules": [{ "target": { "url": "http://a", "authHeader": "Bearer x" } }],
+            "proxy": { "password": "p", "noProxy": ["localhost"] },
+            "apiKey": null,
+        });
+
+        assert_eq!(redact_secrets(&mut value), 2);
+        assert!(value["schedules"][0]["target"]["authHeader"].is_null());
+        assert_eq!(value["schedules"][0]["target"]["url"], "http://a");
+        assert!(value["proxy"]["password"].is_null());
+    }
+}
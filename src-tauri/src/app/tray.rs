@@ -0,0 +1,383 @@
+// ============================================
+// Tray Status Icon (desktop only)
+// 托盘图标随所有会话的聚合状态（空闲 / 运行中 / 等待处理 / 出错）与系统深浅色主题切换，
+// 窗口全部隐藏时也能一眼看出是否在运行；Windows / Linux 的窗口与任务栏图标叠加同色状态点，
+// macOS Dock 图标无法在运行时替换，沿用徽标数字
+// ============================================
+
+use crate::app::{
+    bridge::{event_data, BridgeState},
+    i18n::Language,
+    permission_relay::PermissionRelayState,
+    presentation::PresentationState,
+    shortcuts,
+    window_context::WindowContextState,
+};
+use serde::Serialize;
+use serde_json::Value;
+use std::{collections::HashSet, sync::Mutex};
+use tauri::{
+    image::Image,
+    menu::{Menu, MenuItem, PredefinedMenuItem},
+    tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
+    Emitter, Manager, Theme,
+};
+
+pub const TRAY_ID: &str = "main";
+const SHOW_ID: &str = "tray-show";
+const QUIT_ID: &str = "tray-quit";
+/// 托盘图标边长（像素）
+const ICON_SIZE: u32 = 32;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrayStatus {
+    Idle,
+    /// 有会话正在运行
+    Busy,
+    /// 有权限请求等待用户处理
+    Attention,
+    /// 有会话出错且用户尚未查看
+    Error,
+}
+
+impl TrayStatus {
+    /// 等待处理优先于出错，出错优先于运行中
+    pub fn aggregate(busy: usize, waiting: usize, errors: usize) -> Self {
+        if waiting > 0 {
+            Self::Attention
+        } else if errors > 0 {
+            Self::Error
+        } else if busy > 0 {
+            Self::Busy
+        } else {
+            Self::Idle
+        }
+    }
+
+    /// 状态点颜色（RGB），空闲时不画
+    fn dot_color(self) -> Option<[u8; 3]> {
+        match self {
+            Self::Idle => None,
+            Self::Busy => Some([0x3b, 0x82, 0xf6]),
+            Self::Attention => Some([0xf5, 0x9e, 0x0b]),
+            Self::Error => Some([0xef, 0x44, 0x44]),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct TrayState {
+    /// 出错且尚未被查看的会话
+    errors: Mutex<HashSet<String>>,
+    theme: Mutex<Option<Theme>>,
+    /// 上次应用到图标的状态，未变化时跳过重绘
+    applied: Mutex<Option<(TrayStatus, Theme)>>,
+}
+
+/// 像素中心到圆的覆盖率，用于抗锯齿
+fn coverage(distance: f32, radius: f32) -> f32 {
+    (radius - distance + 0.5).clamp(0.0, 1.0)
+}
+
+fn blend(pixel: &mut [u8], color: [u8; 3], alpha: f32) {
+    let base = pixel[3] as f32 / 255.0;
+    let out = alpha + base * (1.0 - alpha);
+    if out <= 0.0 {
+        return;
+    }
+    for (channel, color) in pixel.iter_mut().zip(color) {
+        let mixed = color as f32 * alpha + *channel as f32 * base * (1.0 - alpha);
+        *channel = (mixed / out).round() as u8;
+    }
+    pixel[3] = (out * 255.0).round() as u8;
+}
+
+/// 在右下角画状态点，并在点周围挖出一圈透明描边，使其在任意底色上都清晰
+fn overlay_dot(rgba: &mut [u8], size: u32, color: [u8; 3]) {
+    let radius = size as f32 * 0.22;
+    let gap = size as f32 * 0.06;
+    let center = size as f32 - radius - 0.5;
+    for y in 0..size {
+        for x in 0..size {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let pixel = &mut rgba[((y * size + x) * 4) as usize..][..4];
+            let cut = coverage(distance, radius + gap);
+            pixel[3] = (pixel[3] as f32 * (1.0 - cut)).round() as u8;
+            blend(pixel, color, coverage(distance, radius));
+        }
+    }
+}
+
+/// 单色圆环字形：深色主题下为白色，浅色主题下为黑色，再叠加状态点
+pub fn render_tray_icon(status: TrayStatus, theme: Theme) -> Vec<u8> {
+    let glyph = match theme {
+        Theme::Dark => [0xff, 0xff, 0xff],
+        _ => [0x1f, 0x1f, 0x1f],
+    };
+    let mut rgba = vec![0u8; (ICON_SIZE * ICON_SIZE * 4) as usize];
+    let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+    let (outer, inner) = (ICON_SIZE as f32 * 0.42, ICON_SIZE as f32 * 0.27);
+    for y in 0..ICON_SIZE {
+        for x in 0..ICON_SIZE {
+            let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+            let alpha = coverage(distance, outer) * (1.0 - coverage(distance, inner));
+            let pixel = &mut rgba[((y * ICON_SIZE + x) * 4) as usize..][..4];
+            blend(pixel, glyph, alpha);
+        }
+    }
+    if let Some(color) = status.dot_color() {
+        overlay_dot(&mut rgba, ICON_SIZE, color);
+    }
+    rgba
+}
+
+fn build_menu(app: &tauri::AppHandle, lang: Language) -> tauri::Result<Menu<tauri::Wry>> {
+    Menu::with_items(
+        app,
+        &[
+            &MenuItem::with_id(app, SHOW_ID, lang.t("tray.show"), true, None::<&str>)?,
+            &MenuItem::with_id(
+                app,
+                shortcuts::NEW_WINDOW,
+                lang.t("menu.newWindow"),
+                true,
+                None::<&str>,
+            )?,
+            &PredefinedMenuItem::separator(app)?,
+            &MenuItem::with_id(app, QUIT_ID, lang.t("menu.quit"), true, None::<&str>)?,
+        ],
+    )
+}
+
+/// 以指定语言（重新）设置托盘菜单；托盘尚未创建时忽略
+pub fn apply_menu(app: &tauri::AppHandle, lang: Language) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    match build_menu(app, lang) {
+        Ok(menu) => {
+            if let Err(e) = tray.set_menu(Some(menu)) {
+                log::warn!("Failed to set tray menu: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Failed to build tray menu: {}", e),
+    }
+}
+
+fn show_windows(app: &tauri::AppHandle) {
+    for window in app.webview_windows().values() {
+        let _ = window.unminimize();
+        let _ = window.show();
+    }
+    if let Some(window) = app.webview_windows().values().next() {
+        let _ = window.set_focus();
+    }
+}
+
+fn current_status(app: &tauri::AppHandle) -> TrayStatus {
+    let busy = app.state::<WindowContextState>().busy_count();
+    let waiting = app
+        .try_state::<PermissionRelayState>()
+        .map_or(0, |state| state.pending().len());
+    let errors = app
+        .state::<TrayState>()
+        .errors
+        .lock()
+        .expect("tray poisoned")
+        .len();
+    TrayStatus::aggregate(busy, waiting, errors)
+}
+
+/// 按当前聚合状态与主题更新托盘图标、提示文本与窗口图标；演示模式下隐藏托盘，退出后恢复
+pub fn refresh(app: &tauri::AppHandle) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let presenting = app.state::<PresentationState>().any_presenting();
+    let _ = tray.set_visible(!presenting);
+    if presenting {
+        return;
+    }
+    let _ = tray.set_tooltip(Some(app.state::<WindowContextState>().tooltip()));
+
+    let state = app.state::<TrayState>();
+    let status = current_status(app);
+    let theme = state
+        .theme
+        .lock()
+        .expect("tray poisoned")
+        .unwrap_or(Theme::Light);
+    {
+        let mut applied = state.applied.lock().expect("tray poisoned");
+        if *applied == Some((status, theme)) {
+            return;
+        }
+        *applied = Some((status, theme));
+    }
+
+    let icon = Image::new_owned(render_tray_icon(status, theme), ICON_SIZE, ICON_SIZE);
+    if let Err(e) = tray.set_icon(Some(icon)) {
+        log::warn!("Failed to update tray icon: {}", e);
+    }
+    // 空闲时使用模板图标，由 macOS 菜单栏自行适配深浅色
+    #[cfg(target_os = "macos")]
+    let _ = tray.set_icon_as_template(status == TrayStatus::Idle);
+
+    #[cfg(not(target_os = "macos"))]
+    if let Some(base) = app.default_window_icon() {
+        let mut rgba = base.rgba().to_vec();
+        if let Some(color) = status.dot_color() {
+            if base.width() == base.height() {
+                overlay_dot(&mut rgba, base.width(), color);
+            }
+        }
+        for window in app.webview_windows().values() {
+            let icon = Image::new_owned(rgba.clone(), base.width(), base.height());
+            let _ = window.set_icon(icon);
+        }
+    }
+
+    let _ = app.emit("tray-status-changed", status);
+}
+
+/// 系统深浅色主题变化
+pub fn set_theme(app: &tauri::AppHandle, theme: Theme) {
+    *app.state::<TrayState>()
+        .theme
+        .lock()
+        .expect("tray poisoned") = Some(theme);
+    refresh(app);
+}
+
+/// 用户回到应用窗口后视为已查看错误
+pub fn clear_errors(app: &tauri::AppHandle) {
+    let cleared = {
+        let mut errors = app
+            .state::<TrayState>()
+            .errors
+            .lock()
+            .expect("tray poisoned");
+        let cleared = !errors.is_empty();
+        errors.clear();
+        cleared
+    };
+    if cleared {
+        refresh(app);
+    }
+}
+
+/// 会话出错时记录，恢复运行或被删除时移除；返回状态是否变化
+fn track_error(state: &TrayState, event: &Value) -> bool {
+    let event = event.get("payload").unwrap_or(event);
+    let properties = &event["properties"];
+    let Some(session_id) = properties["sessionID"]
+        .as_str()
+        .or_else(|| properties["info"]["id"].as_str())
+    else {
+        return false;
+    };
+    let mut errors = state.errors.lock().expect("tray poisoned");
+    match event["type"].as_str() {
+        Some("session.error") => errors.insert(session_id.to_string()),
+        Some("session.status") if properties["status"]["type"] == "busy" => {
+            errors.remove(session_id)
+        }
+        Some("session.deleted") => errors.remove(session_id),
+        _ => false,
+    }
+}
+
+/// 创建托盘图标并注册 bridge 事件观察者
+pub fn install(app: &tauri::AppHandle, lang: Language) {
+    let theme = app
+        .webview_windows()
+        .values()
+        .next()
+        .and_then(|window| window.theme().ok());
+    *app.state::<TrayState>()
+        .theme
+        .lock()
+        .expect("tray poisoned") = theme;
+
+    let theme = theme.unwrap_or(Theme::Light);
+    let built = build_menu(app, lang).and_then(|menu| {
+        TrayIconBuilder::with_id(TRAY_ID)
+            .icon(Image::new_owned(
+                render_tray_icon(TrayStatus::Idle, theme),
+                ICON_SIZE,
+                ICON_SIZE,
+            ))
+            .tooltip(app.state::<WindowContextState>().tooltip())
+            .menu(&menu)
+            .show_menu_on_left_click(false)
+            .on_menu_event(|app, event| match event.id().as_ref() {
+                SHOW_ID => show_windows(app),
+                QUIT_ID => app.exit(0),
+                // 新建窗口由应用级菜单事件处理
+                _ => {}
+            })
+            .on_tray_icon_event(|tray, event| {
+                if let TrayIconEvent::Click {
+                    button: MouseButton::Left,
+                    button_state: MouseButtonState::Up,
+                    ..
+                } = event
+                {
+                    show_windows(tray.app_handle());
+                }
+            })
+            .build(app)
+    });
+    if let Err(e) = built {
+        log::warn!("Failed to create tray icon: {}", e);
+        return;
+    }
+    refresh(app);
+
+    let handle = app.clone();
+    app.state::<BridgeState>()
+        .add_event_tap(Box::new(move |_args, block| {
+            if !block.contains("\"session.") {
+                return;
+            }
+            let Some(event) =
+                event_data(block).and_then(|data| serde_json::from_str::<Value>(&data).ok())
+            else {
+                return;
+            };
+            if track_error(&handle.state::<TrayState>(), &event) {
+                refresh(&handle);
+            }
+        }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn aggregates_status_and_renders_variants() {
+        assert_eq!(TrayStatus::aggregate(0, 0, 0), TrayStatus::Idle);
+        assert_eq!(TrayStatus::aggregate(2, 0, 0), TrayStatus::Busy);
+        assert_eq!(TrayStatus::aggregate(2, 0, 1), TrayStatus::Error);
+        assert_eq!(TrayStatus::aggregate(2, 1, 1), TrayStatus::Attention);
+
+        let pixel = |rgba: &[u8], x: u32, y: u32| {
+            let start = ((y * ICON_SIZE + x) * 4) as usize;
+            rgba[start..start + 4].to_vec()
+        };
+        // 圆环左侧：深色主题为白色，浅色主题为黑色
+        let ring = (4, ICON_SIZE / 2);
+        let dark = render_tray_icon(TrayStatus::Idle, Theme::Dark);
+        let light = render_tray_icon(TrayStatus::Idle, Theme::Light);
+        assert_eq!(pixel(&dark, ring.0, ring.1), vec![0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(pixel(&light, ring.0, ring.1), vec![0x1f, 0x1f, 0x1f, 0xff]);
+        // 中心透明，右下角只有出现状态时才有颜色
+        assert_eq!(pixel(&dark, ICON_SIZE / 2, ICON_SIZE / 2)[3], 0);
+        let corner = ICON_SIZE - 5;
+        assert_eq!(pixel(&dark, corner, corner)[3], 0);
+        let error = render_tray_icon(TrayStatus::Error, Theme::Dark);
+        assert_eq!(pixel(&error, corner, corner), vec![0xef, 0x44, 0x44, 0xff]);
+    }
+}
@@ -0,0 +1,417 @@
+// ============================================
+// Watch Mode Jobs (desktop only)
+// 项目文件变化时（匹配 glob）自动重新发送 prompt，带防抖与并发保护
+// ============================================
+
+use crate::app::api::{self, ServerTarget};
+use notify::{RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+use tauri::{Emitter, Manager};
+use tokio::sync::mpsc;
+
+const DEFAULT_DEBOUNCE_MS: u64 = 2000;
+/// 等待一次运行结束（会话回到 idle）的轮询间隔与上限
+const RUN_POLL_INTERVAL: Duration = Duration::from_secs(3);
+const RUN_MAX_DURATION: Duration = Duration::from_secs(60 * 60);
+/// 这些目录下的变化永远不触发
+const IGNORED_DIRS: &[&str] = &[".git", "node_modules", "target", ".opencode"];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchJob {
+    pub id: String,
+    pub name: String,
+    pub enabled: bool,
+    /// 监听的项目根目录
+    pub root: String,
+    /// 相对 root 的 glob，如 `src/**/*.rs`
+    pub patterns: Vec<String>,
+    pub target: ServerTarget,
+    pub prompt: String,
+    #[serde(default)]
+    pub model: Option<String>,
+    #[serde(default)]
+    pub agent: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default = "default_debounce_ms")]
+    pub debounce_ms: u64,
+}
+
+fn default_debounce_ms() -> u64 {
+    DEFAULT_DEBOUNCE_MS
+}
+
+impl WatchJob {
+    /// 与定时 prompt 相同，watch-jobs.json 中不保存 `auth_header`，
+    /// 需要认证的服务器须设置 `server_id`
+    fn strip_credentials(&mut self) -> Result<(), String> {
+        self.target.strip_auth_header()
+    }
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WatchJobEvent {
+    job_id: String,
+    files: Vec<String>,
+    session_id: Option<String>,
+    error: Option<String>,
+}
+
+/// 运行中的 job：持有 watcher（drop 即停止监听）
+struct ActiveWatch {
+    _watcher: notify::RecommendedWatcher,
+    running: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+pub struct WatchState {
+    jobs: Mutex<Vec<WatchJob>>,
+    active: Mutex<HashMap<String, ActiveWatch>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl WatchState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("watch-jobs.json"));
+        let mut jobs: Vec<WatchJob> = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+
+        // 旧版本会把 auth_header 写进文件：清除，无法改用钥匙串的 job 停用
+        let mut scrubbed = false;
+        for job in &mut jobs {
+            if job.target.auth_header.is_none() {
+                continue;
+            }
+            scrubbed = true;
+            if let Err(e) = job.strip_credentials() {
+                log::warn!("Disabling watch job '{}': {}", job.name, e);
+                job.enabled = false;
+            }
+        }
+
+        let state = Self {
+            jobs: Mutex::new(jobs),
+            active: Mutex::new(HashMap::new()),
+            path: Mutex::new(path),
+        };
+        if scrubbed {
+            let jobs = state.jobs.lock().expect("watch state poisoned");
+            if let Err(e) = state.persist(&jobs) {
+                log::warn!("Failed to rewrite watch-jobs.json: {}", e);
+            }
+        }
+        state
+    }
+
+    pub fn list(&self) -> Vec<WatchJob> {
+        self.jobs.lock().expect("watch state poisoned").clone()
+    }
+
+    pub fn is_running(&self, id: &str) -> bool {
+        self.active
+            .lock()
+            .expect("watch state poisoned")
+            .get(id)
+            .is_some_and(|active| active.running.load(Ordering::SeqCst))
+    }
+
+    /// 新建或更新 job，返回去掉明文凭据后保存的 job
+    pub fn upsert(&self, mut job: WatchJob) -> Result<WatchJob, String> {
+        if job.patterns.is_empty() {
+            return Err("at least one glob pattern is required".to_string());
+        }
+        if !Path::new(&job.root).is_dir() {
+            return Err(format!("'{}' is not a directory", job.root));
+        }
+        job.strip_credentials()?;
+
+        let mut jobs = self.jobs.lock().expect("watch state poisoned");
+        match jobs.iter_mut().find(|item| item.id == job.id) {
+            Some(existing) => *existing = job.clone(),
+            None => jobs.push(job.clone()),
+        }
+        self.persist(&jobs)?;
+        Ok(job)
+    }
+
+    /// 整体替换（导入设置时使用）；调用方负责重新启动监听
+    pub fn replace_all(&self, mut new_jobs: Vec<WatchJob>) -> Result<(), String> {
+        for job in &mut new_jobs {
+            job.strip_credentials()?;
+        }
+        let ids: Vec<String> = self
+            .active
+            .lock()
+            .expect("watch state poisoned")
+            .keys()
+            .cloned()
+            .collect();
+        for id in ids {
+            self.stop(&id);
+        }
+        let mut jobs = self.jobs.lock().expect("watch state poisoned");
+        *jobs = new_jobs;
+        self.persist(&jobs)
+    }
+
+    pub fn remove(&self, id: &str) -> Result<bool, String> {
+        self.stop(id);
+        let mut jobs = self.jobs.lock().expect("watch state poisoned");
+        let before = jobs.len();
+        jobs.retain(|job| job.id != id);
+        let removed = jobs.len() != before;
+        if removed {
+            self.persist(&jobs)?;
+        }
+        Ok(removed)
+    }
+
+    pub fn stop(&self, id: &str) {
+        self.active.lock().expect("watch state poisoned").remove(id);
+    }
+
+    fn persist(&self, jobs: &[WatchJob]) -> Result<(), String> {
+        let path = self.path.lock().expect("watch state poisoned").clone();
+        let path = path.ok_or("app config dir unavailable")?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(jobs).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// 简单 glob 匹配：`*` 匹配单层内任意字符，`**` 跨目录，`?` 匹配单个字符
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    fn matches(pattern: &[u8], path: &[u8]) -> bool {
+        match (pattern.first(), path.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) if pattern.get(1) == Some(&b'*') => {
+                let rest = pattern[2..].strip_prefix(b"/").unwrap_or(&pattern[2..]);
+                (0..=path.len()).any(|i| matches(rest, &path[i..]))
+            }
+            (Some(b'*'), _) => {
+                let rest = &pattern[1..];
+                let segment_end = path.iter().position(|c| *c == b'/').unwrap_or(path.len());
+                (0..=segment_end).any(|i| matches(rest, &path[i..]))
+            }
+            (Some(b'?'), Some(c)) if *c != b'/' => matches(&pattern[1..], &path[1..]),
+            (Some(p), Some(c)) if p == c => matches(&pattern[1..], &path[1..]),
+            _ => false,
+        }
+    }
+    matches(pattern.as_bytes(), path.as_bytes())
+}
+
+fn relative_match(job: &WatchJob, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(&job.root).ok()?;
+    if relative
+        .components()
+        .any(|part| IGNORED_DIRS.contains(&part.as_os_str().to_string_lossy().as_ref()))
+    {
+        return None;
+    }
+
+    let relative = relative.to_string_lossy().replace('\\', "/");
+    job.patterns
+        .iter()
+        .any(|pattern| glob_match(pattern, &relative))
+        .then_some(relative)
+}
+
+/// 轮询 `/session/status`，直到会话不再忙碌
+async fn wait_until_idle(target: &ServerTarget, session_id: &str) {
+    let started = std::time::Instant::now();
+    while started.elapsed() < RUN_MAX_DURATION {
+        tokio::time::sleep(RUN_POLL_INTERVAL).await;
+        let Ok(statuses) = api::get_json(target, "/session/status").await else {
+            continue;
+        };
+        let busy = statuses
+            .get(session_id)
+            .and_then(|status| status.get("type"))
+            .and_then(|kind| kind.as_str())
+            .is_some_and(|kind| kind != "idle");
+        if !busy {
+            return;
+        }
+    }
+}
+
+/// 开始监听一个 job；已在监听时先停止旧的
+pub fn start_watch(app: &tauri::AppHandle, job: WatchJob) -> Result<(), String> {
+    let state = app.state::<WatchState>();
+    state.stop(&job.id);
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<PathBuf>();
+    let running = Arc::new(AtomicBool::new(false));
+
+    let guard = running.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        // 运行中（agent 自己在改文件）的变化不计入，避免自我触发
+        if guard.load(Ordering::SeqCst) {
+            return;
+        }
+        if let Ok(event) = res {
+            if event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove() {
+                for path in event.paths {
+                    let _ = tx.send(path);
+                }
+            }
+        }
+    })
+    .map_err(|e| format!("failed to create file watcher: {}", e))?;
+    watcher
+        .watch(Path::new(&job.root), RecursiveMode::Recursive)
+        .map_err(|e| format!("failed to watch '{}': {}", job.root, e))?;
+
+    state.active.lock().expect("watch state poisoned").insert(
+        job.id.clone(),
+        ActiveWatch {
+            _watcher: watcher,
+            running: running.clone(),
+        },
+    );
+    log::info!("Watch job '{}' started on {}", job.name, job.root);
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let debounce = Duration::from_millis(job.debounce_ms.max(100));
+
+        // watcher 被移除后 tx 随之 drop，循环自然结束
+        while let Some(first) = rx.recv().await {
+            let mut files = BTreeSet::new();
+            files.extend(relative_match(&job, &first));
+
+            // 防抖：持续有变化时继续等待，安静 debounce 时长后才触发
+            while let Ok(Some(path)) = tokio::time::timeout(debounce, rx.recv()).await {
+                files.extend(relative_match(&job, &path));
+            }
+            if files.is_empty() || running.swap(true, Ordering::SeqCst) {
+                continue;
+            }
+
+            let files: Vec<String> = files.into_iter().collect();
+            log::info!(
+                "Watch job '{}' triggered by {} file(s)",
+                job.name,
+                files.len()
+            );
+            let result = api::send_prompt(
+                &job.target,
+                job.session_id.as_deref(),
+                &job.prompt,
+                job.model.as_deref(),
+                job.agent.as_deref(),
+            )
+            .await;
+
+            let (session_id, error) = match result {
+                Ok(session_id) => (Some(session_id), None),
+                Err(error) => (None, Some(error)),
+            };
+            let _ = app.emit(
+                "watch-job-triggered",
+                WatchJobEvent {
+                    job_id: job.id.clone(),
+                    files: files.clone(),
+                    session_id: session_id.clone(),
+                    error: error.clone(),
+                },
+            );
+
+            if let Some(session_id) = session_id.as_deref() {
+                wait_until_idle(&job.target, session_id).await;
+            }
+
+            // 丢弃运行期间积压的变化（多为 agent 自身的修改）
+            while rx.try_recv().is_ok() {}
+            running.store(false, Ordering::SeqCst);
+            let _ = app.emit(
+                "watch-job-finished",
+                WatchJobEvent {
+                    job_id: job.id.clone(),
+                    files,
+                    session_id,
+                    error,
+                },
+            );
+        }
+    });
+
+    Ok(())
+}
+
+/// 启动时恢复所有启用的 job
+pub fn start_enabled_watches(app: &tauri::AppHandle) {
+    let jobs = app.state::<WatchState>().list();
+    for job in jobs.into_iter().filter(|job| job.enabled) {
+        let name = job.name.clone();
+        if let Err(e) = start_watch(app, job) {
+            log::warn!("Failed to start watch job '{}': {}", name, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{glob_match, WatchJob};
+
+    #[test]
+    fn glob_match_handles_single_and_double_star() {
+        assert!(glob_match("src/**/*.rs", "src/app/mod.rs"));
+        assert!(glob_match("src/**/*.rs", "src/main.rs"));
+        assert!(glob_match("*.toml", "Cargo.toml"));
+        assert!(!glob_match("*.toml", "crates/a/Cargo.toml"));
+        assert!(glob_match("tests/test_?.py", "tests/test_a.py"));
+        assert!(!glob_match("src/**/*.rs", "docs/readme.md"));
+    }
+
+    #[test]
+    fn watch_jobs_never_keep_auth_headers() {
+        let job = |target: serde_json::Value| -> WatchJob {
+            serde_json::from_value(serde_json::json!({
+                "id": "w",
+                "name": "tests",
+                "enabled": true,
+                "root": "/repo",
+                "patterns": ["src/**/*.rs"],
+                "target": target,
+                "prompt": "fix the failing tests",
+            }))
+            .unwrap()
+        };
+
+        // 有服务器 id 时凭据来自钥匙串，丢弃明文
+        let mut with_id = job(serde_json::json!({
+            "url": "http://localhost:4096",
+            "authHeader": "Bearer secret",
+            "serverId": "work",
+        }));
+        assert!(with_id.strip_credentials().is_ok());
+        assert_eq!(with_id.target.auth_header, None);
+        assert!(!serde_json::to_string(&with_id).unwrap().contains("secret"));
+
+        // 只有明文凭据：拒绝
+        let mut without_id = job(serde_json::json!({
+            "url": "http://localhost:4096",
+            "authHeader": "Bearer secret",
+        }));
+        assert!(without_id.strip_credentials().is_err());
+        assert_eq!(without_id.target.auth_header, None);
+    }
+}
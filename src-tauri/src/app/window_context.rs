@@ -0,0 +1,178 @@
+// ============================================
+// Window Context (desktop only)
+// 前端上报每个窗口当前的会话标题 / 项目 / 忙碌状态，
+// 同步到原生窗口标题与 Dock / 任务栏徽标，方便在系统窗口切换器中区分窗口
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Mutex};
+
+const APP_NAME: &str = "OpenCode";
+/// 标题各段的最大字符数，过长的会话标题会撑爆任务栏
+const MAX_SEGMENT_CHARS: usize = 60;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowContext {
+    pub title: Option<String>,
+    pub project: Option<String>,
+    pub busy: bool,
+    /// 窗口正在显示的会话
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+fn segment(value: Option<&str>) -> Option<String> {
+    let value = value?.split_whitespace().collect::<Vec<_>>().join(" ");
+    if value.is_empty() {
+        return None;
+    }
+    if value.chars().count() <= MAX_SEGMENT_CHARS {
+        return Some(value);
+    }
+    let truncated: String = value.chars().take(MAX_SEGMENT_CHARS - 1).collect();
+    Some(format!("{}…", truncated.trim_end()))
+}
+
+/// "fix-auth-bug — my-repo"，缺失的段省略
+fn context_label(context: &WindowContext) -> Option<String> {
+    let mut parts: Vec<String> = [context.title.as_deref(), context.project.as_deref()]
+        .into_iter()
+        .filter_map(segment)
+        .collect();
+    parts.dedup();
+    (!parts.is_empty()).then(|| parts.join(" — "))
+}
+
+/// "fix-auth-bug — my-repo — OpenCode"；忙碌时加前缀标记
+pub fn format_title(context: &WindowContext) -> String {
+    let title = match context_label(context) {
+        Some(label) => format!("{} — {}", label, APP_NAME),
+        None => APP_NAME.to_string(),
+    };
+    if context.busy {
+        format!("● {}", title)
+    } else {
+        title
+    }
+}
+
+#[derive(Default)]
+pub struct WindowContextState {
+    /// window label → 最近一次上报的上下文
+    contexts: Mutex<HashMap<String, WindowContext>>,
+}
+
+impl WindowContextState {
+    pub fn set(&self, label: &str, context: WindowContext) {
+        self.contexts
+            .lock()
+            .expect("window context poisoned")
+            .insert(label.to_string(), context);
+    }
+
+    pub fn forget(&self, label: &str) {
+        self.contexts
+            .lock()
+            .expect("window context poisoned")
+            .remove(label);
+    }
+
+    /// 正在运行会话的窗口数，用作应用徽标数字
+    pub fn busy_count(&self) -> usize {
+        self.contexts
+            .lock()
+            .expect("window context poisoned")
+            .values()
+            .filter(|context| context.busy)
+            .count()
+    }
+
+    /// 是否有窗口正在显示该会话
+    pub fn showing(&self, session_id: &str) -> bool {
+        self.contexts
+            .lock()
+            .expect("window context poisoned")
+            .values()
+            .any(|context| context.session_id.as_deref() == Some(session_id))
+    }
+
+    /// 托盘提示文本：应用名 + 每个忙碌窗口一行
+    pub fn tooltip(&self) -> String {
+        let contexts = self.contexts.lock().expect("window context poisoned");
+        let mut busy: Vec<String> = contexts
+            .values()
+            .filter(|context| context.busy)
+            .map(|context| context_label(context).unwrap_or_else(|| "Untitled".to_string()))
+            .collect();
+        busy.sort();
+
+        match busy.len() {
+            0 => APP_NAME.to_string(),
+            _ => format!("{} — {} running\n{}", APP_NAME, busy.len(), busy.join("\n")),
+        }
+    }
+}
+
+/// 把所有窗口的忙碌数同步到应用徽标（macOS Dock / Linux 启动器；Windows 不支持时忽略）与托盘图标；
+/// 演示模式下清除徽标，退出时恢复
+pub fn refresh_badge(app: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    let count = app.state::<WindowContextState>().busy_count();
+    let presenting = app
+        .state::<crate::app::presentation::PresentationState>()
+        .any_presenting();
+    let badge = (count > 0 && !presenting).then_some(count as i64);
+    for window in app.webview_windows().values() {
+        if let Err(e) = window.set_badge_count(badge) {
+            log::debug!("Badge count not supported: {}", e);
+            break;
+        }
+    }
+    crate::app::tray::refresh(app);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_title_and_tooltip() {
+        let context = WindowContext {
+            title: Some("fix-auth-bug".to_string()),
+            project: Some("my-repo".to_string()),
+            busy: false,
+            session_id: None,
+        };
+        assert_eq!(format_title(&context), "fix-auth-bug — my-repo — OpenCode");
+        assert_eq!(format_title(&WindowContext::default()), "OpenCode");
+        assert_eq!(
+            format_title(&WindowContext {
+                title: Some("  ".to_string()),
+                project: Some("my-repo".to_string()),
+                busy: true,
+                session_id: None,
+            }),
+            "● my-repo — OpenCode"
+        );
+
+        let state = WindowContextState::default();
+        state.set("main", context.clone());
+        assert_eq!(state.tooltip(), "OpenCode");
+        state.set(
+            "second",
+            WindowContext {
+                busy: true,
+                ..context
+            },
+        );
+        assert_eq!(state.busy_count(), 1);
+        assert_eq!(
+            state.tooltip(),
+            "OpenCode — 1 running\nfix-auth-bug — my-repo"
+        );
+        state.forget("second");
+        assert_eq!(state.busy_count(), 0);
+    }
+}
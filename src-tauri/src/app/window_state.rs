@@ -0,0 +1,327 @@
+// ============================================
+// Window Geometry (desktop only)
+// 按窗口 label 与所绑定的项目目录记录大小、位置、所在显示器与最大化状态，保存到 window-state.json：
+// - 新窗口优先恢复同一目录上次关闭时的位置，其次是同一 label 的
+// - 恢复时确认位置仍在某个显示器内；显示器已断开或分辨率变化时，移到原显示器（或主显示器）居中
+// - 最大化 / 最小化时不覆盖之前记录的大小与位置，只更新最大化状态
+// 安全模式下不读取也不写入
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, sync::Mutex};
+use tauri::Manager;
+
+/// 小于该尺寸的记录视为无效（窗口被意外缩到极小）
+const MIN_WIDTH: u32 = 400;
+const MIN_HEIGHT: u32 = 300;
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SavedWindowState {
+    pub width: u32,
+    pub height: u32,
+    pub x: i32,
+    pub y: i32,
+    pub maximized: bool,
+    /// 窗口所在显示器的名称
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct WindowStateFile {
+    /// window label → 状态
+    windows: HashMap<String, SavedWindowState>,
+    /// 项目目录 → 状态
+    directories: HashMap<String, SavedWindowState>,
+}
+
+#[derive(Default)]
+pub struct WindowGeometryState {
+    file: Mutex<WindowStateFile>,
+    path: Option<PathBuf>,
+    /// window label → 创建时绑定的目录
+    bound: Mutex<HashMap<String, String>>,
+}
+
+impl WindowGeometryState {
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let path = crate::app::profile::config_dir(app).map(|dir| dir.join("window-state.json"));
+        let data = path
+            .as_ref()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .unwrap_or_default();
+        // 旧版本只保存主窗口的一条记录
+        let file = match serde_json::from_str::<SavedWindowState>(&data) {
+            Ok(main) => WindowStateFile {
+                windows: HashMap::from([("main".to_string(), main)]),
+                ..Default::default()
+            },
+            Err(_) => serde_json::from_str(&data).unwrap_or_default(),
+        };
+        Self {
+            file: Mutex::new(file),
+            path,
+            bound: Mutex::default(),
+        }
+    }
+
+    /// 记录窗口绑定的目录，关闭时同时按目录保存
+    pub fn bind(&self, label: &str, directory: &str) {
+        self.bound
+            .lock()
+            .expect("window state poisoned")
+            .insert(label.to_string(), directory.to_string());
+    }
+
+    fn lookup(&self, label: &str, directory: Option<&str>) -> Option<SavedWindowState> {
+        let file = self.file.lock().expect("window state poisoned");
+        directory
+            .and_then(|directory| file.directories.get(directory))
+            .or_else(|| file.windows.get(label))
+            .cloned()
+    }
+
+    fn persist(&self, file: &WindowStateFile) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(data) = serde_json::to_string_pretty(file) {
+            let _ = std::fs::write(path, data);
+        }
+    }
+}
+
+/// 显示器的物理坐标范围
+#[derive(Clone, Debug)]
+struct MonitorRect {
+    name: Option<String>,
+    x: i32,
+    y: i32,
+    width: u32,
+    height: u32,
+}
+
+impl MonitorRect {
+    fn from_monitor(monitor: &tauri::Monitor) -> Self {
+        Self {
+            name: monitor.name().cloned(),
+            x: monitor.position().x,
+            y: monitor.position().y,
+            width: monitor.size().width,
+            height: monitor.size().height,
+        }
+    }
+
+    fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x
+            && y >= self.y
+            && i64::from(x) < i64::from(self.x) + i64::from(self.width)
+            && i64::from(y) < i64::from(self.y) + i64::from(self.height)
+    }
+}
+
+/// 计算恢复后的 (x, y, 宽, 高)。以标题栏附近的一点判断窗口是否可见、可以拖动；
+/// 不可见时放到记录的显示器（已断开则为主显示器）中央，尺寸不超过该显示器
+fn placement(
+    state: &SavedWindowState,
+    monitors: &[MonitorRect],
+    primary: Option<&MonitorRect>,
+) -> (i32, i32, u32, u32) {
+    let anchor_x = state.x.saturating_add((state.width / 2).min(100) as i32);
+    let anchor_y = state.y.saturating_add(10);
+    if let Some(monitor) = monitors
+        .iter()
+        .find(|monitor| monitor.contains(anchor_x, anchor_y))
+    {
+        return (
+            state.x,
+            state.y,
+            state.width.min(monitor.width),
+            state.height.min(monitor.height),
+        );
+    }
+
+    let target = monitors
+        .iter()
+        .find(|monitor| monitor.name.is_some() && monitor.name == state.monitor)
+        .or(primary)
+        .or(monitors.first());
+    let Some(target) = target else {
+        return (state.x, state.y, state.width, state.height);
+    };
+    let width = state.width.min(target.width);
+    let height = state.height.min(target.height);
+    (
+        target.x + ((target.width - width) / 2) as i32,
+        target.y + ((target.height - height) / 2) as i32,
+        width,
+        height,
+    )
+}
+
+/// 保存窗口当前的几何状态（按 label，绑定了目录时也按目录）
+pub fn save(window: &tauri::Window) {
+    let app = window.app_handle();
+    let Some(state) = app.try_state::<WindowGeometryState>() else {
+        return;
+    };
+    let label = window.label();
+    let previous = state
+        .file
+        .lock()
+        .expect("window state poisoned")
+        .windows
+        .get(label)
+        .cloned();
+
+    let maximized = window.is_maximized().unwrap_or(false);
+    let minimized = window.is_minimized().unwrap_or(false);
+    let saved = if maximized || minimized {
+        // 最大化 / 最小化时的尺寸不是用户调整的，沿用之前的记录
+        match previous {
+            Some(previous) => SavedWindowState {
+                maximized,
+                ..previous
+            },
+            None if minimized => return,
+            None => {
+                let Ok(size) = window.outer_size() else {
+                    return;
+                };
+                SavedWindowState {
+                    width: size.width,
+                    height: size.height,
+                    x: 0,
+                    y: 0,
+                    maximized,
+                    monitor: None,
+                }
+            }
+        }
+    } else {
+        let (Ok(size), Ok(position)) = (window.outer_size(), window.outer_position()) else {
+            return;
+        };
+        SavedWindowState {
+            width: size.width,
+            height: size.height,
+            x: position.x,
+            y: position.y,
+            maximized: false,
+            monitor: window
+                .current_monitor()
+                .ok()
+                .flatten()
+                .and_then(|monitor| monitor.name().cloned()),
+        }
+    };
+
+    let directory = state
+        .bound
+        .lock()
+        .expect("window state poisoned")
+        .get(label)
+        .cloned();
+    let mut file = state.file.lock().expect("window state poisoned");
+    file.windows.insert(label.to_string(), saved.clone());
+    if let Some(directory) = directory {
+        file.directories.insert(directory, saved);
+    }
+    state.persist(&file);
+}
+
+/// 恢复窗口的几何状态；`directory` 为新窗口绑定的项目目录
+pub fn restore(window: &tauri::WebviewWindow, directory: Option<&str>) {
+    let app = window.app_handle();
+    let Some(state) = app.try_state::<WindowGeometryState>() else {
+        return;
+    };
+    if let Some(directory) = directory {
+        state.bind(window.label(), directory);
+    }
+    let Some(saved) = state.lookup(window.label(), directory) else {
+        return;
+    };
+
+    let monitors: Vec<MonitorRect> = window
+        .available_monitors()
+        .unwrap_or_default()
+        .iter()
+        .map(MonitorRect::from_monitor)
+        .collect();
+    let primary = window
+        .primary_monitor()
+        .ok()
+        .flatten()
+        .map(|monitor| MonitorRect::from_monitor(&monitor));
+    let (x, y, width, height) = placement(&saved, &monitors, primary.as_ref());
+
+    if width >= MIN_WIDTH && height >= MIN_HEIGHT {
+        let _ = window.set_size(tauri::PhysicalSize::new(width, height));
+    }
+    let _ = window.set_position(tauri::PhysicalPosition::new(x, y));
+    if saved.maximized {
+        let _ = window.maximize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn places_windows_on_connected_monitors() {
+        let monitor = |name: &str, x, width, height| MonitorRect {
+            name: Some(name.to_string()),
+            x,
+            y: 0,
+            width,
+            height,
+        };
+        let monitors = [
+            monitor("left", 0, 1920, 1080),
+            monitor("right", 1920, 2560, 1440),
+        ];
+        let saved = |x, y, width, height, monitor: &str| SavedWindowState {
+            width,
+            height,
+            x,
+            y,
+            maximized: false,
+            monitor: Some(monitor.to_string()),
+        };
+
+        // 仍在显示器内：原样恢复
+        assert_eq!(
+            placement(
+                &saved(2000, 100, 1200, 800, "right"),
+                &monitors,
+                monitors.first()
+            ),
+            (2000, 100, 1200, 800)
+        );
+        // 右侧显示器分辨率变小后超出：回到该显示器中央
+        assert_eq!(
+            placement(
+                &saved(4400, 100, 1200, 800, "right"),
+                &monitors,
+                monitors.first()
+            ),
+            (1920 + 680, 320, 1200, 800)
+        );
+        // 显示器已断开：移到主显示器中央，尺寸不超过主显示器
+        assert_eq!(
+            placement(
+                &saved(-3000, 0, 2400, 1200, "gone"),
+                &monitors,
+                monitors.first()
+            ),
+            (0, 0, 1920, 1080)
+        );
+    }
+}
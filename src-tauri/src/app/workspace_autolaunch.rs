@@ -0,0 +1,215 @@
+// ============================================
+// Workspace Autolaunch (desktop only)
+// 工作区 = 所有固定（pinned）且带托管服务的服务器配置档：
+// - 开启后应用启动时并行启动工作区中的服务（各自的端口与工作目录），也可通过 launch_workspace 手动启动
+// - 每个服务等到健康检查通过或超时，状态变化时发出 `workspace-service-status`
+// - 全部结束后发出 `workspace-ready`，附带各服务的最终状态
+// 事件可能早于前端监听，前端启动后可用 get_workspace_status 取得当前状态
+// ============================================
+
+use crate::app::{
+    commands::{opencode::is_service_running, server_profiles::start_profile},
+    server_profiles::{ServerProfile, ServerProfilesState},
+    service::ServiceState,
+    settings::SettingsStore,
+};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use tauri::{Emitter, Manager};
+
+pub const WORKSPACE_AUTOLAUNCH_SETTINGS_KEY: &str = "workspaceAutolaunch";
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WorkspaceAutolaunchConfig {
+    /// 应用启动时启动工作区
+    pub enabled: bool,
+    /// 每个服务等待健康检查通过的最长时间（秒）
+    pub health_timeout_secs: u64,
+}
+
+impl Default for WorkspaceAutolaunchConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            health_timeout_secs: 60,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WorkspaceServiceState {
+    Starting,
+    Ready,
+    Failed,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceServiceStatus {
+    pub profile_id: String,
+    pub name: String,
+    pub url: String,
+    pub state: WorkspaceServiceState,
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct WorkspaceState {
+    statuses: Mutex<Vec<WorkspaceServiceStatus>>,
+    /// 防止重复启动同一工作区
+    running: Mutex<bool>,
+}
+
+impl WorkspaceState {
+    pub fn statuses(&self) -> Vec<WorkspaceServiceStatus> {
+        self.statuses
+            .lock()
+            .expect("workspace state poisoned")
+            .clone()
+    }
+
+    fn update(&self, status: &WorkspaceServiceStatus) {
+        let mut statuses = self.statuses.lock().expect("workspace state poisoned");
+        match statuses
+            .iter_mut()
+            .find(|existing| existing.profile_id == status.profile_id)
+        {
+            Some(existing) => *existing = status.clone(),
+            None => statuses.push(status.clone()),
+        }
+    }
+}
+
+/// 去掉与前面的配置档使用同一地址的配置档，同一端口只能启动一个服务
+fn dedupe_by_url(profiles: Vec<ServerProfile>) -> Vec<ServerProfile> {
+    let mut seen: Vec<String> = Vec::new();
+    profiles
+        .into_iter()
+        .filter(|profile| {
+            if seen.contains(&profile.url) {
+                log::warn!(
+                    "Workspace: skipping profile '{}', {} is already used by another profile",
+                    profile.id,
+                    profile.url
+                );
+                return false;
+            }
+            seen.push(profile.url.clone());
+            true
+        })
+        .collect()
+}
+
+fn report(app: &tauri::AppHandle, status: WorkspaceServiceStatus) {
+    app.state::<WorkspaceState>().update(&status);
+    let _ = app.emit("workspace-service-status", status);
+}
+
+async fn launch_one(app: &tauri::AppHandle, profile: ServerProfile, timeout: Duration) {
+    let mut status = WorkspaceServiceStatus {
+        profile_id: profile.id.clone(),
+        name: profile.name.clone(),
+        url: profile.url.clone(),
+        state: WorkspaceServiceState::Starting,
+        error: None,
+    };
+    report(app, status.clone());
+
+    let started = Instant::now();
+    let result = match start_profile(&app.state::<ServiceState>(), profile).await {
+        Ok(result) => {
+            if let Some(url) = result.url {
+                status.url = url;
+            }
+            // 启动流程只等待有限时间，这里继续等到健康检查通过
+            loop {
+                if is_service_running(&status.url).await {
+                    break Ok(());
+                }
+                if started.elapsed() > timeout {
+                    break Err(format!(
+                        "health check did not pass within {}s",
+                        timeout.as_secs()
+                    ));
+                }
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+        Err(e) => Err(e),
+    };
+    match result {
+        Ok(()) => {
+            log::info!(
+                "Workspace: '{}' is ready at {}",
+                status.profile_id,
+                status.url
+            );
+            status.state = WorkspaceServiceState::Ready;
+        }
+        Err(e) => {
+            log::warn!("Workspace: '{}' failed: {}", status.profile_id, e);
+            status.state = WorkspaceServiceState::Failed;
+            status.error = Some(e);
+        }
+    }
+    report(app, status);
+}
+
+/// 并行启动工作区中的所有服务，等待全部就绪或失败后返回各服务状态
+pub async fn launch(app: &tauri::AppHandle) -> Result<Vec<WorkspaceServiceStatus>, String> {
+    let state = app.state::<WorkspaceState>();
+    {
+        let mut running = state.running.lock().expect("workspace state poisoned");
+        if *running {
+            return Err("workspace is already launching".to_string());
+        }
+        *running = true;
+    }
+
+    let config: WorkspaceAutolaunchConfig = app
+        .state::<SettingsStore>()
+        .get_as(WORKSPACE_AUTOLAUNCH_SETTINGS_KEY)
+        .unwrap_or_default();
+    let timeout = Duration::from_secs(config.health_timeout_secs.max(1));
+    let profiles = dedupe_by_url(app.state::<ServerProfilesState>().pinned());
+    log::info!("Launching workspace with {} service(s)", profiles.len());
+
+    state
+        .statuses
+        .lock()
+        .expect("workspace state poisoned")
+        .clear();
+    join_all(
+        profiles
+            .into_iter()
+            .map(|profile| launch_one(app, profile, timeout)),
+    )
+    .await;
+
+    *state.running.lock().expect("workspace state poisoned") = false;
+    let statuses = state.statuses();
+    let _ = app.emit("workspace-ready", &statuses);
+    Ok(statuses)
+}
+
+/// 应用启动时按设置启动工作区
+pub fn spawn_autolaunch(app: tauri::AppHandle) {
+    let config: WorkspaceAutolaunchConfig = app
+        .state::<SettingsStore>()
+        .get_as(WORKSPACE_AUTOLAUNCH_SETTINGS_KEY)
+        .unwrap_or_default();
+    if !config.enabled {
+        return;
+    }
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = launch(&app).await {
+            log::warn!("Workspace autolaunch: {}", e);
+        }
+    });
+}
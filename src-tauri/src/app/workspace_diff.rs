@@ -0,0 +1,361 @@
+// ============================================
+// Workspace Diff (desktop only)
+// 比较检查点 tree 与当前工作区，解析 git diff 输出为按文件的结构化 hunk，分批回调
+// ============================================
+
+use crate::app::{checkpoints, git};
+use serde::Serialize;
+use std::{
+    io::{BufRead, BufReader},
+    path::Path,
+    process::Stdio,
+};
+
+/// 每批回调的文件数
+const BATCH_FILES: usize = 50;
+/// 单个文件保留的 diff 行数上限（生成文件、lockfile 等），超出只计数
+const MAX_LINES_PER_FILE: usize = 2000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileStatus {
+    Added,
+    Modified,
+    Deleted,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LineKind {
+    Context,
+    Added,
+    Removed,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: LineKind,
+    pub text: String,
+    pub old_line: Option<u32>,
+    pub new_line: Option<u32>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hunk {
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// `@@` 之后的函数/段落提示
+    pub section: String,
+    pub lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub path: String,
+    pub status: FileStatus,
+    pub binary: bool,
+    pub additions: u32,
+    pub deletions: u32,
+    pub hunks: Vec<Hunk>,
+    /// 行数超过上限，hunks 不完整
+    pub truncated: bool,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSummary {
+    pub files: usize,
+    pub additions: u32,
+    pub deletions: u32,
+}
+
+fn unquote(path: &str) -> String {
+    path.strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+        .unwrap_or(path)
+        .to_string()
+}
+
+/// `@@ -1,3 +1,4 @@ fn main()` → (1, 3, 1, 4, "fn main()")
+fn parse_hunk_header(line: &str) -> Option<(u32, u32, u32, u32, String)> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (ranges, section) = rest.split_once(" @@")?;
+    let (old, new) = ranges.split_once(" +")?;
+    let range = |range: &str| -> Option<(u32, u32)> {
+        match range.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((range.parse().ok()?, 1)),
+        }
+    };
+    let (old_start, old_lines) = range(old)?;
+    let (new_start, new_lines) = range(new)?;
+    Some((
+        old_start,
+        old_lines,
+        new_start,
+        new_lines,
+        section.trim().to_string(),
+    ))
+}
+
+/// 逐行解析 `git diff --no-renames` 输出
+#[derive(Default)]
+pub struct DiffParser {
+    current: Option<FileDiff>,
+    old_line: u32,
+    new_line: u32,
+    old_remaining: u32,
+    new_remaining: u32,
+    kept_lines: usize,
+}
+
+impl DiffParser {
+    /// 喂入一行（不含换行符），一个文件解析完成时返回它
+    pub fn push_line(&mut self, line: &str) -> Option<FileDiff> {
+        if self.old_remaining > 0 || self.new_remaining > 0 {
+            self.push_hunk_line(line);
+            return None;
+        }
+
+        if let Some(header) = line.strip_prefix("diff --git ") {
+            let finished = self.current.take();
+            // 无重命名时两侧路径相同："a/<p> b/<p>"
+            let path = header
+                .strip_prefix("a/")
+                .filter(|rest| rest.len() >= 3)
+                .map(|rest| rest[..(rest.len() - 3) / 2].to_string())
+                .unwrap_or_else(|| header.to_string());
+            self.current = Some(FileDiff {
+                path: unquote(&path),
+                status: FileStatus::Modified,
+                binary: false,
+                additions: 0,
+                deletions: 0,
+                hunks: Vec::new(),
+                truncated: false,
+            });
+            self.kept_lines = 0;
+            return finished;
+        }
+
+        let file = self.current.as_mut()?;
+        if line.starts_with("new file mode") {
+            file.status = FileStatus::Added;
+        } else if line.starts_with("deleted file mode") {
+            file.status = FileStatus::Deleted;
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if let Some(path) = line.strip_prefix("+++ b/") {
+            file.path = unquote(path);
+        } else if let Some(path) = line.strip_prefix("--- a/") {
+            file.path = unquote(path);
+        } else if let Some((old_start, old_lines, new_start, new_lines, section)) =
+            parse_hunk_header(line)
+        {
+            self.old_line = old_start;
+            self.new_line = new_start;
+            self.old_remaining = old_lines;
+            self.new_remaining = new_lines;
+            file.hunks.push(Hunk {
+                old_start,
+                old_lines,
+                new_start,
+                new_lines,
+                section,
+                lines: Vec::new(),
+            });
+        }
+        None
+    }
+
+    fn push_hunk_line(&mut self, line: &str) {
+        let Some(file) = self.current.as_mut() else {
+            return;
+        };
+        let (kind, text) = match line.split_at_checked(1) {
+            Some(("+", text)) => (LineKind::Added, text),
+            Some(("-", text)) => (LineKind::Removed, text),
+            Some((" ", text)) => (LineKind::Context, text),
+            // "\ No newline at end of file"
+            Some(("\\", _)) => return,
+            _ => (LineKind::Context, ""),
+        };
+
+        let (old_line, new_line) = match kind {
+            LineKind::Added => {
+                file.additions += 1;
+                self.new_remaining = self.new_remaining.saturating_sub(1);
+                self.new_line += 1;
+                (None, Some(self.new_line - 1))
+            }
+            LineKind::Removed => {
+                file.deletions += 1;
+                self.old_remaining = self.old_remaining.saturating_sub(1);
+                self.old_line += 1;
+                (Some(self.old_line - 1), None)
+            }
+            LineKind::Context => {
+                self.old_remaining = self.old_remaining.saturating_sub(1);
+                self.new_remaining = self.new_remaining.saturating_sub(1);
+                self.old_line += 1;
+                self.new_line += 1;
+                (Some(self.old_line - 1), Some(self.new_line - 1))
+            }
+        };
+
+        if self.kept_lines >= MAX_LINES_PER_FILE {
+            file.truncated = true;
+            return;
+        }
+        self.kept_lines += 1;
+        if let Some(hunk) = file.hunks.last_mut() {
+            hunk.lines.push(DiffLine {
+                kind,
+                text: text.to_string(),
+                old_line,
+                new_line,
+            });
+        }
+    }
+
+    pub fn finish(&mut self) -> Option<FileDiff> {
+        self.old_remaining = 0;
+        self.new_remaining = 0;
+        self.current.take()
+    }
+}
+
+/// 比较 `tree` 与 `root` 当前工作区，每解析出一批文件调用一次 `on_batch`
+pub fn diff_tree_to_workdir(
+    root: &Path,
+    tree: &str,
+    on_batch: &mut dyn FnMut(Vec<FileDiff>),
+) -> Result<DiffSummary, String> {
+    let current = checkpoints::snapshot_tree(root)?;
+    let mut child = git::command()
+        .args([
+            "-c",
+            "core.quotePath=false",
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            "-U3",
+            tree,
+            &current,
+        ])
+        .current_dir(root)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+
+    let stdout = child.stdout.take().ok_or("git stdout unavailable")?;
+    let mut reader = BufReader::new(stdout);
+    let mut parser = DiffParser::default();
+    let mut summary = DiffSummary::default();
+    let mut batch = Vec::new();
+    let mut raw = Vec::new();
+
+    let mut collect = |file: FileDiff, batch: &mut Vec<FileDiff>| {
+        summary.files += 1;
+        summary.additions += file.additions;
+        summary.deletions += file.deletions;
+        batch.push(file);
+        if batch.len() >= BATCH_FILES {
+            on_batch(std::mem::take(batch));
+        }
+    };
+
+    loop {
+        raw.clear();
+        let n = reader
+            .read_until(b'\n', &mut raw)
+            .map_err(|e| e.to_string())?;
+        if n == 0 {
+            break;
+        }
+        let line = String::from_utf8_lossy(&raw);
+        let line = line.trim_end_matches('\n').trim_end_matches('\r');
+        if let Some(file) = parser.push_line(line) {
+            collect(file, &mut batch);
+        }
+    }
+    if let Some(file) = parser.finish() {
+        collect(file, &mut batch);
+    }
+    if !batch.is_empty() {
+        on_batch(batch);
+    }
+
+    let output = child.wait_with_output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(format!(
+            "git diff failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_unified_diff_into_files_and_hunks() {
+        let output = "\
+diff --git a/src/main.rs b/src/main.rs
+index 1111111..2222222 100644
+--- a/src/main.rs
++++ b/src/main.rs
+@@ -1,3 +1,3 @@ fn main() {
+ fn main() {
+--- old
++++ new
+ }
+diff --git a/new file.txt b/new file.txt
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ b/new file.txt
+@@ -0,0 +1 @@
++hello
+\\ No newline at end of file
+diff --git a/logo.png b/logo.png
+deleted file mode 100644
+index 4444444..0000000
+Binary files a/logo.png and /dev/null differ
+";
+        let mut parser = DiffParser::default();
+        let mut files: Vec<FileDiff> = output
+            .lines()
+            .filter_map(|line| parser.push_line(line))
+            .collect();
+        files.extend(parser.finish());
+
+        assert_eq!(files.len(), 3);
+        let modified = &files[0];
+        assert_eq!(modified.path, "src/main.rs");
+        assert_eq!(modified.status, FileStatus::Modified);
+        assert_eq!((modified.additions, modified.deletions), (1, 1));
+        assert_eq!(modified.hunks[0].section, "fn main() {");
+        assert_eq!(modified.hunks[0].lines[1].kind, LineKind::Removed);
+        assert_eq!(modified.hunks[0].lines[1].text, "-- old");
+        assert_eq!(modified.hunks[0].lines[2].new_line, Some(2));
+
+        assert_eq!(files[1].path, "new file.txt");
+        assert_eq!(files[1].status, FileStatus::Added);
+        assert_eq!(files[1].hunks[0].lines.len(), 1);
+
+        assert_eq!(files[2].status, FileStatus::Deleted);
+        assert!(files[2].binary);
+    }
+}
@@ -0,0 +1,165 @@
+// ============================================
+// WSL Launch (desktop only, Windows)
+// 在 WSL 发行版中运行 opencode serve，供代码与工具链都在 WSL 里的 Windows 用户使用：
+// - `wsl.exe --exec` 直接执行，参数不经 Linux shell 二次解析；经登录 shell 取得用户 PATH
+// - Windows 路径（C:\... 与 \\wsl$\<distro>\...）转换为 WSL 内的路径
+// - 环境变量需列入 WSLENV 才会传入 Linux 侧
+// - 服务端口经 WSL 的 localhost 转发访问，健康检查与普通实例相同
+// ============================================
+
+use serde::{Deserialize, Serialize};
+use std::{path::Path, process::Command};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct WslTarget {
+    /// 发行版名称；None 使用默认发行版
+    pub distro: Option<String>,
+}
+
+/// 把 Windows 路径转换为 WSL 内的路径；已是 Linux 路径时原样返回
+pub fn to_wsl_path(path: &str) -> Option<String> {
+    let path = path.replace('\\', "/");
+    if path.starts_with('/') && !path.starts_with("//") {
+        return Some(path);
+    }
+
+    // \\wsl$\Ubuntu\home\me 或 \\wsl.localhost\Ubuntu\home\me → /home/me
+    let lower = path.to_ascii_lowercase();
+    for prefix in ["//wsl$/", "//wsl.localhost/"] {
+        if lower.starts_with(prefix) {
+            let inner = path[prefix.len()..]
+                .split_once('/')
+                .map_or("", |(_, inner)| inner);
+            return Some(format!("/{}", inner.trim_end_matches('/')));
+        }
+    }
+
+    // canonicalize 产生的 \\?\C:\ 前缀
+    let path = path.strip_prefix("//?/").unwrap_or(&path);
+    let drive = path.chars().next().filter(char::is_ascii_alphabetic)?;
+    let rest = path[1..].strip_prefix(':')?.trim_matches('/');
+    let mut mapped = format!("/mnt/{}", drive.to_ascii_lowercase());
+    if !rest.is_empty() {
+        mapped.push('/');
+        mapped.push_str(rest);
+    }
+    Some(mapped)
+}
+
+/// 构建在 WSL 中运行 `binary args...` 的命令；`cwd` 为 Windows 或 WSL 路径
+pub fn command(
+    target: &WslTarget,
+    binary: &str,
+    args: &[String],
+    cwd: Option<&Path>,
+    env_keys: &[&str],
+) -> Result<Command, String> {
+    let mut cmd = Command::new("wsl.exe");
+    if let Some(distro) = target
+        .distro
+        .as_deref()
+        .map(str::trim)
+        .filter(|distro| !distro.is_empty())
+    {
+        cmd.args(["--distribution", distro]);
+    }
+    if let Some(cwd) = cwd {
+        let cwd = to_wsl_path(&cwd.to_string_lossy())
+            .ok_or_else(|| format!("cannot map '{}' into WSL", cwd.display()))?;
+        cmd.args(["--cd", &cwd]);
+    }
+
+    let binary = match binary.trim() {
+        "" => "opencode".to_string(),
+        binary => to_wsl_path(binary).unwrap_or_else(|| binary.to_string()),
+    };
+    // 登录 shell 读取 ~/.profile 得到用户的 PATH，再 exec 成 opencode 本身
+    cmd.args(["--exec", "sh", "-lc", "exec \"$0\" \"$@\"", &binary])
+        .args(args);
+
+    if !env_keys.is_empty() {
+        let mut wslenv = std::env::var("WSLENV").unwrap_or_default();
+        for key in env_keys {
+            if !wslenv.is_empty() {
+                wslenv.push(':');
+            }
+            wslenv.push_str(key);
+        }
+        cmd.env("WSLENV", wslenv);
+    }
+    Ok(cmd)
+}
+
+/// wsl.exe 默认以 UTF-16LE 输出
+#[cfg(any(target_os = "windows", test))]
+fn decode_output(bytes: &[u8]) -> String {
+    if bytes.len() >= 2 && bytes.len() % 2 == 0 && bytes.iter().skip(1).step_by(2).all(|b| *b == 0)
+    {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        return String::from_utf16_lossy(&units);
+    }
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// 已安装的发行版，默认发行版在前
+pub fn list_distros() -> Result<Vec<String>, String> {
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        let output = Command::new("wsl.exe")
+            .args(["--list", "--quiet"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map_err(|e| format!("failed to run wsl.exe: {}", e))?;
+        if !output.status.success() {
+            return Err(decode_output(&output.stdout).trim().to_string());
+        }
+        Ok(decode_output(&output.stdout)
+            .lines()
+            .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\u{feff}'))
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect())
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        Err("WSL is only available on Windows".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_windows_paths_into_wsl() {
+        assert_eq!(
+            to_wsl_path(r"C:\Users\me\repo").as_deref(),
+            Some("/mnt/c/Users/me/repo")
+        );
+        assert_eq!(to_wsl_path(r"D:\").as_deref(), Some("/mnt/d"));
+        assert_eq!(to_wsl_path(r"\\?\E:\work\").as_deref(), Some("/mnt/e/work"));
+        assert_eq!(
+            to_wsl_path(r"\\wsl$\Ubuntu\home\me\repo").as_deref(),
+            Some("/home/me/repo")
+        );
+        assert_eq!(
+            to_wsl_path(r"\\wsl.localhost\Debian\").as_deref(),
+            Some("/")
+        );
+        assert_eq!(to_wsl_path("/home/me").as_deref(), Some("/home/me"));
+        assert_eq!(to_wsl_path("relative/dir"), None);
+
+        let utf16: Vec<u8> = "Ubuntu\r\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert_eq!(decode_output(&utf16), "Ubuntu\r\n");
+    }
+}